@@ -0,0 +1,76 @@
+//! 大型翻译目录的基准测试：`cargo bench --bench catalog`
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use i18n_app::merge::{self, Resolution};
+use i18n_app::translation::{self, TranslationDelta, TranslationFile};
+use serde_json::{Map, Value};
+
+/// 基准测试使用的 key 数量
+const KEYS: usize = 50_000;
+
+/// `namespace.section.key` 三层嵌套的翻译，每个 section 20 个 key。
+/// `edit` 不为 0 时每隔 `edit` 个 key 修改一次文本
+fn catalog(edit: usize) -> Value {
+    let mut root = Map::new();
+    for i in 0..KEYS {
+        let text = if edit > 0 && i % edit == 0 {
+            format!("Edited text number {}", i)
+        } else {
+            format!("Translated text number {}", i)
+        };
+        let namespace = root
+            .entry(format!("namespace{}", i / 1000))
+            .or_insert_with(|| Value::Object(Map::new()));
+        let section = namespace
+            .as_object_mut()
+            .unwrap()
+            .entry(format!("section{}", i / 20 % 50))
+            .or_insert_with(|| Value::Object(Map::new()));
+        section
+            .as_object_mut()
+            .unwrap()
+            .insert(format!("key{}", i % 20), Value::String(text));
+    }
+    Value::Object(root)
+}
+
+fn file(value: &Value) -> TranslationFile {
+    TranslationFile::from_content(
+        "en-US".to_string(),
+        "en-US.json".to_string(),
+        translation::flatten_json(value),
+    )
+}
+
+fn bench_flatten(c: &mut Criterion) {
+    let value = catalog(0);
+    let flat = translation::flatten_json(&value);
+    c.bench_function("flatten_json 50k", |b| {
+        b.iter(|| translation::flatten_json(black_box(&value)))
+    });
+    c.bench_function("unflatten_json 50k", |b| {
+        b.iter(|| translation::unflatten_json(black_box(&flat)).unwrap())
+    });
+}
+
+fn bench_merge(c: &mut Criterion) {
+    let base = catalog(0);
+    let local = catalog(7);
+    let remote = catalog(5);
+    c.bench_function("merge_three_way 50k", |b| {
+        b.iter(|| {
+            merge::merge_three_way(&base, &local, &remote, &mut |_| Ok(Resolution::Unresolved))
+                .unwrap()
+        })
+    });
+}
+
+fn bench_diff(c: &mut Criterion) {
+    let local = file(&catalog(5));
+    let remote = file(&catalog(0));
+    c.bench_function("translation_delta 50k", |b| {
+        b.iter(|| TranslationDelta::new(black_box(&local), black_box(&remote)))
+    });
+}
+
+criterion_group!(benches, bench_flatten, bench_merge, bench_diff);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+//! 在编译时记录 commit、构建时间和目标平台，供 `i18n-app version` 输出
+
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    // 没有 .git 的构建（如从 crates.io 安装）可以通过环境变量传入 commit
+    let commit = std::env::var("I18N_APP_COMMIT").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+    println!(
+        "cargo:rustc-env=I18N_APP_COMMIT={}",
+        commit.unwrap_or_else(|| "unknown".to_string())
+    );
+
+    // 支持可重现构建：设置了 SOURCE_DATE_EPOCH 时使用它作为构建时间
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default()
+        });
+    println!("cargo:rustc-env=I18N_APP_BUILD_TIMESTAMP={}", timestamp);
+    println!(
+        "cargo:rustc-env=I18N_APP_TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+
+    // 只在 commit 变化时重新生成，避免每次编译都重新构建
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+    println!("cargo:rerun-if-env-changed=I18N_APP_COMMIT");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
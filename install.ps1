@@ -0,0 +1,29 @@
+# Windows 安装脚本：下载最新版本的 i18n-app 并加入当前用户的 PATH
+$ErrorActionPreference = "Stop"
+
+$Repo = "lexiaoyao20/i18n-app"
+$Asset = "i18n-app-windows-x86_64.exe"
+$InstallDir = Join-Path $env:LOCALAPPDATA "i18n-app\bin"
+
+if (-not [Environment]::Is64BitOperatingSystem) {
+    Write-Error "不支持的架构，目前只提供 x86_64 版本"
+}
+
+$Release = Invoke-RestMethod "https://api.github.com/repos/$Repo/releases/latest"
+$Download = $Release.assets | Where-Object { $_.name -eq $Asset } | Select-Object -First 1
+if (-not $Download) {
+    Write-Error "版本 $($Release.tag_name) 没有 $Asset"
+}
+
+New-Item -ItemType Directory -Force -Path $InstallDir | Out-Null
+$Target = Join-Path $InstallDir "i18n-app.exe"
+Write-Host "正在下载 i18n-app $($Release.tag_name)..."
+Invoke-WebRequest -Uri $Download.browser_download_url -OutFile $Target
+
+$UserPath = [Environment]::GetEnvironmentVariable("Path", "User")
+if (($UserPath -split ";") -notcontains $InstallDir) {
+    [Environment]::SetEnvironmentVariable("Path", "$UserPath;$InstallDir", "User")
+    Write-Host "已把 $InstallDir 加入 PATH，请重新打开终端"
+}
+
+Write-Host "i18n-app $($Release.tag_name) 已安装到 $Target"
@@ -1,9 +1,110 @@
-use crate::{config::Config, translation::TranslationFile};
-use anyhow::{anyhow, Result};
-use reqwest::Client;
+use crate::config::Config;
+use crate::rate_limit;
+use crate::translation::{self, Content, TranslationDelta, TranslationFile};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use futures_util::StreamExt;
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// API 调用失败的具体原因，调用方可以据此决定重试、跳过还是中止
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("Failed to send request to {url}: {source}")]
+    Network {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("Request to {url} was rejected with status {status}, please check your credentials and permissions (run `i18n-app auth status`)")]
+    Auth { url: String, status: StatusCode },
+    #[error("Request to {url} was rate limited by the server")]
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("Request to {url} failed with status {status}{}", format_server_message(*.code, .message.as_deref()))]
+    Http {
+        url: String,
+        status: StatusCode,
+        code: Option<i32>,
+        message: Option<String>,
+    },
+    #[error("Server returned error for {url}: code={code}, message={message}")]
+    Server {
+        url: String,
+        code: i32,
+        message: String,
+    },
+    #[error("Failed to parse response from {url}: {source}")]
+    Deserialization {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Failed to {operation} for {url}: {source}")]
+    Compression {
+        url: String,
+        operation: &'static str,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to save the response from {url}: {source}")]
+    Write {
+        url: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Network access is disabled in offline mode (request to {url})")]
+    Offline { url: String },
+    #[error("{backend} backend error: {message}")]
+    Backend {
+        backend: &'static str,
+        message: String,
+    },
+    #[error("Upload to {url} failed: {} of {} chunks failed ({}/{} keys uploaded)", .summary.failed_chunks, .summary.total_chunks, .summary.uploaded_keys, .summary.total_keys)]
+    UploadIncomplete { url: String, summary: UploadSummary },
+}
+
+impl ApiError {
+    /// 网络错误、限流（429）和 5xx 可以重试，其他错误重试也不会成功
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ApiError::Network { .. })
+            || self.status().is_some_and(|status| {
+                status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            })
+    }
+
+    /// 认证失败或处于离线模式时后续请求也必然失败，应中止整个操作而不是逐个语言重试
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, ApiError::Auth { .. } | ApiError::Offline { .. })
+    }
+
+    /// 错误对应的 HTTP 状态码，限流统一为 429，网络、解析等错误没有状态码
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ApiError::Auth { status, .. } | ApiError::Http { status, .. } => Some(*status),
+            ApiError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            _ => None,
+        }
+    }
+}
+
+fn format_server_message(code: Option<i32>, message: Option<&str>) -> String {
+    match (code, message) {
+        (Some(code), Some(message)) => format!(" (code={}, message={})", code, message),
+        (Some(code), None) => format!(" (code={})", code),
+        (None, Some(message)) => format!(" ({})", message),
+        (None, None) => String::new(),
+    }
+}
+
+pub type ApiResult<T> = std::result::Result<T, ApiError>;
 
 #[derive(Debug, Serialize)]
 struct ConfigRequest {
@@ -13,6 +114,9 @@ struct ConfigRequest {
     sub_system_name: Vec<String>,
     #[serde(rename = "versionNo")]
     version_no: String,
+    /// 上次同步时的 taskHash，服务器据此判断是否有变化
+    #[serde(rename = "taskHash", skip_serializing_if = "Option::is_none")]
+    task_hash: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -27,91 +131,281 @@ struct UploadRequest {
     #[serde(rename = "versionNo")]
     version_no: String,
     #[serde(rename = "termAndText")]
-    term_and_text: HashMap<String, String>,
+    term_and_text: Content,
 }
 
+/// 增量上传请求，只包含新增和修改的 key
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeltaUploadRequest {
+    sub_system_name: String,
+    product_code: String,
+    language_code: String,
+    path: String,
+    version_no: String,
+    added: Content,
+    changed: Content,
+    base_hashes: HashMap<Arc<str>, String>,
+}
+
+/// 请求头中携带的后端 API 版本，服务器在响应头中返回实际使用的版本
+pub const API_VERSION_HEADER: &str = "x-locazy-api-version";
+/// 当前 CLI 兼容的后端 API 版本，迁移期间同时支持当前版本和下一个版本
+pub const SUPPORTED_API_VERSIONS: &[&str] = &["1", "2"];
+/// long-polling 响应的 capabilities 中包含该值时，服务器支持增量上传
+pub const DELTA_UPLOAD_CAPABILITY: &str = "deltaUpload";
+
+// 以下响应结构对缺失字段使用默认值，对新版本 API 的字段名使用别名，
+// 以便后端增删或重命名字段时 CLI 不会直接解析失败。`code` 是协议的一部分，缺失时按解析失败处理
 #[derive(Debug, Deserialize, Serialize)]
 pub struct LongPollingResponse {
     pub code: i32,
+    #[serde(default, alias = "msg")]
     pub message: String,
+    #[serde(default)]
     pub data: LongPollingData,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LongPollingData {
-    #[serde(rename = "taskHash")]
-    #[allow(dead_code)]
+    /// 服务器翻译的版本标识，内容没有变化时保持不变
+    #[serde(rename = "taskHash", default)]
     pub task_hash: Option<String>,
+    #[serde(default, alias = "items")]
     pub files: Option<Vec<FileDownloadInfo>>,
+    /// 服务器支持的可选功能，如 `deltaUpload`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
+    /// 服务器端有变化的 term，可以是 key 字符串或包含修改人、时间等信息的对象
+    #[serde(
+        rename = "change_terms",
+        alias = "changeTerms",
+        default,
+        deserialize_with = "deserialize_change_terms",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub change_terms: Vec<ChangedTerm>,
+}
+
+impl LongPollingData {
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+}
+
+/// 服务器端变化的 term，除 term 外的字段服务器不一定提供
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedTerm {
+    #[serde(alias = "key", alias = "termKey")]
+    pub term: String,
+    #[serde(
+        default,
+        alias = "lang",
+        alias = "languageCode",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub language: Option<String>,
+    #[serde(
+        default,
+        alias = "subSystemName",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sub_system: Option<String>,
+    #[serde(
+        default,
+        alias = "operator",
+        alias = "updatedBy",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub changed_by: Option<String>,
+    /// 修改时间，RFC 3339 字符串或毫秒时间戳
+    #[serde(
+        default,
+        alias = "updatedAt",
+        alias = "updateTime",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub changed_at: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChangedTermRepr {
+    Term(String),
+    Detail(ChangedTerm),
+}
+
+fn deserialize_change_terms<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<ChangedTerm>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let terms = Option::<Vec<ChangedTermRepr>>::deserialize(deserializer)?;
+    Ok(terms
+        .unwrap_or_default()
+        .into_iter()
+        .map(|term| match term {
+            ChangedTermRepr::Term(term) => ChangedTerm {
+                term,
+                ..Default::default()
+            },
+            ChangedTermRepr::Detail(term) => term,
+        })
+        .collect())
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileDownloadInfo {
+    #[serde(default, alias = "subSystemName")]
     pub sub_system: String,
+    #[serde(default, alias = "languageCode")]
     pub lang: String,
-    #[serde(rename = "internalUrl")]
+    #[serde(rename = "internalUrl", default)]
     pub internal_url: String,
+    #[serde(default, alias = "downloadUrl")]
     pub url: String,
 }
 
-pub async fn upload_translation(config: &Config, translation: &TranslationFile) -> Result<()> {
-    let client = Client::new();
-    let url = format!("{}/api/At.Locazy/cli/terms/upload", config.host);
+#[derive(Debug, Deserialize)]
+struct IdentityResponse {
+    code: i32,
+    #[serde(default, alias = "msg")]
+    message: String,
+    #[serde(default)]
+    data: Identity,
+}
 
-    // 获取父目录路径
-    let parent_path = Path::new(&translation.relative_path)
-        .parent()
-        .and_then(|p| p.to_str())
-        .unwrap_or("");
-    let path = format!("{}/{}", config.path_prefix, parent_path);
-    let request = UploadRequest {
-        sub_system_name: config.sub_system_name.clone(),
-        version_no: config.version_no.clone(),
-        term_and_text: translation.content.clone(),
-        product_code: config.product_code.clone(),
-        path,
-        language_code: translation.language_code.clone(),
-    };
+/// 当前凭据对应的用户和权限
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Identity {
+    #[serde(
+        default,
+        alias = "userName",
+        alias = "username",
+        alias = "name",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, alias = "roles", skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+    /// 有权限访问的子系统，为空表示服务器没有返回
+    #[serde(
+        default,
+        alias = "subSystemNames",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub sub_systems: Vec<String>,
+}
 
-    // 添加更详细的上传信息日志
-    tracing::info!(
-        "Uploading {} keys for language: {}, path: {}",
-        translation.content.len(),
-        translation.language_code,
-        translation.relative_path
-    );
-    tracing::info!(
-        "Keys to upload: {:?}",
-        translation.content.keys().collect::<Vec<_>>()
-    );
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    code: i32,
+    message: Option<String>,
+    data: Option<String>,
+}
 
-    // 在 debug 模式下打印具体要上传的内容
-    #[cfg(debug_assertions)]
-    {
-        tracing::debug!(
-            "Upload request content: {}",
-            serde_json::to_string_pretty(&request)?
-        );
-    }
+const UPLOAD_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RATE_LIMITED_RETRIES: u32 = 5;
 
-    let response = match client.post(&url).json(&request).send().await {
-        Ok(resp) => resp,
-        Err(e) => {
-            tracing::error!("Failed to send request to [{}]: {}", url, e);
-            return Err(anyhow!("Failed to send request to {}: {}", url, e));
+/// `url` 与配置的 host 的协议、主机和端口是否都相同
+fn is_api_host(config: &Config, url: &str) -> bool {
+    match (reqwest::Url::parse(&config.host), reqwest::Url::parse(url)) {
+        (Ok(host), Ok(url)) => {
+            host.scheme() == url.scheme()
+                && host.host_str() == url.host_str()
+                && host.port_or_known_default() == url.port_or_known_default()
         }
+        _ => false,
+    }
+}
+
+/// 发送请求前按配置限流，遇到 429 时按 Retry-After 等待后重试
+pub(crate) async fn send_politely<F>(config: &Config, url: &str, build: F) -> ApiResult<Response>
+where
+    F: Fn() -> RequestBuilder,
+{
+    if config.offline {
+        return Err(ApiError::Offline {
+            url: url.to_string(),
+        });
+    }
+
+    // 服务器返回的下载链接可能指向 CDN 或预签名地址，token 只发给配置的 host
+    let token = if is_api_host(config, url) {
+        config.api_token()
+    } else {
+        None
     };
+    let mut attempt = 0;
+    loop {
+        rate_limit::acquire(config.requests_per_second).await;
+        let mut request = build().header(API_VERSION_HEADER, &config.api_version);
+        if let Some(token) = &token {
+            request = request.bearer_auth(token);
+        }
+        if config.compression {
+            request = request.header(ACCEPT_ENCODING, GZIP_ENCODING);
+        }
+        let response = request.send().await.map_err(|source| {
+            tracing::error!("Failed to send request to [{}]: {}", url, source);
+            ApiError::Network {
+                url: url.to_string(),
+                source,
+            }
+        })?;
+        if response.status() != StatusCode::TOO_MANY_REQUESTS {
+            return Ok(response);
+        }
+
+        let wait = rate_limit::parse_retry_after(
+            response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok()),
+        );
+        if attempt >= MAX_RATE_LIMITED_RETRIES {
+            return Err(ApiError::RateLimited {
+                url: url.to_string(),
+                retry_after: Some(wait),
+            });
+        }
+
+        attempt += 1;
+        tracing::warn!(
+            "Rate limited by server, retrying in {:?} ({}/{})",
+            wait,
+            attempt,
+            MAX_RATE_LIMITED_RETRIES
+        );
+        tokio::time::sleep(wait).await;
+    }
+}
 
+/// 读取响应内容，并把非 2xx 状态转换为对应的 `ApiError`
+pub(crate) async fn read_response(url: &str, response: Response) -> ApiResult<String> {
+    check_api_version(url, &response);
     let status = response.status();
-    let text = match response.text().await {
-        Ok(t) => t,
-        Err(e) => {
-            tracing::error!("Failed to read response from [{}]: {}", url, e);
-            return Err(anyhow!("Failed to read response from {}: {}", url, e));
+    let gzipped = is_gzipped(&response);
+    let bytes = response.bytes().await.map_err(|source| {
+        tracing::error!("Failed to read response from [{}]: {}", url, source);
+        ApiError::Network {
+            url: url.to_string(),
+            source,
         }
+    })?;
+    let bytes = if gzipped {
+        gunzip(&bytes).map_err(|source| decompress_error(url.to_string(), source))?
+    } else {
+        bytes.to_vec()
     };
+    let text = String::from_utf8_lossy(&bytes).into_owned();
 
     #[cfg(debug_assertions)]
     {
@@ -119,8 +413,13 @@ pub async fn upload_translation(config: &Config, translation: &TranslationFile)
         tracing::debug!("Response body: {}", text);
     }
 
-    if !status.is_success() {
-        if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&text) {
+    if status.is_success() {
+        return Ok(text);
+    }
+
+    let error_response = serde_json::from_str::<ErrorResponse>(&text).ok();
+    match &error_response {
+        Some(error_response) => {
             tracing::error!(
                 "API request failed [{}]: status={}, code={}, message={}",
                 url,
@@ -128,31 +427,336 @@ pub async fn upload_translation(config: &Config, translation: &TranslationFile)
                 error_response.code,
                 error_response.message.as_deref().unwrap_or("No message")
             );
-            if let Some(data) = error_response.data {
-                if let Some(first_line) = data.lines().next() {
-                    tracing::error!("Error details: {}", first_line);
-                }
+            if let Some(first_line) = error_response
+                .data
+                .as_deref()
+                .and_then(|data| data.lines().next())
+            {
+                tracing::error!("Error details: {}", first_line);
+            }
+        }
+        None => tracing::error!(
+            "API request failed [{}]: status={}, response={}",
+            url,
+            status,
+            text
+        ),
+    }
+
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(ApiError::Auth {
+            url: url.to_string(),
+            status,
+        });
+    }
+
+    Err(ApiError::Http {
+        url: url.to_string(),
+        status,
+        code: error_response.as_ref().map(|e| e.code),
+        message: error_response.and_then(|e| e.message),
+    })
+}
+
+const GZIP_ENCODING: &str = "gzip";
+
+fn is_gzipped(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(GZIP_ENCODING.as_bytes()))
+}
+
+fn gzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+fn gunzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+/// 服务器返回了 CLI 不认识的 API 版本时给出提示，但仍尝试按兼容方式解析
+fn check_api_version(url: &str, response: &Response) {
+    if let Some(version) = response
+        .headers()
+        .get(API_VERSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        if !SUPPORTED_API_VERSIONS.contains(&version) {
+            tracing::warn!(
+                "Server [{}] responded with API version {}, supported versions: {:?}. Please run 'i18n-app update'",
+                url,
+                version,
+                SUPPORTED_API_VERSIONS
+            );
+        }
+    }
+}
+
+/// 分片上传的汇总结果
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UploadSummary {
+    pub total_keys: usize,
+    pub uploaded_keys: usize,
+    pub total_chunks: usize,
+    pub failed_chunks: usize,
+}
+
+pub async fn upload_translation(
+    config: &Config,
+    translation: &TranslationFile,
+) -> ApiResult<UploadSummary> {
+    let client = Client::new();
+    let url = format!("{}/api/At.Locazy/cli/terms/upload", config.host);
+    let path = upload_path(config, &translation.relative_path);
+
+    // 添加更详细的上传信息日志
+    tracing::info!(
+        "Uploading {} keys for language: {}, path: {}",
+        translation.content.len(),
+        translation.language_code,
+        translation.relative_path
+    );
+
+    let chunks = split_into_chunks(&translation.content, config.upload_chunk_size);
+    let mut summary = UploadSummary {
+        total_keys: translation.content.len(),
+        total_chunks: chunks.len(),
+        ..Default::default()
+    };
+
+    let mut last_error = None;
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let chunk_len = chunk.len();
+        tracing::info!(
+            "Uploading chunk {}/{} ({} keys) for language: {}",
+            index + 1,
+            summary.total_chunks,
+            chunk_len,
+            translation.language_code
+        );
+        tracing::info!("Keys to upload: {:?}", chunk.keys().collect::<Vec<_>>());
+
+        let request = UploadRequest {
+            sub_system_name: config.sub_system_name().to_string(),
+            version_no: config.version_no.clone(),
+            term_and_text: chunk,
+            product_code: config.product_code.clone(),
+            path: path.clone(),
+            language_code: translation.language_code.clone(),
+        };
+
+        match upload_chunk_with_retry(&client, config, &url, &request).await {
+            Ok(()) => summary.uploaded_keys += chunk_len,
+            // 认证失败时其余分片也不可能成功
+            Err(e) if e.is_fatal() => return Err(e),
+            Err(e) => {
+                tracing::error!(
+                    "Chunk {}/{} for language {} failed: {}",
+                    index + 1,
+                    summary.total_chunks,
+                    translation.language_code,
+                    e
+                );
+                summary.failed_chunks += 1;
+                last_error = Some(e);
             }
         }
-        return Err(anyhow!("Upload to {} failed with status {}", url, status));
     }
 
+    tracing::info!(
+        "Upload report for {}: {}/{} keys uploaded, {}/{} chunks succeeded",
+        translation.language_code,
+        summary.uploaded_keys,
+        summary.total_keys,
+        summary.total_chunks - summary.failed_chunks,
+        summary.total_chunks
+    );
+
+    if let Some(e) = last_error {
+        // 全部分片都失败时直接返回原始错误，部分成功时返回汇总
+        if summary.uploaded_keys == 0 {
+            return Err(e);
+        }
+        return Err(ApiError::UploadIncomplete { url, summary });
+    }
+
+    Ok(summary)
+}
+
+/// 上传请求中的 path：使用第一条匹配文件的 uploadPaths 规则，没有匹配时是 pathPrefix 加上文件的父目录
+fn upload_path(config: &Config, relative_path: &str) -> String {
+    let relative_path = relative_path.replace('\\', "/");
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    let rule = config.upload_paths.iter().find(|rule| {
+        glob::Pattern::new(&translation::template_glob(
+            rule.pattern.trim_start_matches("./"),
+        ))
+        .is_ok_and(|pattern| pattern.matches_with(&relative_path, options))
+    });
+    if let Some(rule) = rule {
+        return rule.path.clone();
+    }
+
+    let parent_path = Path::new(&relative_path)
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or("");
+    format!("{}/{}", config.path_prefix, parent_path)
+}
+
+/// 增量上传：只发送新增和修改的 key 及修改前的值的哈希，内容较小，不分片
+pub async fn upload_translation_delta(
+    config: &Config,
+    delta: &TranslationDelta,
+) -> ApiResult<UploadSummary> {
+    let client = Client::new();
+    let url = format!("{}/api/At.Locazy/cli/terms/upload-delta", config.host);
+
+    tracing::info!(
+        "Uploading delta for language: {} ({} added, {} changed), path: {}",
+        delta.language_code,
+        delta.added.len(),
+        delta.changed.len(),
+        delta.relative_path
+    );
+
+    let request = DeltaUploadRequest {
+        sub_system_name: config.sub_system_name().to_string(),
+        product_code: config.product_code.clone(),
+        language_code: delta.language_code.clone(),
+        path: upload_path(config, &delta.relative_path),
+        version_no: config.version_no.clone(),
+        added: delta.added.clone(),
+        changed: delta.changed.clone(),
+        base_hashes: delta.base_hashes.clone(),
+    };
+    upload_chunk_with_retry(&client, config, &url, &request).await?;
+
+    Ok(UploadSummary {
+        total_keys: delta.key_count(),
+        uploaded_keys: delta.key_count(),
+        total_chunks: 1,
+        failed_chunks: 0,
+    })
+}
+
+/// 按 key 排序后切分成若干分片，保证分片结果稳定
+fn split_into_chunks(content: &Content, chunk_size: usize) -> Vec<Content> {
+    if content.is_empty() {
+        return vec![Content::new()];
+    }
+
+    let mut entries: Vec<_> = content.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let chunk_size = if chunk_size == 0 {
+        entries.len()
+    } else {
+        chunk_size
+    };
+
+    entries
+        .chunks(chunk_size)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|(k, v)| ((*k).clone(), (*v).clone()))
+                .collect()
+        })
+        .collect()
+}
+
+async fn upload_chunk_with_retry<T: Serialize>(
+    client: &Client,
+    config: &Config,
+    url: &str,
+    request: &T,
+) -> ApiResult<()> {
+    let max_retries = config.upload_retries;
+    let mut attempt = 0;
+    loop {
+        match send_upload_request(client, config, url, request).await {
+            Ok(()) => return Ok(()),
+            Err(e) if e.is_retryable() && attempt < max_retries => {
+                attempt += 1;
+                tracing::warn!(
+                    "Upload attempt failed, retrying ({}/{}): {}",
+                    attempt,
+                    max_retries,
+                    e
+                );
+                tokio::time::sleep(UPLOAD_RETRY_DELAY).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn send_upload_request<T: Serialize>(
+    client: &Client,
+    config: &Config,
+    url: &str,
+    request: &T,
+) -> ApiResult<()> {
+    // 在 debug 模式下打印具体要上传的内容
+    #[cfg(debug_assertions)]
+    {
+        if let Ok(body) = serde_json::to_string_pretty(request) {
+            tracing::debug!("Upload request content: {}", body);
+        }
+    }
+
+    let response = if config.compression {
+        // 翻译文本压缩率很高，慢速网络下可以明显缩短上传时间
+        let body = serde_json::to_vec(request).map_err(|e| ApiError::Backend {
+            backend: "http",
+            message: format!("Failed to serialize upload request: {}", e),
+        })?;
+        let body = gzip(&body).map_err(|source| ApiError::Compression {
+            url: url.to_string(),
+            operation: "compress request",
+            source,
+        })?;
+        send_politely(config, url, || {
+            client
+                .post(url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(CONTENT_ENCODING, GZIP_ENCODING)
+                .body(body.clone())
+        })
+        .await?
+    } else {
+        send_politely(config, url, || client.post(url).json(request)).await?
+    };
+    read_response(url, response).await?;
     Ok(())
 }
 
-pub async fn get_translation_config(config: &Config) -> Result<LongPollingResponse> {
+pub async fn get_translation_config(
+    config: &Config,
+    task_hash: Option<&str>,
+) -> ApiResult<LongPollingResponse> {
     let client = Client::new();
     let url = format!("{}/api/At.Locazy/user/i18n/long-polling", config.host);
 
     let request_body = ConfigRequest {
         product_code: config.product_code.clone(),
-        sub_system_name: vec![config.sub_system_name.clone()],
+        sub_system_name: config.sub_systems.clone(),
         version_no: config.version_no.clone(),
+        task_hash: task_hash.map(str::to_string),
     };
 
     tracing::info!(
         "Fetching translation config for system: {}, product: {}, version: {}",
-        config.sub_system_name,
+        config.sub_systems.join(","),
         config.product_code,
         config.version_no
     );
@@ -162,59 +766,41 @@ pub async fn get_translation_config(config: &Config) -> Result<LongPollingRespon
         "curl -X POST '{}' -H 'preview: {}' -H 'Content-Type: application/json' -d '{}'",
         url,
         config.preview_mode,
-        serde_json::to_string(&request_body)?
+        serde_json::to_string(&request_body).unwrap_or_default()
     );
 
-    let response = match client
-        .post(&url)
-        .header("preview", &config.preview_mode)
-        .json(&request_body)
-        .send()
-        .await
-    {
-        Ok(resp) => resp,
-        Err(e) => {
-            tracing::error!("Failed to send request to [{}]: {}", url, e);
-            return Err(anyhow!("Failed to send request to {}: {}", url, e));
-        }
-    };
-
-    let status = response.status();
-    let text = match response.text().await {
-        Ok(t) => t,
-        Err(e) => {
-            tracing::error!("Failed to read response from [{}]: {}", url, e);
-            return Err(anyhow!("Failed to read response from {}: {}", url, e));
-        }
-    };
+    let response = send_politely(config, &url, || {
+        client
+            .post(&url)
+            .header("preview", &config.preview_mode)
+            .json(&request_body)
+    })
+    .await?;
+    let text = read_response(&url, response).await?;
 
-    #[cfg(debug_assertions)]
-    {
-        tracing::debug!("Response status: {}", status);
-        tracing::debug!("Response body: {}", text);
+    let mut response: LongPollingResponse =
+        serde_json::from_str(&text).map_err(|source| ApiError::Deserialization {
+            url: url.clone(),
+            source,
+        })?;
+    if response.code != 0 {
+        return Err(ApiError::Server {
+            url,
+            code: response.code,
+            message: response.message,
+        });
     }
 
-    if !status.is_success() {
-        if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&text) {
-            tracing::error!(
-                "API request failed [{}]: status={}, code={}, message={}",
-                url,
-                status,
-                error_response.code,
-                error_response.message.as_deref().unwrap_or("No message")
-            );
-            if let Some(data) = error_response.data {
-                if let Some(first_line) = data.lines().next() {
-                    tracing::error!("Error details: {}", first_line);
-                }
+    // 缺少语言代码的条目无法使用，跳过而不是让整个请求失败
+    if let Some(files) = response.data.files.as_mut() {
+        files.retain(|file| {
+            if file.lang.is_empty() {
+                tracing::warn!("Skipping file entry without language code: {:?}", file);
+                false
+            } else {
+                true
             }
-        }
-        return Err(anyhow!("Request to {} failed with status {}", url, status));
-    }
-
-    let response: LongPollingResponse = serde_json::from_str(&text)?;
-    if response.code != 0 {
-        return Err(anyhow!("Request failed: {}", response.message));
+        });
     }
 
     tracing::info!(
@@ -225,64 +811,109 @@ pub async fn get_translation_config(config: &Config) -> Result<LongPollingRespon
     Ok(response)
 }
 
-#[derive(Debug, Deserialize)]
-struct ErrorResponse {
-    code: i32,
-    message: Option<String>,
-    data: Option<String>,
+/// 用当前凭据查询用户信息，凭据无效时返回 `ApiError::Auth`
+pub async fn whoami(config: &Config) -> ApiResult<Identity> {
+    let client = Client::new();
+    let url = format!("{}/api/At.Locazy/cli/whoami", config.host);
+
+    let response = send_politely(config, &url, || {
+        client
+            .get(&url)
+            .header("preview", &config.preview_mode)
+            .query(&[("productCode", &config.product_code)])
+    })
+    .await?;
+    let text = read_response(&url, response).await?;
+
+    let response: IdentityResponse =
+        serde_json::from_str(&text).map_err(|source| ApiError::Deserialization {
+            url: url.clone(),
+            source,
+        })?;
+    if response.code != 0 {
+        return Err(ApiError::Server {
+            url,
+            code: response.code,
+            message: response.message,
+        });
+    }
+    Ok(response.data)
 }
 
-pub async fn download_translation(config: &Config, download_url: &str) -> Result<String> {
+pub async fn download_translation(config: &Config, download_url: &str) -> ApiResult<String> {
+    let mut body = Vec::new();
+    download_translation_to(config, download_url, &mut body).await?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// 下载翻译文件，边下载边（按需解压后）写入 `out`，大文件不需要完整读入内存
+pub async fn download_translation_to(
+    config: &Config,
+    download_url: &str,
+    out: &mut (dyn Write + Send),
+) -> ApiResult<()> {
     let client = Client::new();
     let url = download_url;
 
     tracing::info!("Downloading translation from: {}", url);
 
-    let response = match client
-        .get(url)
-        .header("preview", &config.preview_mode)
-        .send()
-        .await
-    {
-        Ok(resp) => resp,
-        Err(e) => {
-            tracing::error!("Failed to send request to [{}]: {}", url, e);
-            return Err(anyhow!("Failed to send request to {}: {}", url, e));
-        }
-    };
-
-    let status = response.status();
-    let text = match response.text().await {
-        Ok(t) => t,
-        Err(e) => {
-            tracing::error!("Failed to read response from [{}]: {}", url, e);
-            return Err(anyhow!("Failed to read response from {}: {}", url, e));
-        }
-    };
-
-    tracing::debug!("Response status: {}", status);
+    let response = send_politely(config, url, || {
+        client.get(url).header("preview", &config.preview_mode)
+    })
+    .await?;
+    if !response.status().is_success() {
+        // 错误响应很小，读完后转换为对应的错误
+        return read_response(url, response).await.map(|_| ());
+    }
+    check_api_version(url, &response);
 
-    if !status.is_success() {
-        tracing::error!(
-            "API request failed [{}]: status={}, response={}",
-            url,
-            status,
-            text
-        );
-        return Err(anyhow!(
-            "Download from {} failed with status {}",
+    if is_gzipped(&response) {
+        let mut decoder = flate2::write::GzDecoder::new(out);
+        write_body(url, response, &mut decoder, decompress_error).await?;
+        decoder
+            .finish()
+            .map_err(|source| decompress_error(url.to_string(), source))?;
+    } else {
+        write_body(url, response, out, |url, source| ApiError::Write {
             url,
-            status
-        ));
+            source,
+        })
+        .await?;
     }
+    Ok(())
+}
 
-    // 直接返回原始文本内容
-    Ok(text)
+fn decompress_error(url: String, source: std::io::Error) -> ApiError {
+    ApiError::Compression {
+        url,
+        operation: "decompress response",
+        source,
+    }
+}
+
+/// 把响应体逐块写入 `out`，写入失败时用 `error` 转换为对应的错误
+async fn write_body(
+    url: &str,
+    response: Response,
+    out: &mut (dyn Write + Send),
+    error: fn(String, std::io::Error) -> ApiError,
+) -> ApiResult<()> {
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|source| ApiError::Network {
+            url: url.to_string(),
+            source,
+        })?;
+        out.write_all(&chunk)
+            .map_err(|source| error(url.to_string(), source))?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anyhow::Result;
     use mockito::Server;
     use std::fs::File;
     use std::io::Write;
@@ -313,6 +944,36 @@ mod tests {
         Ok((temp_dir, config))
     }
 
+    #[test]
+    fn test_upload_path() -> Result<()> {
+        let (_temp_dir, mut config) = create_test_config("https://test.com")?;
+        assert_eq!(
+            upload_path(&config, "locales/web/en-US.json"),
+            "app/locales/web"
+        );
+
+        config.upload_paths = vec![
+            crate::config::UploadPathRule {
+                pattern: "locales/web/*.json".to_string(),
+                path: "frontend/main".to_string(),
+            },
+            crate::config::UploadPathRule {
+                pattern: "packages/*/{lang}.json".to_string(),
+                path: "shared".to_string(),
+            },
+        ];
+        assert_eq!(
+            upload_path(&config, "locales/web/en-US.json"),
+            "frontend/main"
+        );
+        assert_eq!(upload_path(&config, "packages/ui/en-US.json"), "shared");
+        assert_eq!(
+            upload_path(&config, "packages/ui/deep/en-US.json"),
+            "app/packages/ui/deep"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_upload_translation_success() -> Result<()> {
         let mut server = Server::new();
@@ -320,13 +981,13 @@ mod tests {
 
         rt.block_on(async {
             let (_temp_dir, config) = create_test_config(&server.url())?;
-            let mut content = HashMap::new();
-            content.insert("test.key".to_string(), "test value".to_string());
+            let content = translation::to_content([("test.key", "test value")]);
 
             let translation = TranslationFile {
                 language_code: "en-US".to_string(),
                 relative_path: "en-US.json".to_string(),
                 content,
+                ..Default::default()
             };
 
             let mock = server
@@ -344,6 +1005,47 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_upload_translation_delta() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, config) = create_test_config(&server.url())?;
+            let upload = TranslationFile::from_content(
+                "en-US".to_string(),
+                "en-US.json".to_string(),
+                HashMap::from([
+                    ("new.key".to_string(), "New".to_string()),
+                    ("old.key".to_string(), "Changed".to_string()),
+                ]),
+            );
+            let remote = TranslationFile::from_content(
+                "en-US".to_string(),
+                "en-US.json".to_string(),
+                HashMap::from([("old.key".to_string(), "Old".to_string())]),
+            );
+            let delta = TranslationDelta::new(&upload, &remote);
+
+            let mock = server
+                .mock("POST", "/api/At.Locazy/cli/terms/upload-delta")
+                .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                    "languageCode": "en-US",
+                    "added": {"new.key": "New"},
+                    "changed": {"old.key": "Changed"},
+                    "baseHashes": {"old.key": crate::translation::text_hash("Old")},
+                })))
+                .with_status(200)
+                .with_body(r#"{"code":0,"message":"success"}"#)
+                .create();
+
+            let summary = upload_translation_delta(&config, &delta).await?;
+            assert_eq!(summary.uploaded_keys, 2);
+            mock.assert();
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_upload_translation_failure() -> Result<()> {
         let mut server = Server::new();
@@ -351,13 +1053,13 @@ mod tests {
 
         rt.block_on(async {
             let (_temp_dir, config) = create_test_config(&server.url())?;
-            let mut content = HashMap::new();
-            content.insert("test.key".to_string(), "test value".to_string());
+            let content = translation::to_content([("test.key", "test value")]);
 
             let translation = TranslationFile {
                 language_code: "en-US".to_string(),
                 relative_path: "en-US.json".to_string(),
                 content,
+                ..Default::default()
             };
 
             let mock = server
@@ -367,6 +1069,220 @@ mod tests {
                 .with_body(r#"{"code":400,"message":"Bad Request","data":null}"#)
                 .create();
 
+            let result = upload_translation(&config, &translation).await;
+            match result {
+                Err(ApiError::Http { status, code, .. }) => {
+                    assert_eq!(status, StatusCode::BAD_REQUEST);
+                    assert_eq!(code, Some(400));
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+
+            mock.assert();
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_upload_translation_auth_failure_is_fatal() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, mut config) = create_test_config(&server.url())?;
+            config.upload_chunk_size = 1;
+            let content = translation::to_content(
+                (0..3).map(|i| (format!("key{}", i), format!("value{}", i))),
+            );
+
+            let translation = TranslationFile {
+                language_code: "en-US".to_string(),
+                relative_path: "en-US.json".to_string(),
+                content,
+                ..Default::default()
+            };
+
+            // 第一个分片认证失败后不应继续发送后续分片
+            let mock = server
+                .mock("POST", "/api/At.Locazy/cli/terms/upload")
+                .with_status(403)
+                .expect(1)
+                .create();
+
+            let err = upload_translation(&config, &translation)
+                .await
+                .expect_err("upload should fail");
+            assert!(err.is_fatal());
+            assert!(!err.is_retryable());
+            assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+
+            mock.assert();
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_get_translation_config_server_error_code() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, config) = create_test_config(&server.url())?;
+
+            let mock = server
+                .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+                .with_status(200)
+                .with_body(r#"{"code":1001,"message":"version not found","data":{}}"#)
+                .create();
+
+            match get_translation_config(&config, None).await {
+                Err(ApiError::Server { code, message, .. }) => {
+                    assert_eq!(code, 1001);
+                    assert_eq!(message, "version not found");
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+            mock.assert();
+
+            // 缺少 code 的响应不是成功响应
+            server.reset();
+            server
+                .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+                .with_body(r#"{"message":"ok","data":{"files":[]}}"#)
+                .create();
+            assert!(matches!(
+                get_translation_config(&config, None).await,
+                Err(ApiError::Deserialization { .. })
+            ));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_api_token_only_sent_to_api_host() -> Result<()> {
+        let mut api = Server::new();
+        let mut cdn = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, config) = create_test_config(&api.url())?;
+            crate::credentials::set_for_test(
+                crate::credentials::Credential::Api {
+                    host: config.host.clone(),
+                },
+                "secret-token",
+            );
+            let polling = api
+                .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+                .match_header("authorization", "Bearer secret-token")
+                .with_body(r#"{"code":0,"data":{}}"#)
+                .create();
+            // 服务器返回的下载链接指向其他 host 时不带 token
+            let download = cdn
+                .mock("GET", "/de.json")
+                .match_header("authorization", mockito::Matcher::Missing)
+                .with_body(r#"{"app/languages": {}}"#)
+                .create();
+
+            get_translation_config(&config, None).await?;
+            download_translation(&config, &format!("{}/de.json", cdn.url())).await?;
+            polling.assert();
+            download.assert();
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_offline_mode_rejects_requests() -> Result<()> {
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            // 使用不可达地址，确保离线模式下根本不会发出请求
+            let (_temp_dir, mut config) = create_test_config("http://127.0.0.1:9")?;
+            config.offline = true;
+
+            let err = get_translation_config(&config, None)
+                .await
+                .expect_err("offline request should fail");
+            assert!(matches!(err, ApiError::Offline { .. }));
+            assert!(err.is_fatal());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_split_into_chunks() {
+        let content =
+            translation::to_content((0..5).map(|i| (format!("key{}", i), format!("value{}", i))));
+
+        let chunks = split_into_chunks(&content, 2);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.iter().map(|c| c.len()).sum::<usize>(), 5);
+        assert!(chunks[0].contains_key("key0") && chunks[0].contains_key("key1"));
+
+        // 0 表示不分片
+        assert_eq!(split_into_chunks(&content, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_upload_translation_in_chunks() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, mut config) = create_test_config(&server.url())?;
+            config.upload_chunk_size = 2;
+            let content = translation::to_content(
+                (0..5).map(|i| (format!("key{}", i), format!("value{}", i))),
+            );
+
+            let translation = TranslationFile {
+                language_code: "en-US".to_string(),
+                relative_path: "en-US.json".to_string(),
+                content,
+                ..Default::default()
+            };
+
+            let mock = server
+                .mock("POST", "/api/At.Locazy/cli/terms/upload")
+                .with_status(200)
+                .with_body(r#"{"code":0,"message":"success"}"#)
+                .expect(3)
+                .create();
+
+            let summary = upload_translation(&config, &translation).await?;
+            assert_eq!(summary.total_chunks, 3);
+            assert_eq!(summary.uploaded_keys, 5);
+            assert_eq!(summary.failed_chunks, 0);
+
+            mock.assert();
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_upload_translation_retries_server_error() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, mut config) = create_test_config(&server.url())?;
+            config.upload_retries = 1;
+            let content = translation::to_content([("test.key", "test value")]);
+
+            let translation = TranslationFile {
+                language_code: "en-US".to_string(),
+                relative_path: "en-US.json".to_string(),
+                content,
+                ..Default::default()
+            };
+
+            let mock = server
+                .mock("POST", "/api/At.Locazy/cli/terms/upload")
+                .with_status(502)
+                .expect(2)
+                .create();
+
             let result = upload_translation(&config, &translation).await;
             assert!(result.is_err());
 
@@ -375,6 +1291,36 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_download_translation_honors_retry_after() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, config) = create_test_config(&server.url())?;
+
+            let throttled = server
+                .mock("GET", "/download/en-US.json")
+                .with_status(429)
+                .with_header("retry-after", "0")
+                .expect(1)
+                .create();
+            let mock = server
+                .mock("GET", "/download/en-US.json")
+                .with_status(200)
+                .with_body("{}")
+                .create();
+
+            let url = format!("{}/download/en-US.json", server.url());
+            let content = download_translation(&config, &url).await?;
+            assert_eq!(content, "{}");
+
+            throttled.assert();
+            mock.assert();
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_get_translation_config_success() -> Result<()> {
         let mut server = Server::new();
@@ -406,13 +1352,14 @@ mod tests {
                 )
                 .create();
 
-            let result = get_translation_config(&config).await;
+            let result = get_translation_config(&config, None).await;
             assert!(result.is_ok());
             if let Ok(res) = result {
                 assert_eq!(res.data.files.as_ref().unwrap().len(), 1);
                 let file_info = &res.data.files.as_ref().unwrap()[0];
                 assert_eq!(file_info.lang, "en-US");
                 assert_eq!(file_info.url, "http://public.url/test.json");
+                assert!(!res.data.supports(DELTA_UPLOAD_CAPABILITY));
             }
 
             mock.assert();
@@ -420,6 +1367,134 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_get_translation_config_sends_task_hash() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, config) = create_test_config(&server.url())?;
+
+            let mock = server
+                .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+                .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                    "taskHash": "hash-1"
+                })))
+                .with_status(200)
+                .with_body(r#"{"code":0,"data":{"taskHash":"hash-1"}}"#)
+                .create();
+
+            let res = get_translation_config(&config, Some("hash-1")).await?;
+            assert_eq!(res.data.task_hash.as_deref(), Some("hash-1"));
+
+            mock.assert();
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_get_translation_config_tolerates_schema_changes() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, config) = create_test_config(&server.url())?;
+
+            // 新版本字段名、缺失字段和未知字段都不应导致解析失败
+            let mock = server
+                .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+                .match_header(API_VERSION_HEADER, "1")
+                .with_status(200)
+                .with_header(API_VERSION_HEADER, "3")
+                .with_body(
+                    r#"{
+                    "code": 0,
+                    "data": {
+                        "change_terms": [
+                            "home.title",
+                            {"key": "home.body", "languageCode": "zh-CN", "operator": "alice", "updatedAt": 1704067200000}
+                        ],
+                        "items": [
+                            {
+                                "languageCode": "zh-CN",
+                                "downloadUrl": "http://public.url/zh-CN.json",
+                                "checksum": "abc"
+                            },
+                            { "url": "http://public.url/unknown.json" }
+                        ],
+                        "newField": true
+                    }
+                }"#,
+                )
+                .create();
+
+            let res = get_translation_config(&config, None).await?;
+            let files = res.data.files.as_ref().unwrap();
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].lang, "zh-CN");
+            assert_eq!(files[0].url, "http://public.url/zh-CN.json");
+            assert!(files[0].internal_url.is_empty());
+            assert_eq!(res.data.change_terms.len(), 2);
+            assert_eq!(res.data.change_terms[0].term, "home.title");
+            assert_eq!(
+                res.data.change_terms[1].changed_by.as_deref(),
+                Some("alice")
+            );
+
+            mock.assert();
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_compression() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, mut config) = create_test_config(&server.url())?;
+            config.compression = true;
+
+            let upload_mock = server
+                .mock("POST", "/api/At.Locazy/cli/terms/upload")
+                .match_header("content-encoding", "gzip")
+                .match_header("accept-encoding", "gzip")
+                .with_status(200)
+                .with_body(r#"{"code":0,"message":"success"}"#)
+                .create();
+            let translation = TranslationFile::from_content(
+                "en-US".to_string(),
+                "en-US.json".to_string(),
+                HashMap::from([("test.key".to_string(), "test value".to_string())]),
+            );
+            upload_translation(&config, &translation).await?;
+            upload_mock.assert();
+
+            let download_mock = server
+                .mock("GET", "/download/en-US.json")
+                .with_status(200)
+                .with_header("content-encoding", "gzip")
+                .with_body(gzip(br#"{"app/languages":{"a":"A"}}"#)?)
+                .create();
+            let url = format!("{}/download/en-US.json", server.url());
+            let text = download_translation(&config, &url).await?;
+            assert_eq!(text, r#"{"app/languages":{"a":"A"}}"#);
+            download_mock.assert();
+
+            // 声明了 gzip 但内容无法解压
+            server
+                .mock("GET", "/download/de-DE.json")
+                .with_status(200)
+                .with_header("content-encoding", "gzip")
+                .with_body("not gzip")
+                .create();
+            let url = format!("{}/download/de-DE.json", server.url());
+            let err = download_translation(&config, &url).await.unwrap_err();
+            assert!(matches!(err, ApiError::Compression { .. }), "{:?}", err);
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_download_translation_success() -> Result<()> {
         let mut server = Server::new();
@@ -461,4 +1536,32 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_whoami() -> Result<()> {
+        let mut server = Server::new();
+        let rt = tokio::runtime::Runtime::new()?;
+
+        rt.block_on(async {
+            let (_temp_dir, config) = create_test_config(&server.url())?;
+            let mock = server
+                .mock("GET", "/api/At.Locazy/cli/whoami?productCode=test")
+                .with_status(200)
+                .with_body(r#"{"code":0,"data":{"userName":"alice","email":"alice@example.com","roles":["upload"],"subSystemNames":["test-system"]}}"#)
+                .create();
+            let identity = whoami(&config).await?;
+            mock.assert();
+            assert_eq!(identity.user.as_deref(), Some("alice"));
+            assert_eq!(identity.permissions, vec!["upload"]);
+            assert_eq!(identity.sub_systems, vec!["test-system"]);
+
+            server
+                .mock("GET", "/api/At.Locazy/cli/whoami?productCode=test")
+                .with_status(403)
+                .create();
+            let error = whoami(&config).await.err();
+            assert!(matches!(error, Some(ApiError::Auth { .. })));
+            Ok(())
+        })
+    }
 }
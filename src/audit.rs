@@ -0,0 +1,283 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::cli::AuditFormat;
+use crate::config::Config;
+use crate::output;
+use crate::translation::{self, TranslationFile};
+use crate::vfs::{FileSystem, RealFs};
+use crate::workspace;
+
+/// 上传审计日志，只追加不修改，clean 命令不会清理
+pub fn audit_file() -> PathBuf {
+    workspace::work_dir().join("audit.log")
+}
+
+/// 一次成功上传的记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub user: String,
+    pub sub_system: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    pub host: String,
+    pub language: String,
+    pub keys: Vec<String>,
+    /// 上传的 key 和值的 SHA-256，用于核对上传内容
+    pub values_hash: String,
+}
+
+impl AuditEntry {
+    pub fn new(config: &Config, translation: &TranslationFile) -> Self {
+        let mut keys: Vec<String> = translation.content.keys().map(|k| k.to_string()).collect();
+        keys.sort();
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            user: current_user().to_string(),
+            sub_system: config.sub_system_name().to_string(),
+            environment: config.environment.clone(),
+            host: config.host.clone(),
+            language: translation.language_code.clone(),
+            values_hash: values_hash(translation, &keys),
+            keys,
+        }
+    }
+
+    /// 记录时间不早于 `since` 当天
+    fn is_since(&self, since: NaiveDate) -> bool {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|time| time.date_naive() >= since)
+            .unwrap_or(false)
+    }
+}
+
+/// 按 key 排序后计算 `key\0value\n` 序列的哈希，与 HashMap 的顺序无关
+fn values_hash(translation: &TranslationFile, sorted_keys: &[String]) -> String {
+    let text: String = sorted_keys
+        .iter()
+        .map(|key| format!("{}\0{}\n", key, translation.content[key.as_str()]))
+        .collect();
+    translation::text_hash(&text)
+}
+
+/// 执行上传的用户：I18N_APP_USER 环境变量、git 的 user.email、系统用户名，依次查找
+fn current_user() -> &'static str {
+    static USER: OnceLock<String> = OnceLock::new();
+    USER.get_or_init(|| {
+        std::env::var("I18N_APP_USER")
+            .ok()
+            .or_else(git_user)
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .filter(|user| !user.trim().is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    })
+}
+
+fn git_user() -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "user.email"])
+        .output()
+        .ok()?;
+    let email = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !email.is_empty()).then_some(email)
+}
+
+/// 追加一条记录，写入失败不影响上传结果
+pub fn record(fs: &dyn FileSystem, config: &Config, translation: &TranslationFile) {
+    let entry = AuditEntry::new(config, translation);
+    if let Err(e) = append_entry(fs, &audit_file(), &entry) {
+        tracing::warn!("Failed to write audit log: {}", e);
+    }
+}
+
+pub fn append_entry(fs: &dyn FileSystem, path: &Path, entry: &AuditEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs.create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(entry)? + "\n";
+    fs.append(path, line.as_bytes())?;
+    Ok(())
+}
+
+/// 读取审计记录，跳过无法解析的行
+pub fn read_entries(fs: &dyn FileSystem, path: &Path) -> Result<Vec<AuditEntry>> {
+    if !fs.exists(path) {
+        return Ok(Vec::new());
+    }
+
+    let content = fs
+        .read_to_string(path)
+        .with_context(|| format!("读取审计日志 {} 失败", path.display()))?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("Skipping invalid audit line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// 每种语言最近一次上传了 `key` 的记录，按语言排序。日志按时间顺序追加，后面的记录覆盖前面的
+pub fn last_pushes<'a>(
+    entries: &'a [AuditEntry],
+    sub_system: &str,
+    key: &str,
+) -> Vec<&'a AuditEntry> {
+    let mut latest: BTreeMap<&str, &AuditEntry> = BTreeMap::new();
+    for entry in entries {
+        if entry.sub_system == sub_system && entry.keys.iter().any(|k| k == key) {
+            latest.insert(&entry.language, entry);
+        }
+    }
+    latest.into_values().collect()
+}
+
+/// CSV 字段，包含逗号、引号或换行时加引号
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(entries: &[AuditEntry]) -> String {
+    let mut csv =
+        String::from("timestamp,user,subSystem,environment,host,language,keys,valuesHash\n");
+    for entry in entries {
+        let fields = [
+            entry.timestamp.as_str(),
+            entry.user.as_str(),
+            entry.sub_system.as_str(),
+            entry.environment.as_deref().unwrap_or(""),
+            entry.host.as_str(),
+            entry.language.as_str(),
+            &entry.keys.join(" "),
+            entry.values_hash.as_str(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// 输出 `since` 当天及之后的上传记录
+pub fn show_audit(since: Option<NaiveDate>, format: AuditFormat) -> Result<()> {
+    let entries: Vec<AuditEntry> = read_entries(&RealFs, &audit_file())?
+        .into_iter()
+        .filter(|entry| since.is_none_or(|since| entry.is_since(since)))
+        .collect();
+
+    match format {
+        AuditFormat::Csv => print!("{}", to_csv(&entries)),
+        AuditFormat::Json => {
+            for entry in &entries {
+                println!("{}", serde_json::to_string(entry)?);
+            }
+        }
+        AuditFormat::Table => {
+            if entries.is_empty() {
+                tracing::info!("暂无上传记录");
+                return Ok(());
+            }
+            let rows = entries.iter().map(|entry| {
+                vec![
+                    entry.timestamp.clone(),
+                    entry.user.clone(),
+                    entry.sub_system.clone(),
+                    entry.environment.clone().unwrap_or_default(),
+                    entry.language.clone(),
+                    entry.keys.len().to_string(),
+                    entry.values_hash.chars().take(12).collect(),
+                ]
+            });
+            output::table(
+                [
+                    "Time",
+                    "User",
+                    "Sub System",
+                    "Environment",
+                    "Language",
+                    "Keys",
+                    "Hash",
+                ],
+                rows,
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_audit_entries() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(".i18n-app").join("audit.log");
+
+        let translation = TranslationFile::from_content(
+            "zh-CN".to_string(),
+            "zh-CN.json".to_string(),
+            HashMap::from([
+                ("home.title".to_string(), "首页".to_string()),
+                ("home.body".to_string(), "你好, \"世界\"".to_string()),
+            ]),
+        );
+        let config = Config {
+            environment: Some("prod".to_string()),
+            ..Default::default()
+        };
+        let mut entry = AuditEntry::new(&config, &translation);
+        assert_eq!(entry.keys, vec!["home.body", "home.title"]);
+        assert_eq!(entry.environment.as_deref(), Some("prod"));
+
+        entry.timestamp = "2024-01-01T09:30:00+08:00".to_string();
+        append_entry(&RealFs, &path, &entry)?;
+        append_entry(
+            &RealFs,
+            &path,
+            &AuditEntry {
+                timestamp: "2023-12-31T23:00:00+08:00".to_string(),
+                user: "a, b".to_string(),
+                ..entry.clone()
+            },
+        )?;
+
+        let entries = read_entries(&RealFs, &path)?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], entry);
+
+        let since = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap_or_default();
+        assert!(entries[0].is_since(since));
+        assert!(!entries[1].is_since(since));
+
+        let pushes = last_pushes(&entries, "app", "home.title");
+        assert_eq!(pushes.len(), 1);
+        assert_eq!(pushes[0].user, "a, b");
+        assert!(last_pushes(&entries, "app", "home.missing").is_empty());
+        assert!(last_pushes(&entries, "admin", "home.title").is_empty());
+
+        let csv = to_csv(&entries[1..]);
+        let line = csv.lines().nth(1).unwrap_or_default();
+        assert!(line.starts_with("2023-12-31T23:00:00+08:00,\"a, b\",app,prod,"));
+        assert!(line.contains(",zh-CN,home.body home.title,"));
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use async_trait::async_trait;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::api::{self, ApiError, ApiResult, Identity, LongPollingResponse, UploadSummary};
+use crate::config::{BackendConfig, Config};
+use crate::translation::{TranslationDelta, TranslationFile};
+
+pub mod blob;
+pub mod git;
+mod sigv4;
+
+/// 翻译后端：获取文件列表、下载翻译文件和上传翻译。
+///
+/// 现有的 Locazy 接口是其中一种实现，接入其他翻译平台（或 S3、git 仓库）时实现这个 trait，
+/// push/pull/download 等流程不需要修改。
+#[async_trait]
+pub trait TranslationBackend: Send + Sync {
+    /// 后端名称，用于日志
+    fn name(&self) -> &'static str;
+
+    /// 获取当前子系统和版本可下载的语言文件列表
+    async fn fetch_config(&self) -> ApiResult<LongPollingResponse>;
+
+    /// 带上次同步的 taskHash 获取文件列表，服务器没有变化时返回相同的 taskHash。
+    /// 不支持的后端忽略 `task_hash`
+    async fn fetch_config_since(&self, task_hash: Option<&str>) -> ApiResult<LongPollingResponse> {
+        let _ = task_hash;
+        self.fetch_config().await
+    }
+
+    /// 当前凭据对应的用户和权限，不提供身份信息的后端返回 None
+    async fn whoami(&self) -> ApiResult<Option<Identity>> {
+        Ok(None)
+    }
+
+    /// 下载一个语言文件，返回原始内容，语言的翻译位于 `<pathPrefix>/languages` 下
+    async fn download_file(&self, url: &str) -> ApiResult<String>;
+
+    /// 下载一个语言文件并写入 `out`。默认先完整下载再写入，支持流式下载的后端边下载边写入
+    async fn download_to(&self, url: &str, out: &mut (dyn Write + Send)) -> ApiResult<()> {
+        let content = self.download_file(url).await?;
+        out.write_all(content.as_bytes())
+            .map_err(|source| ApiError::Write {
+                url: url.to_string(),
+                source,
+            })
+    }
+
+    /// 上传一种语言的翻译
+    async fn upload_terms(&self, translation: &TranslationFile) -> ApiResult<UploadSummary>;
+
+    /// 只上传相对服务器当前内容的变化，不支持增量上传的后端上传全部变化的 key
+    async fn upload_delta(&self, delta: &TranslationDelta) -> ApiResult<UploadSummary> {
+        self.upload_terms(&delta.to_translation_file()).await
+    }
+}
+
+/// Locazy 平台的 HTTP 接口
+pub struct LocazyBackend {
+    config: Config,
+    /// 最近一次 long-polling 响应声明支持增量上传
+    delta_upload: AtomicBool,
+}
+
+impl LocazyBackend {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            delta_upload: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationBackend for LocazyBackend {
+    fn name(&self) -> &'static str {
+        "locazy"
+    }
+
+    async fn fetch_config(&self) -> ApiResult<LongPollingResponse> {
+        self.fetch_config_since(None).await
+    }
+
+    async fn fetch_config_since(&self, task_hash: Option<&str>) -> ApiResult<LongPollingResponse> {
+        let response = api::get_translation_config(&self.config, task_hash).await?;
+        let delta_upload = response.data.supports(api::DELTA_UPLOAD_CAPABILITY);
+        if delta_upload {
+            tracing::debug!("Server supports delta upload");
+        }
+        self.delta_upload.store(delta_upload, Ordering::Relaxed);
+        Ok(response)
+    }
+
+    async fn whoami(&self) -> ApiResult<Option<Identity>> {
+        api::whoami(&self.config).await.map(Some)
+    }
+
+    async fn download_file(&self, url: &str) -> ApiResult<String> {
+        api::download_translation(&self.config, url).await
+    }
+
+    async fn download_to(&self, url: &str, out: &mut (dyn Write + Send)) -> ApiResult<()> {
+        api::download_translation_to(&self.config, url, out).await
+    }
+
+    async fn upload_terms(&self, translation: &TranslationFile) -> ApiResult<UploadSummary> {
+        api::upload_translation(&self.config, translation).await
+    }
+
+    async fn upload_delta(&self, delta: &TranslationDelta) -> ApiResult<UploadSummary> {
+        if self.delta_upload.load(Ordering::Relaxed) {
+            api::upload_translation_delta(&self.config, delta).await
+        } else {
+            self.upload_terms(&delta.to_translation_file()).await
+        }
+    }
+}
+
+/// 根据配置创建翻译后端
+pub fn from_config(config: &Config) -> Box<dyn TranslationBackend> {
+    match &config.backend {
+        BackendConfig::Locazy => Box::new(LocazyBackend::new(config.clone())),
+        BackendConfig::Git(git) => Box::new(git::GitBackend::new(config.clone(), git.clone())),
+        BackendConfig::Blob(blob) => Box::new(blob::BlobBackend::new(config.clone(), blob.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_locazy_backend_fetch_config() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_status(200)
+            .with_body(r#"{"code":0,"message":"ok","data":{"files":[{"lang":"en-US","url":"http://x/en-US.json"}]}}"#)
+            .create_async()
+            .await;
+
+        let config = Config {
+            host: server.url(),
+            ..Default::default()
+        };
+        let backend = from_config(&config);
+        assert_eq!(backend.name(), "locazy");
+
+        let response = backend.fetch_config().await?;
+        let files = response.data.files.unwrap_or_default();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].lang, "en-US");
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_locazy_backend_negotiates_delta_upload() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_status(200)
+            .with_body(r#"{"code":0,"data":{"files":[],"capabilities":["deltaUpload"]}}"#)
+            .create_async()
+            .await;
+        let delta_mock = server
+            .mock("POST", "/api/At.Locazy/cli/terms/upload-delta")
+            .with_status(200)
+            .with_body(r#"{"code":0}"#)
+            .create_async()
+            .await;
+
+        let config = Config {
+            host: server.url(),
+            ..Default::default()
+        };
+        let backend = LocazyBackend::new(config);
+        backend.fetch_config().await?;
+
+        let upload = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            [("a", "A")],
+        );
+        let remote = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            crate::translation::Content::new(),
+        );
+        let summary = backend
+            .upload_delta(&TranslationDelta::new(&upload, &remote))
+            .await?;
+        assert_eq!(summary.uploaded_keys, 1);
+        delta_mock.assert_async().await;
+        Ok(())
+    }
+}
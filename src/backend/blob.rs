@@ -0,0 +1,426 @@
+use async_trait::async_trait;
+use reqwest::header::{CACHE_CONTROL, CONTENT_TYPE};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use tokio::sync::Mutex;
+
+use super::sigv4::{self, Credentials};
+use super::TranslationBackend;
+use crate::api::{
+    self, ApiError, ApiResult, FileDownloadInfo, LongPollingData, LongPollingResponse,
+    UploadSummary,
+};
+use crate::config::{BlobBackendConfig, Config};
+use crate::translation::{self, TranslationFile};
+
+const BACKEND_NAME: &str = "blob";
+/// 清单文件名，格式与 `bundle` 命令生成的 `index.json` 相同
+const MANIFEST_FILE: &str = "index.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    languages: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+    keys: usize,
+    file: String,
+    hash: String,
+    size: usize,
+}
+
+/// 对象存储后端：push 时把翻译 JSON 上传到 bucket，pull/download 时从 bucket 读取
+pub struct BlobBackend {
+    config: Config,
+    blob: BlobBackendConfig,
+    client: Client,
+    /// 同一进程内依次更新清单
+    manifest_lock: Mutex<()>,
+}
+
+fn error(message: impl Into<String>) -> ApiError {
+    ApiError::Backend {
+        backend: BACKEND_NAME,
+        message: message.into(),
+    }
+}
+
+/// 把 `${NAME}` 替换为环境变量的值，未设置的变量替换为空字符串
+fn expand_env(value: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        result.push_str(&std::env::var(&rest[start + 2..start + len]).unwrap_or_default());
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+impl BlobBackend {
+    pub fn new(config: Config, blob: BlobBackendConfig) -> Self {
+        Self {
+            config,
+            blob,
+            client: Client::new(),
+            manifest_lock: Mutex::new(()),
+        }
+    }
+
+    /// 对象的完整地址，bucket 地址中的查询参数保留在末尾
+    fn object_url(&self, name: &str) -> String {
+        let (base, query) = match self.blob.url.split_once('?') {
+            Some((base, query)) => (base, Some(query)),
+            None => (self.blob.url.as_str(), None),
+        };
+        let path = [
+            self.blob.prefix.trim_matches('/'),
+            self.config.sub_system_name(),
+            name,
+        ]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("/");
+        let url = format!("{}/{}", base.trim_end_matches('/'), path);
+        match query {
+            Some(query) => format!("{}?{}", url, query),
+            None => url,
+        }
+    }
+
+    /// 请求附带的 header：配置的 header，以及配置了 `s3` 时的签名
+    fn headers(&self, method: &Method, url: &str, body: &[u8]) -> ApiResult<Vec<(String, String)>> {
+        let mut headers: Vec<(String, String)> = self
+            .blob
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), expand_env(value)))
+            .collect();
+        if let Some(s3) = &self.blob.s3 {
+            let credentials = Credentials::from_env().ok_or_else(|| {
+                error("AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY must be set to sign S3 requests")
+            })?;
+            let parsed = reqwest::Url::parse(url)
+                .map_err(|e| error(format!("invalid url {}: {}", url, e)))?;
+            let signed = sigv4::sign(
+                method.as_str(),
+                &parsed,
+                body,
+                &credentials,
+                &s3.region,
+                chrono::Utc::now(),
+            );
+            headers.extend(
+                signed
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value)),
+            );
+        }
+        Ok(headers)
+    }
+
+    fn request(&self, method: Method, url: &str, headers: &[(String, String)]) -> RequestBuilder {
+        headers.iter().fold(
+            self.client.request(method, url),
+            |builder, (name, value)| builder.header(name, value),
+        )
+    }
+
+    /// 读取对象，不存在时返回 None
+    async fn get_object(&self, url: &str) -> ApiResult<Option<String>> {
+        let headers = self.headers(&Method::GET, url, b"")?;
+        let response = api::send_politely(&self.config, url, || {
+            self.request(Method::GET, url, &headers)
+        })
+        .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        api::read_response(url, response).await.map(Some)
+    }
+
+    async fn put_object(&self, url: &str, body: &str) -> ApiResult<()> {
+        let headers = self.headers(&Method::PUT, url, body.as_bytes())?;
+        let response = api::send_politely(&self.config, url, || {
+            self.request(Method::PUT, url, &headers)
+                .header(CONTENT_TYPE, "application/json")
+                .header(CACHE_CONTROL, &self.blob.cache_control)
+                .body(body.to_string())
+        })
+        .await?;
+        api::read_response(url, response).await?;
+        Ok(())
+    }
+
+    async fn read_manifest(&self) -> ApiResult<Manifest> {
+        let url = self.object_url(MANIFEST_FILE);
+        match self.get_object(&url).await? {
+            Some(text) => serde_json::from_str(&text)
+                .map_err(|source| ApiError::Deserialization { url, source }),
+            None => Ok(Manifest::default()),
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationBackend for BlobBackend {
+    fn name(&self) -> &'static str {
+        BACKEND_NAME
+    }
+
+    async fn fetch_config(&self) -> ApiResult<LongPollingResponse> {
+        let manifest = self.read_manifest().await?;
+        let files: Vec<FileDownloadInfo> = manifest
+            .languages
+            .iter()
+            .map(|(lang, entry)| FileDownloadInfo {
+                sub_system: self.config.sub_system_name().to_string(),
+                lang: lang.clone(),
+                internal_url: String::new(),
+                url: self.object_url(&entry.file),
+            })
+            .collect();
+        tracing::info!("Got {} language files", files.len());
+
+        Ok(LongPollingResponse {
+            code: 0,
+            message: String::new(),
+            data: LongPollingData {
+                task_hash: None,
+                files: Some(files),
+                ..Default::default()
+            },
+        })
+    }
+
+    async fn download_file(&self, url: &str) -> ApiResult<String> {
+        tracing::info!("Downloading translation from: {}", url);
+        let text = self
+            .get_object(url)
+            .await?
+            .ok_or_else(|| error(format!("{} does not exist", url)))?;
+        let value: serde_json::Value =
+            serde_json::from_str(&text).map_err(|source| ApiError::Deserialization {
+                url: url.to_string(),
+                source,
+            })?;
+        // 与 Locazy 的下载格式一致，翻译位于 `<pathPrefix>/languages` 下
+        let lang_key = format!("{}/languages", self.config.path_prefix);
+        Ok(serde_json::json!({ lang_key: value }).to_string())
+    }
+
+    async fn upload_terms(&self, translation: &TranslationFile) -> ApiResult<UploadSummary> {
+        let lang = &translation.language_code;
+        let name = format!("{}.json", lang);
+        let url = self.object_url(&name);
+
+        // bucket 中的文件可能包含其他来源的 key，合并后整体覆盖
+        let mut content = match self.get_object(&url).await? {
+            Some(text) => {
+                let value =
+                    serde_json::from_str(&text).map_err(|source| ApiError::Deserialization {
+                        url: url.clone(),
+                        source,
+                    })?;
+                translation::to_content(translation::flatten_json(&value))
+            }
+            None => Default::default(),
+        };
+        let uploaded_keys = translation
+            .content
+            .iter()
+            .filter(|(key, value)| content.get(*key) != Some(*value))
+            .count();
+        let summary = UploadSummary {
+            total_keys: translation.content.len(),
+            uploaded_keys,
+            total_chunks: 1,
+            failed_chunks: 0,
+        };
+        if uploaded_keys == 0 {
+            tracing::info!("{} is up to date in {}", lang, url);
+            return Ok(summary);
+        }
+
+        content.extend(translation.content.clone());
+        let value = translation::unflatten_json(&content)
+            .map_err(|e| error(format!("failed to build {}: {:#}", name, e)))?;
+        let body = value.to_string();
+        tracing::info!("Uploading {} keys of {} to {}", uploaded_keys, lang, url);
+        self.put_object(&url, &body).await?;
+
+        let _guard = self.manifest_lock.lock().await;
+        let mut manifest = self.read_manifest().await?;
+        manifest.languages.insert(
+            lang.clone(),
+            ManifestEntry {
+                keys: content.len(),
+                file: name,
+                hash: format!("{:x}", Sha256::digest(body.as_bytes())),
+                size: body.len(),
+            },
+        );
+        let manifest = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| error(format!("failed to serialize manifest: {}", e)))?;
+        self.put_object(&self.object_url(MANIFEST_FILE), &manifest)
+            .await?;
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::S3SigningConfig;
+    use anyhow::Result;
+    use mockito::Matcher;
+    use std::collections::HashMap;
+
+    fn backend(url: String) -> BlobBackend {
+        let config = Config {
+            sub_systems: vec!["app".to_string()],
+            path_prefix: "app".to_string(),
+            ..Default::default()
+        };
+        let blob = BlobBackendConfig {
+            url,
+            prefix: "i18n".to_string(),
+            cache_control: "public, max-age=60".to_string(),
+            headers: HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer ${I18N_APP_TEST_BLOB_TOKEN}".to_string(),
+            )]),
+            s3: None,
+        };
+        BlobBackend::new(config, blob)
+    }
+
+    #[test]
+    fn test_object_url_keeps_query() {
+        let backend = backend("https://account.blob.core.windows.net/assets/?sv=1&sig=x".into());
+        assert_eq!(
+            backend.object_url("en-US.json"),
+            "https://account.blob.core.windows.net/assets/i18n/app/en-US.json?sv=1&sig=x"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_s3_requests_are_signed() -> Result<()> {
+        std::env::set_var("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE");
+        std::env::set_var("AWS_SECRET_ACCESS_KEY", "secret");
+        let mut server = mockito::Server::new_async().await;
+        let get_manifest = server
+            .mock("GET", "/i18n/app/index.json")
+            .match_header(
+                "authorization",
+                Matcher::Regex(
+                    "^AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/\\d{8}/eu-west-1/s3/aws4_request, \
+                     SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature=[0-9a-f]{64}$"
+                        .to_string(),
+                ),
+            )
+            .match_header(
+                "x-amz-content-sha256",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            )
+            .match_header("x-amz-date", Matcher::Regex("^\\d{8}T\\d{6}Z$".to_string()))
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let mut backend = backend(server.url());
+        backend.blob.headers.clear();
+        backend.blob.s3 = Some(S3SigningConfig {
+            region: "eu-west-1".to_string(),
+        });
+        let response = backend.fetch_config().await?;
+        assert!(response.data.files.unwrap_or_default().is_empty());
+        get_manifest.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_blob_backend_upload_and_fetch() -> Result<()> {
+        std::env::set_var("I18N_APP_TEST_BLOB_TOKEN", "secret");
+        let mut server = mockito::Server::new_async().await;
+        let get_file = server
+            .mock("GET", "/i18n/app/en-US.json")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_body(r#"{"home":{"title":"Home"}}"#)
+            .create_async()
+            .await;
+        let put_file = server
+            .mock("PUT", "/i18n/app/en-US.json")
+            .match_header("cache-control", "public, max-age=60")
+            .match_body(Matcher::Json(
+                serde_json::json!({"home": {"title": "Home"}, "ok": "OK"}),
+            ))
+            .with_status(200)
+            .create_async()
+            .await;
+        let get_manifest = server
+            .mock("GET", "/i18n/app/index.json")
+            .with_status(404)
+            .create_async()
+            .await;
+        let put_manifest = server
+            .mock("PUT", "/i18n/app/index.json")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "languages": {"en-US": {"keys": 2, "file": "en-US.json"}}
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let backend = backend(server.url());
+        let translation = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            HashMap::from([
+                ("home.title".to_string(), "Home".to_string()),
+                ("ok".to_string(), "OK".to_string()),
+            ]),
+        );
+        let summary = backend.upload_terms(&translation).await?;
+        assert_eq!(summary.total_keys, 2);
+        assert_eq!(summary.uploaded_keys, 1);
+        get_file.assert_async().await;
+        put_file.assert_async().await;
+        get_manifest.assert_async().await;
+        put_manifest.assert_async().await;
+
+        server
+            .mock("GET", "/i18n/app/index.json")
+            .with_status(200)
+            .with_body(
+                r#"{"languages":{"en-US":{"keys":2,"file":"en-US.json","hash":"x","size":1}}}"#,
+            )
+            .create_async()
+            .await;
+        let response = backend.fetch_config().await?;
+        let files = response.data.files.unwrap_or_default();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files[0].url,
+            format!("{}/i18n/app/en-US.json", server.url())
+        );
+
+        let content: serde_json::Value =
+            serde_json::from_str(&backend.download_file(&files[0].url).await?)?;
+        assert_eq!(
+            content,
+            serde_json::json!({"app/languages": {"home": {"title": "Home"}}})
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+use super::TranslationBackend;
+use crate::api::{
+    ApiError, ApiResult, FileDownloadInfo, LongPollingData, LongPollingResponse, UploadSummary,
+};
+use crate::config::{Config, GitBackendConfig};
+use crate::locations;
+use crate::translation::{self, Content, TranslationFile};
+
+const BACKEND_NAME: &str = "git";
+
+/// 以 git 仓库存储翻译：pull 前 fetch 远程分支，push 时提交修改的 key 并推送
+pub struct GitBackend {
+    config: Config,
+    git: GitBackendConfig,
+    /// 远程仓库的本地工作副本
+    checkout_dir: PathBuf,
+    /// 同一进程内的 git 操作依次执行
+    lock: Mutex<()>,
+}
+
+fn error(message: impl Into<String>) -> ApiError {
+    ApiError::Backend {
+        backend: BACKEND_NAME,
+        message: message.into(),
+    }
+}
+
+impl GitBackend {
+    pub fn new(config: Config, git: GitBackendConfig) -> Self {
+        let checkout_dir = locations::cache_home()
+            .join("git")
+            .join(config.sub_system_name());
+        Self::with_checkout_dir(config, git, checkout_dir)
+    }
+
+    pub fn with_checkout_dir(config: Config, git: GitBackendConfig, checkout_dir: PathBuf) -> Self {
+        Self {
+            config,
+            git,
+            checkout_dir,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 当前子系统的翻译文件目录
+    fn language_dir(&self) -> PathBuf {
+        self.checkout_dir
+            .join(&self.git.path)
+            .join(self.config.sub_system_name())
+    }
+
+    async fn run_git(&self, args: &[&str]) -> ApiResult<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.checkout_dir)
+            .args(args)
+            .output()
+            .await
+            .map_err(|e| error(format!("failed to run git: {}", e)))?;
+        if !output.status.success() {
+            return Err(error(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// 创建或更新工作副本，使其与远程分支一致；远程还没有该分支时在第一次 push 时创建
+    async fn sync_checkout(&self) -> ApiResult<()> {
+        if self.checkout_dir.join(".git").exists() {
+            self.run_git(&["remote", "set-url", "origin", &self.git.url])
+                .await?;
+        } else {
+            std::fs::create_dir_all(&self.checkout_dir)
+                .map_err(|e| error(format!("failed to create checkout directory: {}", e)))?;
+            self.run_git(&["init", "--quiet"]).await?;
+            self.run_git(&["remote", "add", "origin", &self.git.url])
+                .await?;
+        }
+
+        tracing::info!("Fetching {} ({})", self.git.url, self.git.branch);
+        self.run_git(&["fetch", "--quiet", "origin"]).await?;
+        let remote_branch = format!("origin/{}", self.git.branch);
+        if self
+            .run_git(&["rev-parse", "--verify", "--quiet", &remote_branch])
+            .await
+            .is_ok()
+        {
+            self.run_git(&[
+                "checkout",
+                "--quiet",
+                "--force",
+                "-B",
+                &self.git.branch,
+                &remote_branch,
+            ])
+            .await?;
+            self.run_git(&["clean", "-fdq"]).await?;
+        } else {
+            let head = format!("refs/heads/{}", self.git.branch);
+            self.run_git(&["symbolic-ref", "HEAD", &head]).await?;
+        }
+        Ok(())
+    }
+}
+
+fn read_flat(path: &Path) -> ApiResult<Content> {
+    if !path.exists() {
+        return Ok(Content::new());
+    }
+    let value = translation::read_json_file(path)
+        .map_err(|e| error(format!("failed to read {}: {:#}", path.display(), e)))?;
+    Ok(translation::to_content(translation::flatten_json(&value)))
+}
+
+/// 提交信息：摘要行加上新增和修改的 key 列表
+fn commit_message(
+    sub_system: &str,
+    lang: &str,
+    added: &[&Arc<str>],
+    changed: &[&Arc<str>],
+) -> String {
+    let mut message = format!(
+        "i18n({}): update {} (+{} ~{})\n",
+        sub_system,
+        lang,
+        added.len(),
+        changed.len()
+    );
+    for (title, keys) in [("Added", added), ("Changed", changed)] {
+        if !keys.is_empty() {
+            message.push_str(&format!("\n{}:\n", title));
+            for key in keys {
+                message.push_str(&format!("- {}\n", key));
+            }
+        }
+    }
+    message
+}
+
+#[async_trait]
+impl TranslationBackend for GitBackend {
+    fn name(&self) -> &'static str {
+        BACKEND_NAME
+    }
+
+    async fn fetch_config(&self) -> ApiResult<LongPollingResponse> {
+        let _guard = self.lock.lock().await;
+        self.sync_checkout().await?;
+
+        let mut files = Vec::new();
+        let dir = self.language_dir();
+        if dir.is_dir() {
+            let entries = std::fs::read_dir(&dir)
+                .map_err(|e| error(format!("failed to read {}: {}", dir.display(), e)))?;
+            for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let Some(lang) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                files.push(FileDownloadInfo {
+                    sub_system: self.config.sub_system_name().to_string(),
+                    lang: lang.to_string(),
+                    internal_url: String::new(),
+                    url: path.display().to_string(),
+                });
+            }
+        }
+        files.sort_by(|a, b| a.lang.cmp(&b.lang));
+        tracing::info!("Got {} language files", files.len());
+
+        Ok(LongPollingResponse {
+            code: 0,
+            message: String::new(),
+            data: LongPollingData {
+                task_hash: self.run_git(&["rev-parse", "HEAD"]).await.ok(),
+                files: Some(files),
+                ..Default::default()
+            },
+        })
+    }
+
+    async fn download_file(&self, url: &str) -> ApiResult<String> {
+        let value = translation::read_json_file(Path::new(url))
+            .map_err(|e| error(format!("failed to read {}: {:#}", url, e)))?;
+        // 与 Locazy 的下载格式一致，翻译位于 `<pathPrefix>/languages` 下
+        let lang_key = format!("{}/languages", self.config.path_prefix);
+        Ok(serde_json::json!({ lang_key: value }).to_string())
+    }
+
+    async fn upload_terms(&self, translation: &TranslationFile) -> ApiResult<UploadSummary> {
+        let _guard = self.lock.lock().await;
+        self.sync_checkout().await?;
+
+        let lang = &translation.language_code;
+        let file = self.language_dir().join(format!("{}.json", lang));
+        let mut content = read_flat(&file)?;
+        let mut added: Vec<&Arc<str>> = Vec::new();
+        let mut changed: Vec<&Arc<str>> = Vec::new();
+        for (key, value) in &translation.content {
+            match content.get(key) {
+                None => added.push(key),
+                Some(existing) if existing != value => changed.push(key),
+                _ => {}
+            }
+        }
+        added.sort();
+        changed.sort();
+
+        let summary = UploadSummary {
+            total_keys: translation.content.len(),
+            uploaded_keys: added.len() + changed.len(),
+            total_chunks: 1,
+            failed_chunks: 0,
+        };
+        if summary.uploaded_keys == 0 {
+            tracing::info!("{} is up to date in {}", lang, self.git.url);
+            return Ok(summary);
+        }
+
+        content.extend(translation.content.clone());
+        let value = translation::unflatten_json(&content)
+            .map_err(|e| error(format!("failed to build {}: {:#}", file.display(), e)))?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| error(format!("failed to create {}: {}", parent.display(), e)))?;
+        }
+        let json = serde_json::to_string_pretty(&value)
+            .map_err(|e| error(format!("failed to serialize {}: {}", lang, e)))?;
+        std::fs::write(&file, json + "\n")
+            .map_err(|e| error(format!("failed to write {}: {}", file.display(), e)))?;
+
+        let relative = file
+            .strip_prefix(&self.checkout_dir)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .to_string();
+        let message = commit_message(self.config.sub_system_name(), lang, &added, &changed);
+        self.run_git(&["add", "--", &relative]).await?;
+        self.run_git(&["commit", "--quiet", "-m", &message]).await?;
+        let refspec = format!("HEAD:refs/heads/{}", self.git.branch);
+        self.run_git(&["push", "--quiet", "origin", &refspec])
+            .await?;
+        tracing::info!(
+            "Pushed {} keys of {} to {} ({})",
+            summary.uploaded_keys,
+            lang,
+            self.git.url,
+            self.git.branch
+        );
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use tempfile::TempDir;
+
+    fn translation(content: &[(&str, &str)]) -> TranslationFile {
+        TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            content.iter().copied(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_git_backend_roundtrip() -> Result<()> {
+        for (name, value) in [
+            ("GIT_AUTHOR_NAME", "i18n-app"),
+            ("GIT_AUTHOR_EMAIL", "i18n-app@example.com"),
+            ("GIT_COMMITTER_NAME", "i18n-app"),
+            ("GIT_COMMITTER_EMAIL", "i18n-app@example.com"),
+        ] {
+            std::env::set_var(name, value);
+        }
+        let temp_dir = TempDir::new()?;
+        let remote = temp_dir.path().join("remote.git");
+        let status = std::process::Command::new("git")
+            .args(["init", "--quiet", "--bare"])
+            .arg(&remote)
+            .status()?;
+        assert!(status.success());
+
+        let config = Config {
+            sub_systems: vec!["app".to_string()],
+            path_prefix: "app".to_string(),
+            ..Default::default()
+        };
+        let git = GitBackendConfig {
+            url: remote.display().to_string(),
+            branch: "i18n".to_string(),
+            path: "locales".to_string(),
+        };
+        let backend = |dir: &str| {
+            GitBackend::with_checkout_dir(config.clone(), git.clone(), temp_dir.path().join(dir))
+        };
+
+        let writer = backend("writer");
+        let summary = writer
+            .upload_terms(&translation(&[("home.title", "Home")]))
+            .await?;
+        assert_eq!(summary.uploaded_keys, 1);
+        let summary = writer
+            .upload_terms(&translation(&[("home.title", "Home page"), ("ok", "OK")]))
+            .await?;
+        assert_eq!(summary.uploaded_keys, 2);
+        let log = writer.run_git(&["log", "-1", "--format=%B"]).await?;
+        assert_eq!(
+            log,
+            "i18n(app): update en-US (+1 ~1)\n\nAdded:\n- ok\n\nChanged:\n- home.title"
+        );
+
+        // 另一个工作副本读取推送的内容
+        let reader = backend("reader");
+        let response = reader.fetch_config().await?;
+        let files = response.data.files.unwrap_or_default();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].lang, "en-US");
+        assert!(response.data.task_hash.is_some());
+        let content: serde_json::Value =
+            serde_json::from_str(&reader.download_file(&files[0].url).await?)?;
+        assert_eq!(
+            content,
+            serde_json::json!({"app/languages": {"home": {"title": "Home page"}, "ok": "OK"}})
+        );
+
+        Ok(())
+    }
+}
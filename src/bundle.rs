@@ -0,0 +1,354 @@
+use anyhow::{Context, Result};
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+/// 清单文件名，记录每种语言的文件名和哈希，供运行时选择要加载的文件
+pub const MANIFEST_FILE: &str = "index.json";
+/// 文件名中使用的哈希长度
+const HASH_PREFIX_LEN: usize = 8;
+
+/// bundle 命令的选项
+#[derive(Debug, Clone, Default)]
+pub struct BundleOptions {
+    pub out: PathBuf,
+    /// 额外生成 `.gz` 预压缩文件
+    pub gzip: bool,
+    /// 额外生成 `.br` 预压缩文件
+    pub brotli: bool,
+    /// 按根节点的 key 拆分为每个命名空间一个文件
+    pub split_namespaces: bool,
+}
+
+/// 清单中的单个文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// 相对输出目录的路径，文件名中带内容哈希
+    pub file: String,
+    /// 内容的 SHA-256
+    pub hash: String,
+    pub size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gzip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brotli: Option<String>,
+}
+
+/// 清单中的一种语言，不拆分时直接记录文件，拆分时按命名空间记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageEntry {
+    pub keys: usize,
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<BundleFile>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub namespaces: BTreeMap<String, BundleFile>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub languages: BTreeMap<String, LanguageEntry>,
+}
+
+impl Manifest {
+    /// 清单中列出的所有文件，包括预压缩文件
+    fn files(&self) -> impl Iterator<Item = &String> {
+        self.languages
+            .values()
+            .flat_map(|entry| entry.file.iter().chain(entry.namespaces.values()))
+            .flat_map(|file| {
+                std::iter::once(&file.file)
+                    .chain(file.gzip.iter())
+                    .chain(file.brotli.iter())
+            })
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(bytes)?;
+    Ok(encoder.finish()?)
+}
+
+fn brotli(bytes: &[u8]) -> Result<Vec<u8>> {
+    // 静态文件只压缩一次，使用最高压缩级别
+    let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+    encoder.write_all(bytes)?;
+    encoder.flush()?;
+    Ok(encoder.into_inner())
+}
+
+/// 把语言代码或命名空间转换成安全的文件名：只保留字母、数字、`-`、`_` 和 `.`，
+/// 其他字符（包括路径分隔符）替换为 `_`，避免写到输出目录之外
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.chars().all(|c| c == '.') {
+        stem.replace('.', "_") + "_"
+    } else {
+        stem
+    }
+}
+
+/// 清单中的路径是否是输出目录内的普通相对路径
+fn is_inside(file: &str) -> bool {
+    let path = Path::new(file);
+    !file.is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+fn count_keys(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => map.values().map(count_keys).sum(),
+        _ => 1,
+    }
+}
+
+/// 以 minified JSON 写入一个文件，文件名为 `<stem>.<hash>.json`
+fn write_file(
+    out: &Path,
+    stem: &str,
+    value: &Value,
+    options: &BundleOptions,
+) -> Result<BundleFile> {
+    let content = serde_json::to_vec(value)?;
+    let hash = sha256_hex(&content);
+    let file = format!("{}.{}.json", stem, &hash[..HASH_PREFIX_LEN]);
+    let path = out.join(&file);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, &content).with_context(|| format!("写入文件 {} 失败", path.display()))?;
+
+    let gzip_file = if options.gzip {
+        let gzip_file = format!("{}.gz", file);
+        fs::write(out.join(&gzip_file), gzip(&content)?)?;
+        Some(gzip_file)
+    } else {
+        None
+    };
+    let brotli_file = if options.brotli {
+        let brotli_file = format!("{}.br", file);
+        fs::write(out.join(&brotli_file), brotli(&content)?)?;
+        Some(brotli_file)
+    } else {
+        None
+    };
+
+    Ok(BundleFile {
+        file,
+        hash,
+        size: content.len(),
+        gzip: gzip_file,
+        brotli: brotli_file,
+    })
+}
+
+/// 删除上一次 bundle 清单中列出的文件，避免旧的哈希文件堆积
+fn remove_previous(out: &Path) -> Result<()> {
+    let manifest_path = out.join(MANIFEST_FILE);
+    if !manifest_path.exists() {
+        return Ok(());
+    }
+    let previous: Manifest = match serde_json::from_str(&fs::read_to_string(&manifest_path)?) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            tracing::warn!("Ignoring invalid bundle manifest: {}", e);
+            return Ok(());
+        }
+    };
+    for file in previous.files() {
+        // 清单可能被手动修改过，只删除输出目录内的文件
+        if !is_inside(file) {
+            tracing::warn!(
+                "Skipping bundle file outside the output directory: {}",
+                file
+            );
+            continue;
+        }
+        let path = out.join(file);
+        if path.is_file() {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// 把各语言的翻译写入输出目录，并生成清单文件
+pub fn write_bundle(
+    languages: &BTreeMap<String, Value>,
+    options: &BundleOptions,
+) -> Result<Manifest> {
+    let out = &options.out;
+    fs::create_dir_all(out).with_context(|| format!("创建目录 {} 失败", out.display()))?;
+    remove_previous(out)?;
+
+    let mut manifest = Manifest::default();
+    for (lang, value) in languages {
+        let mut entry = LanguageEntry {
+            keys: count_keys(value),
+            file: None,
+            namespaces: BTreeMap::new(),
+        };
+        match value {
+            Value::Object(map) if options.split_namespaces => {
+                for (namespace, content) in map {
+                    let stem = format!("{}/{}", file_stem(lang), file_stem(namespace));
+                    let file = write_file(out, &stem, content, options)?;
+                    entry.namespaces.insert(namespace.clone(), file);
+                }
+            }
+            _ => entry.file = Some(write_file(out, &file_stem(lang), value, options)?),
+        }
+        manifest.languages.insert(lang.clone(), entry);
+    }
+
+    fs::write(
+        out.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_bundle() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let languages = BTreeMap::from([
+            (
+                "en-US".to_string(),
+                json!({"home": {"title": "Home"}, "common": {"ok": "OK", "cancel": "Cancel"}}),
+            ),
+            ("zh-CN".to_string(), json!({"home": {"title": "首页"}})),
+        ]);
+        let mut options = BundleOptions {
+            out: temp_dir.path().join("dist"),
+            gzip: true,
+            brotli: true,
+            split_namespaces: false,
+        };
+
+        let manifest = write_bundle(&languages, &options)?;
+        let en = &manifest.languages["en-US"];
+        assert_eq!(en.keys, 3);
+        let file = en.file.as_ref().unwrap();
+        assert!(file.file.starts_with("en-US.") && file.file.ends_with(".json"));
+        let content = fs::read_to_string(options.out.join(&file.file))?;
+        assert_eq!(
+            content,
+            r#"{"common":{"cancel":"Cancel","ok":"OK"},"home":{"title":"Home"}}"#
+        );
+        assert_eq!(file.hash, sha256_hex(content.as_bytes()));
+        assert!(options.out.join(file.gzip.as_ref().unwrap()).exists());
+        let compressed = fs::read(options.out.join(file.brotli.as_ref().unwrap()))?;
+        let mut decompressed = Vec::new();
+        brotli::BrotliDecompress(&mut compressed.as_slice(), &mut decompressed)?;
+        assert_eq!(decompressed, content.as_bytes());
+
+        // 拆分命名空间后重新生成，上一次的文件被清理
+        options.split_namespaces = true;
+        options.gzip = false;
+        options.brotli = false;
+        let split = write_bundle(&languages, &options)?;
+        assert!(!options.out.join(&file.file).exists());
+        let common = &split.languages["en-US"].namespaces["common"];
+        assert!(common.file.starts_with("en-US/common."));
+        assert_eq!(
+            fs::read_to_string(options.out.join(&common.file))?,
+            r#"{"cancel":"Cancel","ok":"OK"}"#
+        );
+        let saved: Manifest =
+            serde_json::from_str(&fs::read_to_string(options.out.join(MANIFEST_FILE))?)?;
+        assert_eq!(saved, split);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_stem() {
+        assert_eq!(file_stem("en-US"), "en-US");
+        assert_eq!(file_stem("app.home"), "app.home");
+        assert_eq!(file_stem("../etc/passwd"), ".._etc_passwd");
+        assert_eq!(file_stem(".."), "___");
+        assert_eq!(file_stem(""), "_");
+        assert_eq!(file_stem("a\\b c"), "a_b_c");
+    }
+
+    #[test]
+    fn test_remove_previous_stays_inside_out() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let out = temp_dir.path().join("dist");
+        fs::create_dir_all(&out)?;
+        let outside = temp_dir.path().join("keep.json");
+        fs::write(&outside, "{}")?;
+        fs::write(out.join("en-US.old.json"), "{}")?;
+        let previous = |file: &str| BundleFile {
+            file: file.to_string(),
+            hash: String::new(),
+            size: 2,
+            gzip: None,
+            brotli: None,
+        };
+        let manifest = Manifest {
+            languages: BTreeMap::from([(
+                "en-US".to_string(),
+                LanguageEntry {
+                    keys: 1,
+                    file: Some(previous("en-US.old.json")),
+                    namespaces: BTreeMap::from([
+                        ("a".to_string(), previous("../keep.json")),
+                        ("b".to_string(), previous(&outside.display().to_string())),
+                    ]),
+                },
+            )]),
+        };
+        fs::write(out.join(MANIFEST_FILE), serde_json::to_string(&manifest)?)?;
+
+        remove_previous(&out)?;
+        assert!(!out.join("en-US.old.json").exists());
+        assert!(outside.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_namespaces_sanitizes_names() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let options = BundleOptions {
+            out: temp_dir.path().join("dist"),
+            split_namespaces: true,
+            ..Default::default()
+        };
+        let languages =
+            BTreeMap::from([("en-US".to_string(), json!({"../../escape": {"a": "A"}}))]);
+
+        let manifest = write_bundle(&languages, &options)?;
+        let file = &manifest.languages["en-US"].namespaces["../../escape"].file;
+        assert!(file.starts_with("en-US/.._.._escape."));
+        assert!(options.out.join(file).exists());
+        assert_eq!(fs::read_dir(temp_dir.path())?.count(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,436 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::journal;
+use crate::translation;
+use crate::vfs::FileSystem;
+use crate::workspace;
+
+/// 同一进程中的清单读写串行执行，避免并发更新时互相覆盖
+static MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn manifest_file() -> PathBuf {
+    workspace::cache_root().join("manifest.json")
+}
+
+/// `config`（单个子系统）的服务器翻译缓存目录 `<子系统>/<host>/<版本>`，每种语言一个 `<语言>.json`。
+/// 不同环境和版本的翻译不同，分开缓存才不会互相覆盖
+pub fn target_dir(config: &Config) -> PathBuf {
+    scoped_dir(&workspace::cache_root(), config)
+}
+
+/// `root` 下 `config`（单个子系统）专用的目录 `<子系统>/<host>/<版本>`，pull 快照也按同样的方式分开保存
+pub fn scoped_dir(root: &Path, config: &Config) -> PathBuf {
+    root.join(config.sub_system_name())
+        .join(path_segment(&config.host))
+        .join(path_segment(&config.version_no))
+}
+
+/// 缓存目录中一种语言的文件。语言代码包含路径分隔符或是 `.`、`..` 时返回 Validation 错误，
+/// 避免读取缓存目录之外的文件
+pub fn language_file(config: &Config, lang: &str) -> Result<PathBuf> {
+    if lang.is_empty() || lang == "." || lang == ".." || lang.contains(['/', '\\']) {
+        return Err(AppError::Validation(format!("无效的语言代码 '{}'", lang)).into());
+    }
+    Ok(target_dir(config).join(format!("{}.json", lang)))
+}
+
+/// 把 host、版本号转换为可以作为目录名的文本，如 `https://i18n.example.com:8443` → `i18n.example.com_8443`
+fn path_segment(text: &str) -> String {
+    let text = text
+        .split_once("://")
+        .map_or(text, |(_, rest)| rest)
+        .trim_end_matches('/');
+    let segment: String = text
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match segment.trim_matches('.') {
+        "" => "_".to_string(),
+        _ => segment,
+    }
+}
+
+/// 缓存中一种语言的文件写入时的校验和与服务器版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub sha256: String,
+    /// 下载时服务器返回的 taskHash，没有时无法判断缓存是否过期，使用前总是重新下载
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_hash: Option<String>,
+    /// 下载时间（RFC 3339），旧版本写入的清单中没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downloaded_at: Option<String>,
+}
+
+/// 缓存文件与清单的比较结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Fresh,
+    /// 服务器翻译已经更新，或者无法确定缓存对应的版本
+    Stale,
+    /// 文件内容与记录的校验和不一致
+    Corrupt,
+    /// 文件或清单中的记录不存在
+    Missing,
+}
+
+/// 缓存文件的清单：上传目标 -> 语言 -> 校验和，push 使用缓存前据此校验，只重新下载过期或损坏的语言。
+/// 上传目标与上传计划相同（`子系统@host#版本`），见 [`journal::push_key`]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheManifest {
+    #[serde(flatten)]
+    targets: BTreeMap<String, BTreeMap<String, CacheEntry>>,
+}
+
+impl CacheManifest {
+    /// 读取清单，文件不存在或无法解析时返回空清单，所有缓存都会被当作缺失
+    pub fn load(fs: &dyn FileSystem, path: &Path) -> Self {
+        let Ok(content) = fs.read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("缓存清单 {} 无法解析，忽略: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// 先写入临时文件再替换，读取时不会看到写了一半的清单
+    pub fn save(&self, fs: &dyn FileSystem, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)?;
+        }
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let content = serde_json::to_string_pretty(self)? + "\n";
+        fs.write(Path::new(&temp_path), content.as_bytes())
+            .and_then(|_| fs.rename(Path::new(&temp_path), path))
+            .with_context(|| format!("写入缓存清单 {} 失败", path.display()))
+    }
+
+    /// 读取清单，用 `f` 修改后写回
+    pub fn update(
+        fs: &dyn FileSystem,
+        path: &Path,
+        f: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        let _guard = MANIFEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut manifest = Self::load(fs, path);
+        f(&mut manifest)?;
+        manifest.save(fs, path)
+    }
+
+    pub fn entries(&self, target: &str) -> Option<&BTreeMap<String, CacheEntry>> {
+        self.targets.get(target)
+    }
+
+    /// 用 `entries` 替换上传目标的全部记录
+    pub fn set_entries(&mut self, target: &str, entries: BTreeMap<String, CacheEntry>) {
+        if entries.is_empty() {
+            self.targets.remove(target);
+        } else {
+            self.targets.insert(target.to_string(), entries);
+        }
+    }
+
+    /// 计算文件的校验和并记录，下载时间为当前时间
+    pub fn record(
+        &mut self,
+        fs: &dyn FileSystem,
+        target: &str,
+        lang: &str,
+        file: &Path,
+        task_hash: Option<&str>,
+    ) -> Result<()> {
+        let entry = CacheEntry {
+            sha256: file_sha256(fs, file)?,
+            task_hash: task_hash.map(str::to_string),
+            downloaded_at: Some(Local::now().to_rfc3339()),
+        };
+        self.targets
+            .entry(target.to_string())
+            .or_default()
+            .insert(lang.to_string(), entry);
+        Ok(())
+    }
+
+    /// 与服务器当前的 `task_hash` 和文件内容比较，判断缓存能否直接使用
+    pub fn check(
+        &self,
+        fs: &dyn FileSystem,
+        target: &str,
+        lang: &str,
+        file: &Path,
+        task_hash: Option<&str>,
+    ) -> CacheStatus {
+        match self.verify(fs, target, lang, file) {
+            CacheStatus::Fresh => {}
+            status => return status,
+        }
+        let entry = &self.targets[target][lang];
+        match (entry.task_hash.as_deref(), task_hash) {
+            (Some(cached), Some(current)) if cached == current => CacheStatus::Fresh,
+            _ => CacheStatus::Stale,
+        }
+    }
+
+    /// 只比较文件内容与记录的校验和，不考虑服务器版本
+    pub fn verify(
+        &self,
+        fs: &dyn FileSystem,
+        target: &str,
+        lang: &str,
+        file: &Path,
+    ) -> CacheStatus {
+        let Some(entry) = self.entries(target).and_then(|e| e.get(lang)) else {
+            return CacheStatus::Missing;
+        };
+        match file_sha256(fs, file) {
+            Ok(sha256) if sha256 == entry.sha256 => CacheStatus::Fresh,
+            Ok(_) => CacheStatus::Corrupt,
+            Err(_) => CacheStatus::Missing,
+        }
+    }
+}
+
+/// 缓存中的一种语言
+#[derive(Debug, Clone)]
+pub struct CachedLanguage {
+    pub lang: String,
+    /// key 数量，文件不是有效的 JSON 时为 None
+    pub keys: Option<usize>,
+    pub size: u64,
+    /// 清单中记录的下载时间，没有记录时为 None
+    pub downloaded_at: Option<DateTime<Local>>,
+    /// 与清单中校验和的比较结果，不考虑服务器版本
+    pub status: CacheStatus,
+}
+
+/// `config` 的缓存目录中的语言，按语言排序，目录不存在时返回空列表
+pub fn list_languages(fs: &dyn FileSystem, config: &Config) -> Result<Vec<CachedLanguage>> {
+    let dir = target_dir(config);
+    if !fs.exists(&dir) {
+        return Ok(Vec::new());
+    }
+    let target = journal::push_key(config);
+    let manifest = CacheManifest::load(fs, &manifest_file());
+    let entries = manifest.entries(&target);
+    let mut languages = Vec::new();
+    for path in fs
+        .read_dir(&dir)
+        .with_context(|| format!("读取 {} 失败", dir.display()))?
+    {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let Some(lang) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        languages.push(CachedLanguage {
+            lang: lang.to_string(),
+            keys: count_keys(fs, &path).ok(),
+            size: fs.len(&path)?,
+            downloaded_at: entries
+                .and_then(|e| e.get(lang))
+                .and_then(|e| e.downloaded_at.as_deref())
+                .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                .map(DateTime::from),
+            status: manifest.verify(fs, &target, lang, &path),
+        });
+    }
+    languages.sort_by(|a, b| a.lang.cmp(&b.lang));
+    Ok(languages)
+}
+
+fn count_keys(fs: &dyn FileSystem, path: &Path) -> Result<usize> {
+    let mut keys = 0;
+    translation::for_each_flat_entry(fs.open(path)?, &mut |_, _| keys += 1)?;
+    Ok(keys)
+}
+
+/// 删除 `config` 的缓存目录和清单中的记录，返回目录是否存在
+pub fn clear(fs: &dyn FileSystem, config: &Config) -> Result<bool> {
+    let dir = target_dir(config);
+    let existed = fs.exists(&dir);
+    workspace::remove_dir_safely_with(fs, &dir)?;
+    let path = manifest_file();
+    if fs.exists(&path) {
+        CacheManifest::update(fs, &path, |manifest| {
+            manifest.set_entries(&journal::push_key(config), BTreeMap::new());
+            Ok(())
+        })?;
+    }
+    Ok(existed)
+}
+
+/// 流式计算文件的 SHA-256，大文件不会完整读入内存
+pub fn file_sha256(fs: &dyn FileSystem, path: &Path) -> Result<String> {
+    let mut file = fs
+        .open(path)
+        .with_context(|| format!("读取 {} 失败", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::{MemoryFs, RealFs};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_manifest_check() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let file = temp_dir.path().join("de-DE.json");
+        let path = temp_dir.path().join("manifest.json");
+        fs::write(&file, r#"{"save": "Speichern"}"#)?;
+
+        let mut manifest = CacheManifest::load(&RealFs, &path);
+        assert_eq!(
+            manifest.check(&RealFs, "app", "de-DE", &file, Some("v1")),
+            CacheStatus::Missing
+        );
+        manifest.record(&RealFs, "app", "de-DE", &file, Some("v1"))?;
+        manifest.save(&RealFs, &path)?;
+
+        let manifest = CacheManifest::load(&RealFs, &path);
+        assert_eq!(
+            manifest.check(&RealFs, "app", "de-DE", &file, Some("v1")),
+            CacheStatus::Fresh
+        );
+        assert_eq!(
+            manifest.check(&RealFs, "app", "de-DE", &file, Some("v2")),
+            CacheStatus::Stale
+        );
+        assert_eq!(
+            manifest.check(&RealFs, "app", "de-DE", &file, None),
+            CacheStatus::Stale
+        );
+        assert_eq!(
+            manifest.check(&RealFs, "admin", "de-DE", &file, Some("v1")),
+            CacheStatus::Missing
+        );
+
+        // 被截断的文件
+        fs::write(&file, r#"{"save": "Spei"#)?;
+        assert_eq!(
+            manifest.check(&RealFs, "app", "de-DE", &file, Some("v1")),
+            CacheStatus::Corrupt
+        );
+
+        CacheManifest::update(&RealFs, &path, |m| {
+            m.set_entries("app", BTreeMap::new());
+            Ok(())
+        })?;
+        assert_eq!(
+            CacheManifest::load(&RealFs, &path),
+            CacheManifest::default()
+        );
+
+        // 损坏的清单当作空清单
+        fs::write(&path, "{")?;
+        assert_eq!(
+            CacheManifest::load(&RealFs, &path),
+            CacheManifest::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_target_dir() {
+        let config = Config {
+            sub_systems: vec!["app".to_string()],
+            host: "https://i18n.example.com:8443/".to_string(),
+            version_no: "1.0.0".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            target_dir(&config),
+            workspace::cache_root()
+                .join("app")
+                .join("i18n.example.com_8443")
+                .join("1.0.0")
+        );
+        assert_ne!(
+            target_dir(&config),
+            target_dir(&config.for_version("2.0.0"))
+        );
+        assert_eq!(path_segment(".."), "_");
+        assert_eq!(
+            scoped_dir(Path::new("snapshots"), &config),
+            Path::new("snapshots/app/i18n.example.com_8443/1.0.0")
+        );
+
+        assert_eq!(
+            language_file(&config, "de-DE").unwrap(),
+            target_dir(&config).join("de-DE.json")
+        );
+        for lang in ["../manifest", "..\\x", "a/b", "..", ""] {
+            let error = language_file(&config, lang).unwrap_err();
+            assert!(
+                matches!(
+                    error.downcast_ref::<AppError>(),
+                    Some(AppError::Validation(_))
+                ),
+                "{}",
+                lang
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_and_clear() -> Result<()> {
+        let fs = MemoryFs::new();
+        let config = Config {
+            sub_systems: vec!["cache-list-test".to_string()],
+            ..Default::default()
+        };
+        let dir = target_dir(&config);
+        fs.write(&dir.join("de-DE.json"), br#"{"home": {"title": "Start"}}"#)?;
+        fs.write(&dir.join("fr-FR.json"), b"{")?;
+        fs.write(&dir.join("notes.txt"), b"")?;
+        let mut manifest = CacheManifest::default();
+        let target = journal::push_key(&config);
+        manifest.record(&fs, &target, "de-DE", &dir.join("de-DE.json"), None)?;
+        manifest.save(&fs, &manifest_file())?;
+
+        let languages = list_languages(&fs, &config)?;
+        assert_eq!(
+            languages
+                .iter()
+                .map(|l| (l.lang.as_str(), l.keys, l.size, l.status))
+                .collect::<Vec<_>>(),
+            vec![
+                ("de-DE", Some(1), 28, CacheStatus::Fresh),
+                ("fr-FR", None, 1, CacheStatus::Missing),
+            ]
+        );
+        // 显示清单中记录的下载时间，而不是文件的修改时间
+        assert!(languages[0].downloaded_at.is_some());
+        assert!(languages[1].downloaded_at.is_none());
+
+        assert!(clear(&fs, &config)?);
+        assert!(list_languages(&fs, &config)?.is_empty());
+        assert!(CacheManifest::load(&fs, &manifest_file())
+            .entries(&target)
+            .is_none());
+        assert!(!clear(&fs, &config)?);
+        Ok(())
+    }
+}
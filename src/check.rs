@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::output;
+use crate::translation::{Content, TranslationFile};
+
+/// check 命令要执行的检查项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckOptions {
+    pub duplicates: bool,
+    pub spelling: bool,
+    pub missing: bool,
+    /// 基准语言中的空文本
+    pub empty_base: bool,
+    /// 检查文件是否为规范格式，只在指定时执行
+    pub format: bool,
+    /// 未指定任何检查项，执行全部检查（拼写检查只在配置了 spellCheck 时执行）
+    pub all: bool,
+}
+
+impl CheckOptions {
+    pub fn new(
+        duplicates: bool,
+        spelling: bool,
+        missing: bool,
+        empty_base: bool,
+        format: bool,
+    ) -> Self {
+        let all = !(duplicates || spelling || missing || empty_base || format);
+        Self {
+            duplicates: duplicates || all,
+            spelling: spelling || all,
+            missing: missing || all,
+            empty_base: empty_base || all,
+            format,
+            all,
+        }
+    }
+}
+
+/// 基准语言中文本相同（或规范化后相同）的一组 key
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub value: String,
+    pub keys: Vec<String>,
+}
+
+impl DuplicateGroup {
+    /// 建议合并使用的共享 key：已有 common 下的 key 时直接复用，否则按文本生成
+    pub fn suggested_key(&self) -> String {
+        if let Some(key) = self.keys.iter().find(|k| k.starts_with("common.")) {
+            return key.clone();
+        }
+        let slug = normalize_for_comparison(&self.value)
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .take(4)
+            .collect::<Vec<_>>()
+            .join("_");
+        if slug.is_empty() {
+            // 非 ASCII 文本无法生成可读的 key，复用第一个 key 的最后一段
+            let last = self.keys[0].rsplit('.').next().unwrap_or(&self.keys[0]);
+            format!("common.{}", last)
+        } else {
+            format!("common.{}", slug)
+        }
+    }
+}
+
+/// 比较用的规范化：忽略大小写、首尾空白、连续空白和末尾标点
+pub fn normalize_for_comparison(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', '。', '!', '！', ':', '：', '…'])
+        .to_lowercase()
+}
+
+/// 查找基准语言中文本重复的 key，按重复次数从多到少排序
+pub fn find_duplicates(content: &Content) -> Vec<DuplicateGroup> {
+    let mut groups: BTreeMap<String, Vec<&Arc<str>>> = BTreeMap::new();
+    for (key, value) in content {
+        let normalized = normalize_for_comparison(value);
+        if normalized.is_empty() {
+            continue;
+        }
+        groups.entry(normalized).or_default().push(key);
+    }
+
+    let mut duplicates: Vec<DuplicateGroup> = groups
+        .into_values()
+        .filter(|keys| keys.len() > 1)
+        .map(|mut keys| {
+            keys.sort();
+            DuplicateGroup {
+                value: content[keys[0]].trim().to_string(),
+                keys: keys.into_iter().map(|key| key.to_string()).collect(),
+            }
+        })
+        .collect();
+    duplicates.sort_by(|a, b| b.keys.len().cmp(&a.keys.len()).then(a.value.cmp(&b.value)));
+    duplicates
+}
+
+/// 新 key 与已有文本的相似匹配
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarMatch {
+    pub key: String,
+    pub value: String,
+    pub similar_key: String,
+    pub similar_value: String,
+    pub similarity: f64,
+}
+
+/// 文本相似度，取规范化后的 Levenshtein 相似度，范围 0.0 ~ 1.0
+pub fn similarity(a: &str, b: &str) -> f64 {
+    strsim::normalized_levenshtein(&normalize_for_comparison(a), &normalize_for_comparison(b))
+}
+
+/// 为每个新 key 找出已有文本中最相似的一条，相似度不低于 threshold 时报告
+pub fn find_similar(new: &Content, existing: &Content, threshold: f64) -> Vec<SimilarMatch> {
+    let mut matches: Vec<SimilarMatch> = new
+        .iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .filter_map(|(key, value)| {
+            existing
+                .iter()
+                .filter(|(other_key, other)| *other_key != key && !other.trim().is_empty())
+                .map(|(other_key, other)| (other_key, other, similarity(value, other)))
+                .filter(|(_, _, score)| *score >= threshold)
+                .max_by(|a, b| a.2.total_cmp(&b.2).then_with(|| b.0.cmp(a.0)))
+                .map(|(other_key, other, score)| SimilarMatch {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    similar_key: other_key.to_string(),
+                    similar_value: other.to_string(),
+                    similarity: score,
+                })
+        })
+        .collect();
+    matches.sort_by(|a, b| a.key.cmp(&b.key));
+    matches
+}
+
+/// 输出相似文本报告，只作提示，不阻止上传
+pub fn print_similar(matches: &[SimilarMatch]) {
+    for m in matches {
+        output::status(
+            output::Status::Skipped,
+            format!(
+                "{}: \"{}\" is {:.0}% similar to {} (\"{}\")",
+                m.key,
+                m.value,
+                m.similarity * 100.0,
+                m.similar_key,
+                m.similar_value
+            ),
+        );
+    }
+}
+
+/// 输出拼写检查结果，返回拼写错误的 key 数量
+pub fn print_misspellings(
+    file: &TranslationFile,
+    misspelled: &BTreeMap<String, Vec<String>>,
+) -> usize {
+    let language = &file.language_code;
+    if misspelled.is_empty() {
+        output::status(
+            output::Status::Ok,
+            format!("{}: no spelling issues", language),
+        );
+        return 0;
+    }
+
+    output::status(
+        output::Status::Failed,
+        format!(
+            "{}: {} keys with spelling issues",
+            language,
+            misspelled.len()
+        ),
+    );
+    let rows = misspelled
+        .iter()
+        .map(|(key, words)| vec![file.location(key), key.clone(), words.join(", ")]);
+    output::table(["Location", "Key", "Misspelled words"], rows);
+    misspelled.len()
+}
+
+/// 输出不是规范格式的翻译文件
+pub fn print_unformatted(paths: &[std::path::PathBuf]) {
+    if paths.is_empty() {
+        output::status(output::Status::Ok, "all translation files are formatted");
+        return;
+    }
+    for path in paths {
+        output::status(
+            output::Status::Failed,
+            format!("{}: not in canonical format", path.display()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translation::to_content;
+
+    #[test]
+    fn test_find_duplicates_groups_normalized_values() {
+        let content = to_content([
+            ("checkout.cancel", "Cancel"),
+            ("dialog.cancel", "cancel"),
+            ("profile.cancel_button", " Cancel. "),
+            ("common.ok", "OK"),
+            ("dialog.ok", "OK"),
+            ("home.title", "Home"),
+            ("empty.a", ""),
+            ("empty.b", " "),
+        ]);
+
+        let groups = find_duplicates(&content);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups[0].keys,
+            vec!["checkout.cancel", "dialog.cancel", "profile.cancel_button"]
+        );
+        assert_eq!(groups[0].value, "Cancel");
+        assert_eq!(groups[0].suggested_key(), "common.cancel");
+        assert_eq!(groups[1].suggested_key(), "common.ok");
+    }
+
+    #[test]
+    fn test_check_options() {
+        let options = CheckOptions::new(false, false, false, true, false);
+        assert!(options.empty_base && !options.missing && !options.all);
+        assert!(CheckOptions::new(false, false, false, false, false).empty_base);
+    }
+
+    #[test]
+    fn test_find_similar_reports_best_match() {
+        let to_map = |pairs: &[(&str, &str)]| to_content(pairs.iter().copied());
+        let existing = to_map(&[
+            ("checkout.confirm_button", "Confirm payment"),
+            ("checkout.cancel", "Cancel"),
+            ("home.title", "Welcome home"),
+        ]);
+        let new = to_map(&[
+            ("checkout.pay_button", "Confirm payments"),
+            ("profile.title", "Edit your profile"),
+        ]);
+
+        let matches = find_similar(&new, &existing, 0.85);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].key, "checkout.pay_button");
+        assert_eq!(matches[0].similar_key, "checkout.confirm_button");
+        assert!(matches[0].similarity > 0.9 && matches[0].similarity < 1.0);
+    }
+}
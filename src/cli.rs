@@ -1,8 +1,42 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::config::{FillMissing, KeyStyle};
+use crate::review::ReviewState;
+
+/// `--version` 的完整输出，包含 build.rs 记录的 commit 和目标平台
+const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("I18N_APP_COMMIT"),
+    " ",
+    env!("I18N_APP_TARGET"),
+    ")"
+);
 
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, long_version = LONG_VERSION)]
 pub struct Cli {
+    /// 离线模式：禁止所有网络请求，只使用本地文件和缓存
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// 禁用彩色输出（也可以设置 NO_COLOR 环境变量）
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// 使用配置文件 environments 中的环境（如 staging、prod），覆盖 host 等配置
+    #[arg(long, global = true)]
+    pub env: Option<String>,
+
+    /// 缓存目录：xdg（系统缓存目录）或路径，覆盖配置文件中的 directories.cache
+    #[arg(long, global = true, value_name = "xdg|PATH")]
+    pub cache_dir: Option<String>,
+
+    /// 日志和运行历史目录：xdg（系统状态目录）或路径，覆盖配置文件中的 directories.state
+    #[arg(long, global = true, value_name = "xdg|PATH")]
+    pub state_dir: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -12,11 +46,46 @@ pub enum Commands {
     /// Initialize a new configuration file
     Init,
 
+    /// 检查配置文件和 .gitignore 等项目设置
+    Doctor {
+        /// 自动修复可以修复的问题，如把 .i18n-app/ 加入 .gitignore
+        #[arg(long)]
+        fix: bool,
+    },
+
     /// Push translation files to the server
     Push {
         /// Path to the directory containing translation files
         #[arg(short, long)]
         path: Option<String>,
+
+        /// 上传前为非基准语言补全缺失的 key：base（基准语言文本，默认）、empty（空字符串）或 placeholder（占位文本）
+        #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "base")]
+        fill_missing: Option<FillMissing>,
+
+        /// 同时上传本地值与服务器不同的 key（列出这些 key 并确认后覆盖服务器上的值）
+        #[arg(long)]
+        overwrite_changed: bool,
+
+        /// 不再询问，直接覆盖
+        #[arg(short, long)]
+        yes: bool,
+
+        /// 推送到 protectedEnvironments 中的环境时不再确认（用于 CI）
+        #[arg(long)]
+        yes_production: bool,
+
+        /// 继续上次中断或部分失败的 push，只上传上传计划中还没有完成的语言，不重新比较本地文件
+        #[arg(long, conflicts_with_all = ["path", "fill_missing", "overwrite_changed"])]
+        resume: bool,
+
+        /// 某种语言上传失败时立即停止（默认继续上传其余语言，最后汇总失败的语言）
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// 依次上传到这些服务器版本，可以多次指定，如 --version 1.4.0 --version 1.5.0（覆盖配置的 pushVersions）
+        #[arg(long = "version", value_name = "VERSION_NO")]
+        versions: Vec<String>,
     },
 
     /// Download translation files from the server
@@ -24,11 +93,618 @@ pub enum Commands {
         /// Path to save the downloaded files
         #[arg(short, long)]
         path: Option<String>,
+
+        /// 输出文件命名模板，支持 {lang}、{namespace}、{subSystem}，如 "{lang}/{namespace}.json"
+        #[arg(long)]
+        name_template: Option<String>,
+
+        #[command(flatten)]
+        key_style: KeyStyleArgs,
+
+        /// 目标目录已存在时不再询问，直接清空后下载（包含非 JSON 文件的目录仍不会被清空）
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// 把服务器上一个版本的翻译复制到另一个版本，只上传值不同的 key（如把文本修复回移到发布分支）
+    Promote {
+        /// 源版本号
+        #[arg(long)]
+        from: String,
+
+        /// 目标版本号
+        #[arg(long)]
+        to: String,
+
+        /// 只复制匹配的 key，支持 * 通配符，以逗号分隔，如 checkout.*
+        #[arg(long, value_delimiter = ',')]
+        keys: Vec<String>,
+
+        /// 只列出每种语言需要复制的 key 数量，不上传
+        #[arg(long)]
+        dry_run: bool,
+
+        /// 目标是 protectedEnvironments 中的环境时不再确认（用于 CI）
+        #[arg(long)]
+        yes_production: bool,
     },
 
     /// 更新到最新版本
     Update,
 
+    /// 按命令定义生成 man 手册页或 Markdown 命令参考，供打包和发布文档使用
+    GenDocs {
+        /// 文档格式
+        #[arg(value_enum)]
+        format: DocsFormat,
+
+        /// 输出位置：man 为目录（每个子命令一个手册页），markdown 为文件。不指定时输出到标准输出
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+
+    /// 显示版本、commit、构建时间和目标平台
+    Version {
+        /// 以 JSON 输出
+        #[arg(long)]
+        json: bool,
+    },
+
     /// 同步翻译文件（从服务器同步到本地）
-    Pull,
+    Pull {
+        /// 为远程存在但本地没有的语言创建本地文件
+        #[arg(long)]
+        create_missing: bool,
+
+        #[command(flatten)]
+        key_style: KeyStyleArgs,
+
+        /// 三方合并遇到冲突时逐个选择保留本地值还是采用服务器的值
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// 把回退链中的翻译写入缺少翻译的语言文件
+        #[arg(long)]
+        materialize_fallbacks: bool,
+
+        /// 即使服务器报告自上次 pull 以来没有变化（taskHash 相同）也重新下载
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// 查看运行日志
+    Logs {
+        /// 打印上一次运行的完整日志，不加此参数时列出所有保留的日志文件
+        #[arg(long)]
+        last: bool,
+    },
+
+    /// 查看本地运行历史（需在配置中开启 history）
+    History {
+        /// 显示最近多少条记录
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// 导出上传审计日志（.i18n-app/audit.log）
+    Audit {
+        /// 只显示该日期（YYYY-MM-DD）及之后的记录
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+
+        /// 输出格式
+        #[arg(long, value_enum, default_value = "table")]
+        format: AuditFormat,
+    },
+
+    /// 导出基准语言和指定语言的翻译（.xlsx、.po），每行带有稳定 ID，交给翻译公司翻译
+    Export {
+        /// 导出的目标语言
+        #[arg(long)]
+        lang: String,
+
+        /// 导出文件
+        #[arg(long)]
+        file: PathBuf,
+
+        /// 只导出目标语言中指定审核状态的 key，如 --only needs-review
+        #[arg(long, value_enum)]
+        only: Option<ReviewState>,
+    },
+
+    /// 把外部交付的翻译文件（.json、.po、.xlsx/.xls/.ods）合并到本地翻译文件，
+    /// 由 export 导出且源文本已经变化的行被跳过
+    Import {
+        /// 导入的语言
+        #[arg(long)]
+        lang: String,
+
+        /// 外部翻译文件
+        #[arg(long)]
+        file: PathBuf,
+
+        /// 不再询问，直接写入
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// 列出服务器端自上次 pull 以来有变化的 term 和修改人（需要服务器提供 change_terms）
+    Changes,
+
+    /// 常驻运行，服务器翻译变化（taskHash 变化）时自动 pull 或提示（见配置 daemon）
+    Daemon,
+
+    /// 列出服务器和本地的所有语言、key 数量和完成度
+    Languages,
+
+    /// 统计本地翻译：每个命名空间的 key 数量、每种语言的词数和字符数，以及基准语言中最长的文本
+    Stats {
+        /// 与该 git 引用（如 v1.2.0、HEAD~20）相比每种语言增加的 key 数量
+        #[arg(long)]
+        since: Option<String>,
+
+        /// 列出基准语言中最长的文本的数量，0 表示不列出
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+
+    /// 估算翻译费用：每种语言未翻译 key 的基准语言词数乘以单价（配置的 wordRates 或 --rate-per-word）
+    Estimate {
+        /// 每个词的单价，用于 wordRates 中没有配置的语言
+        #[arg(long)]
+        rate_per_word: Option<f64>,
+
+        /// 要估算的语言，以逗号分隔，如 de,fr,ja；默认为本地所有非基准语言
+        #[arg(long, value_delimiter = ',')]
+        langs: Vec<String>,
+    },
+
+    /// 保存发布版本的完整翻译快照，之后对比两个版本之间变化的文本
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// 对比本地翻译与最近一次缓存的服务器翻译（无需网络）
+    Diff {
+        /// Path to the directory containing translation files
+        #[arg(short, long)]
+        path: Option<String>,
+    },
+
+    /// 在所有语言的翻译中搜索 key 和值（支持正则表达式），按 文件:key: 值 输出
+    Grep {
+        /// 搜索的正则表达式
+        pattern: String,
+
+        /// 只搜索该语言
+        #[arg(short, long)]
+        lang: Option<String>,
+
+        /// 只搜索 key，不搜索值
+        #[arg(long)]
+        keys_only: bool,
+
+        /// 按普通文本匹配，不作为正则表达式
+        #[arg(short = 'F', long)]
+        fixed_strings: bool,
+
+        /// 忽略大小写
+        #[arg(short, long)]
+        ignore_case: bool,
+    },
+
+    /// 读取本地翻译中一个 key 在各语言的值，指定语言时只输出值本身
+    Get {
+        /// 要读取的 key，如 checkout.title
+        key: String,
+
+        /// 只输出该语言的值
+        #[arg(short, long)]
+        lang: Option<String>,
+    },
+
+    /// 修改本地翻译文件中一个 key 的值（key 不存在时新增），保留文件的格式和注释
+    Set {
+        /// 要修改的 key，如 checkout.title
+        key: String,
+
+        /// 新的值
+        value: String,
+
+        /// 要修改的语言
+        #[arg(short, long)]
+        lang: String,
+
+        /// 配置了多个子系统且无法按 key 确定时，指定子系统
+        #[arg(long)]
+        sub_system: Option<String>,
+
+        /// 修改后立即 push，只有这个 key 会覆盖服务器上不同的值
+        #[arg(long)]
+        push: bool,
+
+        /// push 到受保护环境时不再确认
+        #[arg(long, requires = "push")]
+        yes_production: bool,
+    },
+
+    /// 批量替换本地翻译值中的文本：先列出每个 key 替换前后的值，确认后写入，下次 push 时上传这些修改
+    Replace {
+        /// 要查找的文本
+        #[arg(long)]
+        find: String,
+
+        /// 替换为的文本，使用 --regex 时可以用 $1 引用分组
+        #[arg(long)]
+        replace: String,
+
+        /// 要替换的语言，all 表示所有语言
+        #[arg(short, long, default_value = "all")]
+        lang: String,
+
+        /// 把 --find 作为正则表达式
+        #[arg(long)]
+        regex: bool,
+
+        /// 不再询问，直接替换
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// 显示一个 key 的全部信息：各语言的本地值和缓存的服务器值、注释、审核状态、最近一次上传和源码中的引用（无需网络）
+    Explain {
+        /// 要查看的 key，如 checkout.title
+        key: String,
+    },
+
+    /// 检查本地翻译文件，不指定选项时执行全部检查
+    Check {
+        /// 查找基准语言中文本相同的 key，建议合并为共享 key
+        #[arg(long)]
+        duplicates: bool,
+
+        /// 使用配置的词典检查拼写
+        #[arg(long)]
+        spelling: bool,
+
+        /// 列出各语言缺少翻译的 key，回退链中有翻译的 key 视为已翻译
+        #[arg(long)]
+        missing: bool,
+
+        /// 列出基准语言中文本为空的 key，emptyBaseValues 为 error 时以退出码 2 退出
+        #[arg(long)]
+        empty_base: bool,
+
+        /// 检查翻译文件是否为规范格式（key 排序、缩进、换行符等与配置一致），不一致时以退出码 2 退出
+        #[arg(long, visible_alias = "check-format")]
+        format: bool,
+    },
+
+    /// 把本地翻译文件改写为配置的规范格式（key 排序、缩进、换行符、Unicode 规范化等）
+    Fmt {
+        /// 只检查不修改，有文件不是规范格式时以退出码 2 退出
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// 按 key 的第一段把每种语言的单个翻译文件拆分为命名空间文件（或反过来合并），并更新配置中的 include
+    Reorganize {
+        /// 拆分为命名空间文件，如 locales/en-US.json → locales/en-US/common.json
+        #[arg(long, required_unless_present = "merge", conflicts_with = "merge")]
+        by_prefix: bool,
+
+        /// 把命名空间文件合并为每种语言一个文件
+        #[arg(long)]
+        merge: bool,
+
+        /// 只显示要改写的文件，不修改
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// 按配置的规则检查翻译内容（缺失、空值、占位符、长度、标签、重复），有 error 级别的问题时以退出码 2 退出
+    Lint {
+        /// 把当前所有问题记录到 .i18n-app/lint-baseline.json，之后只有新问题会导致失败
+        #[arg(long)]
+        write_baseline: bool,
+
+        /// 输出格式，sarif 输出 SARIF 2.1.0 JSON，可上传到 GitHub code scanning 等代码审查工具。
+        /// 默认 text，指定 --out 时默认 sarif
+        #[arg(long, value_enum)]
+        format: Option<LintFormat>,
+
+        /// 把 SARIF 写入文件而不是标准输出，不能和 --format text 一起使用
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// 以 stdio 运行语言服务器（LSP），为编辑器提供诊断、悬停显示翻译和跳转到 key 的定义
+    Lsp,
+
+    /// 管理 key 的审核状态（translated / reviewed / needs-review）
+    Review {
+        #[command(subcommand)]
+        action: ReviewAction,
+    },
+
+    /// 管理保存在系统钥匙串中的 API token 和 GitHub token
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+
+    /// 验证当前凭据并显示对应的用户和权限，等同于 auth status
+    Whoami,
+
+    /// 生成供应用运行时加载的静态翻译包：每种语言一个 minified JSON 和带哈希的清单
+    Bundle {
+        /// 输出目录
+        #[arg(long, default_value = "dist/i18n")]
+        out: String,
+        /// 额外生成 gzip 预压缩文件（.gz）
+        #[arg(long)]
+        gzip: bool,
+        /// 额外生成 brotli 预压缩文件（.br）
+        #[arg(long)]
+        brotli: bool,
+        /// 按根节点的 key 拆分，每个命名空间一个文件
+        #[arg(long)]
+        split_namespaces: bool,
+    },
+
+    /// 启动本地 HTTP 服务器，在 /i18n/{lang}.json 提供本地翻译，文件变化时通过 /i18n/events 通知
+    Serve {
+        /// 监听端口
+        #[arg(long, default_value_t = 8787)]
+        port: u16,
+        /// 启动前先从服务器 pull 最新翻译
+        #[arg(long)]
+        pull: bool,
+    },
+
+    /// 根据基准语言生成 key 的类型定义或常量文件
+    Codegen {
+        #[command(subcommand)]
+        target: CodegenTarget,
+
+        /// 生成后继续监视本地翻译文件，有变化时重新生成
+        #[arg(long, global = true)]
+        watch: bool,
+    },
+
+    /// 查看最近一次下载的服务器翻译缓存（无需网络）
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// 清理 .i18n-app 工作目录，不指定选项时清理全部内容
+    Clean {
+        /// 清理服务器翻译缓存
+        #[arg(long)]
+        cache: bool,
+        /// 清理历史运行日志（保留本次运行的日志）
+        #[arg(long)]
+        logs: bool,
+        /// 清理备份文件
+        #[arg(long)]
+        backups: bool,
+        /// 清理缓存、日志、备份和预览文件
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// 其他命令交给 PATH 中的 `i18n-app-<命令>` 插件执行
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub enum CodegenTarget {
+    /// TypeScript 类型定义，包含所有 key 的联合类型
+    Ts {
+        /// 输出文件
+        #[arg(long, default_value = "src/i18n/keys.d.ts")]
+        out: String,
+        /// 同时根据占位符生成每个 key 的插值参数类型
+        #[arg(long)]
+        params: bool,
+    },
+    /// Rust 模块，包含 Key 枚举和内嵌的各语言翻译
+    Rust {
+        /// 输出文件
+        #[arg(long, default_value = "src/i18n.rs")]
+        out: String,
+    },
+    /// Dart 常量类 TranslationKeys（key 名称 -> 点号分隔的 key）
+    Dart {
+        /// 输出文件
+        #[arg(long, default_value = "lib/i18n/translation_keys.dart")]
+        out: String,
+    },
+    /// Kotlin 常量对象 TranslationKeys
+    Kotlin {
+        /// 输出文件
+        #[arg(long, default_value = "TranslationKeys.kt")]
+        out: String,
+    },
+    /// Swift 常量枚举 TranslationKeys
+    Swift {
+        /// 输出文件
+        #[arg(long, default_value = "TranslationKeys.swift")]
+        out: String,
+    },
+    /// VS Code 代码片段文件，补全 key 并以基准语言文本作为说明
+    Snippets {
+        /// 输出文件
+        #[arg(long, default_value = ".vscode/i18n-keys.code-snippets")]
+        out: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReviewAction {
+    /// 设置 key 的审核状态
+    Set {
+        /// 语言代码
+        #[arg(short, long)]
+        lang: String,
+
+        /// 审核状态
+        #[arg(value_enum)]
+        state: ReviewState,
+
+        /// 要设置的 key
+        #[arg(required_unless_present = "all")]
+        keys: Vec<String>,
+
+        /// 设置该语言的所有 key
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// 列出 key 的审核状态
+    List {
+        /// 只列出指定语言
+        #[arg(short, long)]
+        lang: Option<String>,
+
+        /// 只列出指定状态的 key，如 --only needs-review
+        #[arg(long, value_enum)]
+        only: Option<ReviewState>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// 把本地所有语言的翻译保存为快照 .i18n-app/releases/<name>.json
+    Save {
+        /// 快照名称，通常是版本号，如 v2.3.0
+        name: String,
+
+        /// 覆盖同名快照
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// 列出快照 <from> 与快照 <to>（默认为当前本地翻译）之间新增、修改和删除的文本
+    Diff {
+        /// 旧版本的快照名称
+        from: String,
+
+        /// 新版本的快照名称，不指定时与当前本地翻译对比
+        to: Option<String>,
+
+        /// 只列出指定语言
+        #[arg(short, long)]
+        lang: Option<String>,
+    },
+
+    /// 列出所有快照
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// 列出缓存中的语言、key 数量、文件大小、下载时间和校验结果
+    Ls,
+
+    /// 输出缓存中一种语言的 JSON
+    Show {
+        /// 语言代码
+        lang: String,
+
+        /// 输出按 key 排序的扁平 JSON（key 以 `.` 连接）
+        #[arg(long)]
+        flat: bool,
+
+        /// 语言在多个子系统的缓存中时，指定子系统
+        #[arg(long)]
+        sub_system: Option<String>,
+    },
+
+    /// 输出各子系统的缓存目录
+    Path,
+
+    /// 删除当前配置的子系统的缓存，下次 push 时重新下载
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum AuthAction {
+    /// 把 token 保存到系统钥匙串，API token 按当前环境的 host 区分
+    Login {
+        /// 保存检查更新时使用的 GitHub token，并删除明文配置文件中的 token
+        #[arg(long)]
+        github: bool,
+
+        /// 从标准输入读取 token，适合脚本和 CI
+        #[arg(long)]
+        with_token: bool,
+    },
+
+    /// 从系统钥匙串删除 token
+    Logout {
+        /// 删除 GitHub token
+        #[arg(long)]
+        github: bool,
+    },
+
+    /// 显示 token 的来源，并向服务器验证凭据，输出对应的用户和权限
+    #[command(visible_alias = "whoami")]
+    Status,
+}
+
+/// lint 结果的输出格式
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintFormat {
+    /// 表格
+    Text,
+    /// SARIF 2.1.0 JSON
+    Sarif,
+}
+
+/// gen-docs 生成的文档格式
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocsFormat {
+    /// roff 格式的 man 手册页
+    Man,
+    /// Markdown 命令参考
+    Markdown,
+}
+
+/// 审计日志的导出格式
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditFormat {
+    /// 表格
+    Table,
+    /// CSV，keys 以空格分隔
+    Csv,
+    /// 每行一条 JSON 记录
+    Json,
+}
+
+/// 输出文件的 key 结构，优先于配置中的 keyStyle
+#[derive(Args, Debug, Clone, Copy)]
+pub struct KeyStyleArgs {
+    /// 以扁平 key（"a.b.c": "x"）写入文件
+    #[arg(long, conflicts_with = "nested")]
+    pub flat: bool,
+
+    /// 以嵌套 JSON 写入文件
+    #[arg(long)]
+    pub nested: bool,
+}
+
+impl KeyStyleArgs {
+    pub fn key_style(&self) -> Option<KeyStyle> {
+        if self.flat {
+            Some(KeyStyle::Flat)
+        } else if self.nested {
+            Some(KeyStyle::Nested)
+        } else {
+            None
+        }
+    }
 }
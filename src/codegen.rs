@@ -0,0 +1,503 @@
+use std::collections::BTreeMap;
+
+use crate::translation;
+
+/// 代码生成的目标语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    TypeScript { params: bool },
+    Rust,
+    Dart,
+    Kotlin,
+    Swift,
+    Snippets,
+}
+
+/// 各语言的扁平翻译，key 为语言代码
+pub type Languages = BTreeMap<String, BTreeMap<String, String>>;
+
+impl Target {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Target::TypeScript { .. } => "ts",
+            Target::Rust => "rust",
+            Target::Dart => "dart",
+            Target::Kotlin => "kotlin",
+            Target::Swift => "swift",
+            Target::Snippets => "snippets",
+        }
+    }
+
+    /// 生成代码，key 以基准语言为准
+    pub fn generate(&self, base_language: &str, languages: &Languages) -> String {
+        let empty = BTreeMap::new();
+        let base = languages.get(base_language).unwrap_or(&empty);
+        match self {
+            Target::TypeScript { params } => typescript(base, *params),
+            Target::Rust => rust(base, languages),
+            Target::Dart => dart(base),
+            Target::Kotlin => kotlin(base),
+            Target::Swift => swift(base),
+            Target::Snippets => snippets(base),
+        }
+    }
+}
+
+/// 生成文件开头的说明
+const GENERATED_HEADER: &str = "由 i18n-app codegen 根据基准语言生成，请勿手动修改";
+
+/// 生成 TypeScript 类型定义：所有 key 的联合类型，`params` 时附带每个 key 的插值参数类型
+pub fn typescript(base: &BTreeMap<String, String>, params: bool) -> String {
+    let mut output = format!("// {}\n\n", GENERATED_HEADER);
+
+    if base.is_empty() {
+        output.push_str("export type TranslationKey = never;\n");
+    } else {
+        output.push_str("export type TranslationKey =\n");
+        for key in base.keys() {
+            output.push_str(&format!("  | {}\n", quote(key)));
+        }
+        output.truncate(output.len() - 1);
+        output.push_str(";\n");
+    }
+
+    if params {
+        output.push_str("\nexport interface TranslationParams {\n");
+        for (key, value) in base {
+            let placeholders = translation::extract_placeholders(value);
+            let fields = if placeholders.is_empty() {
+                "Record<string, never>".to_string()
+            } else {
+                let fields: Vec<String> = placeholders
+                    .iter()
+                    .map(|name| format!("{}: string | number", quote(name)))
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            };
+            output.push_str(&format!("  {}: {};\n", quote(key), fields));
+        }
+        output.push_str("}\n");
+    }
+    output
+}
+
+/// 生成 Rust 模块：`Key` 枚举和以静态数组内嵌的各语言翻译，运行时不需要解析 JSON
+pub fn rust(base: &BTreeMap<String, String>, languages: &Languages) -> String {
+    let keys: Vec<&String> = base.keys().collect();
+    let variants = unique_names(keys.iter().map(|key| upper_camel_case(key)));
+    let mut output = format!("// {}\n\n", GENERATED_HEADER);
+
+    output.push_str("/// 翻译 key\n");
+    output.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n");
+    output.push_str("pub enum Key {\n");
+    for (key, variant) in keys.iter().zip(&variants) {
+        output.push_str(&format!("    /// `{}`\n    {},\n", key, variant));
+    }
+    output.push_str("}\n\n");
+
+    output.push_str("impl Key {\n");
+    output.push_str(&format!("    pub const ALL: [Key; {}] = [", keys.len()));
+    output.push_str(
+        &variants
+            .iter()
+            .map(|v| format!("Key::{}", v))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    output.push_str("];\n\n");
+    output.push_str("    /// 点号分隔的 key\n");
+    output.push_str(
+        "    pub fn as_str(self) -> &'static str {\n        KEYS[self as usize]\n    }\n\n",
+    );
+    output.push_str("    pub fn from_key(key: &str) -> Option<Key> {\n");
+    output.push_str("        KEYS.binary_search(&key).ok().map(|i| Key::ALL[i])\n    }\n}\n\n");
+
+    output.push_str(&format!(
+        "const KEYS: [&str; {}] = [{}];\n\n",
+        keys.len(),
+        keys.iter()
+            .map(|k| format!("{:?}", k))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    output.push_str(&format!(
+        "pub const LANGUAGES: [&str; {}] = [{}];\n\n",
+        languages.len(),
+        languages
+            .keys()
+            .map(|l| format!("{:?}", l))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    for (lang, content) in languages {
+        output.push_str(&format!(
+            "const {}: [Option<&str>; {}] = [\n",
+            language_const(lang),
+            keys.len()
+        ));
+        for key in &keys {
+            match content.get(*key) {
+                Some(value) => output.push_str(&format!("    Some({:?}),\n", value)),
+                None => output.push_str("    None,\n"),
+            }
+        }
+        output.push_str("];\n\n");
+    }
+
+    output.push_str("/// 查找翻译，语言或 key 不存在时返回 None\n");
+    output.push_str("pub fn translate(lang: &str, key: Key) -> Option<&'static str> {\n");
+    output.push_str("    let values = match lang {\n");
+    for lang in languages.keys() {
+        output.push_str(&format!(
+            "        {:?} => &{},\n",
+            lang,
+            language_const(lang)
+        ));
+    }
+    output.push_str("        _ => return None,\n    };\n    values[key as usize]\n}\n");
+    output
+}
+
+/// `home.page_title` -> `HomePageTitle`，不以字母开头时加上 `K` 前缀
+fn upper_camel_case(key: &str) -> String {
+    let mut name: String = key
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name.insert(0, 'K');
+    }
+    if name == "Self" {
+        name.push('_');
+    }
+    name
+}
+
+/// 类名，Dart/Kotlin/Swift 的常量都放在这个类型中
+const CONSTANTS_TYPE: &str = "TranslationKeys";
+
+/// Dart 常量类，常量名为 lowerCamelCase
+pub fn dart(base: &BTreeMap<String, String>) -> String {
+    let names = unique_names(base.keys().map(|key| {
+        let name = lower_camel_case(key);
+        if DART_KEYWORDS.contains(&name.as_str()) {
+            name + "_"
+        } else {
+            name
+        }
+    }));
+    let mut output = format!(
+        "// {}\n\nclass {} {{\n  {}._();\n\n",
+        GENERATED_HEADER, CONSTANTS_TYPE, CONSTANTS_TYPE
+    );
+    for (key, name) in base.keys().zip(&names) {
+        output.push_str(&format!(
+            "  static const String {} = {};\n",
+            name,
+            quote_escaping_dollar(key)
+        ));
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// Kotlin 常量对象，常量名为 SCREAMING_SNAKE_CASE
+pub fn kotlin(base: &BTreeMap<String, String>) -> String {
+    let names = unique_names(base.keys().map(|key| screaming_snake_case(key)));
+    let mut output = format!("// {}\n\nobject {} {{\n", GENERATED_HEADER, CONSTANTS_TYPE);
+    for (key, name) in base.keys().zip(&names) {
+        output.push_str(&format!(
+            "    const val {} = {}\n",
+            name,
+            quote_escaping_dollar(key)
+        ));
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// Swift 常量枚举，常量名为 lowerCamelCase，与关键字重名时加反引号
+pub fn swift(base: &BTreeMap<String, String>) -> String {
+    let names = unique_names(base.keys().map(|key| lower_camel_case(key)));
+    let mut output = format!("// {}\n\nenum {} {{\n", GENERATED_HEADER, CONSTANTS_TYPE);
+    for (key, name) in base.keys().zip(&names) {
+        let name = if SWIFT_KEYWORDS.contains(&name.as_str()) {
+            format!("`{}`", name)
+        } else {
+            name.clone()
+        };
+        output.push_str(&format!("    static let {} = {}\n", name, quote(key)));
+    }
+    output.push_str("}\n");
+    output
+}
+
+const DART_KEYWORDS: &[&str] = &[
+    "assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+    "enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new", "null",
+    "rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var", "void", "while",
+    "with",
+];
+
+const SWIFT_KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "continue",
+    "default",
+    "defer",
+    "do",
+    "else",
+    "enum",
+    "extension",
+    "false",
+    "for",
+    "func",
+    "guard",
+    "if",
+    "import",
+    "in",
+    "init",
+    "is",
+    "let",
+    "nil",
+    "operator",
+    "protocol",
+    "repeat",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "super",
+    "switch",
+    "throw",
+    "throws",
+    "true",
+    "try",
+    "var",
+    "where",
+    "while",
+];
+
+/// 生成 VS Code 代码片段文件（`.code-snippets`）：输入 key 即可补全，基准语言文本作为说明
+pub fn snippets(base: &BTreeMap<String, String>) -> String {
+    let snippets: BTreeMap<&String, serde_json::Value> = base
+        .iter()
+        .map(|(key, value)| {
+            let snippet = serde_json::json!({
+                "prefix": key,
+                // 代码片段中 `$` 和 `\` 有特殊含义
+                "body": key.replace('\\', "\\\\").replace('$', "\\$"),
+                "description": value,
+            });
+            (key, snippet)
+        })
+        .collect();
+    format!(
+        "// {}\n{}\n",
+        GENERATED_HEADER,
+        serde_json::to_string_pretty(&snippets).unwrap_or_default()
+    )
+}
+
+/// `home.page_title` -> `homePageTitle`
+fn lower_camel_case(key: &str) -> String {
+    let name = upper_camel_case(key);
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// `home.pageTitle` -> `HOME_PAGE_TITLE`，不以字母开头时加上 `K_` 前缀
+fn screaming_snake_case(key: &str) -> String {
+    let mut name = String::new();
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !name.is_empty() && !name.ends_with('_') {
+                name.push('_');
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lower {
+            name.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        name.push(c.to_ascii_uppercase());
+    }
+    let mut name = name.trim_end_matches('_').to_string();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name.insert_str(0, "K_");
+    }
+    name
+}
+
+/// 重名时依次加上数字后缀
+fn unique_names(names: impl Iterator<Item = String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    names
+        .map(|name| {
+            let mut unique = name.clone();
+            let mut suffix = 2;
+            while !seen.insert(unique.clone()) {
+                unique = format!("{}{}", name, suffix);
+                suffix += 1;
+            }
+            unique
+        })
+        .collect()
+}
+
+/// `zh-CN` -> `LANG_ZH_CN`
+fn language_const(lang: &str) -> String {
+    let name: String = lang
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("LANG_{}", name)
+}
+
+/// Dart 和 Kotlin 的字符串中 `$` 表示插值，需要转义
+fn quote_escaping_dollar(text: &str) -> String {
+    quote(text).replace('$', "\\$")
+}
+
+/// JSON 字符串字面量同时也是合法的 TypeScript 字符串字面量
+fn quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typescript() {
+        let base = BTreeMap::from([
+            ("home.title".to_string(), "Home".to_string()),
+            (
+                "home.greeting".to_string(),
+                "Hello {name}, {count, plural, one {# message} other {# messages}}".to_string(),
+            ),
+        ]);
+
+        assert_eq!(
+            typescript(&base, true),
+            r#"// 由 i18n-app codegen 根据基准语言生成，请勿手动修改
+
+export type TranslationKey =
+  | "home.greeting"
+  | "home.title";
+
+export interface TranslationParams {
+  "home.greeting": { "name": string | number; "count": string | number };
+  "home.title": Record<string, never>;
+}
+"#
+        );
+        assert!(
+            typescript(&BTreeMap::new(), false).ends_with("export type TranslationKey = never;\n")
+        );
+    }
+
+    #[test]
+    fn test_mobile_constants() {
+        let base = BTreeMap::from([
+            ("home.pageTitle".to_string(), "Home".to_string()),
+            ("default".to_string(), "Default".to_string()),
+            ("price.$amount".to_string(), "{amount}".to_string()),
+        ]);
+
+        let dart = dart(&base);
+        assert!(dart.contains("class TranslationKeys {\n  TranslationKeys._();\n"));
+        assert!(dart.contains("  static const String default_ = \"default\";\n"));
+        assert!(dart.contains("  static const String homePageTitle = \"home.pageTitle\";\n"));
+        assert!(dart.contains(r#"  static const String priceAmount = "price.\$amount";"#));
+
+        let kotlin = kotlin(&base);
+        assert!(kotlin.contains("object TranslationKeys {\n"));
+        assert!(kotlin.contains("    const val HOME_PAGE_TITLE = \"home.pageTitle\"\n"));
+        assert!(kotlin.contains(r#"    const val PRICE_AMOUNT = "price.\$amount""#));
+
+        let swift = swift(&base);
+        assert!(swift.contains("enum TranslationKeys {\n"));
+        assert!(swift.contains("    static let `default` = \"default\"\n"));
+        assert!(swift.contains("    static let priceAmount = \"price.$amount\"\n"));
+    }
+
+    #[test]
+    fn test_snippets() {
+        let base = BTreeMap::from([
+            ("home.title".to_string(), "Home".to_string()),
+            ("price.$amount".to_string(), "{amount} off".to_string()),
+        ]);
+        assert_eq!(
+            snippets(&base),
+            r#"// 由 i18n-app codegen 根据基准语言生成，请勿手动修改
+{
+  "home.title": {
+    "body": "home.title",
+    "description": "Home",
+    "prefix": "home.title"
+  },
+  "price.$amount": {
+    "body": "price.\\$amount",
+    "description": "{amount} off",
+    "prefix": "price.$amount"
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_rust() {
+        let languages = Languages::from([
+            (
+                "en-US".to_string(),
+                BTreeMap::from([
+                    ("home.page_title".to_string(), "Home".to_string()),
+                    ("home.page-title".to_string(), "Home \"quoted\"".to_string()),
+                    ("404".to_string(), "Not found".to_string()),
+                ]),
+            ),
+            (
+                "zh-CN".to_string(),
+                BTreeMap::from([("home.page_title".to_string(), "首页".to_string())]),
+            ),
+        ]);
+
+        let output = Target::Rust.generate("en-US", &languages);
+        assert!(output.contains("    /// `404`\n    K404,\n"));
+        assert!(
+            output.contains("    HomePageTitle,\n    /// `home.page_title`\n    HomePageTitle2,\n")
+        );
+        assert!(output
+            .contains(r#"const KEYS: [&str; 3] = ["404", "home.page-title", "home.page_title"];"#));
+        assert!(output.contains(
+            "const LANG_ZH_CN: [Option<&str>; 3] = [\n    None,\n    None,\n    Some(\"首页\"),\n];"
+        ));
+        assert!(output.contains(r#"    Some("Home \"quoted\""),"#));
+        assert!(output.contains(r#"        "zh-CN" => &LANG_ZH_CN,"#));
+    }
+}
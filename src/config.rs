@@ -1,52 +1,950 @@
-use anyhow::{anyhow, Result};
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-const DEFAULT_CONFIG_FILE: &str = ".i18n-app.json";
+use crate::credentials::{self, Credential};
+use crate::translation;
 
-#[derive(Debug, Serialize, Deserialize)]
+pub const DEFAULT_CONFIG_FILE: &str = ".i18n-app.json";
+const DEFAULT_UPLOAD_CHUNK_SIZE: usize = 500;
+const DEFAULT_UPLOAD_RETRIES: u32 = 3;
+const DEFAULT_UPLOAD_CONCURRENCY: usize = 1;
+const DEFAULT_API_VERSION: &str = "1";
+const DEFAULT_FUZZY_MATCH_THRESHOLD: f64 = 0.85;
+const DEFAULT_MISSING_PLACEHOLDER: &str = "__MISSING__";
+const DEFAULT_GIT_BRANCH: &str = "main";
+const DEFAULT_BLOB_CACHE_CONTROL: &str = "public, max-age=300";
+const DEFAULT_MAX_LENGTH_RATIO: f64 = 3.0;
+const DEFAULT_DAEMON_INTERVAL_SECONDS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub host: String,
-    #[serde(rename = "subSystemName")]
-    pub sub_system_name: String,
+    /// 子系统名称，配置文件中可以是字符串或字符串数组
+    #[serde(
+        rename = "subSystemName",
+        deserialize_with = "deserialize_sub_systems",
+        serialize_with = "serialize_sub_systems"
+    )]
+    pub sub_systems: Vec<String>,
     #[serde(rename = "productCode")]
     pub product_code: String,
     #[serde(rename = "versionNo")]
     pub version_no: String,
+    /// push 时依次上传到这些服务器版本（如同时维护的多个发布分支），为空时只上传到 versionNo。
+    /// 命令行的 `--version` 优先于配置
+    #[serde(
+        rename = "pushVersions",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub push_versions: Vec<String>,
     #[serde(rename = "baseLanguage")]
     pub base_language: String,
     #[serde(rename = "previewMode")]
     pub preview_mode: String,
     #[serde(rename = "pathPrefix")]
     pub path_prefix: String,
+    /// 翻译文件所在的目录，include 和 exclude 相对于这个目录，默认是当前目录。
+    /// 命令行参数 `--path` 优先于配置
+    #[serde(rename = "basePath", default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
     pub include: Vec<String>,
     pub exclude: Vec<String>,
+    /// 子系统到 include 规则的映射，未配置的子系统使用全局 include
+    #[serde(
+        rename = "subSystemInclude",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub sub_system_include: HashMap<String, Vec<String>>,
+    /// 按本地文件指定上传时使用的服务器 path，使用第一条匹配的规则，
+    /// 没有匹配的规则时使用 pathPrefix 加上文件的父目录
+    #[serde(rename = "uploadPaths", default, skip_serializing_if = "Vec::is_empty")]
+    pub upload_paths: Vec<UploadPathRule>,
+    /// 按本地文件指定占位符语法，push/pull 时与服务器使用的 `{name}` 互相转换，
+    /// 使用第一条匹配的规则，没有匹配的规则时不转换
+    #[serde(
+        rename = "placeholderStyles",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub placeholder_styles: Vec<PlaceholderStyleRule>,
+    /// 单次上传请求包含的最大 key 数量，0 表示不分片
+    #[serde(rename = "uploadChunkSize", default = "default_upload_chunk_size")]
+    pub upload_chunk_size: usize,
+    /// 每个分片上传失败后的最大重试次数
+    #[serde(rename = "uploadRetries", default = "default_upload_retries")]
+    pub upload_retries: u32,
+    /// push 时同时上传的语言数量，pull 时同时下载的语言数量
+    #[serde(rename = "uploadConcurrency", default = "default_upload_concurrency")]
+    pub upload_concurrency: usize,
+    /// 每秒最多发送的 API 请求数，不设置表示不限流
+    #[serde(
+        rename = "requestsPerSecond",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub requests_per_second: Option<f64>,
+    /// 以 gzip 压缩上传请求体，并请求服务器压缩响应
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compression: bool,
+    /// pull 时不会被服务器内容覆盖的 key，支持 `legal.*` 这样的通配符
+    #[serde(
+        rename = "protectedKeys",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub protected_keys: Vec<String>,
+    /// push 前是否为非基准语言补全缺失的 key，以及补全时使用的值，不设置表示不补全
+    #[serde(
+        rename = "fillMissingKeys",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fill_missing_keys: Option<FillMissing>,
+    /// 基准语言中的空文本：error 时 push 失败，warn（默认）时提示，allow 时不检查
+    #[serde(
+        rename = "emptyBaseValues",
+        default,
+        skip_serializing_if = "EmptyBasePolicy::is_default"
+    )]
+    pub empty_base_values: EmptyBasePolicy,
+    /// fillMissingKeys 为 placeholder 时写入的占位文本
+    #[serde(rename = "missingPlaceholder", default = "default_missing_placeholder")]
+    pub missing_placeholder: String,
+    /// 统计完成度和缺失翻译时，把与基准语言文本完全相同的翻译视为未翻译
+    #[serde(
+        rename = "identicalAsUntranslated",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub identical_as_untranslated: bool,
+    /// 有意与基准语言相同的文本（如 `OK`）或 key（支持通配符，如 `brand.*`），不视为未翻译
+    #[serde(
+        rename = "identicalAllowlist",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub identical_allowlist: Vec<String>,
+    /// estimate 命令使用的每种语言每个词的翻译单价，如 `{"de": 0.12, "ja": 0.15}`
+    #[serde(
+        rename = "wordRates",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub word_rates: HashMap<String, f64>,
+    /// push 时不会上传的 key，如 `debug.*`、`internal.*`
+    #[serde(rename = "ignoredKeys", default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_keys: Vec<String>,
+    /// pull 时是否为远程独有的语言创建本地文件
+    #[serde(rename = "createMissingLanguages", default)]
+    pub create_missing_languages: bool,
+    /// 是否在 .i18n-app/history.jsonl 中记录每次运行的命令、耗时和结果
+    #[serde(default)]
+    pub history: bool,
+    /// download 输出文件的命名模板，如 `{lang}/{namespace}.json`，默认 `{lang}.json`
+    #[serde(
+        rename = "nameTemplate",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name_template: Option<String>,
+    /// download/pull 写入文件时的 key 结构，不设置时保持服务器返回的结构
+    #[serde(rename = "keyStyle", default, skip_serializing_if = "Option::is_none")]
+    pub key_style: Option<KeyStyle>,
+    /// 读取和写入翻译时是否把文本规范化为 Unicode NFC
+    #[serde(rename = "normalizeNfc", default)]
+    pub normalize_nfc: bool,
+    /// 写入 JSON 文件时的格式
+    #[serde(
+        rename = "jsonFormat",
+        default,
+        skip_serializing_if = "JsonFormat::is_default"
+    )]
+    pub json_format: JsonFormat,
+    /// 翻译文件的 JSON Schema 路径，pull/download 写入本地之前用它校验服务器返回的内容
+    #[serde(
+        rename = "translationSchema",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub translation_schema: Option<String>,
+    /// push 时报告与已有文本相似的新 key 的相似度阈值，大于 1 表示关闭
+    #[serde(
+        rename = "fuzzyMatchThreshold",
+        default = "default_fuzzy_match_threshold"
+    )]
+    pub fuzzy_match_threshold: f64,
+    /// 语言的回退链，如 `{"pt-PT": ["pt-BR", "en-US"]}`，按顺序查找缺失的翻译
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fallbacks: HashMap<String, Vec<String>>,
+    /// check 命令的拼写检查配置
+    #[serde(
+        rename = "spellCheck",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub spell_check: Option<SpellCheckConfig>,
+    /// lint 命令的规则级别和参数
+    #[serde(default, skip_serializing_if = "LintConfig::is_default")]
+    pub lint: LintConfig,
+    /// push/pull 完成后发送通知的 webhook
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+    /// push/pull 前后执行的 shell 命令
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+    /// daemon 命令发现服务器变化时的处理方式和请求间隔
+    #[serde(default, skip_serializing_if = "DaemonConfig::is_default")]
+    pub daemon: DaemonConfig,
+    /// 缓存和日志等文件的存放位置，默认位于项目的 `.i18n-app` 目录
+    #[serde(default, skip_serializing_if = "DirectoriesConfig::is_default")]
+    pub directories: DirectoriesConfig,
+    /// 翻译存储后端，默认使用 Locazy 服务
+    #[serde(default, skip_serializing_if = "BackendConfig::is_default")]
+    pub backend: BackendConfig,
+    /// 请求的后端 API 版本，用于后端接口迁移期间的版本协商
+    #[serde(rename = "apiVersion", default = "default_api_version")]
+    pub api_version: String,
+    /// 环境名到 host 等配置的映射，通过 `--env` 选择，如 `{"staging": {"host": "..."}}`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub environments: HashMap<String, EnvironmentConfig>,
+    /// push 到这些环境前需要确认或使用 `--yes-production`，如 `["prod"]`
+    #[serde(
+        rename = "protectedEnvironments",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub protected_environments: Vec<String>,
+    /// 离线模式，由命令行参数 `--offline` 设置，不写入配置文件
+    #[serde(skip)]
+    pub offline: bool,
+    /// 当前使用的环境，由命令行参数 `--env` 设置，不写入配置文件
+    #[serde(skip)]
+    pub environment: Option<String>,
+    /// 配置文件所在的目录，translationSchema 等相对路径相对于这个目录，不写入配置文件
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+fn deserialize_sub_systems<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(name) => vec![name],
+        OneOrMany::Many(names) => names,
+    };
+    if names.is_empty() {
+        return Err(serde::de::Error::custom(
+            "subSystemName must contain at least one sub system",
+        ));
+    }
+    Ok(names)
+}
+
+fn serialize_sub_systems<S>(names: &[String], serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match names {
+        [name] => serializer.serialize_str(name),
+        names => names.serialize(serializer),
+    }
+}
+
+fn default_upload_chunk_size() -> usize {
+    DEFAULT_UPLOAD_CHUNK_SIZE
+}
+
+fn default_upload_retries() -> u32 {
+    DEFAULT_UPLOAD_RETRIES
+}
+
+fn default_upload_concurrency() -> usize {
+    DEFAULT_UPLOAD_CONCURRENCY
+}
+
+fn default_api_version() -> String {
+    DEFAULT_API_VERSION.to_string()
+}
+
+fn default_missing_placeholder() -> String {
+    DEFAULT_MISSING_PLACEHOLDER.to_string()
+}
+
+fn default_max_length_ratio() -> f64 {
+    DEFAULT_MAX_LENGTH_RATIO
+}
+
+fn default_fuzzy_match_threshold() -> f64 {
+    DEFAULT_FUZZY_MATCH_THRESHOLD
+}
+
+/// push 补全缺失 key 时使用的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum FillMissing {
+    /// 基准语言的文本
+    Base,
+    /// 空字符串
+    Empty,
+    /// missingPlaceholder 配置的占位文本
+    Placeholder,
+}
+
+/// 基准语言中空文本的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmptyBasePolicy {
+    /// push 和 check 失败
+    Error,
+    /// 输出提示，不影响结果
+    #[default]
+    Warn,
+    /// 不检查
+    Allow,
+}
+
+impl EmptyBasePolicy {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 对应的 lint 级别，基准语言的空文本由 empty-values 规则报告
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::Error => Severity::Error,
+            Self::Warn => Severity::Warning,
+            Self::Allow => Severity::Off,
+        }
+    }
+}
+
+/// 输出文件的 key 结构
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStyle {
+    /// 嵌套对象：`{"a": {"b": "x"}}`
+    Nested,
+    /// 扁平 key：`{"a.b": "x"}`
+    Flat,
+}
+
+/// 写入 JSON 文件的格式，便于与项目的 Prettier 等格式化配置保持一致
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonFormat {
+    /// 缩进：空格数（如 2、4）、"tab" 或 "minified"
+    #[serde(default)]
+    pub indent: JsonIndent,
+    /// 文件末尾是否添加换行
+    #[serde(rename = "trailingNewline", default)]
+    pub trailing_newline: bool,
+    /// 是否把非 ASCII 字符转义为 `\uXXXX`，默认保留 UTF-8 原文
+    #[serde(rename = "escapeNonAscii", default)]
+    pub escape_non_ascii: bool,
+    /// 换行符，默认 LF，不随操作系统变化
+    #[serde(rename = "lineEnding", default)]
+    pub line_ending: LineEnding,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl JsonFormat {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "IndentRepr", into = "IndentRepr")]
+pub enum JsonIndent {
+    Spaces(u8),
+    Tab,
+    Minified,
+}
+
+impl Default for JsonIndent {
+    fn default() -> Self {
+        JsonIndent::Spaces(2)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum IndentRepr {
+    Spaces(u8),
+    Name(String),
+}
+
+impl TryFrom<IndentRepr> for JsonIndent {
+    type Error = String;
+
+    fn try_from(repr: IndentRepr) -> std::result::Result<Self, Self::Error> {
+        match repr {
+            IndentRepr::Spaces(0) => Ok(JsonIndent::Minified),
+            IndentRepr::Spaces(n) => Ok(JsonIndent::Spaces(n)),
+            IndentRepr::Name(name) => match name.as_str() {
+                "tab" => Ok(JsonIndent::Tab),
+                "minified" => Ok(JsonIndent::Minified),
+                _ => name
+                    .parse::<u8>()
+                    .map(|n| JsonIndent::try_from(IndentRepr::Spaces(n)))
+                    .map_err(|_| {
+                        format!(
+                            "invalid indent '{}', expected a number, \"tab\" or \"minified\"",
+                            name
+                        )
+                    })?,
+            },
+        }
+    }
+}
+
+impl From<JsonIndent> for IndentRepr {
+    fn from(indent: JsonIndent) -> Self {
+        match indent {
+            JsonIndent::Spaces(n) => IndentRepr::Spaces(n),
+            JsonIndent::Tab => IndentRepr::Name("tab".to_string()),
+            JsonIndent::Minified => IndentRepr::Name("minified".to_string()),
+        }
+    }
+}
+
+/// 拼写检查配置，只检查配置了词典的语言
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpellCheckConfig {
+    /// 语言到词典文件的映射，支持 hunspell 的 .dic 文件（按同名 .aff 文件展开词缀）或每行一个单词的词表
+    #[serde(default)]
+    pub dictionaries: HashMap<String, Vec<String>>,
+    /// 语言到额外单词的映射
+    #[serde(default)]
+    pub words: HashMap<String, Vec<String>>,
+    /// 所有语言共用的白名单文件（如产品名），每行一个单词，`#` 开头为注释
+    #[serde(
+        rename = "allowlistFile",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allowlist_file: Option<String>,
+}
+
+/// lint 规则的级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    #[serde(alias = "warn")]
+    Warning,
+    Error,
+}
+
+/// lint 配置，`rules` 中未列出的规则使用各自的默认级别
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintConfig {
+    /// 规则 ID 到级别的映射，如 `{"length": "off", "empty-values": "error"}`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rules: HashMap<String, Severity>,
+    /// length 规则：译文长度超过基准语言文本的倍数时报告
+    #[serde(default = "default_max_length_ratio")]
+    pub max_length_ratio: f64,
+    /// length 规则：译文的最大字符数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// nbsp 规则：语言到需要在前面加不换行空格的标点，如 `{"fr": "!?:;»"}`。
+    /// 未配置的语言使用内置规则，设为空字符串可关闭
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub nbsp_before: HashMap<String, String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            rules: HashMap::new(),
+            max_length_ratio: DEFAULT_MAX_LENGTH_RATIO,
+            max_length: None,
+            nbsp_before: HashMap::new(),
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// push/pull 前后执行的 shell 命令，pre 命令以非零退出码结束时中止操作
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HooksConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_push: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_push: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_pull: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_pull: Option<String>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == HooksConfig::default()
+    }
+}
+
+/// 上传路径规则：匹配 `pattern`（相对于 basePath 的 glob，支持 `{lang}`、`{namespace}`）的文件
+/// 上传到服务器的 `path`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadPathRule {
+    pub pattern: String,
+    pub path: String,
+}
+
+/// 本地文件的占位符语法，服务器上统一使用 `{name}`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlaceholderStyle {
+    /// `{name}`，与服务器相同，不转换
+    #[default]
+    Braces,
+    /// `{{name}}`，如 i18next
+    DoubleBraces,
+    /// iOS 的 `%@`，多个占位符时为 `%1$@`、`%2$@`
+    Ios,
+    /// Android 的 `%s`，多个占位符时为 `%1$s`、`%2$s`
+    Android,
+}
+
+impl PlaceholderStyle {
+    /// 按位置而不是名称引用参数的语法
+    pub fn is_positional(self) -> bool {
+        matches!(self, Self::Ios | Self::Android)
+    }
+}
+
+/// 占位符语法规则：匹配 `pattern`（相对于 basePath 的 glob，支持 `{lang}`、`{namespace}`）的文件
+/// 使用 `style`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaceholderStyleRule {
+    pub pattern: String,
+    pub style: PlaceholderStyle,
+}
+
+/// 可以移出项目目录的文件位置，值为 `xdg`（系统的缓存/状态目录）或路径，不设置时使用 `.i18n-app`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoriesConfig {
+    /// 服务器翻译缓存和 git 后端的工作副本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+    /// 运行日志和运行历史
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
+impl DirectoriesConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// daemon 命令的配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonConfig {
+    /// 服务器翻译变化时自动 pull（默认）还是只提示
+    #[serde(default)]
+    pub mode: DaemonMode,
+    /// 两次 long-polling 请求之间的最短间隔（秒）
+    #[serde(default = "default_daemon_interval_seconds")]
+    pub interval_seconds: u64,
+    /// 服务器翻译变化时是否发送桌面通知
+    #[serde(default)]
+    pub desktop_notifications: bool,
+    /// 桌面通知只包含这些命名空间（key 的第一段）中的 key，为空时不过滤
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify_namespaces: Vec<String>,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            mode: DaemonMode::default(),
+            interval_seconds: DEFAULT_DAEMON_INTERVAL_SECONDS,
+            desktop_notifications: false,
+            notify_namespaces: vec![],
+        }
+    }
+}
+
+impl DaemonConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_daemon_interval_seconds() -> u64 {
+    DEFAULT_DAEMON_INTERVAL_SECONDS
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DaemonMode {
+    /// pull 并合并到本地文件
+    #[default]
+    Pull,
+    /// 只提示有变化，不修改本地文件
+    Notify,
+}
+
+/// webhook 通知配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// 消息格式：json（默认）、slack 或 teams
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    #[default]
+    Json,
+    Slack,
+    Teams,
+}
+
+/// 一个环境的配置，未设置的字段沿用顶层配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_no: Option<String>,
+}
+
+/// 翻译存储后端配置，例如 `{"type": "git", "url": "git@example.com:org/i18n.git"}`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackendConfig {
+    /// Locazy 服务（host 等配置项）
+    #[default]
+    Locazy,
+    /// 以单独的 git 仓库（或分支）存储翻译
+    Git(GitBackendConfig),
+    /// 以 S3/GCS/Azure 等对象存储的 bucket 存储翻译，通过 HTTP PUT/GET 读写
+    Blob(BlobBackendConfig),
+}
+
+impl BackendConfig {
+    pub fn is_default(&self) -> bool {
+        *self == BackendConfig::default()
+    }
+}
+
+/// git 仓库后端，翻译文件位于仓库的 `<path>/<子系统>/<语言>.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitBackendConfig {
+    /// 远程仓库地址，`git clone` 支持的任何地址
+    pub url: String,
+    #[serde(default = "default_git_branch")]
+    pub branch: String,
+    /// 翻译文件在仓库中的目录，默认为仓库根目录
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+}
+
+fn default_git_branch() -> String {
+    DEFAULT_GIT_BRANCH.to_string()
+}
+
+/// 对象存储后端，翻译文件位于 `<url>/<prefix>/<子系统>/<语言>.json`，并维护同目录下的 `index.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobBackendConfig {
+    /// bucket 的地址，可以带查询参数（例如 Azure 的 SAS token）
+    pub url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub prefix: String,
+    /// 上传文件的 Cache-Control
+    #[serde(default = "default_blob_cache_control")]
+    pub cache_control: String,
+    /// 每个请求附带的 header，值中的 `${NAME}` 会替换为环境变量，用于认证
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// 设置后使用 AWS Signature V4 对请求签名，用于 S3 及兼容 S3 的存储
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s3: Option<S3SigningConfig>,
+}
+
+/// S3 签名配置，凭证读取 AWS_ACCESS_KEY_ID、AWS_SECRET_ACCESS_KEY 和 AWS_SESSION_TOKEN 环境变量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3SigningConfig {
+    /// bucket 所在的区域，例如 `us-east-1`
+    pub region: String,
+}
+
+fn default_blob_cache_control() -> String {
+    DEFAULT_BLOB_CACHE_CONTROL.to_string()
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             host: "https://backoffice.devactstrade.com".to_string(),
-            sub_system_name: "app".to_string(),
+            sub_systems: vec!["app".to_string()],
             product_code: "bos".to_string(),
             version_no: "1.0.0".to_string(),
+            push_versions: vec![],
             base_language: "en-US".to_string(),
             preview_mode: "1".to_string(),
             path_prefix: "/app".to_string(),
             include: vec![],
             exclude: vec![],
+            sub_system_include: HashMap::new(),
+            upload_chunk_size: DEFAULT_UPLOAD_CHUNK_SIZE,
+            upload_retries: DEFAULT_UPLOAD_RETRIES,
+            upload_concurrency: DEFAULT_UPLOAD_CONCURRENCY,
+            requests_per_second: None,
+            compression: false,
+            protected_keys: vec![],
+            empty_base_values: EmptyBasePolicy::default(),
+            identical_as_untranslated: false,
+            identical_allowlist: vec![],
+            word_rates: HashMap::new(),
+            fill_missing_keys: None,
+            missing_placeholder: default_missing_placeholder(),
+            ignored_keys: vec![],
+            create_missing_languages: false,
+            history: false,
+            name_template: None,
+            base_path: None,
+            upload_paths: Vec::new(),
+            placeholder_styles: Vec::new(),
+            key_style: None,
+            normalize_nfc: false,
+            json_format: JsonFormat::default(),
+            translation_schema: None,
+            fuzzy_match_threshold: DEFAULT_FUZZY_MATCH_THRESHOLD,
+            fallbacks: HashMap::new(),
+            spell_check: None,
+            lint: LintConfig::default(),
+            webhook: None,
+            hooks: HooksConfig::default(),
+            daemon: DaemonConfig::default(),
+            directories: DirectoriesConfig::default(),
+            backend: BackendConfig::default(),
+            api_version: default_api_version(),
+            environments: HashMap::new(),
+            protected_environments: vec![],
+            offline: false,
+            environment: None,
+            root: PathBuf::new(),
         }
     }
 }
 
 impl Config {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
         let content = fs::read_to_string(path)?;
-        let config: Config = serde_json::from_str(&content)?;
+        let mut config: Config = serde_json::from_str(&content)?;
+        config.root = path.parent().map(Path::to_path_buf).unwrap_or_default();
         Ok(config)
     }
 
+    /// 配置中的相对路径按配置文件所在的目录解析，绝对路径保持不变
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// 把配置文件中的 include 模式替换为新的模式。直接替换文本，保留文件的格式和字段顺序
+    pub fn replace_include_patterns<P: AsRef<Path>>(
+        path: P,
+        replacements: &[(String, String)],
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let mut content = fs::read_to_string(path)?;
+        for (old, new) in replacements {
+            content = content.replace(&serde_json::to_string(old)?, &serde_json::to_string(new)?);
+        }
+        fs::write(path, content).with_context(|| format!("写入 {} 失败", path.display()))
+    }
+
+    /// 当前配置对应的（第一个）子系统名称
+    pub fn sub_system_name(&self) -> &str {
+        self.sub_systems.first().map(String::as_str).unwrap_or("")
+    }
+
+    /// 语言的回退链，未配置时为空
+    pub fn fallback_chain(&self, lang: &str) -> &[String] {
+        self.fallbacks.get(lang).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 用 `environments` 中的配置覆盖 host 等字段，环境不存在时返回错误
+    pub fn apply_environment(&mut self, name: &str) -> Result<()> {
+        let Some(env) = self.environments.get(name).cloned() else {
+            let mut known: Vec<&str> = self.environments.keys().map(String::as_str).collect();
+            known.sort();
+            return Err(if known.is_empty() {
+                anyhow!("未知的环境 '{}'，配置文件中没有定义 environments", name)
+            } else {
+                anyhow!("未知的环境 '{}'，可用环境：{}", name, known.join(", "))
+            });
+        };
+        if let Some(host) = env.host {
+            self.host = host;
+        }
+        if let Some(preview_mode) = env.preview_mode {
+            self.preview_mode = preview_mode;
+        }
+        if let Some(product_code) = env.product_code {
+            self.product_code = product_code;
+        }
+        if let Some(version_no) = env.version_no {
+            self.version_no = version_no;
+        }
+        self.environment = Some(name.to_string());
+        Ok(())
+    }
+
+    /// 当前环境在 protectedEnvironments 中时返回环境名
+    pub fn protected_environment(&self) -> Option<&str> {
+        self.environment
+            .as_deref()
+            .filter(|env| self.protected_environments.iter().any(|p| p == env))
+    }
+
+    /// 按子系统拆分配置，每个结果只包含一个子系统，include 规则按映射替换
+    pub fn split_by_sub_system(&self) -> Vec<Config> {
+        self.sub_systems
+            .iter()
+            .map(|name| {
+                let mut config = self.clone();
+                config.sub_systems = vec![name.clone()];
+                if let Some(include) = self.sub_system_include.get(name) {
+                    config.include = include.clone();
+                }
+                config
+            })
+            .collect()
+    }
+
+    /// 上传到另一个服务器版本的配置：本地文件的位置（basePath、include、exclude 和规则的 pattern）
+    /// 仍按当前版本号确定，服务器上的 pathPrefix 等使用 `version_no`
+    pub fn for_version(&self, version_no: &str) -> Config {
+        let mut config = self.clone();
+        config.base_path = self.base_path.as_deref().map(|p| self.render_path_vars(p));
+        config.include = self
+            .include
+            .iter()
+            .map(|p| self.render_path_vars(p))
+            .collect();
+        config.exclude = self
+            .exclude
+            .iter()
+            .map(|p| self.render_path_vars(p))
+            .collect();
+        for rule in &mut config.upload_paths {
+            rule.pattern = self.render_path_vars(&rule.pattern);
+        }
+        for rule in &mut config.placeholder_styles {
+            rule.pattern = self.render_path_vars(&rule.pattern);
+        }
+        config.version_no = version_no.to_string();
+        config
+    }
+
+    /// 文件使用的占位符语法：第一条匹配 placeholderStyles 的规则，没有匹配时与服务器相同
+    pub fn placeholder_style(&self, relative_path: &str) -> PlaceholderStyle {
+        self.placeholder_styles
+            .iter()
+            .find(|rule| translation::path_matches(&rule.pattern, relative_path))
+            .map(|rule| rule.style)
+            .unwrap_or_default()
+    }
+
+    /// 开启 identicalAsUntranslated 时返回该语言使用的 allowlist，基准语言本身不比较
+    pub fn identical_allowlist(&self, lang: &str) -> Option<&[String]> {
+        (self.identical_as_untranslated && lang != self.base_language)
+            .then_some(self.identical_allowlist.as_slice())
+    }
+
+    /// 替换路径中的 `{versionNo}`、`{productCode}`，只有一个子系统时也替换 `{subSystem}`。
+    /// `{lang}`、`{namespace}` 在读写文件时按语言和命名空间确定
+    pub fn render_path_vars(&self, template: &str) -> String {
+        let rendered = template
+            .replace("{versionNo}", &self.version_no)
+            .replace("{productCode}", &self.product_code);
+        match self.sub_systems.as_slice() {
+            [sub_system] => rendered.replace("{subSystem}", sub_system),
+            _ => rendered,
+        }
+    }
+
+    /// 替换 basePath、include、exclude、pathPrefix、nameTemplate 和 uploadPaths 中在运行前就能确定的变量
+    pub fn with_path_vars(mut self) -> Self {
+        self.include = self
+            .include
+            .iter()
+            .map(|p| self.render_path_vars(p))
+            .collect();
+        self.exclude = self
+            .exclude
+            .iter()
+            .map(|p| self.render_path_vars(p))
+            .collect();
+        self.path_prefix = self.render_path_vars(&self.path_prefix);
+        self.base_path = self.base_path.as_deref().map(|p| self.render_path_vars(p));
+        self.upload_paths = self
+            .upload_paths
+            .iter()
+            .map(|rule| UploadPathRule {
+                pattern: self.render_path_vars(&rule.pattern),
+                path: self.render_path_vars(&rule.path),
+            })
+            .collect();
+        self.placeholder_styles = self
+            .placeholder_styles
+            .iter()
+            .map(|rule| PlaceholderStyleRule {
+                pattern: self.render_path_vars(&rule.pattern),
+                style: rule.style,
+            })
+            .collect();
+        self.name_template = self
+            .name_template
+            .as_deref()
+            .map(|t| self.render_path_vars(t));
+        self
+    }
+
     pub fn init() -> Result<()> {
         if Path::new(DEFAULT_CONFIG_FILE).exists() {
             return Err(anyhow!("Configuration file already exists"));
@@ -80,32 +978,80 @@ impl Config {
         Self::from_file(DEFAULT_CONFIG_FILE)
     }
 
-    /// 获取 GitHub Token
+    /// 读取已存在的配置文件，文件不存在或无法解析时返回 None，不会创建默认配置
+    pub fn try_load() -> Option<Self> {
+        if !Path::new(DEFAULT_CONFIG_FILE).exists() {
+            return None;
+        }
+        Self::from_file(DEFAULT_CONFIG_FILE).ok()
+    }
+
+    /// 当前 host 的 API token（`I18N_APP_API_TOKEN` 或系统钥匙串），请求时作为 Bearer token 发送
+    pub fn api_token(&self) -> Option<String> {
+        credentials::get(&Credential::Api {
+            host: self.host.clone(),
+        })
+    }
+
+    /// 获取 GitHub Token：`I18N_APP_GITHUB_TOKEN`、系统钥匙串、明文配置文件，依次查找
     pub fn get_github_token() -> Option<String> {
-        // 获取用户主目录
-        if let Some(home_dir) = dirs::home_dir() {
-            // 构建 ~/.config/i18n-app/config.toml 路径
-            let config_path = home_dir
-                .join(".config")
-                .join("i18n-app")
-                .join("config.toml");
-
-            // 读取并解析配置文件
-            if let Ok(content) = std::fs::read_to_string(config_path) {
-                if let Ok(config) = content.parse::<toml::Table>() {
-                    if let Some(github) = config.get("github") {
-                        if let Some(token) = github.get("token") {
-                            if let Some(token_str) = token.as_str() {
-                                return Some(token_str.to_string());
-                            }
-                        }
-                    }
-                }
-            }
+        if let Some(token) = credentials::get(&Credential::Github) {
+            return Some(token);
         }
 
-        None
+        let token = Self::plaintext_github_token()?;
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        WARNED.call_once(|| {
+            tracing::warn!(
+                "GitHub token 以明文保存在 {}，建议运行 `i18n-app auth login --github` 改为保存到系统钥匙串",
+                user_config_file().display()
+            )
+        });
+        Some(token)
     }
+
+    /// ~/.config/i18n-app/config.toml 中明文保存的 GitHub token
+    pub fn plaintext_github_token() -> Option<String> {
+        let content = fs::read_to_string(user_config_file()).ok()?;
+        let config = content.parse::<toml::Table>().ok()?;
+        config
+            .get("github")?
+            .get("token")?
+            .as_str()
+            .map(str::to_string)
+    }
+
+    /// 从明文配置文件中删除 GitHub token，返回是否删除了内容
+    pub fn remove_plaintext_github_token() -> Result<bool> {
+        let path = user_config_file();
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Ok(false);
+        };
+        let mut config = content
+            .parse::<toml::Table>()
+            .with_context(|| format!("解析 {} 失败", path.display()))?;
+        let Some(github) = config.get_mut("github").and_then(|g| g.as_table_mut()) else {
+            return Ok(false);
+        };
+        if github.remove("token").is_none() {
+            return Ok(false);
+        }
+        if github.is_empty() {
+            config.remove("github");
+        }
+        fs::write(&path, toml::to_string(&config)?)
+            .with_context(|| format!("写入 {} 失败", path.display()))?;
+        Ok(true)
+    }
+}
+
+/// 用户级配置文件 ~/.config/i18n-app/config.toml
+pub fn user_config_file() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".config")
+        .join("i18n-app")
+        .join("config.toml")
 }
 
 #[cfg(test)]
@@ -114,6 +1060,37 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_with_path_vars() {
+        let config = Config {
+            product_code: "shop".to_string(),
+            version_no: "2.0".to_string(),
+            sub_systems: vec!["web".to_string()],
+            include: vec!["{subSystem}/{versionNo}/{lang}/*.json".to_string()],
+            path_prefix: "{productCode}".to_string(),
+            ..Config::default()
+        }
+        .with_path_vars();
+        assert_eq!(config.include, vec!["web/2.0/{lang}/*.json"]);
+        assert_eq!(config.path_prefix, "shop");
+    }
+
+    #[test]
+    fn test_for_version() {
+        let config = Config {
+            version_no: "1.4.0".to_string(),
+            include: vec!["locales/{versionNo}/{lang}.json".to_string()],
+            path_prefix: "/app/{versionNo}".to_string(),
+            ..Config::default()
+        }
+        .for_version("1.5.0")
+        .with_path_vars();
+        // 读取同一份本地文件，上传到另一个版本
+        assert_eq!(config.include, vec!["locales/1.4.0/{lang}.json"]);
+        assert_eq!(config.path_prefix, "/app/1.5.0");
+        assert_eq!(config.version_no, "1.5.0");
+    }
+
     #[test]
     fn test_config_load() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -135,15 +1112,116 @@ mod tests {
 
         let config = Config::from_file(&config_path)?;
         assert_eq!(config.host, "https://test.com");
-        assert_eq!(config.sub_system_name, "test-system");
+        assert_eq!(config.sub_system_name(), "test-system");
         assert_eq!(config.product_code, "test");
         assert_eq!(config.base_language, "en-US");
         assert_eq!(config.preview_mode, "1");
         assert_eq!(config.path_prefix, "test");
+        assert_eq!(config.upload_chunk_size, DEFAULT_UPLOAD_CHUNK_SIZE);
+        assert_eq!(config.upload_retries, DEFAULT_UPLOAD_RETRIES);
 
         Ok(())
     }
 
+    #[test]
+    fn test_config_multiple_sub_systems() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config_path = temp_dir.path().join("test_config.json");
+
+        let test_config = r#"{
+            "host": "https://test.com",
+            "subSystemName": ["app", "admin"],
+            "productCode": "test",
+            "versionNo": "1.0.0",
+            "baseLanguage": "en-US",
+            "previewMode": "1",
+            "pathPrefix": "test",
+            "include": ["*.json"],
+            "exclude": [],
+            "subSystemInclude": {
+                "admin": ["admin/*.json"]
+            }
+        }"#;
+
+        fs::write(&config_path, test_config)?;
+
+        let config = Config::from_file(&config_path)?;
+        assert_eq!(config.sub_systems, vec!["app", "admin"]);
+
+        let configs = config.split_by_sub_system();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].sub_system_name(), "app");
+        assert_eq!(configs[0].include, vec!["*.json"]);
+        assert_eq!(configs[1].sub_system_name(), "admin");
+        assert_eq!(configs[1].include, vec!["admin/*.json"]);
+
+        // 单个子系统仍然序列化为字符串，保持配置文件格式不变
+        let single = serde_json::to_value(&configs[0])?;
+        assert_eq!(single["subSystemName"], "app");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_environment() -> Result<()> {
+        let mut config: Config = serde_json::from_str(
+            r#"{
+            "host": "https://dev.test.com",
+            "subSystemName": "app",
+            "productCode": "test",
+            "versionNo": "1.0.0",
+            "baseLanguage": "en-US",
+            "previewMode": "1",
+            "pathPrefix": "test",
+            "include": ["*.json"],
+            "exclude": [],
+            "environments": {
+                "staging": {"host": "https://staging.test.com"},
+                "prod": {"host": "https://test.com", "previewMode": "0"}
+            }
+        }"#,
+        )?;
+
+        config.apply_environment("prod")?;
+        assert_eq!(config.host, "https://test.com");
+        assert_eq!(config.preview_mode, "0");
+        assert_eq!(config.product_code, "test");
+        assert_eq!(config.environment.as_deref(), Some("prod"));
+        assert_eq!(config.protected_environment(), None);
+        config.protected_environments = vec!["prod".to_string()];
+        assert_eq!(config.protected_environment(), Some("prod"));
+
+        let err = config
+            .apply_environment("qa")
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(err.contains("prod, staging"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_empty_sub_systems() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("test_config.json");
+
+        let invalid_config = r#"{
+            "host": "https://test.com",
+            "subSystemName": [],
+            "productCode": "test",
+            "versionNo": "1.0.0",
+            "baseLanguage": "en-US",
+            "previewMode": "1",
+            "pathPrefix": "test",
+            "include": [],
+            "exclude": []
+        }"#;
+
+        fs::write(&config_path, invalid_config).unwrap();
+
+        assert!(Config::from_file(&config_path).is_err());
+    }
+
     #[test]
     fn test_config_load_missing_field() {
         let temp_dir = TempDir::new().unwrap();
@@ -158,4 +1236,19 @@ mod tests {
         let result = Config::from_file(&config_path);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_json_format_indent() {
+        let parse = |json: &str| serde_json::from_str::<JsonFormat>(json).map(|f| f.indent);
+
+        assert_eq!(parse("{}").unwrap(), JsonIndent::Spaces(2));
+        assert_eq!(parse(r#"{"indent": 4}"#).unwrap(), JsonIndent::Spaces(4));
+        assert_eq!(parse(r#"{"indent": "tab"}"#).unwrap(), JsonIndent::Tab);
+        assert_eq!(parse(r#"{"indent": 0}"#).unwrap(), JsonIndent::Minified);
+        assert_eq!(
+            parse(r#"{"indent": "minified"}"#).unwrap(),
+            JsonIndent::Minified
+        );
+        assert!(parse(r#"{"indent": "spaces"}"#).is_err());
+    }
 }
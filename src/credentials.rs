@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// 系统钥匙串中的服务名
+const KEYRING_SERVICE: &str = "i18n-app";
+/// 覆盖钥匙串中 API token 的环境变量，适合 CI 等没有钥匙串的环境
+pub const API_TOKEN_ENV: &str = "I18N_APP_API_TOKEN";
+/// 覆盖钥匙串中 GitHub token 的环境变量
+pub const GITHUB_TOKEN_ENV: &str = "I18N_APP_GITHUB_TOKEN";
+
+/// 保存在系统钥匙串中的凭据
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Credential {
+    /// 翻译服务的 API token，按 host 区分，不同环境可以使用不同的 token
+    Api { host: String },
+    /// 检查更新时使用的 GitHub token
+    Github,
+}
+
+impl Credential {
+    fn account(&self) -> String {
+        match self {
+            Credential::Api { host } => format!("api:{}", host.trim_end_matches('/')),
+            Credential::Github => "github".to_string(),
+        }
+    }
+
+    fn env_var(&self) -> &'static str {
+        match self {
+            Credential::Api { .. } => API_TOKEN_ENV,
+            Credential::Github => GITHUB_TOKEN_ENV,
+        }
+    }
+
+    /// 在单独的线程中操作钥匙串：Linux 的 secret service 客户端会创建自己的 tokio 运行时，
+    /// 不能在命令所在的运行时线程中阻塞调用
+    fn with_entry<T: Send>(
+        &self,
+        f: impl FnOnce(keyring::Entry) -> keyring::Result<T> + Send,
+    ) -> Result<T, keyring::Error> {
+        let account = self.account();
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| f(keyring::Entry::new(KEYRING_SERVICE, &account)?))
+                .join()
+                .unwrap_or_else(|_| {
+                    Err(keyring::Error::PlatformFailure(
+                        "keyring thread panicked".into(),
+                    ))
+                })
+        })
+    }
+}
+
+/// 每次请求都会查找 token，查找结果在进程内缓存
+fn cache() -> &'static Mutex<HashMap<Credential, Option<String>>> {
+    static CACHE: OnceLock<Mutex<HashMap<Credential, Option<String>>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// 把 token 保存到系统钥匙串
+pub fn store(credential: &Credential, token: &str) -> Result<()> {
+    credential
+        .with_entry(|entry| entry.set_password(token))
+        .with_context(|| {
+            format!(
+                "写入系统钥匙串失败，没有钥匙串的环境（如 CI）可以使用环境变量 {}",
+                credential.env_var()
+            )
+        })?;
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(credential.clone(), Some(token.to_string()));
+    }
+    Ok(())
+}
+
+/// 从系统钥匙串删除 token，返回是否存在
+pub fn delete(credential: &Credential) -> Result<bool> {
+    if let Ok(mut cache) = cache().lock() {
+        cache.remove(credential);
+    }
+    match credential.with_entry(|entry| entry.delete_credential()) {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e).context("从系统钥匙串删除凭据失败"),
+    }
+}
+
+/// token 的来源，没有 token 时返回 None
+pub fn source(credential: &Credential) -> Option<String> {
+    if std::env::var(credential.env_var()).is_ok_and(|token| !token.trim().is_empty()) {
+        return Some(format!("environment variable {}", credential.env_var()));
+    }
+    get(credential).map(|_| "keyring".to_string())
+}
+
+/// 查找 token：环境变量优先，其次是系统钥匙串。钥匙串不可用时视为没有 token
+pub fn get(credential: &Credential) -> Option<String> {
+    if let Some(token) = std::env::var(credential.env_var())
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+    {
+        return Some(token);
+    }
+    if let Some(cached) = cache().lock().ok()?.get(credential) {
+        return cached.clone();
+    }
+    // 测试不读取开发者本机的钥匙串
+    if cfg!(test) {
+        return None;
+    }
+
+    let token = match credential.with_entry(|entry| entry.get_password()) {
+        Ok(token) => Some(token),
+        Err(e) => {
+            tracing::debug!("No {} in keyring: {}", credential.account(), e);
+            None
+        }
+    };
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(credential.clone(), token.clone());
+    }
+    token
+}
+
+/// 测试中代替钥匙串提供凭据
+#[cfg(test)]
+pub fn set_for_test(credential: Credential, token: &str) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(credential, Some(token.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credential_account() {
+        let credential = Credential::Api {
+            host: "https://example.com/".to_string(),
+        };
+        assert_eq!(credential.account(), "api:https://example.com");
+        assert_eq!(Credential::Github.account(), "github");
+        assert_eq!(get(&credential), None);
+    }
+}
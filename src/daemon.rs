@@ -0,0 +1,336 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use crate::api::ApiError;
+use crate::config::{Config, DaemonMode};
+use crate::desktop;
+use crate::error::AppError;
+use crate::hooks;
+use crate::output::{self, Status};
+use crate::service::{PullOptions, TranslationService};
+
+/// 一个子系统的 long-polling 状态
+struct Watched {
+    config: Config,
+    service: TranslationService,
+    /// 最近一次处理过的 taskHash，启动时取上次 pull 保存的值
+    known_hash: Option<String>,
+}
+
+/// 通知中每种语言最多列出的 key 数量
+const MAX_KEYS_PER_LANGUAGE: usize = 3;
+
+/// pull 前后两次快照中新增或修改的 key，按语言分组
+fn changed_keys(
+    before: &HashMap<String, HashMap<String, String>>,
+    after: &HashMap<String, HashMap<String, String>>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut changed = BTreeMap::new();
+    for (lang, content) in after {
+        let previous = before.get(lang);
+        let mut keys: Vec<String> = content
+            .iter()
+            .filter(|(key, value)| previous.and_then(|p| p.get(*key)) != Some(*value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        if !keys.is_empty() {
+            keys.sort();
+            changed.insert(lang.clone(), keys);
+        }
+    }
+    changed
+}
+
+/// key 的第一段是否在 `namespaces` 中，`namespaces` 为空时总是 true
+fn in_namespaces(key: &str, namespaces: &[String]) -> bool {
+    namespaces.is_empty()
+        || namespaces.iter().any(|ns| {
+            key == ns
+                || key
+                    .strip_prefix(ns.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+}
+
+/// 通知正文：每种语言一行，如 `zh-CN: 5 keys (home.title, home.body, …)`，过滤后没有 key 时返回 None
+fn summarize(changed: &BTreeMap<String, Vec<String>>, namespaces: &[String]) -> Option<String> {
+    let lines: Vec<String> = changed
+        .iter()
+        .filter_map(|(lang, keys)| {
+            let keys: Vec<&str> = keys
+                .iter()
+                .map(String::as_str)
+                .filter(|key| in_namespaces(key, namespaces))
+                .collect();
+            if keys.is_empty() {
+                return None;
+            }
+            let mut shown = keys[..keys.len().min(MAX_KEYS_PER_LANGUAGE)].join(", ");
+            if keys.len() > MAX_KEYS_PER_LANGUAGE {
+                shown.push_str(", …");
+            }
+            let unit = if keys.len() == 1 { "key" } else { "keys" };
+            Some(format!("{}: {} {} ({})", lang, keys.len(), unit, shown))
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// 认证失败等错误重试也不会成功，daemon 直接退出
+fn is_fatal(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<ApiError>())
+        .any(ApiError::is_fatal)
+}
+
+/// 持续请求服务器，taskHash 变化时按配置自动 pull 或只提示，Ctrl-C 退出
+pub async fn run(config: Config) -> Result<()> {
+    if config.offline {
+        return Err(AppError::Validation(
+            "daemon 命令需要访问服务器，离线模式下无法执行".to_string(),
+        )
+        .into());
+    }
+
+    let mut watched = Vec::new();
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config.clone());
+        let known_hash = service.synced_task_hash()?;
+        watched.push(Watched {
+            config,
+            service,
+            known_hash,
+        });
+    }
+    let interval = Duration::from_secs(config.daemon.interval_seconds.max(1));
+    tracing::info!(
+        "daemon 已启动（{:?} 模式，间隔 {} 秒），按 Ctrl-C 退出",
+        config.daemon.mode,
+        interval.as_secs()
+    );
+
+    loop {
+        let started = Instant::now();
+        tokio::select! {
+            result = poll_all(&mut watched, config.daemon.mode) => result?,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+        // 服务器没有保持连接时，至少间隔 interval 再请求
+        tokio::select! {
+            _ = tokio::time::sleep(interval.saturating_sub(started.elapsed())) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    tracing::info!("daemon 已退出");
+    Ok(())
+}
+
+async fn poll_all(watched: &mut [Watched], mode: DaemonMode) -> Result<()> {
+    for item in watched {
+        if let Err(e) = poll(item, mode).await {
+            if is_fatal(&e) {
+                return Err(e);
+            }
+            tracing::warn!(
+                "{}: 检查服务器变化失败，稍后重试：{:#}",
+                item.config.sub_system_name(),
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn poll(item: &mut Watched, mode: DaemonMode) -> Result<()> {
+    let data = item.service.poll_remote(item.known_hash.as_deref()).await?;
+    let Some(task_hash) = data.task_hash else {
+        tracing::debug!("后端没有返回 taskHash，无法判断服务器是否有变化");
+        return Ok(());
+    };
+    if item.known_hash.as_deref() == Some(task_hash.as_str()) {
+        return Ok(());
+    }
+
+    let sub_system = item.config.sub_system_name();
+    tracing::info!("{}: 服务器翻译有变化（taskHash {}）", sub_system, task_hash);
+    let changed = match mode {
+        DaemonMode::Pull => {
+            let before = item.service.snapshots()?;
+            hooks::run_pre(&item.config, "pull")?;
+            item.service
+                .sync_translations(PullOptions::default())
+                .await?;
+            hooks::run_post(&item.config, "pull")?;
+            // 部分语言同步失败时 pull 不保存 taskHash，下一轮仍然认为服务器有变化并重试
+            item.known_hash = item.service.synced_task_hash()?;
+            changed_keys(&before, &item.service.snapshots()?)
+        }
+        DaemonMode::Notify => {
+            let terms: Vec<&str> = data
+                .change_terms
+                .iter()
+                .map(|term| term.term.as_str())
+                .collect();
+            let detail = if terms.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", terms.join(", "))
+            };
+            output::status(
+                Status::Ok,
+                format!(
+                    "{}: server translations changed{}, run `i18n-app pull` to sync",
+                    sub_system, detail
+                ),
+            );
+            let mut changed: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for term in &data.change_terms {
+                let language = term.language.clone().unwrap_or_else(|| "*".to_string());
+                changed.entry(language).or_default().push(term.term.clone());
+            }
+            item.known_hash = Some(task_hash);
+            changed
+        }
+    };
+
+    let daemon = &item.config.daemon;
+    if daemon.desktop_notifications {
+        if let Some(body) = summarize(&changed, &daemon.notify_namespaces) {
+            desktop::notify(
+                &format!("i18n-app: {} translations changed", sub_system),
+                &body,
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_poll_tracks_task_hash() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_status(200)
+            .with_body(r#"{"code":0,"data":{"taskHash":"h2","change_terms":["home.title"]}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = Config {
+            host: server.url(),
+            ..Default::default()
+        };
+        let mut item = Watched {
+            service: TranslationService::new(config.clone()),
+            config,
+            known_hash: Some("h1".to_string()),
+        };
+        poll(&mut item, DaemonMode::Notify).await?;
+        assert_eq!(item.known_hash.as_deref(), Some("h2"));
+        // taskHash 没有变化时不再提示
+        poll(&mut item, DaemonMode::Notify).await?;
+        assert_eq!(item.known_hash.as_deref(), Some("h2"));
+
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_changed_keys() {
+        let snapshot = |entries: &[(&str, &str)]| -> HashMap<String, String> {
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let before = HashMap::from([(
+            "zh-CN".to_string(),
+            snapshot(&[("home.title", "首页"), ("home.body", "旧")]),
+        )]);
+        let after = HashMap::from([
+            (
+                "zh-CN".to_string(),
+                snapshot(&[
+                    ("home.title", "首页"),
+                    ("home.body", "新"),
+                    ("checkout.pay", "支付"),
+                ]),
+            ),
+            (
+                "de-DE".to_string(),
+                snapshot(&[("a.1", "1"), ("a.2", "2"), ("a.3", "3"), ("a.4", "4")]),
+            ),
+        ]);
+
+        let changed = changed_keys(&before, &after);
+        assert_eq!(changed["zh-CN"], vec!["checkout.pay", "home.body"]);
+        assert_eq!(
+            summarize(&changed, &[]).as_deref(),
+            Some("de-DE: 4 keys (a.1, a.2, a.3, …)\nzh-CN: 2 keys (checkout.pay, home.body)")
+        );
+        assert_eq!(
+            summarize(&changed, &["home".to_string()]).as_deref(),
+            Some("zh-CN: 1 key (home.body)")
+        );
+        assert_eq!(summarize(&changed, &["homepage".to_string()]), None);
+    }
+
+    #[tokio::test]
+    async fn test_pull_keeps_task_hash_after_partial_failure() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        std::fs::write(temp_dir.path().join("de.json"), r#"{"save": "Speichern"}"#)?;
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(format!(
+                r#"{{"code":0,"data":{{"taskHash":"h2","files":[{{"lang":"en-US","url":"{0}/en-US.json"}},{{"lang":"de","url":"{0}/de.json"}}]}}}}"#,
+                server.url()
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/en-US.json")
+            .with_body(r#"{"app/languages": {"save": "Save!"}}"#)
+            .create_async()
+            .await;
+        let de = server
+            .mock("GET", "/de.json")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let config = Config {
+            host: server.url(),
+            path_prefix: "app".to_string(),
+            base_path: Some(temp_dir.path().display().to_string()),
+            include: vec!["*.json".to_string()],
+            ..Default::default()
+        };
+        let mut item = Watched {
+            service: TranslationService::new(config.clone())
+                .with_fs(std::sync::Arc::new(crate::vfs::MemoryFs::new())),
+            config,
+            known_hash: Some("h1".to_string()),
+        };
+        poll(&mut item, DaemonMode::Pull).await?;
+        // de 没有同步，taskHash 没有保存，下一轮重新 pull
+        assert_eq!(item.known_hash, None);
+
+        de.remove_async().await;
+        server
+            .mock("GET", "/de.json")
+            .with_body(r#"{"app/languages": {"save": "Speichern!"}}"#)
+            .create_async()
+            .await;
+        poll(&mut item, DaemonMode::Pull).await?;
+        assert_eq!(item.known_hash.as_deref(), Some("h2"));
+        Ok(())
+    }
+}
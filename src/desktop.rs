@@ -0,0 +1,71 @@
+use std::process::Command;
+
+/// 标题和正文通过环境变量传给通知命令，不需要处理 shell 转义
+const TITLE_ENV: &str = "I18N_APP_NOTIFY_TITLE";
+const BODY_ENV: &str = "I18N_APP_NOTIFY_BODY";
+
+#[cfg(target_os = "macos")]
+fn notify_command() -> Command {
+    let mut command = Command::new("osascript");
+    command.args([
+        "-e",
+        &format!(
+            "display notification (system attribute \"{}\") with title (system attribute \"{}\")",
+            BODY_ENV, TITLE_ENV
+        ),
+    ]);
+    command
+}
+
+/// Windows 上以 PowerShell 的 AppUserModelID 发送 toast 通知，未注册的 ID 不会显示通知
+#[cfg(windows)]
+const POWERSHELL_APP_ID: &str =
+    r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+#[cfg(windows)]
+fn notify_command() -> Command {
+    // toast 交给系统显示，PowerShell 不需要等待通知消失
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-Command",
+        &format!(
+            "$m = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime]; \
+             $xml = $m::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+             $text = $xml.GetElementsByTagName('text'); \
+             $text.Item(0).AppendChild($xml.CreateTextNode($env:{})) | Out-Null; \
+             $text.Item(1).AppendChild($xml.CreateTextNode($env:{})) | Out-Null; \
+             $m::CreateToastNotifier('{}').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+            TITLE_ENV, BODY_ENV, POWERSHELL_APP_ID
+        ),
+    ]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn notify_command() -> Command {
+    let mut command = Command::new("sh");
+    command.args([
+        "-c",
+        &format!(
+            "notify-send --app-name=i18n-app \"${}\" \"${}\"",
+            TITLE_ENV, BODY_ENV
+        ),
+    ]);
+    command
+}
+
+/// 发送桌面通知（macOS 使用 osascript，Windows 使用 PowerShell 显示 toast，Linux 使用 notify-send），
+/// 在后台线程中执行，失败时只记录日志。只调用系统自带的命令，不提供系统托盘图标
+pub fn notify(title: &str, body: &str) {
+    let mut command = notify_command();
+    command.env(TITLE_ENV, title).env(BODY_ENV, body);
+    std::thread::spawn(move || match command.output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => tracing::warn!(
+            "Desktop notification failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => tracing::warn!("Desktop notification failed: {}", e),
+    });
+}
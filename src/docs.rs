@@ -0,0 +1,211 @@
+use anyhow::{Context, Result};
+use clap::{Arg, Command, CommandFactory};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::cli::{Cli, DocsFormat};
+
+/// 按 clap 的命令定义生成文档。`out` 为 None 时输出到标准输出：
+/// man 只输出主命令的手册页，markdown 输出完整的命令参考
+pub fn run(format: DocsFormat, out: Option<&Path>) -> Result<()> {
+    let command = Cli::command();
+    match (format, out) {
+        (DocsFormat::Man, Some(dir)) => {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("创建目录 {} 失败", dir.display()))?;
+            let pages = man_pages(command)?;
+            for (name, content) in &pages {
+                let path = dir.join(name);
+                std::fs::write(&path, content)
+                    .with_context(|| format!("写入文件 {} 失败", path.display()))?;
+            }
+            println!("Generated {} man pages in {}", pages.len(), dir.display());
+        }
+        (DocsFormat::Man, None) => {
+            let page = render_man(&command)?;
+            print!("{}", String::from_utf8_lossy(&page));
+        }
+        (DocsFormat::Markdown, Some(path)) => {
+            std::fs::write(path, markdown(&command))
+                .with_context(|| format!("写入文件 {} 失败", path.display()))?;
+            println!("Generated CLI reference {}", path.display());
+        }
+        (DocsFormat::Markdown, None) => print!("{}", markdown(&command)),
+    }
+    Ok(())
+}
+
+fn render_man(command: &Command) -> Result<Vec<u8>> {
+    let mut page = Vec::new();
+    clap_mangen::Man::new(command.clone()).render(&mut page)?;
+    Ok(page)
+}
+
+/// 主命令和每个子命令各一个手册页，如 `i18n-app.1`、`i18n-app-push.1`
+fn man_pages(command: Command) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut command = command;
+    command.build();
+    let mut pages = Vec::new();
+    collect_man_pages(&command, command.get_name(), &mut pages)?;
+    Ok(pages)
+}
+
+fn collect_man_pages(
+    command: &Command,
+    name: &str,
+    pages: &mut Vec<(String, Vec<u8>)>,
+) -> Result<()> {
+    let command = command.clone().name(name.to_string());
+    pages.push((format!("{}.1", name), render_man(&command)?));
+    for sub in visible_subcommands(&command) {
+        collect_man_pages(sub, &format!("{}-{}", name, sub.get_name()), pages)?;
+    }
+    Ok(())
+}
+
+fn visible_subcommands(command: &Command) -> impl Iterator<Item = &Command> {
+    command
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set() && sub.get_name() != "help")
+}
+
+/// 生成 Markdown 格式的命令参考，每个子命令一节
+fn markdown(command: &Command) -> String {
+    let mut command = command.clone();
+    command.build();
+    let mut doc = String::new();
+    let _ = writeln!(doc, "# {} 命令参考\n", command.get_name());
+    let _ = writeln!(
+        doc,
+        "<!-- 由 `i18n-app gen-docs markdown` 生成，请勿手动修改 -->\n"
+    );
+    write_command_markdown(&mut doc, &command, command.get_name(), 2);
+    doc
+}
+
+fn write_command_markdown(doc: &mut String, command: &Command, path: &str, level: usize) {
+    if level > 2 {
+        let _ = writeln!(doc, "{} `{}`\n", "#".repeat(level.min(6)), path);
+    }
+    if let Some(about) = command.get_long_about().or(command.get_about()) {
+        let _ = writeln!(doc, "{}\n", about);
+    }
+    let usage = command.clone().render_usage().to_string();
+    let usage = usage.trim_start_matches("Usage: ");
+    let _ = writeln!(doc, "```text\n{}\n```\n", usage);
+
+    let (positionals, options): (Vec<&Arg>, Vec<&Arg>) = command
+        .get_arguments()
+        // 全局选项只在主命令中列出
+        .filter(|arg| !arg.is_hide_set() && (level == 2 || !arg.is_global_set()))
+        .partition(|arg| arg.is_positional());
+    if !positionals.is_empty() {
+        let _ = writeln!(doc, "参数：\n");
+        for arg in positionals {
+            write_arg_markdown(doc, arg);
+        }
+        doc.push('\n');
+    }
+    if !options.is_empty() {
+        let _ = writeln!(doc, "选项：\n");
+        for arg in options {
+            write_arg_markdown(doc, arg);
+        }
+        doc.push('\n');
+    }
+
+    let subcommands: Vec<&Command> = visible_subcommands(command).collect();
+    if !subcommands.is_empty() {
+        let _ = writeln!(doc, "子命令：\n");
+        for sub in &subcommands {
+            let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+            let _ = writeln!(doc, "- `{}`: {}", sub.get_name(), about);
+        }
+        doc.push('\n');
+        for sub in subcommands {
+            let sub_path = format!("{} {}", path, sub.get_name());
+            write_command_markdown(doc, sub, &sub_path, level + 1);
+        }
+    }
+}
+
+fn write_arg_markdown(doc: &mut String, arg: &Arg) {
+    // 开关类选项没有值，也不显示默认的 false
+    let takes_values = arg.get_action().takes_values();
+    let value = arg
+        .get_value_names()
+        .filter(|_| takes_values)
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| format!(" <{}>", name))
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    let name = match (arg.get_short(), arg.get_long()) {
+        _ if arg.is_positional() => format!("<{}>", arg.get_id()),
+        (Some(short), Some(long)) => format!("-{}, --{}{}", short, long, value),
+        (None, Some(long)) => format!("--{}{}", long, value),
+        (Some(short), None) => format!("-{}{}", short, value),
+        (None, None) => arg.get_id().to_string(),
+    };
+    let help = arg
+        .get_long_help()
+        .or(arg.get_help())
+        .map(|h| h.to_string().replace('\n', " "))
+        .unwrap_or_default();
+    let mut line = format!("- `{}`", name);
+    if !help.is_empty() {
+        let _ = write!(line, ": {}", help);
+    }
+    let values: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| format!("`{}`", v.get_name()))
+        .collect();
+    if !values.is_empty() && takes_values {
+        let _ = write!(line, "（可选值：{}）", values.join("、"));
+    }
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy().to_string())
+        .collect();
+    if !defaults.is_empty() && takes_values {
+        let _ = write!(line, "（默认：`{}`）", defaults.join(","));
+    }
+    let _ = writeln!(doc, "{}", line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_reference() {
+        let doc = markdown(&Cli::command());
+        assert!(doc.starts_with("# i18n-app 命令参考"));
+        assert!(doc.contains("### `i18n-app push`"));
+        assert!(doc.contains("#### `i18n-app auth login`"));
+        assert_eq!(doc.matches("- `--offline`").count(), 1);
+        assert!(doc.contains("- `--fix`"));
+        assert!(!doc.contains("`i18n-app help`"));
+    }
+
+    #[test]
+    fn test_man_pages() -> Result<()> {
+        let pages = man_pages(Cli::command())?;
+        let names: Vec<&str> = pages.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names[0], "i18n-app.1");
+        assert!(names.contains(&"i18n-app-push.1"));
+        assert!(names.contains(&"i18n-app-auth-login.1"));
+        let push = &pages
+            .iter()
+            .find(|(name, _)| name == "i18n-app-push.1")
+            .unwrap()
+            .1;
+        assert!(String::from_utf8_lossy(push).contains(".TH i18n-app-push"));
+        Ok(())
+    }
+}
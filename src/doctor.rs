@@ -0,0 +1,52 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::config::{self, Config};
+use crate::error::AppError;
+use crate::output::{self, Status};
+use crate::workspace;
+
+/// 检查项目配置和工作目录，`fix` 为 true 时修复可以自动修复的问题
+pub fn run(fix: bool) -> Result<()> {
+    let mut problems = 0;
+
+    match Config::from_file(config::DEFAULT_CONFIG_FILE) {
+        Ok(_) => output::status(
+            Status::Ok,
+            format!("{} is valid", config::DEFAULT_CONFIG_FILE),
+        ),
+        Err(e) => {
+            problems += 1;
+            output::status(
+                Status::Failed,
+                format!(
+                    "{} cannot be loaded: {} (run `i18n-app init` to create it)",
+                    config::DEFAULT_CONFIG_FILE,
+                    e
+                ),
+            );
+        }
+    }
+
+    let gitignore = Path::new(".gitignore");
+    let ignored = std::fs::read_to_string(gitignore)
+        .map(|content| workspace::is_work_dir_ignored(&content))
+        .unwrap_or(false);
+    if ignored {
+        output::status(Status::Ok, ".i18n-app/ is ignored by .gitignore");
+    } else if fix {
+        workspace::ensure_root_gitignore(Path::new("."))?;
+        output::status(Status::Ok, "added .i18n-app/ to .gitignore");
+    } else {
+        problems += 1;
+        output::status(
+            Status::Failed,
+            ".i18n-app/ is not ignored by .gitignore (run `i18n-app doctor --fix`)",
+        );
+    }
+
+    if problems > 0 {
+        return Err(AppError::Validation(format!("doctor 发现 {} 个问题", problems)).into());
+    }
+    Ok(())
+}
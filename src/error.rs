@@ -0,0 +1,96 @@
+use crate::api::ApiError;
+
+/// 程序退出码，供脚本根据失败类型分支处理
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    /// 未归类的错误
+    pub const FAILURE: i32 = 1;
+    /// 参数或翻译文件校验失败
+    pub const VALIDATION: i32 = 2;
+    /// 网络或服务器 API 错误
+    pub const NETWORK: i32 = 3;
+    /// 配置文件缺失或无效
+    pub const CONFIG: i32 = 4;
+    /// pull 时有未解决的合并冲突，冲突写入 `<lang>.conflicts.json`，对应的文件未修改
+    pub const CONFLICTS: i32 = 5;
+    /// 被 Ctrl-C 中断，与 shell 中 SIGINT 的惯例一致
+    pub const CANCELLED: i32 = 130;
+}
+
+/// 需要映射到特定退出码的错误，通常作为 anyhow 的 context 附加在底层错误上
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    Config(String),
+    #[error("{0}")]
+    Conflicts(String),
+    /// 插件以非零退出码结束，主程序使用相同的退出码
+    #[error("plugin {name} exited with code {code}")]
+    Plugin { name: String, code: i32 },
+    /// 被 Ctrl-C 中断
+    #[error("operation cancelled, nothing corrupted")]
+    Cancelled,
+}
+
+impl AppError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Validation(_) => exit_code::VALIDATION,
+            AppError::Config(_) => exit_code::CONFIG,
+            AppError::Conflicts(_) => exit_code::CONFLICTS,
+            AppError::Plugin { code, .. } => *code,
+            AppError::Cancelled => exit_code::CANCELLED,
+        }
+    }
+}
+
+/// 根据错误链中的错误类型确定退出码
+pub fn exit_code_for(err: &anyhow::Error) -> i32 {
+    // 作为 context 附加的 AppError 不会出现在 chain 的 downcast 中，需要单独检查
+    if let Some(app_error) = err.downcast_ref::<AppError>() {
+        return app_error.exit_code();
+    }
+
+    for cause in err.chain() {
+        if let Some(app_error) = cause.downcast_ref::<AppError>() {
+            return app_error.exit_code();
+        }
+        if cause.is::<ApiError>() || cause.is::<reqwest::Error>() {
+            return exit_code::NETWORK;
+        }
+    }
+    exit_code::FAILURE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_code_for() {
+        let config_error = anyhow::anyhow!("missing field `host`")
+            .context(AppError::Config("配置文件无效".to_string()));
+        assert_eq!(exit_code_for(&config_error), exit_code::CONFIG);
+
+        let api_error: anyhow::Error = ApiError::Offline {
+            url: "http://localhost".to_string(),
+        }
+        .into();
+        let wrapped = Err::<(), _>(api_error).context("push failed").unwrap_err();
+        assert_eq!(exit_code_for(&wrapped), exit_code::NETWORK);
+
+        let validation: anyhow::Error = AppError::Validation("bad".to_string()).into();
+        assert_eq!(exit_code_for(&validation), exit_code::VALIDATION);
+
+        let cancelled: anyhow::Error = AppError::Cancelled.into();
+        assert_eq!(exit_code_for(&cancelled), exit_code::CANCELLED);
+
+        assert_eq!(
+            exit_code_for(&anyhow::anyhow!("unknown")),
+            exit_code::FAILURE
+        );
+    }
+}
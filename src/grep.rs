@@ -0,0 +1,187 @@
+use anyhow::Result;
+use console::style;
+use regex::{NoExpand, Regex, RegexBuilder};
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::translation::TranslationFile;
+
+/// grep 命令的搜索选项
+#[derive(Debug, Clone, Default)]
+pub struct GrepOptions {
+    pub pattern: String,
+    /// 只看该语言
+    pub lang: Option<String>,
+    /// 只搜索 key，不搜索值
+    pub keys_only: bool,
+    /// 按普通文本匹配，不作为正则表达式
+    pub fixed_strings: bool,
+    pub ignore_case: bool,
+}
+
+impl GrepOptions {
+    /// 编译搜索模式，无效的正则表达式返回 Validation 错误
+    pub fn regex(&self) -> Result<Regex> {
+        let pattern = if self.fixed_strings {
+            regex::escape(&self.pattern)
+        } else {
+            self.pattern.clone()
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(self.ignore_case)
+            .build()
+            .map_err(|e| {
+                AppError::Validation(format!("无效的正则表达式 '{}': {}", self.pattern, e)).into()
+            })
+    }
+}
+
+/// 文件中 key 或值匹配 `regex` 的条目，`keys_only` 时只匹配 key，按 key 排序。
+/// 值是解析 JSON 之后的文本，转义字符（如 `\"`、`\u00e9`）已经还原
+pub fn find_matches<'a>(
+    file: &'a TranslationFile,
+    regex: &Regex,
+    keys_only: bool,
+) -> Vec<(&'a str, &'a str)> {
+    let mut matches: Vec<(&str, &str)> = file
+        .content
+        .iter()
+        .filter(|(key, value)| regex.is_match(key) || (!keys_only && regex.is_match(value)))
+        .map(|(key, value)| (key.as_ref(), value.as_ref()))
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// 一个值替换前后的文本
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replacement {
+    pub key: Arc<str>,
+    pub before: Arc<str>,
+    pub after: String,
+}
+
+/// 把文件中值匹配 `regex` 的部分替换为 `replacement`，返回值有变化的 key，按 key 排序。
+/// `literal` 时替换文本原样插入，否则可以用 `$1`、`${name}` 引用分组
+pub fn find_replacements(
+    file: &TranslationFile,
+    regex: &Regex,
+    replacement: &str,
+    literal: bool,
+) -> Vec<Replacement> {
+    let mut replacements: Vec<Replacement> = file
+        .content
+        .iter()
+        .filter_map(|(key, value)| {
+            let after = if literal {
+                regex.replace_all(value, NoExpand(replacement))
+            } else {
+                regex.replace_all(value, replacement)
+            };
+            (after != **value).then(|| Replacement {
+                key: key.clone(),
+                before: value.clone(),
+                after: after.into_owned(),
+            })
+        })
+        .collect();
+    replacements.sort_by(|a, b| a.key.cmp(&b.key));
+    replacements
+}
+
+/// 输出中的一行：换行显示为 `\n`，开启颜色时高亮匹配的部分
+pub fn display(text: &str, regex: &Regex) -> String {
+    let text = text.replace('\r', "\\r").replace('\n', "\\n");
+    if !crate::output::colors_enabled() {
+        return text;
+    }
+    regex
+        .replace_all(&text, |caps: &regex::Captures| {
+            style(&caps[0]).red().bold().to_string()
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches() -> Result<()> {
+        let file = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            [
+                ("checkout.shipping", "Free shipping on orders over $50"),
+                ("checkout.free_gift", "Gift"),
+                ("checkout.quote", "Say \"free\""),
+                ("home.title", "Home"),
+            ],
+        );
+        let options = GrepOptions {
+            pattern: "free".to_string(),
+            ignore_case: true,
+            ..Default::default()
+        };
+        let regex = options.regex()?;
+        assert_eq!(
+            find_matches(&file, &regex, false),
+            vec![
+                ("checkout.free_gift", "Gift"),
+                ("checkout.quote", "Say \"free\""),
+                ("checkout.shipping", "Free shipping on orders over $50"),
+            ]
+        );
+        assert_eq!(
+            find_matches(&file, &regex, true),
+            vec![("checkout.free_gift", "Gift")]
+        );
+
+        let options = GrepOptions {
+            pattern: "$50".to_string(),
+            fixed_strings: true,
+            ..Default::default()
+        };
+        assert_eq!(find_matches(&file, &options.regex()?, false).len(), 1);
+
+        let options = GrepOptions {
+            pattern: "(".to_string(),
+            ..Default::default()
+        };
+        assert!(options.regex().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_replacements() -> Result<()> {
+        let file = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            [
+                ("footer", "© Acme Inc. All rights reserved"),
+                ("about", "Acme Inc and Acme Inc Labs"),
+                ("home", "Home"),
+            ],
+        );
+        let regex = Regex::new(&regex::escape("Acme Inc"))?;
+        let replacements = find_replacements(&file, &regex, "Acme $1 Ltd", true);
+        assert_eq!(
+            replacements
+                .iter()
+                .map(|r| (r.key.as_ref(), r.after.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("about", "Acme $1 Ltd and Acme $1 Ltd Labs"),
+                ("footer", "© Acme $1 Ltd. All rights reserved"),
+            ]
+        );
+        assert_eq!(&*replacements[0].before, "Acme Inc and Acme Inc Labs");
+
+        let regex = Regex::new(r"Acme (Inc)\b")?;
+        let replacements = find_replacements(&file, &regex, "[$1]", false);
+        assert_eq!(replacements[0].after, "[Inc] and [Inc] Labs");
+        // 替换后没有变化的值不返回
+        assert!(find_replacements(&file, &regex, "Acme Inc", false).is_empty());
+        Ok(())
+    }
+}
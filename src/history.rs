@@ -0,0 +1,180 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::locations;
+use crate::output;
+
+static KEYS_UPLOADED: AtomicUsize = AtomicUsize::new(0);
+static KEYS_DOWNLOADED: AtomicUsize = AtomicUsize::new(0);
+static LANGUAGES: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// 本地运行历史文件，只在配置 `history: true` 时写入，不会发送到任何地方
+pub fn history_file() -> PathBuf {
+    locations::state_home().join("history.jsonl")
+}
+
+/// 一次命令运行的记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub duration_ms: u128,
+    pub keys_uploaded: usize,
+    pub keys_downloaded: usize,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 记录本次运行上传的 key 数量
+pub fn record_uploaded(count: usize) {
+    KEYS_UPLOADED.fetch_add(count, Ordering::Relaxed);
+}
+
+/// 记录本次运行下载的 key 数量
+pub fn record_downloaded(count: usize) {
+    KEYS_DOWNLOADED.fetch_add(count, Ordering::Relaxed);
+}
+
+/// 记录本次运行处理过的语言
+pub fn record_language(lang: &str) {
+    LANGUAGES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(lang.to_string());
+}
+
+pub fn languages() -> Vec<String> {
+    LANGUAGES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .cloned()
+        .collect()
+}
+
+pub fn keys_uploaded() -> usize {
+    KEYS_UPLOADED.load(Ordering::Relaxed)
+}
+
+pub fn keys_downloaded() -> usize {
+    KEYS_DOWNLOADED.load(Ordering::Relaxed)
+}
+
+pub fn append_entry(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// 读取历史记录，跳过无法解析的行
+pub fn read_entries(path: &Path) -> Result<Vec<HistoryEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("读取历史文件 {} 失败", path.display()))?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("Skipping invalid history line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// 以表格形式打印最近的历史记录
+pub fn show_history(limit: usize) -> Result<()> {
+    let entries = read_entries(&history_file())?;
+    if entries.is_empty() {
+        tracing::info!("暂无历史记录，可在配置文件中设置 \"history\": true 开启");
+        return Ok(());
+    }
+
+    let start = entries.len().saturating_sub(limit);
+    let rows = entries[start..].iter().map(|entry| {
+        vec![
+            entry.timestamp.clone(),
+            format!("{} {}", entry.command, entry.args.join(" "))
+                .trim()
+                .to_string(),
+            format!("{:.1}s", entry.duration_ms as f64 / 1000.0),
+            entry.keys_uploaded.to_string(),
+            entry.keys_downloaded.to_string(),
+            if entry.success {
+                "✓".to_string()
+            } else {
+                format!("✗ {}", entry.error.as_deref().unwrap_or(""))
+            },
+        ]
+    });
+    output::table(
+        [
+            "Time",
+            "Command",
+            "Duration",
+            "Uploaded",
+            "Downloaded",
+            "Outcome",
+        ],
+        rows,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_and_read_entries() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(".i18n-app").join("history.jsonl");
+
+        let entry = HistoryEntry {
+            timestamp: "2024-01-01T00:00:00+08:00".to_string(),
+            command: "push".to_string(),
+            args: vec!["push".to_string()],
+            duration_ms: 1200,
+            keys_uploaded: 12,
+            keys_downloaded: 300,
+            success: true,
+            error: None,
+        };
+        append_entry(&path, &entry)?;
+        append_entry(
+            &path,
+            &HistoryEntry {
+                command: "pull".to_string(),
+                success: false,
+                error: Some("network".to_string()),
+                ..entry.clone()
+            },
+        )?;
+        fs::write(&path, fs::read_to_string(&path)? + "not json\n")?;
+
+        let entries = read_entries(&path)?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], entry);
+        assert_eq!(entries[1].error.as_deref(), Some("network"));
+
+        Ok(())
+    }
+}
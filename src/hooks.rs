@@ -0,0 +1,166 @@
+use anyhow::{bail, Context, Result};
+
+use crate::config::Config;
+use crate::history;
+use crate::platform;
+use crate::service::TranslationService;
+
+/// hook 执行的时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Pre,
+    Post,
+}
+
+/// 传给 hook 的运行信息
+#[derive(Debug, Clone, Default)]
+struct HookContext {
+    command: String,
+    sub_systems: Vec<String>,
+    /// pre 阶段为本地翻译文件的语言，post 阶段为本次处理过的语言
+    languages: Vec<String>,
+    keys_uploaded: usize,
+    keys_downloaded: usize,
+}
+
+impl HookContext {
+    fn env(&self, hook: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("I18N_APP_HOOK", hook.to_string()),
+            ("I18N_APP_COMMAND", self.command.clone()),
+            ("I18N_APP_SUB_SYSTEMS", self.sub_systems.join(",")),
+            ("I18N_APP_LANGUAGES", self.languages.join(",")),
+            ("I18N_APP_KEYS_UPLOADED", self.keys_uploaded.to_string()),
+            ("I18N_APP_KEYS_DOWNLOADED", self.keys_downloaded.to_string()),
+        ]
+    }
+}
+
+/// 配置中的 hook 名称和命令
+fn hook_for<'a>(
+    config: &'a Config,
+    command: &str,
+    stage: Stage,
+) -> Option<(&'static str, &'a str)> {
+    let hooks = &config.hooks;
+    let (name, hook) = match (command, stage) {
+        ("push", Stage::Pre) => ("prePush", &hooks.pre_push),
+        ("push", Stage::Post) => ("postPush", &hooks.post_push),
+        ("pull", Stage::Pre) => ("prePull", &hooks.pre_pull),
+        ("pull", Stage::Post) => ("postPull", &hooks.post_pull),
+        _ => return None,
+    };
+    hook.as_deref().map(|hook| (name, hook))
+}
+
+/// 执行 hook 命令，以非零退出码结束时返回错误
+fn run_hook(name: &str, script: &str, context: &HookContext) -> Result<()> {
+    tracing::info!("Running {} hook: {}", name, script);
+    let status = platform::shell(script)
+        .envs(context.env(name))
+        .status()
+        .with_context(|| format!("无法执行 {} hook: {}", name, script))?;
+    if !status.success() {
+        match status.code() {
+            Some(code) => bail!("{} hook 失败（退出码 {}）: {}", name, code, script),
+            None => bail!("{} hook 被信号终止: {}", name, script),
+        }
+    }
+    Ok(())
+}
+
+/// 执行 push/pull 前的 hook，失败时中止命令
+pub fn run_pre(config: &Config, command: &str) -> Result<()> {
+    let Some((name, script)) = hook_for(config, command, Stage::Pre) else {
+        return Ok(());
+    };
+    let mut languages = Vec::new();
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        languages.extend(
+            service
+                .local_language_files()?
+                .into_iter()
+                .map(|(lang, _)| lang),
+        );
+    }
+    languages.sort();
+    languages.dedup();
+
+    let context = HookContext {
+        command: command.to_string(),
+        sub_systems: config.sub_systems.clone(),
+        languages,
+        ..Default::default()
+    };
+    run_hook(name, script, &context)
+}
+
+/// push/pull 成功后执行 hook
+pub fn run_post(config: &Config, command: &str) -> Result<()> {
+    let Some((name, script)) = hook_for(config, command, Stage::Post) else {
+        return Ok(());
+    };
+    let context = HookContext {
+        command: command.to_string(),
+        sub_systems: config.sub_systems.clone(),
+        languages: history::languages(),
+        keys_uploaded: history::keys_uploaded(),
+        keys_downloaded: history::keys_downloaded(),
+    };
+    run_hook(name, script, &context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HooksConfig;
+
+    #[test]
+    fn test_hook_for() {
+        let config = Config {
+            hooks: HooksConfig {
+                post_pull: Some("npm run format".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            hook_for(&config, "pull", Stage::Post),
+            Some(("postPull", "npm run format"))
+        );
+        assert_eq!(hook_for(&config, "pull", Stage::Pre), None);
+        assert_eq!(hook_for(&config, "download", Stage::Post), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook() {
+        let context = HookContext {
+            command: "push".to_string(),
+            languages: vec!["en-US".to_string(), "zh-CN".to_string()],
+            keys_uploaded: 3,
+            ..Default::default()
+        };
+        let script = r#"test "$I18N_APP_HOOK" = prePush && test "$I18N_APP_LANGUAGES" = en-US,zh-CN && test "$I18N_APP_KEYS_UPLOADED" = 3 && test -z "$I18N_APP_DRY_RUN""#;
+        assert!(run_hook("prePush", script, &context).is_ok());
+
+        let err = run_hook("prePush", "exit 3", &context).unwrap_err();
+        assert!(err.to_string().contains("退出码 3"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_run_hook() {
+        let context = HookContext {
+            command: "push".to_string(),
+            languages: vec!["en-US".to_string(), "zh-CN".to_string()],
+            ..Default::default()
+        };
+        let script = r#"if "%I18N_APP_HOOK%"=="prePush" (if "%I18N_APP_LANGUAGES%"=="en-US,zh-CN" (exit 0)) & exit 1"#;
+        assert!(run_hook("prePush", script, &context).is_ok());
+
+        let err = run_hook("prePush", "exit 3", &context).unwrap_err();
+        assert!(err.to_string().contains("退出码 3"));
+    }
+}
@@ -0,0 +1,477 @@
+use anyhow::{anyhow, Context, Result};
+use calamine::{Data, Reader};
+use rust_xlsxwriter::{Format, Workbook};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::error::AppError;
+use crate::translation;
+
+/// 表格中可以作为 key 列的表头，靠前的优先
+const KEY_HEADERS: &[&str] = &["key", "id", "term"];
+/// 导出时写入的稳定 ID 列
+const STABLE_ID_HEADER: &str = "Stable ID";
+/// PO 文件中记录稳定 ID 的注释前缀，如 `#. stable-id: 1a2b3c4d5e6f`
+const PO_STABLE_ID_PREFIX: &str = "stable-id:";
+
+/// 外部文件中的一条译文
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedEntry {
+    pub value: String,
+    /// 导出时的稳定 ID，外部文件不是由 export 生成时为空
+    pub stable_id: Option<String>,
+}
+
+/// key 和基准语言文本的稳定 ID，源文本修改后 ID 随之变化
+pub fn stable_id(key: &str, source: &str) -> String {
+    translation::text_hash(&format!("{}\0{}", key, source))
+        .chars()
+        .take(12)
+        .collect()
+}
+/// 表格中没有以语言代码命名的列时，可以作为译文列的表头
+const VALUE_HEADERS: &[&str] = &["translation", "target", "value", "text"];
+
+/// 读取外部交付的翻译文件，返回 key 到译文的映射，空译文会被忽略。
+/// 支持 JSON（扁平或嵌套）、gettext PO 和 Excel/ODS 表格
+pub fn read_external(path: &Path, lang: &str) -> Result<HashMap<String, ImportedEntry>> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let entries = match extension.as_str() {
+        "json" => translation::read_flat_json_file(path)?
+            .into_iter()
+            .map(|(key, value)| {
+                let entry = ImportedEntry {
+                    value,
+                    stable_id: None,
+                };
+                (key, entry)
+            })
+            .collect(),
+        "po" => parse_po(&translation::decode_text(&fs::read(path)?)?)
+            .with_context(|| format!("解析 PO 文件 {} 失败", path.display()))?,
+        "xlsx" | "xlsm" | "xls" | "ods" => read_spreadsheet(path, lang)?,
+        _ => {
+            return Err(AppError::Validation(format!(
+                "不支持的导入文件格式 {}，支持 .json、.po、.xlsx、.xls 和 .ods",
+                path.display()
+            ))
+            .into())
+        }
+    };
+    Ok(entries
+        .into_iter()
+        .filter(|(key, entry)| !key.trim().is_empty() && !entry.value.trim().is_empty())
+        .collect())
+}
+
+/// 读取第一个工作表：第一行为表头，key 列为 key/id/term，
+/// 译文列为与语言代码同名的列或 translation/target/value/text，只有两列时使用第二列
+fn read_spreadsheet(path: &Path, lang: &str) -> Result<HashMap<String, ImportedEntry>> {
+    let mut workbook = calamine::open_workbook_auto(path)
+        .with_context(|| format!("打开表格 {} 失败", path.display()))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| anyhow!("表格 {} 中没有工作表", path.display()))?
+        .with_context(|| format!("读取表格 {} 失败", path.display()))?;
+    let rows: Vec<Vec<String>> = range
+        .rows()
+        .map(|row| row.iter().map(cell_text).collect())
+        .collect();
+    rows_to_entries(&rows, lang).with_context(|| format!("解析表格 {} 失败", path.display()))
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        cell => cell.to_string(),
+    }
+}
+
+fn rows_to_entries(rows: &[Vec<String>], lang: &str) -> Result<HashMap<String, ImportedEntry>> {
+    let Some((header, data)) = rows.split_first() else {
+        return Ok(HashMap::new());
+    };
+    let header: Vec<String> = header
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let find = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|h| h == name))
+    };
+    let id_column = find(&[STABLE_ID_HEADER.to_lowercase().as_str()]);
+    let key_column = find(KEY_HEADERS)
+        .ok_or_else(|| anyhow!("第一行中没有 key 列（表头应为 {}）", KEY_HEADERS.join("/")))?;
+    let value_column = find(&[lang.to_lowercase().as_str()])
+        .or_else(|| find(VALUE_HEADERS))
+        .or_else(|| (header.len() == 2).then_some(1 - key_column))
+        .ok_or_else(|| {
+            anyhow!(
+                "第一行中没有译文列（表头应为 {} 或 {}）",
+                lang,
+                VALUE_HEADERS.join("/")
+            )
+        })?;
+
+    Ok(data
+        .iter()
+        .filter_map(|row| {
+            let key = row.get(key_column)?.trim();
+            let entry = ImportedEntry {
+                value: row.get(value_column)?.clone(),
+                stable_id: id_column
+                    .and_then(|column| row.get(column))
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty()),
+            };
+            Some((key.to_string(), entry))
+        })
+        .collect())
+}
+
+/// PO 字符串字面量的转义
+fn unescape_po(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 去掉引号的 PO 字符串，如 `"Hello \"x\""`
+fn po_string(text: &str) -> Result<String> {
+    let text = text.trim();
+    let inner = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .ok_or_else(|| anyhow!("无效的 PO 字符串：{}", text))?;
+    Ok(unescape_po(inner))
+}
+
+#[derive(Default)]
+struct PoEntry {
+    context: Option<String>,
+    id: Option<String>,
+    translation: Option<String>,
+    stable_id: Option<String>,
+    fuzzy: bool,
+}
+
+#[derive(Clone, Copy)]
+enum PoField {
+    Context,
+    Id,
+    Translation,
+    /// msgid_plural 和 msgstr[1] 等复数形式，导入时忽略
+    Ignored,
+}
+
+impl PoEntry {
+    /// key 使用 msgctxt，没有 msgctxt 时使用 msgid；fuzzy 条目和文件头不导入
+    fn finish(self, entries: &mut HashMap<String, ImportedEntry>) {
+        if self.fuzzy {
+            return;
+        }
+        let key = self.context.or(self.id).unwrap_or_default();
+        if let (false, Some(value)) = (key.is_empty(), self.translation) {
+            let stable_id = self.stable_id;
+            entries.insert(key, ImportedEntry { value, stable_id });
+        }
+    }
+}
+
+/// 解析 gettext PO 文件
+fn parse_po(text: &str) -> Result<HashMap<String, ImportedEntry>> {
+    let mut entries = HashMap::new();
+    let mut entry = PoEntry::default();
+    let mut field = PoField::Ignored;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            std::mem::take(&mut entry).finish(&mut entries);
+            field = PoField::Ignored;
+            continue;
+        }
+        // 注释属于下一个条目，上一个条目已经有 msgstr 时先结束它
+        if line.starts_with('#') && entry.translation.is_some() {
+            std::mem::take(&mut entry).finish(&mut entries);
+            field = PoField::Ignored;
+        }
+        if let Some(flags) = line.strip_prefix("#,") {
+            entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            continue;
+        }
+        if let Some(id) = line
+            .strip_prefix("#.")
+            .and_then(|comment| comment.trim().strip_prefix(PO_STABLE_ID_PREFIX))
+        {
+            entry.stable_id = Some(id.trim().to_string());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('"') {
+            let text = po_string(line)?;
+            let target = match field {
+                PoField::Context => &mut entry.context,
+                PoField::Id => &mut entry.id,
+                PoField::Translation => &mut entry.translation,
+                PoField::Ignored => continue,
+            };
+            target.get_or_insert_with(String::new).push_str(&text);
+            continue;
+        }
+
+        let (keyword, value) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| anyhow!("无法解析的行：{}", line))?;
+        // 上一个条目已经有 msgstr 时，新的 msgctxt/msgid 开始下一个条目
+        if matches!(keyword, "msgctxt" | "msgid") && entry.translation.is_some() {
+            std::mem::take(&mut entry).finish(&mut entries);
+        }
+        field = match keyword {
+            "msgctxt" => PoField::Context,
+            "msgid" => PoField::Id,
+            "msgstr" | "msgstr[0]" => PoField::Translation,
+            _ => PoField::Ignored,
+        };
+        let value = po_string(value)?;
+        match field {
+            PoField::Context => entry.context = Some(value),
+            PoField::Id => entry.id = Some(value),
+            PoField::Translation => entry.translation = Some(value),
+            PoField::Ignored => {}
+        }
+    }
+    entry.finish(&mut entries);
+    Ok(entries)
+}
+
+/// 导出给翻译公司的一行：key、基准语言文本和当前译文
+#[derive(Debug, Clone)]
+pub struct ExportRow {
+    pub key: String,
+    pub source: String,
+    pub translation: String,
+}
+
+/// 写入导出文件，每行带有稳定 ID，导入时用来发现导出后源文本有变化的行。
+/// 支持 Excel 表格和 gettext PO
+pub fn write_external(
+    path: &Path,
+    base_language: &str,
+    lang: &str,
+    rows: &[ExportRow],
+) -> Result<()> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    match extension.as_str() {
+        "xlsx" => write_spreadsheet(path, base_language, lang, rows),
+        "po" => fs::write(path, format_po(lang, rows))
+            .with_context(|| format!("写入 {} 失败", path.display())),
+        _ => Err(AppError::Validation(format!(
+            "不支持的导出文件格式 {}，支持 .xlsx 和 .po",
+            path.display()
+        ))
+        .into()),
+    }
+}
+
+/// 表头为 Stable ID、Key、基准语言代码、目标语言代码，与导入时的表头规则一致
+fn write_spreadsheet(
+    path: &Path,
+    base_language: &str,
+    lang: &str,
+    rows: &[ExportRow],
+) -> Result<()> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    let bold = Format::new().set_bold();
+    for (column, header) in [STABLE_ID_HEADER, "Key", base_language, lang]
+        .into_iter()
+        .enumerate()
+    {
+        sheet.write_string_with_format(0, column as u16, header, &bold)?;
+    }
+    for (index, row) in rows.iter().enumerate() {
+        let line = index as u32 + 1;
+        sheet.write_string(line, 0, stable_id(&row.key, &row.source))?;
+        sheet.write_string(line, 1, &row.key)?;
+        sheet.write_string(line, 2, &row.source)?;
+        sheet.write_string(line, 3, &row.translation)?;
+    }
+    sheet.set_column_width(1, 40)?;
+    sheet.set_column_width(2, 60)?;
+    sheet.set_column_width(3, 60)?;
+    workbook
+        .save(path)
+        .with_context(|| format!("写入表格 {} 失败", path.display()))
+}
+
+fn escape_po(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+/// key 写入 msgctxt，基准语言文本写入 msgid，稳定 ID 写入注释
+fn format_po(lang: &str, rows: &[ExportRow]) -> String {
+    let mut po = format!(
+        "msgid \"\"\nmsgstr \"\"\n\"Language: {}\\n\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n",
+        escape_po(lang)
+    );
+    for row in rows {
+        po.push_str(&format!(
+            "\n#. {} {}\nmsgctxt \"{}\"\nmsgid \"{}\"\nmsgstr \"{}\"\n",
+            PO_STABLE_ID_PREFIX,
+            stable_id(&row.key, &row.source),
+            escape_po(&row.key),
+            escape_po(&row.source),
+            escape_po(&row.translation)
+        ));
+    }
+    po
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_po() -> Result<()> {
+        let po = r#"
+msgid ""
+msgstr ""
+"Language: de-DE\n"
+
+#: src/home.ts:3
+msgctxt "home.title"
+msgid "Home"
+msgstr "Startseite"
+
+msgid "home.body"
+msgstr ""
+"Hallo \"Welt\"\n"
+"zweite Zeile"
+
+#, fuzzy
+msgctxt "home.stale"
+msgid "Old"
+msgstr "Alt"
+
+msgctxt "home.items"
+msgid "{count} item"
+msgid_plural "{count} items"
+msgstr[0] "{count} Artikel"
+msgstr[1] "{count} Artikel (viele)"
+msgctxt "home.empty"
+msgid "Empty"
+msgstr ""
+"#;
+        let entries = parse_po(po)?;
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries["home.title"].value, "Startseite");
+        assert_eq!(entries["home.body"].value, "Hallo \"Welt\"\nzweite Zeile");
+        assert_eq!(entries["home.items"].value, "{count} Artikel");
+        assert_eq!(entries["home.empty"].value, "");
+        assert!(!entries.contains_key("home.stale"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rows_to_entries() -> Result<()> {
+        let rows = |cells: &[&[&str]]| -> Vec<Vec<String>> {
+            cells
+                .iter()
+                .map(|row| row.iter().map(|c| c.to_string()).collect())
+                .collect()
+        };
+
+        let entries = rows_to_entries(
+            &rows(&[
+                &["Key", "en-US", "de-DE"],
+                &["home.title", "Home", "Startseite"],
+                &["home.body"],
+            ]),
+            "de-DE",
+        )?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries["home.title"].value, "Startseite");
+        assert_eq!(entries["home.title"].stable_id, None);
+
+        // export 生成的表格：ID 列不会被当作 key 列
+        let entries = rows_to_entries(
+            &rows(&[
+                &["Stable ID", "Key", "en-US", "de-DE"],
+                &["1a2b3c4d5e6f", "home.title", "Home", "Startseite"],
+            ]),
+            "de-DE",
+        )?;
+        assert_eq!(entries["home.title"].value, "Startseite");
+        assert_eq!(
+            entries["home.title"].stable_id.as_deref(),
+            Some("1a2b3c4d5e6f")
+        );
+
+        // 只有两列时第二列为译文
+        let entries = rows_to_entries(&rows(&[&["ID", "Text DE"], &["a", "A"]]), "de-DE")?;
+        assert_eq!(entries["a"].value, "A");
+
+        assert!(rows_to_entries(&rows(&[&["name", "de-DE"]]), "de-DE").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_po_export_roundtrip() -> Result<()> {
+        let rows = vec![
+            ExportRow {
+                key: "home.title".to_string(),
+                source: "Say \"hi\"\nnow".to_string(),
+                translation: "Sag \"hallo\"\njetzt".to_string(),
+            },
+            ExportRow {
+                key: "home.body".to_string(),
+                source: "Body".to_string(),
+                translation: String::new(),
+            },
+        ];
+        let entries = parse_po(&format_po("de-DE", &rows))?;
+        assert_eq!(entries.len(), 2);
+        let title = &entries["home.title"];
+        assert_eq!(title.value, "Sag \"hallo\"\njetzt");
+        assert_eq!(
+            title.stable_id.as_deref(),
+            Some(stable_id("home.title", "Say \"hi\"\nnow").as_str())
+        );
+        // 源文本变化后 ID 不同
+        assert_ne!(
+            stable_id("home.title", "Say hi"),
+            stable_id("home.title", "Say hello")
+        );
+        Ok(())
+    }
+}
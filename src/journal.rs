@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::translation::TranslationDelta;
+use crate::vfs::FileSystem;
+use crate::workspace;
+
+pub fn journal_file() -> PathBuf {
+    workspace::work_dir().join("push-journal.json")
+}
+
+/// push 计划中一种语言的上传
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedUpload {
+    /// 本地文件路径，只用于输出
+    pub full_path: String,
+    /// 服务器上还没有该语言，上传全部内容而不是增量
+    pub first_upload: bool,
+    pub delta: TranslationDelta,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// 一次还没有完成的 push
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPush {
+    /// 开始 push 的时间（RFC 3339）
+    pub started_at: String,
+    pub uploads: Vec<PlannedUpload>,
+}
+
+impl PendingPush {
+    pub fn remaining(&self) -> usize {
+        self.uploads.iter().filter(|u| !u.done).count()
+    }
+}
+
+/// 开始上传前写入的上传计划，中断后 `push --resume` 从中继续，
+/// 按 `<子系统>@<host>#<版本>` 区分，完成后删除对应的记录
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PushJournal {
+    #[serde(default)]
+    pushes: BTreeMap<String, PendingPush>,
+}
+
+/// 上传目标：子系统、host 和版本，切换环境或版本的 push 互不影响
+pub fn push_key(config: &Config) -> String {
+    format!(
+        "{}@{}#{}",
+        config.sub_system_name(),
+        config.host,
+        config.version_no
+    )
+}
+
+impl PushJournal {
+    /// 读取上传计划，文件不存在时返回空计划
+    pub fn load(fs: &dyn FileSystem, path: &Path) -> Result<Self> {
+        if !fs.exists(path) {
+            return Ok(Self::default());
+        }
+        let content = fs
+            .read_to_string(path)
+            .with_context(|| format!("读取上传计划 {} 失败", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("解析上传计划 {} 失败", path.display()))
+    }
+
+    /// 没有未完成的 push 时删除文件。先写入临时文件再重命名，中断时不会留下不完整的文件
+    pub fn save(&self, fs: &dyn FileSystem, path: &Path) -> Result<()> {
+        if self.pushes.is_empty() {
+            if fs.exists(path) {
+                fs.remove_file(path)
+                    .with_context(|| format!("删除上传计划 {} 失败", path.display()))?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        let content = serde_json::to_string_pretty(self)? + "\n";
+        fs.write(&temp, content.as_bytes())
+            .with_context(|| format!("写入上传计划 {} 失败", temp.display()))?;
+        fs.rename(&temp, path)
+            .with_context(|| format!("写入上传计划 {} 失败", path.display()))
+    }
+
+    pub fn get(&self, config: &Config) -> Option<&PendingPush> {
+        self.pushes.get(&push_key(config))
+    }
+
+    /// 记录新的上传计划，替换同一目标未完成的计划
+    pub fn start(&mut self, config: &Config, uploads: Vec<PlannedUpload>) {
+        let pending = PendingPush {
+            started_at: chrono::Local::now().to_rfc3339(),
+            uploads,
+        };
+        self.pushes.insert(push_key(config), pending);
+    }
+
+    pub fn mark_done(&mut self, config: &Config, index: usize) {
+        if let Some(upload) = self
+            .pushes
+            .get_mut(&push_key(config))
+            .and_then(|pending| pending.uploads.get_mut(index))
+        {
+            upload.done = true;
+        }
+    }
+
+    pub fn finish(&mut self, config: &Config) {
+        self.pushes.remove(&push_key(config));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::translation_file;
+    use crate::translation::TranslationFile;
+    use crate::vfs::MemoryFs;
+
+    #[test]
+    fn test_journal_roundtrip() -> Result<()> {
+        let fs = MemoryFs::new();
+        let path = Path::new(".i18n-app").join("push-journal.json");
+        let config = Config::default();
+        let upload = |lang: &str| {
+            let file = translation_file(lang, &[("save", "Save")]);
+            PlannedUpload {
+                full_path: format!("locales/{}.json", lang),
+                first_upload: true,
+                delta: TranslationDelta::new(&file, &TranslationFile::default()),
+                done: false,
+            }
+        };
+
+        let mut journal = PushJournal::load(&fs, &path)?;
+        journal.start(&config, vec![upload("en-US"), upload("de")]);
+        journal.mark_done(&config, 0);
+        journal.save(&fs, &path)?;
+
+        let journal = PushJournal::load(&fs, &path)?;
+        let pending = journal.get(&config).expect("pending push");
+        assert_eq!(pending.remaining(), 1);
+        assert_eq!(&*pending.uploads[1].delta.added["save"], "Save");
+        // 其他版本没有未完成的 push
+        let other = Config {
+            version_no: "2.0.0".to_string(),
+            ..Config::default()
+        };
+        assert!(journal.get(&other).is_none());
+
+        let mut journal = journal;
+        journal.finish(&config);
+        journal.save(&fs, &path)?;
+        assert!(fs.paths().is_empty());
+        Ok(())
+    }
+}
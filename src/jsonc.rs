@@ -0,0 +1,425 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// JSONC 文件中的注释，按扁平 key 记录位置，重写文件时放回原处
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct JsonComments {
+    /// 根对象之前的注释
+    pub header: Vec<String>,
+    /// key 之前单独成行的注释
+    pub leading: HashMap<String, Vec<String>>,
+    /// 与 key 的值在同一行、位于值之后的注释
+    pub trailing: HashMap<String, Vec<String>>,
+    /// 对象结束 `}` 之前的注释，key 为对象的路径，根对象为空字符串
+    pub closing: HashMap<String, Vec<String>>,
+    /// 根对象之后的注释
+    pub footer: Vec<String>,
+}
+
+impl JsonComments {
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty()
+            && self.leading.is_empty()
+            && self.trailing.is_empty()
+            && self.closing.is_empty()
+            && self.footer.is_empty()
+    }
+}
+
+/// 读取字符串字面量（`start` 指向开头的引号），返回结束引号之后的位置
+fn skip_string(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// 读取注释（`start` 指向开头的 `/`），返回注释结束后的位置，不是注释时返回 None
+fn skip_comment(chars: &[char], start: usize) -> Option<usize> {
+    match chars.get(start + 1) {
+        Some('/') => {
+            let mut i = start + 2;
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            Some(i)
+        }
+        Some('*') => {
+            let mut i = start + 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                i += 1;
+            }
+            Some((i + 2).min(chars.len()))
+        }
+        _ => None,
+    }
+}
+
+/// 去掉注释和结尾多余的逗号，得到标准 JSON，换行保持不变以便错误信息中的行号可用
+pub fn strip_comments(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '"' => {
+                let end = skip_string(&chars, i);
+                output.extend(&chars[i..end]);
+                i = end;
+            }
+            '/' => match skip_comment(&chars, i) {
+                Some(end) => {
+                    output.extend(chars[i..end].iter().filter(|c| **c == '\n'));
+                    i = end;
+                }
+                None => {
+                    output.push('/');
+                    i += 1;
+                }
+            },
+            ',' => {
+                // 结尾逗号：后面只有空白和注释，然后是 } 或 ]
+                let mut j = i + 1;
+                loop {
+                    while j < chars.len() && chars[j].is_whitespace() {
+                        j += 1;
+                    }
+                    match (chars.get(j), skip_comment(&chars, j)) {
+                        (Some('/'), Some(end)) => j = end,
+                        _ => break,
+                    }
+                }
+                if !matches!(chars.get(j), Some('}') | Some(']')) {
+                    output.push(',');
+                }
+                i += 1;
+            }
+            c => {
+                output.push(c);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+enum Frame {
+    Object {
+        path: Option<String>,
+        key: Option<String>,
+    },
+    Array,
+}
+
+fn child_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// 提取 JSONC 中的注释及其对应的 key。数组中的注释无法稳定定位，会被忽略
+pub fn extract_comments(text: &str) -> JsonComments {
+    let chars: Vec<char> = text.chars().collect();
+    let mut comments = JsonComments::default();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut last_key: Option<String> = None;
+    let mut line_has_content = false;
+    let mut expecting_key = false;
+    let mut root_closed = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                line_has_content = false;
+                i += 1;
+            }
+            '/' if skip_comment(&chars, i).is_some() => {
+                let end = skip_comment(&chars, i).unwrap_or(chars.len());
+                // CRLF 文件中的注释去掉 \r，换行符由输出格式决定
+                let comment = chars[i..end]
+                    .iter()
+                    .collect::<String>()
+                    .replace("\r\n", "\n")
+                    .trim_end_matches('\r')
+                    .to_string();
+                match &last_key {
+                    Some(key) if line_has_content && !root_closed => comments
+                        .trailing
+                        .entry(key.clone())
+                        .or_default()
+                        .push(comment),
+                    _ if root_closed => comments.footer.push(comment),
+                    _ => pending.push(comment),
+                }
+                i = end;
+            }
+            '"' => {
+                let end = skip_string(&chars, i);
+                if expecting_key {
+                    let raw: String = chars[i..end].iter().collect();
+                    let key = serde_json::from_str::<String>(&raw).unwrap_or_default();
+                    if let Some(Frame::Object { path, key: current }) = stack.last_mut() {
+                        let full_path = path.as_deref().map(|p| child_path(p, &key));
+                        if let Some(full_path) = &full_path {
+                            if !pending.is_empty() {
+                                comments
+                                    .leading
+                                    .insert(full_path.clone(), std::mem::take(&mut pending));
+                            }
+                        }
+                        pending.clear();
+                        last_key = full_path;
+                        *current = Some(key);
+                    }
+                    expecting_key = false;
+                }
+                line_has_content = true;
+                i = end;
+            }
+            '{' => {
+                let path = match stack.last() {
+                    None => {
+                        comments.header.append(&mut pending);
+                        Some(String::new())
+                    }
+                    Some(Frame::Object {
+                        path: Some(p),
+                        key: Some(k),
+                    }) => Some(child_path(p, k)),
+                    _ => None,
+                };
+                stack.push(Frame::Object { path, key: None });
+                expecting_key = true;
+                line_has_content = true;
+                i += 1;
+            }
+            '[' => {
+                stack.push(Frame::Array);
+                expecting_key = false;
+                line_has_content = true;
+                i += 1;
+            }
+            '}' | ']' => {
+                if let Some(Frame::Object {
+                    path: Some(path), ..
+                }) = stack.last()
+                {
+                    if !pending.is_empty() {
+                        comments
+                            .closing
+                            .insert(path.clone(), std::mem::take(&mut pending));
+                    }
+                }
+                pending.clear();
+                stack.pop();
+                root_closed = stack.is_empty();
+                last_key = match stack.last() {
+                    Some(Frame::Object {
+                        path: Some(p),
+                        key: Some(k),
+                    }) => Some(child_path(p, k)),
+                    _ => None,
+                };
+                expecting_key = false;
+                line_has_content = true;
+                i += 1;
+            }
+            ',' => {
+                expecting_key = matches!(stack.last(), Some(Frame::Object { .. }));
+                line_has_content = true;
+                i += 1;
+            }
+            c if c.is_whitespace() => i += 1,
+            _ => {
+                line_has_content = true;
+                i += 1;
+            }
+        }
+    }
+    comments
+}
+
+/// 带注释输出 JSON，格式与 serde_json 的 pretty 输出一致
+pub fn to_string_with_comments(
+    value: &Value,
+    comments: &JsonComments,
+    indent: &str,
+    escape_string: &dyn Fn(&str) -> Result<String>,
+) -> Result<String> {
+    let mut writer = CommentWriter {
+        output: String::new(),
+        comments,
+        indent,
+        escape_string,
+    };
+    for comment in &comments.header {
+        writer.output.push_str(comment);
+        writer.output.push('\n');
+    }
+    writer.write_value(value, Some(""), 0)?;
+    for comment in &comments.footer {
+        writer.output.push('\n');
+        writer.output.push_str(comment);
+    }
+    Ok(writer.output)
+}
+
+struct CommentWriter<'a> {
+    output: String,
+    comments: &'a JsonComments,
+    indent: &'a str,
+    escape_string: &'a dyn Fn(&str) -> Result<String>,
+}
+
+impl CommentWriter<'_> {
+    fn newline(&mut self, depth: usize) {
+        self.output.push('\n');
+        for _ in 0..depth {
+            self.output.push_str(self.indent);
+        }
+    }
+
+    fn write_value(&mut self, value: &Value, path: Option<&str>, depth: usize) -> Result<()> {
+        match value {
+            Value::Object(map) => {
+                let closing = path.and_then(|p| self.comments.closing.get(p));
+                if map.is_empty() && closing.is_none() {
+                    self.output.push_str("{}");
+                    return Ok(());
+                }
+                self.output.push('{');
+                for (i, (key, child)) in map.iter().enumerate() {
+                    let child_path = path.map(|p| child_path(p, key));
+                    let leading = child_path
+                        .as_ref()
+                        .and_then(|p| self.comments.leading.get(p));
+                    for comment in leading.into_iter().flatten() {
+                        self.newline(depth + 1);
+                        self.output.push_str(comment);
+                    }
+                    self.newline(depth + 1);
+                    let key = (self.escape_string)(key)?;
+                    self.output.push_str(&key);
+                    self.output.push_str(": ");
+                    self.write_value(child, child_path.as_deref(), depth + 1)?;
+                    if i + 1 < map.len() {
+                        self.output.push(',');
+                    }
+                    let trailing = child_path
+                        .as_ref()
+                        .and_then(|p| self.comments.trailing.get(p));
+                    for comment in trailing.into_iter().flatten() {
+                        self.output.push(' ');
+                        self.output.push_str(comment);
+                    }
+                }
+                for comment in closing.into_iter().flatten() {
+                    self.newline(depth + 1);
+                    self.output.push_str(comment);
+                }
+                self.newline(depth);
+                self.output.push('}');
+            }
+            Value::Array(items) => {
+                if items.is_empty() {
+                    self.output.push_str("[]");
+                    return Ok(());
+                }
+                self.output.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    self.newline(depth + 1);
+                    self.write_value(item, None, depth + 1)?;
+                    if i + 1 < items.len() {
+                        self.output.push(',');
+                    }
+                }
+                self.newline(depth);
+                self.output.push(']');
+            }
+            Value::String(s) => {
+                let escaped = (self.escape_string)(s)?;
+                self.output.push_str(&escaped);
+            }
+            other => self.output.push_str(&other.to_string()),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"// Home page strings
+{
+  // Shown in the browser tab
+  "home": {
+    "title": "Home", // keep short
+    /* Subtitle under the hero image */
+    "subtitle": "Welcome, // not a comment",
+  },
+  "url": "https://example.com/*not*/",
+  // TODO: legal review
+}
+"#;
+
+    #[test]
+    fn test_strip_comments() -> Result<()> {
+        let value: Value = serde_json::from_str(&strip_comments(SOURCE))?;
+        assert_eq!(value["home"]["subtitle"], "Welcome, // not a comment");
+        assert_eq!(value["url"], "https://example.com/*not*/");
+        Ok(())
+    }
+
+    #[test]
+    fn test_comments_roundtrip() -> Result<()> {
+        let comments = extract_comments(SOURCE);
+        assert_eq!(comments.header, vec!["// Home page strings"]);
+        assert_eq!(
+            comments.leading["home"],
+            vec!["// Shown in the browser tab"]
+        );
+        assert_eq!(comments.trailing["home.title"], vec!["// keep short"]);
+        assert_eq!(
+            comments.leading["home.subtitle"],
+            vec!["/* Subtitle under the hero image */"]
+        );
+        assert_eq!(comments.closing[""], vec!["// TODO: legal review"]);
+
+        // 新增 key 后重写，注释仍在原来的 key 旁边
+        let mut value: Value = serde_json::from_str(&strip_comments(SOURCE))?;
+        value["home"]["button"] = Value::String("Go".to_string());
+        let escape = |s: &str| Ok(serde_json::to_string(s)?);
+        let output = to_string_with_comments(&value, &comments, "  ", &escape)?;
+        assert_eq!(
+            output,
+            r#"// Home page strings
+{
+  // Shown in the browser tab
+  "home": {
+    "button": "Go",
+    /* Subtitle under the hero image */
+    "subtitle": "Welcome, // not a comment",
+    "title": "Home" // keep short
+  },
+  "url": "https://example.com/*not*/"
+  // TODO: legal review
+}"#
+        );
+        assert_eq!(extract_comments(&output), comments);
+
+        Ok(())
+    }
+}
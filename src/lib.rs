@@ -1,4 +1,14 @@
 pub mod api;
+pub mod backend;
 pub mod cli;
 pub mod config;
+pub mod credentials;
+pub mod jsonc;
+pub mod locations;
+pub mod merge;
+pub mod rate_limit;
+pub mod review;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 pub mod translation;
+pub mod vfs;
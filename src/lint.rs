@@ -0,0 +1,201 @@
+mod baseline;
+mod rules;
+mod sarif;
+
+use anyhow::Result;
+
+use crate::config::{LintConfig, Severity};
+use crate::error::AppError;
+use crate::output;
+use crate::translation::{self, Span, TranslationFile};
+
+pub use baseline::{baseline_path, Baseline};
+pub use sarif::to_sarif;
+
+/// 规则发现的问题，级别由配置决定
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub language: String,
+    /// 问题所在的翻译文件（相对路径）
+    pub file: String,
+    pub key: String,
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+/// 带规则 ID 和级别的 lint 结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub language: String,
+    pub file: String,
+    pub key: String,
+    pub message: String,
+    /// key 在文件中的位置，缺失的 key 没有位置
+    pub span: Option<Span>,
+}
+
+/// 规则检查的输入
+pub struct LintContext<'a> {
+    pub config: &'a LintConfig,
+    pub base: &'a TranslationFile,
+    /// 基准语言以外的翻译文件，已按回退链补全
+    pub translations: &'a [TranslationFile],
+    /// 配置了 identicalAsUntranslated 时为 identicalAllowlist，与基准文本相同的译文也算缺失
+    pub identical_allowlist: Option<&'a [String]>,
+}
+
+/// lint 规则，`id` 即配置中 `lint.rules` 使用的名称
+pub trait Rule {
+    fn id(&self) -> &'static str;
+    fn default_severity(&self) -> Severity;
+    /// 规则的简短说明，用于 SARIF 等报告
+    fn description(&self) -> &'static str;
+    fn check(&self, context: &LintContext) -> Vec<Finding>;
+}
+
+/// 所有内置规则
+pub fn rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(rules::MissingKeys),
+        Box::new(rules::EmptyValues),
+        Box::new(rules::PlaceholderMismatch),
+        Box::new(rules::Length),
+        Box::new(rules::Markup),
+        Box::new(rules::Duplicates),
+        Box::new(rules::Whitespace),
+        Box::new(rules::TerminalPunctuation),
+        Box::new(rules::Nbsp),
+    ]
+}
+
+/// 执行未关闭的规则，`only` 不为空时只执行其中的规则，结果按语言、key、规则排序
+pub fn run(context: &LintContext, only: &[&str]) -> Result<Vec<Diagnostic>> {
+    let rules = rules();
+    if let Some(unknown) = context
+        .config
+        .rules
+        .keys()
+        .find(|id| !rules.iter().any(|rule| rule.id() == id.as_str()))
+    {
+        let known: Vec<&str> = rules.iter().map(|rule| rule.id()).collect();
+        return Err(AppError::Config(format!(
+            "未知的 lint 规则 '{}'，可用规则：{}",
+            unknown,
+            known.join(", ")
+        ))
+        .into());
+    }
+
+    let mut diagnostics = Vec::new();
+    for rule in &rules {
+        if !only.is_empty() && !only.contains(&rule.id()) {
+            continue;
+        }
+        let severity = context
+            .config
+            .rules
+            .get(rule.id())
+            .copied()
+            .unwrap_or_else(|| rule.default_severity());
+        if severity == Severity::Off {
+            continue;
+        }
+        diagnostics.extend(rule.check(context).into_iter().map(|finding| Diagnostic {
+            rule: rule.id(),
+            severity,
+            language: finding.language,
+            file: finding.file,
+            key: finding.key,
+            message: finding.message,
+            span: finding.span,
+        }));
+    }
+    diagnostics.sort_by(|a, b| (&a.language, &a.key, a.rule).cmp(&(&b.language, &b.key, b.rule)));
+    Ok(diagnostics)
+}
+
+/// 输出 lint 结果，返回 error 级别的数量
+pub fn print(diagnostics: &[Diagnostic]) -> usize {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if diagnostics.is_empty() {
+        output::status(output::Status::Ok, "no lint issues");
+        return 0;
+    }
+
+    let rows = diagnostics.iter().map(|d| {
+        vec![
+            format!("{:?}", d.severity).to_lowercase(),
+            d.rule.to_string(),
+            translation::location(&d.file, d.span),
+            d.key.clone(),
+            d.message.clone(),
+        ]
+    });
+    output::table(["Severity", "Rule", "Location", "Key", "Message"], rows);
+    let status = if errors > 0 {
+        output::Status::Failed
+    } else {
+        output::Status::Skipped
+    };
+    output::status(
+        status,
+        format!("{} errors, {} warnings", errors, diagnostics.len() - errors),
+    );
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::translation_file;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_run_applies_severities() -> Result<()> {
+        let base = translation_file("en-US", &[("greeting", "Hello {name}"), ("ok", "OK")]);
+        let translations = vec![translation_file("zh-CN", &[("greeting", "你好")])];
+        let config = LintConfig {
+            rules: HashMap::from([("missing-keys".to_string(), Severity::Off)]),
+            ..Default::default()
+        };
+        let context = LintContext {
+            config: &config,
+            base: &base,
+            translations: &translations,
+            identical_allowlist: None,
+        };
+
+        let diagnostics = run(&context, &[])?;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "placeholder-mismatch");
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].key, "greeting");
+        assert!(run(&context, &["duplicates", "missing-keys"])?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_rejects_unknown_rule() {
+        let base = translation_file("en-US", &[]);
+        let config = LintConfig {
+            rules: HashMap::from([("no-such-rule".to_string(), Severity::Error)]),
+            ..Default::default()
+        };
+        let context = LintContext {
+            config: &config,
+            base: &base,
+            translations: &[],
+            identical_allowlist: None,
+        };
+        let err = run(&context, &[])
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(err.contains("no-such-rule"));
+    }
+}
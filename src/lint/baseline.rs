@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Diagnostic;
+use crate::workspace;
+
+/// baseline 中记录的一条已有问题，不比较说明文字，文字变化不会让问题重新出现
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    sub_system: String,
+    rule: String,
+    language: String,
+    key: String,
+}
+
+impl Entry {
+    fn new(sub_system: &str, diagnostic: &Diagnostic) -> Self {
+        Self {
+            sub_system: sub_system.to_string(),
+            rule: diagnostic.rule.to_string(),
+            language: diagnostic.language.clone(),
+            key: diagnostic.key.clone(),
+        }
+    }
+}
+
+/// 已知问题的 baseline，lint 只对不在其中的问题报错
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    violations: BTreeSet<Entry>,
+}
+
+pub fn baseline_path() -> PathBuf {
+    workspace::work_dir().join("lint-baseline.json")
+}
+
+impl Baseline {
+    /// 读取 baseline 文件，不存在时返回空 baseline
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("读取 lint baseline {} 失败", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("解析 lint baseline {} 失败", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content + "\n")
+            .with_context(|| format!("写入 lint baseline {} 失败", path.display()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    pub fn record(&mut self, sub_system: &str, diagnostics: &[Diagnostic]) {
+        self.violations
+            .extend(diagnostics.iter().map(|d| Entry::new(sub_system, d)));
+    }
+
+    /// 去掉 baseline 中已有的问题，返回新问题和被忽略的数量
+    pub fn filter(
+        &self,
+        sub_system: &str,
+        diagnostics: Vec<Diagnostic>,
+    ) -> (Vec<Diagnostic>, usize) {
+        let total = diagnostics.len();
+        let new: Vec<Diagnostic> = diagnostics
+            .into_iter()
+            .filter(|d| !self.violations.contains(&Entry::new(sub_system, d)))
+            .collect();
+        let known = total - new.len();
+        (new, known)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Severity;
+    use tempfile::TempDir;
+
+    fn diagnostic(rule: &'static str, key: &str) -> Diagnostic {
+        Diagnostic {
+            rule,
+            severity: Severity::Error,
+            language: "zh-CN".to_string(),
+            file: "zh-CN.json".to_string(),
+            key: key.to_string(),
+            message: "message".to_string(),
+            span: None,
+        }
+    }
+
+    #[test]
+    fn test_baseline_roundtrip_and_filter() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(".i18n-app").join("lint-baseline.json");
+        assert_eq!(Baseline::load(&path)?.len(), 0);
+
+        let mut baseline = Baseline::default();
+        baseline.record("app", &[diagnostic("markup", "legacy")]);
+        baseline.save(&path)?;
+
+        let baseline = Baseline::load(&path)?;
+        let (new, known) = baseline.filter(
+            "app",
+            vec![
+                diagnostic("markup", "legacy"),
+                diagnostic("markup", "fresh"),
+                diagnostic("length", "legacy"),
+            ],
+        );
+        assert_eq!(known, 1);
+        assert_eq!(
+            new.iter()
+                .map(|d| (d.rule, d.key.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("markup", "fresh"), ("length", "legacy")]
+        );
+
+        // 其他子系统的同名 key 不受影响
+        let (new, known) = baseline.filter("web", vec![diagnostic("markup", "legacy")]);
+        assert_eq!((new.len(), known), (1, 0));
+        Ok(())
+    }
+}
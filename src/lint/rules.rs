@@ -0,0 +1,704 @@
+use std::collections::BTreeMap;
+
+use super::{Finding, LintContext, Rule};
+use crate::check;
+use crate::config::{LintConfig, Severity};
+use crate::translation::{self, TranslationFile};
+
+/// 基准语言文本少于该字符数时不检查长度倍数，短文本的比例没有参考意义
+const MIN_RATIO_BASE_CHARS: usize = 10;
+
+fn finding(file: &TranslationFile, key: &str, message: String) -> Finding {
+    Finding {
+        language: file.language_code.clone(),
+        file: file.relative_path.clone(),
+        span: file.spans.get(key).copied(),
+        key: key.to_string(),
+        message,
+    }
+}
+
+/// 基准语言和译文中都有非空文本的 key
+fn translated_pairs<'a>(
+    base: &'a TranslationFile,
+    file: &'a TranslationFile,
+) -> impl Iterator<Item = (&'a str, &'a str, &'a str)> {
+    base.content.iter().filter_map(move |(key, base_value)| {
+        file.content
+            .get(key)
+            .filter(|value| !value.trim().is_empty() && !base_value.trim().is_empty())
+            .map(|value| (&**key, &**base_value, &**value))
+    })
+}
+
+/// 计数差异：返回 (a 中多出的, b 中多出的)
+fn multiset_diff(a: &[String], b: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut counts: BTreeMap<&str, i32> = BTreeMap::new();
+    for item in a {
+        *counts.entry(item).or_default() += 1;
+    }
+    for item in b {
+        *counts.entry(item).or_default() -= 1;
+    }
+    let mut only_a = Vec::new();
+    let mut only_b = Vec::new();
+    for (item, count) in counts {
+        let target = if count > 0 { &mut only_a } else { &mut only_b };
+        target.extend((0..count.abs()).map(|_| item.to_string()));
+    }
+    (only_a, only_b)
+}
+
+/// 基准语言中有、译文中没有的 key，配置了 identicalAsUntranslated 时还包括与基准文本相同的译文
+pub struct MissingKeys;
+
+impl Rule for MissingKeys {
+    fn id(&self) -> &'static str {
+        "missing-keys"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "Key exists in the base language but is missing or untranslated in the translation"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for file in context.translations {
+            for (key, base_value) in &context.base.content {
+                match file.content.get(key) {
+                    None => findings.push(finding(file, key, "missing translation".to_string())),
+                    // 空文本由 empty-values 报告
+                    Some(value)
+                        if !value.trim().is_empty()
+                            && !translation::is_translated(
+                                key,
+                                base_value,
+                                value,
+                                context.identical_allowlist,
+                            ) =>
+                    {
+                        findings.push(finding(
+                            file,
+                            key,
+                            "same text as the base language".to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// 文本为空或只有空白
+pub struct EmptyValues;
+
+impl Rule for EmptyValues {
+    fn id(&self) -> &'static str {
+        "empty-values"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "Value is empty or whitespace only"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        std::iter::once(context.base)
+            .chain(context.translations)
+            .flat_map(|file| {
+                file.content
+                    .iter()
+                    .filter(|(_, value)| value.trim().is_empty())
+                    .map(move |(key, _)| finding(file, key, "empty value".to_string()))
+            })
+            .collect()
+    }
+}
+
+/// 译文的占位符与基准语言不一致
+pub struct PlaceholderMismatch;
+
+impl Rule for PlaceholderMismatch {
+    fn id(&self) -> &'static str {
+        "placeholder-mismatch"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn description(&self) -> &'static str {
+        "Placeholders differ from the base language"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for file in context.translations {
+            for (key, base_value, value) in translated_pairs(context.base, file) {
+                let mut expected = translation::extract_placeholders(base_value);
+                let mut actual = translation::extract_placeholders(value);
+                expected.sort();
+                expected.dedup();
+                actual.sort();
+                actual.dedup();
+                let (missing, extra) = multiset_diff(&expected, &actual);
+                let mut problems = Vec::new();
+                if !missing.is_empty() {
+                    problems.push(format!("missing {{{}}}", missing.join("}, {")));
+                }
+                if !extra.is_empty() {
+                    problems.push(format!("unexpected {{{}}}", extra.join("}, {")));
+                }
+                if !problems.is_empty() {
+                    findings.push(finding(file, key, problems.join("; ")));
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// 译文过长：超过 `maxLength` 或超过基准语言文本长度的 `maxLengthRatio` 倍
+pub struct Length;
+
+impl Rule for Length {
+    fn id(&self) -> &'static str {
+        "length"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "Translation exceeds maxLength or maxLengthRatio times the base text"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for file in context.translations {
+            for (key, base_value, value) in translated_pairs(context.base, file) {
+                let len = value.chars().count();
+                let base_len = base_value.chars().count();
+                if let Some(max) = context.config.max_length.filter(|max| len > *max) {
+                    findings.push(finding(
+                        file,
+                        key,
+                        format!("{} characters exceeds maxLength {}", len, max),
+                    ));
+                } else if base_len >= MIN_RATIO_BASE_CHARS
+                    && len as f64 > base_len as f64 * context.config.max_length_ratio
+                {
+                    findings.push(finding(
+                        file,
+                        key,
+                        format!(
+                            "{} characters is {:.1}x the base text",
+                            len,
+                            len as f64 / base_len as f64
+                        ),
+                    ));
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// 提取 HTML/XML 标签，如 `<b>`、`</b>`、`<br/>`，标签属性不参与比较
+fn extract_tags(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find(['<', '>']) else {
+            break;
+        };
+        if rest.as_bytes()[end] == b'<' {
+            rest = &rest[end..];
+            continue;
+        }
+        // `<` 后紧跟空白的不是标签，如 `1 < 2`
+        let inner = &rest[..end];
+        rest = &rest[end + 1..];
+        if inner.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let closing = inner.starts_with('/');
+        let self_closing = !closing && inner.ends_with('/');
+        let name: String = inner
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            continue;
+        }
+        tags.push(match (closing, self_closing) {
+            (true, _) => format!("</{}>", name),
+            (_, true) => format!("<{}/>", name),
+            _ => format!("<{}>", name),
+        });
+    }
+    tags.sort();
+    tags
+}
+
+/// 译文的标签与基准语言不一致
+pub struct Markup;
+
+impl Rule for Markup {
+    fn id(&self) -> &'static str {
+        "markup"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn description(&self) -> &'static str {
+        "HTML/XML tags differ from the base language"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for file in context.translations {
+            for (key, base_value, value) in translated_pairs(context.base, file) {
+                let (missing, extra) =
+                    multiset_diff(&extract_tags(base_value), &extract_tags(value));
+                let mut problems = Vec::new();
+                if !missing.is_empty() {
+                    problems.push(format!("missing {}", missing.join(" ")));
+                }
+                if !extra.is_empty() {
+                    problems.push(format!("unexpected {}", extra.join(" ")));
+                }
+                if !problems.is_empty() {
+                    findings.push(finding(file, key, problems.join("; ")));
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// 基准语言中文本相同的 key，与 `check --duplicates` 使用相同的比较方式
+pub struct Duplicates;
+
+impl Rule for Duplicates {
+    fn id(&self) -> &'static str {
+        "duplicates"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "Base language text is duplicated under another key"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        check::find_duplicates(&context.base.content)
+            .into_iter()
+            .flat_map(|group| {
+                let message = format!(
+                    "same text as {}, consider sharing {}",
+                    group.keys[0],
+                    group.suggested_key()
+                );
+                group
+                    .keys
+                    .into_iter()
+                    .skip(1)
+                    .map(move |key| finding(context.base, &key, message.clone()))
+            })
+            .collect()
+    }
+}
+
+/// 文本中的空白问题：首尾空白和连续空格
+fn whitespace_problems(value: &str) -> (bool, bool, bool) {
+    (
+        value.starts_with(char::is_whitespace),
+        value.ends_with(char::is_whitespace),
+        value.trim().contains("  "),
+    )
+}
+
+/// 首尾空白和连续空格。译文中与基准语言一致的空白（如拼接用的 `"Name: "`）不报告，
+/// 但基准语言有而译文丢失的首尾空白会报告
+pub struct Whitespace;
+
+impl Rule for Whitespace {
+    fn id(&self) -> &'static str {
+        "whitespace"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "Leading/trailing whitespace or double spaces"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (key, value) in &context.base.content {
+            let (leading, trailing, double) = whitespace_problems(value);
+            let problems = [
+                (leading, "leading whitespace"),
+                (trailing, "trailing whitespace"),
+                (double, "double space"),
+            ];
+            for (_, problem) in problems.into_iter().filter(|(found, _)| *found) {
+                findings.push(finding(context.base, key, problem.to_string()));
+            }
+        }
+        for file in context.translations {
+            for (key, base_value, value) in translated_pairs(context.base, file) {
+                let expected = whitespace_problems(base_value);
+                let actual = whitespace_problems(value);
+                let problems = [
+                    (expected.0, actual.0, "leading whitespace"),
+                    (expected.1, actual.1, "trailing whitespace"),
+                ];
+                for (expected, actual, problem) in problems {
+                    match (expected, actual) {
+                        (false, true) => findings.push(finding(file, key, problem.to_string())),
+                        (true, false) => findings.push(finding(
+                            file,
+                            key,
+                            format!("missing {} of the base text", problem),
+                        )),
+                        _ => {}
+                    }
+                }
+                if actual.2 && !expected.2 {
+                    findings.push(finding(file, key, "double space".to_string()));
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// 结尾标点的类别，全角和半角标点视为相同
+fn terminal_punctuation(value: &str) -> Option<&'static str> {
+    let value = value.trim_end();
+    if value.ends_with("...") || value.ends_with('…') {
+        return Some("…");
+    }
+    match value.chars().last()? {
+        '.' | '。' | '．' => Some("."),
+        '!' | '！' => Some("!"),
+        '?' | '？' | '؟' => Some("?"),
+        ':' | '：' => Some(":"),
+        ';' | '；' => Some(";"),
+        _ => None,
+    }
+}
+
+/// 译文的结尾标点与基准语言不一致，如基准语言以 `.` 结尾而译文以 `!` 结尾或没有标点
+pub struct TerminalPunctuation;
+
+impl Rule for TerminalPunctuation {
+    fn id(&self) -> &'static str {
+        "terminal-punctuation"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "Terminal punctuation differs from the base language"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for file in context.translations {
+            for (key, base_value, value) in translated_pairs(context.base, file) {
+                let message = match (
+                    terminal_punctuation(base_value),
+                    terminal_punctuation(value),
+                ) {
+                    (expected, actual) if expected == actual => continue,
+                    (Some(expected), Some(actual)) => {
+                        format!(
+                            "ends with '{}' but the base text ends with '{}'",
+                            actual, expected
+                        )
+                    }
+                    (Some(expected), None) => format!("missing terminal '{}'", expected),
+                    (None, Some(actual)) => format!("unexpected terminal '{}'", actual),
+                    (None, None) => continue,
+                };
+                findings.push(finding(file, key, message));
+            }
+        }
+        findings
+    }
+}
+
+/// 未配置 `nbspBefore` 的语言使用的规则：法语在 `! ? : ; »` 前使用不换行空格
+const DEFAULT_NBSP_BEFORE: &[(&str, &str)] = &[("fr", "!?:;»")];
+
+/// 不换行空格和窄不换行空格
+fn is_nbsp(c: char) -> bool {
+    c == '\u{00A0}' || c == '\u{202F}'
+}
+
+/// 语言需要加不换行空格的标点，先按完整语言代码查找，再按主语言（如 `fr-CA` 的 `fr`）查找
+fn nbsp_policy<'a>(config: &'a LintConfig, language: &str) -> Option<&'a str> {
+    let primary = language.split(['-', '_']).next().unwrap_or(language);
+    [language, primary].into_iter().find_map(|lang| {
+        config
+            .nbsp_before
+            .get(lang)
+            .map(String::as_str)
+            .or_else(|| {
+                DEFAULT_NBSP_BEFORE
+                    .iter()
+                    .find(|(code, _)| *code == lang)
+                    .map(|(_, chars)| *chars)
+            })
+    })
+}
+
+/// 按语言的规则检查标点前的不换行空格，如法语的 `Bonjour !` 要求 `!` 前是不换行空格
+pub struct Nbsp;
+
+impl Rule for Nbsp {
+    fn id(&self) -> &'static str {
+        "nbsp"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "Punctuation is not preceded by a non-breaking space as the language requires"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for file in std::iter::once(context.base).chain(context.translations) {
+            let Some(policy) = nbsp_policy(context.config, &file.language_code) else {
+                continue;
+            };
+            for (key, value) in &file.content {
+                let chars: Vec<char> = value.chars().collect();
+                let mut problems = Vec::new();
+                for (i, &c) in chars.iter().enumerate().skip(1) {
+                    // 只检查作为标点使用的字符，跳过 `10:30`、`https://` 和 `?!` 中的第二个
+                    let followed_by_space = chars
+                        .get(i + 1)
+                        .is_none_or(|next| next.is_whitespace() || policy.contains(*next));
+                    let previous = chars[i - 1];
+                    if !policy.contains(c) || !followed_by_space || policy.contains(previous) {
+                        continue;
+                    }
+                    if previous.is_whitespace() && !is_nbsp(previous) {
+                        problems.push(format!("regular space before '{}'", c));
+                    } else if !is_nbsp(previous) {
+                        problems.push(format!("missing non-breaking space before '{}'", c));
+                    }
+                }
+                problems.dedup();
+                if !problems.is_empty() {
+                    findings.push(finding(file, key, problems.join("; ")));
+                }
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::translation_file;
+
+    fn messages(
+        rule: &dyn Rule,
+        base: &TranslationFile,
+        translations: &[TranslationFile],
+    ) -> Vec<String> {
+        let config = LintConfig {
+            max_length: Some(20),
+            ..Default::default()
+        };
+        let context = LintContext {
+            config: &config,
+            base,
+            translations,
+            identical_allowlist: None,
+        };
+        let mut messages: Vec<String> = rule
+            .check(&context)
+            .into_iter()
+            .map(|f| format!("{} {}: {}", f.language, f.key, f.message))
+            .collect();
+        messages.sort();
+        messages
+    }
+
+    #[test]
+    fn test_extract_tags() {
+        assert_eq!(
+            extract_tags(r#"Click <a href="/x">here</a><br/> or 1 < 2 <b >now</b>"#),
+            vec!["</a>", "</b>", "<a>", "<b>", "<br/>"]
+        );
+        assert!(extract_tags("a < b and c > d, <<>>").is_empty());
+    }
+
+    #[test]
+    fn test_rules() {
+        let base = translation_file(
+            "en-US",
+            &[
+                ("greeting", "Hello {name}"),
+                ("bold", "<b>Save</b> now"),
+                ("long", "Short text"),
+                ("empty", ""),
+                ("ok", "OK"),
+                ("confirm", "ok."),
+            ],
+        );
+        let translations = vec![translation_file(
+            "zh-CN",
+            &[
+                ("greeting", "你好 {user}"),
+                ("bold", "<b>保存</b><i>现在</i>"),
+                ("long", "这是一段非常非常非常非常非常非常长的译文文本"),
+                ("empty", "  "),
+            ],
+        )];
+
+        assert_eq!(
+            messages(&MissingKeys, &base, &translations),
+            vec![
+                "zh-CN confirm: missing translation",
+                "zh-CN ok: missing translation"
+            ]
+        );
+        assert_eq!(
+            messages(&EmptyValues, &base, &translations),
+            vec!["en-US empty: empty value", "zh-CN empty: empty value"]
+        );
+        assert_eq!(
+            messages(&PlaceholderMismatch, &base, &translations),
+            vec!["zh-CN greeting: missing {name}; unexpected {user}"]
+        );
+        assert_eq!(
+            messages(&Markup, &base, &translations),
+            vec!["zh-CN bold: unexpected </i> <i>"]
+        );
+        assert_eq!(
+            messages(&Length, &base, &translations),
+            vec!["zh-CN long: 22 characters exceeds maxLength 20"]
+        );
+        assert_eq!(
+            messages(&Duplicates, &base, &translations),
+            vec!["en-US ok: same text as confirm, consider sharing common.ok"]
+        );
+    }
+
+    #[test]
+    fn test_missing_keys_identical_as_untranslated() {
+        let base = translation_file("en-US", &[("brand", "Acme"), ("save", "Save")]);
+        let translations = vec![translation_file(
+            "de-DE",
+            &[("brand", "Acme"), ("save", "Save")],
+        )];
+        let config = LintConfig::default();
+        let allowlist = vec!["Acme".to_string()];
+        let context = LintContext {
+            config: &config,
+            base: &base,
+            translations: &translations,
+            identical_allowlist: Some(&allowlist),
+        };
+        let keys: Vec<String> = MissingKeys
+            .check(&context)
+            .into_iter()
+            .map(|f| f.key)
+            .collect();
+        assert_eq!(keys, vec!["save"]);
+        assert!(messages(&MissingKeys, &base, &translations).is_empty());
+    }
+
+    #[test]
+    fn test_whitespace_and_punctuation_rules() {
+        let base = translation_file(
+            "en-US",
+            &[
+                ("label", "Name: "),
+                ("saved", "Saved."),
+                ("hello", "Hello!"),
+                ("spaced", "Too  many"),
+                ("time", "At 10:30"),
+            ],
+        );
+        let translations = vec![
+            translation_file(
+                "zh-CN",
+                &[
+                    ("label", "名称："),
+                    ("saved", " 已保存。"),
+                    ("hello", "你好"),
+                    ("spaced", "太多"),
+                    ("time", "在 10:30 ！"),
+                ],
+            ),
+            translation_file(
+                "fr-FR",
+                &[
+                    ("label", "Nom\u{00A0}: "),
+                    ("saved", "Enregistré."),
+                    ("hello", "Bonjour !"),
+                    ("spaced", "Trop  nombreux"),
+                    ("time", "À 10:30?!"),
+                ],
+            ),
+        ];
+
+        assert_eq!(
+            messages(&Whitespace, &base, &translations),
+            vec![
+                "en-US label: trailing whitespace",
+                "en-US spaced: double space",
+                "zh-CN label: missing trailing whitespace of the base text",
+                "zh-CN saved: leading whitespace",
+            ]
+        );
+        assert_eq!(
+            messages(&TerminalPunctuation, &base, &translations),
+            vec![
+                "fr-FR time: unexpected terminal '!'",
+                "zh-CN hello: missing terminal '!'",
+                "zh-CN time: unexpected terminal '!'",
+            ]
+        );
+        assert_eq!(
+            messages(&Nbsp, &base, &translations),
+            vec![
+                "fr-FR hello: regular space before '!'",
+                "fr-FR time: missing non-breaking space before '?'",
+            ]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use serde::Serialize;
+
+use super::{Diagnostic, Rule};
+use crate::config::Severity;
+
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/lexiaoyao20/i18n-app";
+
+#[derive(Serialize)]
+struct Log {
+    version: &'static str,
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<RuleDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleDescriptor {
+    id: &'static str,
+    short_description: Message,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        _ => "warning",
+    }
+}
+
+/// 把 lint 结果转换为 SARIF 2.1.0 日志，供 GitHub/GitLab 等代码审查工具显示为注释
+pub fn to_sarif(rules: &[Box<dyn Rule>], diagnostics: &[Diagnostic]) -> serde_json::Value {
+    let results = diagnostics
+        .iter()
+        .map(|d| SarifResult {
+            rule_id: d.rule,
+            level: level(d.severity),
+            message: Message {
+                text: format!("{} ({}): {}", d.key, d.language, d.message),
+            },
+            locations: vec![Location {
+                physical_location: PhysicalLocation {
+                    artifact_location: ArtifactLocation {
+                        uri: d.file.replace('\\', "/"),
+                    },
+                    region: d.span.map(|span| Region {
+                        start_line: span.line,
+                        start_column: span.column,
+                    }),
+                },
+            }],
+        })
+        .collect();
+    let log = Log {
+        version: SARIF_VERSION,
+        schema: SARIF_SCHEMA,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: INFORMATION_URI,
+                    rules: rules
+                        .iter()
+                        .map(|rule| RuleDescriptor {
+                            id: rule.id(),
+                            short_description: Message {
+                                text: rule.description().to_string(),
+                            },
+                        })
+                        .collect(),
+                },
+            },
+            results,
+        }],
+    };
+    serde_json::to_value(log).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint;
+    use crate::translation::Span;
+
+    #[test]
+    fn test_to_sarif() {
+        let diagnostics = vec![Diagnostic {
+            rule: "markup",
+            severity: Severity::Error,
+            language: "zh-CN".to_string(),
+            file: "locales\\zh-CN.json".to_string(),
+            key: "home.title".to_string(),
+            message: "missing <b>".to_string(),
+            span: Some(Span { line: 3, column: 5 }),
+        }];
+        let sarif = to_sarif(&lint::rules(), &diagnostics);
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().map(Vec::len),
+            Some(9)
+        );
+        assert_eq!(
+            run["results"][0],
+            serde_json::json!({
+                "ruleId": "markup",
+                "level": "error",
+                "message": {"text": "home.title (zh-CN): missing <b>"},
+                "locations": [{"physicalLocation": {
+                    "artifactLocation": {"uri": "locales/zh-CN.json"},
+                    "region": {"startLine": 3, "startColumn": 5}
+                }}]
+            })
+        );
+    }
+}
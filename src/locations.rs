@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::translation;
+
+/// 工具的工作目录，缓存和日志默认也放在这里
+pub const WORK_DIR: &str = ".i18n-app";
+/// 使用系统缓存/状态目录的配置值
+const XDG: &str = "xdg";
+
+static CACHE_HOME: OnceLock<PathBuf> = OnceLock::new();
+static STATE_HOME: OnceLock<PathBuf> = OnceLock::new();
+
+/// 设置缓存和状态目录，只能在启动时调用一次。`None` 表示使用工作目录
+pub fn configure_dirs(cache: Option<&str>, state: Option<&str>) -> Result<()> {
+    if let Some(cache) = cache {
+        let _ = CACHE_HOME.set(resolve_dir(cache, dirs::cache_dir())?);
+    }
+    if let Some(state) = state {
+        let base = dirs::state_dir().or_else(dirs::data_local_dir);
+        let _ = STATE_HOME.set(resolve_dir(state, base)?);
+    }
+    Ok(())
+}
+
+/// `xdg` 解析为系统目录下按项目区分的子目录，其他值作为路径
+fn resolve_dir(value: &str, xdg_base: Option<PathBuf>) -> Result<PathBuf> {
+    if !value.eq_ignore_ascii_case(XDG) {
+        return Ok(PathBuf::from(value));
+    }
+    let base = xdg_base.ok_or_else(|| anyhow!("无法确定系统的缓存/状态目录"))?;
+    Ok(base
+        .join("i18n-app")
+        .join(project_id(&std::env::current_dir()?)))
+}
+
+/// 项目目录名加上绝对路径的哈希，不同位置的同名项目不会共用目录
+fn project_id(project: &Path) -> String {
+    let project = project
+        .canonicalize()
+        .unwrap_or_else(|_| project.to_path_buf());
+    let name: String = project
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let hash: String = translation::text_hash(&project.to_string_lossy())
+        .chars()
+        .take(8)
+        .collect();
+    format!("{}-{}", name, hash)
+}
+
+/// 缓存（服务器翻译、git 工作副本）的根目录
+pub fn cache_home() -> PathBuf {
+    CACHE_HOME
+        .get()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(WORK_DIR))
+}
+
+/// 日志和运行历史的根目录
+pub fn state_home() -> PathBuf {
+    STATE_HOME
+        .get()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(WORK_DIR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_dirs() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let base = temp_dir.path().join("cache");
+        assert_eq!(
+            resolve_dir("build/i18n", Some(base.clone()))?,
+            PathBuf::from("build/i18n")
+        );
+        let xdg = resolve_dir("xdg", Some(base.clone()))?;
+        assert!(xdg.starts_with(base.join("i18n-app")));
+        assert!(resolve_dir("xdg", None).is_err());
+
+        // 同名项目位于不同路径时目录不同
+        let a = temp_dir.path().join("a").join("web");
+        let b = temp_dir.path().join("b").join("web");
+        assert!(project_id(&a).starts_with("web-"));
+        assert_ne!(project_id(&a), project_id(&b));
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::locations;
+
+/// 保留最近多少次运行的日志
+pub const LOG_RETENTION: usize = 10;
+const LOG_FILE_PREFIX: &str = "run-";
+const LOG_FILE_EXTENSION: &str = "log";
+
+/// 日志目录，每次运行生成一个带时间戳的日志文件
+pub fn log_dir() -> PathBuf {
+    locations::state_home().join("logs")
+}
+
+/// 为本次运行创建新的日志文件，并清理超出保留数量的旧日志
+pub fn init_log_file() -> Result<File> {
+    init_log_file_in(&log_dir(), LOG_RETENTION)
+}
+
+pub fn init_log_file_in(dir: &Path, retention: usize) -> Result<File> {
+    fs::create_dir_all(dir)?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f");
+    let log_file = dir.join(format!(
+        "{}{}.{}",
+        LOG_FILE_PREFIX, timestamp, LOG_FILE_EXTENSION
+    ));
+    let file = File::create(&log_file)?;
+
+    // 本次运行的日志已创建，保留最新的 retention 个文件
+    let files = list_log_files_in(dir)?;
+    if files.len() > retention {
+        for old in &files[..files.len() - retention] {
+            if let Err(e) = fs::remove_file(old) {
+                eprintln!("Failed to remove old log file {}: {}", old.display(), e);
+            }
+        }
+    }
+
+    Ok(file)
+}
+
+/// 按时间从旧到新列出日志文件
+pub fn list_log_files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().and_then(|e| e.to_str()) == Some(LOG_FILE_EXTENSION)
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    // 文件名中的时间戳格式固定，按名称排序即按时间排序
+    files.sort();
+    Ok(files)
+}
+
+/// 上一次运行的日志（最新的文件属于当前运行）
+pub fn previous_log_file_in(dir: &Path) -> Result<Option<PathBuf>> {
+    let files = list_log_files_in(dir)?;
+    Ok(files.len().checked_sub(2).map(|i| files[i].clone()))
+}
+
+/// 打印上一次运行的日志，或列出所有保留的日志文件
+pub fn show_logs(last: bool) -> Result<()> {
+    let dir = log_dir();
+    if last {
+        match previous_log_file_in(&dir)? {
+            Some(path) => {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("读取日志文件 {} 失败", path.display()))?;
+                print!("{}", content);
+            }
+            None => tracing::warn!("没有找到上一次运行的日志"),
+        }
+    } else {
+        for path in list_log_files_in(&dir)? {
+            println!("{}", path.display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_init_log_file() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let log_dir = temp_dir.path().join("logs");
+
+        init_log_file_in(&log_dir, LOG_RETENTION)?;
+
+        let files = list_log_files_in(&log_dir)?;
+        assert_eq!(files.len(), 1);
+        assert!(files[0].exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_rotation_keeps_latest_runs() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let log_dir = temp_dir.path().join("logs");
+        fs::create_dir_all(&log_dir)?;
+        for name in [
+            "run-20240101-000000-000.log",
+            "run-20240102-000000-000.log",
+            "run-20240103-000000-000.log",
+        ] {
+            fs::write(log_dir.join(name), name)?;
+        }
+        // 不是日志的文件不受影响
+        fs::write(log_dir.join("notes.txt"), "keep")?;
+
+        init_log_file_in(&log_dir, 2)?;
+
+        let files = list_log_files_in(&log_dir)?;
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("run-20240103-000000-000.log"));
+        assert!(log_dir.join("notes.txt").exists());
+
+        let previous = previous_log_file_in(&log_dir)?.unwrap();
+        assert!(previous.ends_with("run-20240103-000000-000.log"));
+
+        Ok(())
+    }
+}
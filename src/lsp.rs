@@ -0,0 +1,705 @@
+use anyhow::{anyhow, Context, Result};
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    DiagnosticSeverity, DiagnosticTag, GotoDefinitionParams, GotoDefinitionResponse, Hover,
+    HoverContents, HoverParams, HoverProviderCapability, InitializeParams, Location, MarkupContent,
+    MarkupKind, NumberOrString, OneOf, Position, PublishDiagnosticsParams, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::{Config, Severity};
+use crate::lint;
+use crate::service::TranslationService;
+use crate::translation::{Span, TranslationFile};
+
+const SOURCE: &str = "i18n-app";
+/// 扫描 `t('key')` 用法的源码文件扩展名
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "js", "jsx", "ts", "tsx", "mjs", "vue", "svelte", "html", "dart", "kt", "swift", "rs", "py",
+];
+/// 扫描工作区时跳过的目录
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor", "Pods"];
+/// 超过这个大小的源码文件不扫描
+const MAX_SOURCE_SIZE: u64 = 1024 * 1024;
+
+/// 源码中的一处 `t('key')` 调用，行列号从 0 开始，列按 UTF-16 计算
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Usage {
+    key: String,
+    line: u32,
+    start: u32,
+    end: u32,
+}
+
+impl Usage {
+    fn range(&self) -> Range {
+        Range::new(
+            Position::new(self.line, self.start),
+            Position::new(self.line, self.end),
+        )
+    }
+
+    fn contains(&self, position: Position) -> bool {
+        position.line == self.line && (self.start..=self.end).contains(&position.character)
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// 查找 `t('key')`、`$t("key")`、`i18n.t(`key`)` 等调用，带插值的模板字符串跳过
+fn find_usages(text: &str) -> Vec<Usage> {
+    let mut usages = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let mut offset = 0;
+        while let Some(found) = line[offset..].find("t(") {
+            let start = offset + found;
+            offset = start + 2;
+            if line[..start].chars().next_back().is_some_and(is_ident_char) {
+                continue;
+            }
+            let rest = &line[offset..];
+            let trimmed = rest.trim_start();
+            let Some(quote) = trimmed
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '\'' | '"' | '`'))
+            else {
+                continue;
+            };
+            let key_start = offset + (rest.len() - trimmed.len()) + 1;
+            let Some(len) = line[key_start..].find(quote) else {
+                continue;
+            };
+            let key = &line[key_start..key_start + len];
+            if key.is_empty() || key.contains("${") || key.contains('\\') {
+                continue;
+            }
+            let column = |byte: usize| line[..byte].encode_utf16().count() as u32;
+            usages.push(Usage {
+                key: key.to_string(),
+                line: line_no as u32,
+                start: column(key_start),
+                end: column(key_start + len),
+            });
+        }
+    }
+    usages
+}
+
+/// 在工作区中查找源码文件
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                source_files(&path, files);
+            }
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+            && entry.metadata().is_ok_and(|m| m.len() <= MAX_SOURCE_SIZE)
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// 工作区源码中引用 `key` 的位置：相对于 `root` 的路径和从 1 开始的行号，按路径排序
+pub fn find_references(root: &Path, key: &str) -> Vec<(PathBuf, u32)> {
+    let mut files = Vec::new();
+    source_files(root, &mut files);
+    files.sort();
+    let mut references = Vec::new();
+    for path in files {
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        references.extend(
+            find_usages(&text)
+                .into_iter()
+                .filter(|usage| usage.key == key)
+                .map(|usage| (relative.clone(), usage.line + 1)),
+        );
+    }
+    references
+}
+
+/// 翻译文件中 key 的范围，包括引号；按字符计算列号，BMP 以外的字符会有偏差
+fn key_range(key: &str, span: Option<Span>) -> Range {
+    let Some(span) = span else {
+        return Range::default();
+    };
+    let name = key.rsplit('.').next().unwrap_or(key);
+    let line = span.line.saturating_sub(1) as u32;
+    let start = span.column.saturating_sub(1) as u32;
+    let end = start + name.encode_utf16().count() as u32 + 2;
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+fn severity(severity: Severity) -> DiagnosticSeverity {
+    match severity {
+        Severity::Error => DiagnosticSeverity::ERROR,
+        _ => DiagnosticSeverity::WARNING,
+    }
+}
+
+/// 所有子系统的本地翻译和 lint 结果
+#[derive(Default)]
+struct Index {
+    base_language: String,
+    /// 翻译文件和它的绝对路径
+    files: Vec<(PathBuf, TranslationFile)>,
+    diagnostics: Vec<lint::Diagnostic>,
+}
+
+impl Index {
+    fn load(config: &Config, root: &Path) -> Result<Self> {
+        let mut index = Index {
+            base_language: config.base_language.clone(),
+            ..Default::default()
+        };
+        for config in config.split_by_sub_system() {
+            let service = TranslationService::new(config);
+            for file in service.local_translations()? {
+                let path = root.join(&file.relative_path);
+                index.files.push((path, file));
+            }
+            index.diagnostics.extend(service.lint()?);
+        }
+        Ok(index)
+    }
+
+    fn is_base(&self, file: &TranslationFile) -> bool {
+        file.language_code == self.base_language
+    }
+
+    fn has_key(&self, key: &str) -> bool {
+        self.files
+            .iter()
+            .any(|(_, file)| self.is_base(file) && file.content.contains_key(key))
+    }
+
+    fn file_at(&self, path: &Path) -> Option<&TranslationFile> {
+        self.files
+            .iter()
+            .find(|(file_path, _)| same_file(file_path, path))
+            .map(|(_, file)| file)
+    }
+
+    /// 翻译文件中位于某一行的 key
+    fn key_at_line<'a>(&self, file: &'a TranslationFile, line: u32) -> Option<&'a str> {
+        file.spans
+            .iter()
+            .find(|(_, span)| span.line == line as usize + 1)
+            .map(|(key, _)| key.as_ref())
+    }
+
+    /// key 在各语言中的翻译，基准语言在前
+    fn hover_markdown(&self, key: &str) -> Option<String> {
+        let mut rows: Vec<(bool, &str, &str)> = self
+            .files
+            .iter()
+            .filter_map(|(_, file)| {
+                file.content.get(key).map(|value| {
+                    (
+                        !self.is_base(file),
+                        file.language_code.as_str(),
+                        value.as_ref(),
+                    )
+                })
+            })
+            .collect();
+        if rows.is_empty() {
+            return None;
+        }
+        rows.sort();
+        let mut markdown = format!("**{}**\n\n| Language | Translation |\n|---|---|\n", key);
+        for (_, lang, value) in rows {
+            markdown.push_str(&format!(
+                "| {} | {} |\n",
+                lang,
+                value.replace('|', "\\|").replace('\n', " ")
+            ));
+        }
+        Some(markdown)
+    }
+
+    /// key 在各翻译文件中的位置，基准语言在前
+    fn definitions(&self, key: &str) -> Vec<Location> {
+        let mut files: Vec<&(PathBuf, TranslationFile)> = self
+            .files
+            .iter()
+            .filter(|(_, file)| file.content.contains_key(key))
+            .collect();
+        files.sort_by_key(|(_, file)| !self.is_base(file));
+        files
+            .into_iter()
+            .filter_map(|(path, file)| {
+                let uri = Url::from_file_path(path).ok()?;
+                Some(Location::new(
+                    uri,
+                    key_range(key, file.spans.get(key).copied()),
+                ))
+            })
+            .collect()
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+struct Server {
+    root: PathBuf,
+    config: Config,
+    index: Index,
+    /// 编辑器中打开的文档，内容可能还没有保存
+    documents: HashMap<Url, String>,
+    /// 工作区源码中用到的 key
+    usages: HashMap<PathBuf, BTreeSet<String>>,
+    /// 上次发布了翻译文件诊断的文档，重新发布时需要清空不再有问题的文档
+    published: HashSet<Url>,
+}
+
+impl Server {
+    fn new(root: PathBuf, config: Config) -> Self {
+        Self {
+            root,
+            config,
+            index: Index::default(),
+            documents: HashMap::new(),
+            usages: HashMap::new(),
+            published: HashSet::new(),
+        }
+    }
+
+    fn scan_workspace(&mut self) {
+        let mut files = Vec::new();
+        source_files(&self.root, &mut files);
+        self.usages = files
+            .into_iter()
+            .filter_map(|path| {
+                let text = fs::read_to_string(&path).ok()?;
+                Some((path, keys_of(&text)))
+            })
+            .collect();
+        tracing::info!("Scanned {} source files", self.usages.len());
+    }
+
+    fn reload(&mut self, connection: &Connection) -> Result<()> {
+        self.index = Index::load(&self.config, &self.root)?;
+        self.publish_translation_diagnostics(connection)
+    }
+
+    fn publish(
+        connection: &Connection,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+    ) -> Result<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        connection
+            .sender
+            .send(Notification::new(PublishDiagnostics::METHOD.to_string(), params).into())?;
+        Ok(())
+    }
+
+    /// 发布翻译文件的 lint 结果，以及基准语言中没有被源码使用的 key
+    fn publish_translation_diagnostics(&mut self, connection: &Connection) -> Result<()> {
+        let mut by_uri: HashMap<Url, Vec<lsp_types::Diagnostic>> = HashMap::new();
+        for d in &self.index.diagnostics {
+            let Ok(uri) = Url::from_file_path(self.root.join(&d.file)) else {
+                continue;
+            };
+            by_uri.entry(uri).or_default().push(lsp_types::Diagnostic {
+                range: key_range(&d.key, d.span),
+                severity: Some(severity(d.severity)),
+                code: Some(NumberOrString::String(d.rule.to_string())),
+                source: Some(SOURCE.to_string()),
+                message: format!("{}: {}", d.key, d.message),
+                ..Default::default()
+            });
+        }
+
+        // 源码中找不到任何调用时可能使用了其他写法，不报告未使用的 key
+        let used: HashSet<&str> = self.usages.values().flatten().map(String::as_str).collect();
+        if !used.is_empty() {
+            for (path, file) in &self.index.files {
+                if !self.index.is_base(file) {
+                    continue;
+                }
+                let Ok(uri) = Url::from_file_path(path) else {
+                    continue;
+                };
+                let mut unused: Vec<&Arc<str>> = file
+                    .content
+                    .keys()
+                    .filter(|key| !used.contains(key.as_ref()))
+                    .collect();
+                unused.sort();
+                by_uri
+                    .entry(uri)
+                    .or_default()
+                    .extend(unused.into_iter().map(|key| lsp_types::Diagnostic {
+                        range: key_range(key, file.spans.get(key.as_ref()).copied()),
+                        severity: Some(DiagnosticSeverity::HINT),
+                        code: Some(NumberOrString::String("unused-key".to_string())),
+                        source: Some(SOURCE.to_string()),
+                        message: format!("{} is not used in source code", key),
+                        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+                        ..Default::default()
+                    }));
+            }
+        }
+
+        let stale: Vec<Url> = self
+            .published
+            .iter()
+            .filter(|uri| !by_uri.contains_key(uri))
+            .cloned()
+            .collect();
+        for uri in stale {
+            Self::publish(connection, uri, Vec::new())?;
+        }
+        self.published = by_uri.keys().cloned().collect();
+        for (uri, diagnostics) in by_uri {
+            Self::publish(connection, uri, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    /// 源码中使用了基准语言里不存在的 key
+    fn publish_source_diagnostics(&self, connection: &Connection, uri: &Url) -> Result<()> {
+        let Some(text) = self.documents.get(uri) else {
+            return Ok(());
+        };
+        let diagnostics = find_usages(text)
+            .into_iter()
+            .filter(|usage| !self.index.has_key(&usage.key))
+            .map(|usage| lsp_types::Diagnostic {
+                range: usage.range(),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String("missing-keys".to_string())),
+                source: Some(SOURCE.to_string()),
+                message: format!(
+                    "{} is not defined in {}",
+                    usage.key, self.index.base_language
+                ),
+                ..Default::default()
+            })
+            .collect();
+        Self::publish(connection, uri.clone(), diagnostics)
+    }
+
+    fn is_translation_file(&self, path: &Path) -> bool {
+        self.index.file_at(path).is_some() || path.extension().is_some_and(|ext| ext == "json")
+    }
+
+    /// 光标处的 key：源码中的 `t('key')` 调用，或翻译文件中所在行的 key
+    fn key_at(&self, uri: &Url, position: Position) -> Option<String> {
+        let path = uri.to_file_path().ok()?;
+        if let Some(file) = self.index.file_at(&path) {
+            return self
+                .index
+                .key_at_line(file, position.line)
+                .map(str::to_string);
+        }
+        let text = self.documents.get(uri)?;
+        find_usages(text)
+            .into_iter()
+            .find(|usage| usage.contains(position))
+            .map(|usage| usage.key)
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => serde_json::from_value::<HoverParams>(request.params)
+                .map_err(|e| e.to_string())
+                .map(|params| {
+                    let position = params.text_document_position_params;
+                    let hover = self
+                        .key_at(&position.text_document.uri, position.position)
+                        .and_then(|key| self.index.hover_markdown(&key))
+                        .map(|value| Hover {
+                            contents: HoverContents::Markup(MarkupContent {
+                                kind: MarkupKind::Markdown,
+                                value,
+                            }),
+                            range: None,
+                        });
+                    serde_json::to_value(hover).unwrap_or_default()
+                }),
+            GotoDefinition::METHOD => {
+                serde_json::from_value::<GotoDefinitionParams>(request.params)
+                    .map_err(|e| e.to_string())
+                    .map(|params| {
+                        let position = params.text_document_position_params;
+                        let locations = self
+                            .key_at(&position.text_document.uri, position.position)
+                            .map(|key| self.index.definitions(&key))
+                            .unwrap_or_default();
+                        serde_json::to_value(GotoDefinitionResponse::Array(locations))
+                            .unwrap_or_default()
+                    })
+            }
+            method => Err(format!("unsupported request {}", method)),
+        };
+        match result {
+            Ok(value) => Response::new_ok(request.id, value),
+            Err(message) => Response::new_err(
+                request.id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                message,
+            ),
+        }
+    }
+
+    fn handle_notification(
+        &mut self,
+        connection: &Connection,
+        notification: Notification,
+    ) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), params.text_document.text);
+                self.publish_source_if_needed(connection, &uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(uri.clone(), change.text);
+                }
+                self.publish_source_if_needed(connection, &uri)?;
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: lsp_types::DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                let Ok(path) = uri.to_file_path() else {
+                    return Ok(());
+                };
+                if self.is_translation_file(&path) {
+                    // 翻译文件可能有语法错误，保留上一次的索引
+                    if let Err(e) = self.reload(connection) {
+                        tracing::warn!("Failed to reload translations: {:#}", e);
+                    }
+                    let open: Vec<Url> = self.documents.keys().cloned().collect();
+                    for uri in open {
+                        self.publish_source_if_needed(connection, &uri)?;
+                    }
+                } else if let Some(text) = self.documents.get(&uri) {
+                    self.usages.insert(path, keys_of(text));
+                    self.publish_translation_diagnostics(connection)?;
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn publish_source_if_needed(&self, connection: &Connection, uri: &Url) -> Result<()> {
+        match uri.to_file_path() {
+            Ok(path) if !self.is_translation_file(&path) => {
+                self.publish_source_diagnostics(connection, uri)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn keys_of(text: &str) -> BTreeSet<String> {
+    find_usages(text)
+        .into_iter()
+        .map(|usage| usage.key)
+        .collect()
+}
+
+/// 以 stdio 运行语言服务器，直到编辑器发送 shutdown/exit
+pub fn run() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let (id, params) = connection.initialize_start()?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    // 以第一个工作区目录作为项目根目录，配置和翻译文件都相对于它
+    if let Some(root) = params
+        .workspace_folders
+        .iter()
+        .flatten()
+        .find_map(|folder| folder.uri.to_file_path().ok())
+    {
+        std::env::set_current_dir(&root)
+            .with_context(|| format!("无法进入工作区 {}", root.display()))?;
+    }
+    let root = std::env::current_dir()?;
+    let mut config = Config::try_load()
+        .ok_or_else(|| anyhow!("{} 中没有可用的 .i18n-app.json", root.display()))?;
+    // 语言服务器只读取本地文件
+    config.offline = true;
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize_finish(
+        id,
+        serde_json::json!({
+            "capabilities": capabilities,
+            "serverInfo": {"name": SOURCE, "version": env!("CARGO_PKG_VERSION")},
+        }),
+    )?;
+    tracing::info!("Language server started in {}", root.display());
+
+    let mut server = Server::new(root, config);
+    server.scan_workspace();
+    server.reload(&connection)?;
+    main_loop(&connection, &mut server)?;
+    // 关闭连接后 IO 线程才会退出
+    drop(connection);
+    io_threads.join()?;
+    tracing::info!("Language server stopped");
+    Ok(())
+}
+
+fn main_loop(connection: &Connection, server: &mut Server) -> Result<()> {
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(response.into())?;
+            }
+            Message::Notification(notification) => {
+                if let Err(e) = server.handle_notification(connection, notification) {
+                    tracing::warn!("Failed to handle notification: {:#}", e);
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::translation_file;
+
+    #[test]
+    fn test_find_usages() {
+        let text = "const a = t('home.title');\n  {$t(\"按钮.ok\")} i18n.t(`x.${id}`) format('no') t( 'b' )";
+        let usages = find_usages(text);
+        assert_eq!(
+            usages,
+            vec![
+                Usage {
+                    key: "home.title".to_string(),
+                    line: 0,
+                    start: 13,
+                    end: 23,
+                },
+                Usage {
+                    key: "按钮.ok".to_string(),
+                    line: 1,
+                    start: 7,
+                    end: 12,
+                },
+                Usage {
+                    key: "b".to_string(),
+                    line: 1,
+                    start: 51,
+                    end: 52,
+                },
+            ]
+        );
+        assert!(usages[0].contains(Position::new(0, 15)));
+        assert!(!usages[0].contains(Position::new(0, 3)));
+    }
+
+    #[test]
+    fn test_find_references() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(&src)?;
+        fs::create_dir_all(temp_dir.path().join("node_modules"))?;
+        fs::write(src.join("a.ts"), "t('other')\nconst x = t('home.title');\n")?;
+        fs::write(src.join("b.md"), "t('home.title')")?;
+        fs::write(
+            temp_dir.path().join("node_modules").join("c.js"),
+            "t('home.title')",
+        )?;
+
+        let references = find_references(temp_dir.path(), "home.title");
+        assert_eq!(references, vec![(PathBuf::from("src/a.ts"), 2)]);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_index_hover_and_definitions() {
+        let mut base = translation_file("en-US", &[("home.title", "Home | Start")]);
+        base.spans = HashMap::from([("home.title".into(), Span { line: 3, column: 5 })]);
+        let index = Index {
+            base_language: "en-US".to_string(),
+            files: vec![
+                (
+                    PathBuf::from("/p/zh-CN.json"),
+                    translation_file("zh-CN", &[("home.title", "首页")]),
+                ),
+                (PathBuf::from("/p/en-US.json"), base),
+            ],
+            diagnostics: Vec::new(),
+        };
+
+        assert!(index.has_key("home.title"));
+        assert!(!index.has_key("missing"));
+        assert_eq!(
+            index.hover_markdown("home.title").as_deref(),
+            Some("**home.title**\n\n| Language | Translation |\n|---|---|\n| en-US | Home \\| Start |\n| zh-CN | 首页 |\n")
+        );
+        assert_eq!(index.hover_markdown("missing"), None);
+
+        let definitions = index.definitions("home.title");
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[0].uri.path(), "/p/en-US.json");
+        assert_eq!(
+            definitions[0].range,
+            Range::new(Position::new(2, 4), Position::new(2, 11))
+        );
+        let (_, base) = &index.files[1];
+        assert_eq!(index.key_at_line(base, 2), Some("home.title"));
+    }
+}
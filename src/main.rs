@@ -1,21 +1,75 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{CommandFactory, FromArgMatches};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::time::Instant;
 use tracing_subscriber::{fmt, layer::Layer, prelude::*, registry::Registry, EnvFilter};
 
 mod api;
+mod audit;
+mod backend;
+mod bundle;
+mod cache;
+mod check;
 mod cli;
+mod codegen;
 mod config;
+mod credentials;
+mod daemon;
+mod desktop;
+mod docs;
+mod doctor;
+mod error;
+mod grep;
+mod history;
+mod hooks;
+mod import;
+mod journal;
+mod jsonc;
+mod lint;
+mod locations;
+mod logs;
+mod lsp;
+mod merge;
+mod output;
+mod pending;
+mod placeholder;
+mod plan;
+mod platform;
+mod plugin;
+mod rate_limit;
+mod release;
+mod review;
+mod schema;
+mod serve;
 mod service;
+mod shutdown;
+mod spell;
+mod stats;
+mod sync_state;
+#[cfg(test)]
+mod test_util;
 mod translation;
 mod update;
+mod version;
+mod vfs;
+mod webhook;
+mod workspace;
 
-use cli::{Cli, Commands};
-use config::Config;
-use service::TranslationService;
+use cli::{
+    AuthAction, CacheAction, Cli, CodegenTarget, Commands, LintFormat, ReviewAction, SnapshotAction,
+};
+use config::{Config, Severity};
+use credentials::Credential;
+use error::{exit_code, AppError};
+use review::ReviewState;
+use service::{PullOptions, PushOptions, TranslationService};
+use vfs::RealFs;
 
-fn setup_logging() -> Result<()> {
+/// `stdout` 为 false 时日志只写入文件
+fn setup_logging(stdout: bool) -> Result<()> {
     // 创建日志文件
-    let log_file = TranslationService::init_log_file()?;
+    let log_file = logs::init_log_file()?;
 
     // 设置环境过滤器
     #[cfg(debug_assertions)]
@@ -25,6 +79,10 @@ fn setup_logging() -> Result<()> {
     let stdout_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
+    // 面向用户的结果已经以彩色形式打印，标准输出中不再重复，只写入日志文件
+    let stdout_filter =
+        stdout_filter.add_directive(format!("{}=off", output::OUTPUT_TARGET).parse()?);
+
     #[cfg(debug_assertions)]
     let file_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug"));
     #[cfg(not(debug_assertions))]
@@ -41,12 +99,13 @@ fn setup_logging() -> Result<()> {
         .with_ansi(false);
 
     let stdout_layer = fmt::layer()
+        .with_ansi(output::colors_enabled())
         .with_target(false)
         .with_thread_ids(false)
         .with_thread_names(false);
 
     Registry::default()
-        .with(stdout_layer.with_filter(stdout_filter))
+        .with(stdout.then(|| stdout_layer.with_filter(stdout_filter)))
         .with(file_layer.with_filter(file_filter))
         .init();
 
@@ -55,11 +114,31 @@ fn setup_logging() -> Result<()> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    setup_logging()?;
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches)?;
+    let command_name = matches.subcommand_name().unwrap_or_default().to_string();
+    output::init(cli.no_color);
+    // 日志目录取决于配置，需要在初始化日志之前确定
+    let directories = Config::try_load()
+        .map(|config| config.directories)
+        .unwrap_or_default();
+    locations::configure_dirs(
+        cli.cache_dir.as_deref().or(directories.cache.as_deref()),
+        cli.state_dir.as_deref().or(directories.state.as_deref()),
+    )?;
+    // 语言服务器通过 stdout 通信，日志只写入文件
+    let lsp = matches!(cli.command, Commands::Lsp);
+    setup_logging(!lsp)?;
+    let offline = cli.offline;
 
-    // 除了 update 命令外，其他命令都先检查更新
-    if !matches!(cli.command, Commands::Update) {
+    // 除了 update、version、gen-docs 和 lsp 命令外，其他命令都先检查更新（离线模式下跳过）
+    if !offline
+        && !lsp
+        && !matches!(
+            cli.command,
+            Commands::Update | Commands::Version { .. } | Commands::GenDocs { .. }
+        )
+    {
         if let Some(release) = update::check_update().await? {
             tracing::info!(
                 "发现新版本 {}，请运行 'i18n-app update' 进行更新",
@@ -71,25 +150,367 @@ async fn main() -> Result<()> {
         }
     }
 
-    match cli.command {
+    let started = Instant::now();
+    let lock = if takes_workspace_lock(&cli.command) {
+        Some(workspace::WorkspaceLock::acquire(&workspace::lock_file())?)
+    } else {
+        None
+    };
+    // daemon 和语言服务器自己处理退出。其他命令在阻塞线程中运行，读取确认输入等同步操作
+    // 不会阻塞信号处理：第一次 Ctrl-C 通知命令在下一个取消点（两次请求或写入之间）返回，
+    // 最多等待 GRACE_PERIOD；再次按 Ctrl-C 时直接退出。文件都是先写临时文件再替换，不会写了一半
+    let mut run = None;
+    let result = if matches!(cli.command, Commands::Daemon | Commands::Lsp) {
+        run_command(cli.command, offline, cli.env.as_deref(), &mut run).await
+    } else {
+        let runtime = tokio::runtime::Handle::current();
+        let env = cli.env.clone();
+        let mut command = tokio::task::spawn_blocking(move || {
+            let mut run = None;
+            let result =
+                runtime.block_on(run_command(cli.command, offline, env.as_deref(), &mut run));
+            (result, run)
+        });
+        tokio::select! {
+            joined = &mut command => {
+                let (result, command_run) =
+                    joined.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+                run = command_run;
+                result
+            }
+            _ = tokio::signal::ctrl_c() => {
+                shutdown::token().cancel();
+                let lock_path = lock.as_ref().map(|_| workspace::lock_file());
+                tokio::spawn(async move {
+                    let _ = tokio::signal::ctrl_c().await;
+                    if let Some(path) = lock_path {
+                        let _ = std::fs::remove_file(path);
+                    }
+                    std::process::exit(exit_code::CANCELLED);
+                });
+                if let Ok(Ok((_, command_run))) =
+                    tokio::time::timeout(shutdown::GRACE_PERIOD, command).await
+                {
+                    run = command_run;
+                }
+                Err(AppError::Cancelled.into())
+            }
+        }
+    };
+    drop(lock);
+    record_history(&command_name, started, &result);
+    if let Err(e) = workspace::ensure_work_dir_gitignore() {
+        tracing::warn!("Failed to write {}/.gitignore: {}", locations::WORK_DIR, e);
+    }
+    webhook::notify(run.as_ref(), &result).await;
+
+    if let Err(e) = result {
+        if matches!(e.downcast_ref::<AppError>(), Some(AppError::Cancelled)) {
+            output::status(output::Status::Skipped, e.to_string());
+            std::process::exit(exit_code::CANCELLED);
+        }
+        tracing::debug!("Exiting with error: {:#}", e);
+        eprintln!("Error: {:?}", e);
+        std::process::exit(error::exit_code_for(&e));
+    }
+    Ok(())
+}
+
+/// 修改本地翻译文件或服务器翻译的命令，运行期间持有工作目录的锁
+fn takes_workspace_lock(command: &Commands) -> bool {
+    match command {
+        Commands::Fmt { check } => !check,
+        Commands::Reorganize { dry_run, .. } => !dry_run,
+        Commands::Push { .. }
+        | Commands::Pull { .. }
+        | Commands::Download { .. }
+        | Commands::Promote { .. }
+        | Commands::Import { .. }
+        | Commands::Replace { .. }
+        | Commands::Set { .. } => true,
+        _ => false,
+    }
+}
+
+/// push/pull 类命令把实际使用的配置写入 `run`，结束后据此发送 webhook 通知
+async fn run_command(
+    command: Commands,
+    offline: bool,
+    env: Option<&str>,
+    run: &mut Option<webhook::Run>,
+) -> Result<()> {
+    match command {
         Commands::Init => handle_init(),
-        Commands::Push { path } => handle_push(path).await,
-        Commands::Download { path } => handle_download(path).await,
+        Commands::Doctor { fix } => doctor::run(fix),
+        Commands::Push {
+            path,
+            fill_missing,
+            overwrite_changed,
+            yes,
+            yes_production,
+            fail_fast,
+            resume,
+            versions,
+        } => {
+            let mut config = load_config(offline, env)?;
+            if fill_missing.is_some() {
+                config.fill_missing_keys = fill_missing;
+            }
+            if !versions.is_empty() {
+                config.push_versions = versions;
+            }
+            let options = PushOptions {
+                overwrite_changed,
+                yes,
+                yes_production,
+                fail_fast,
+                resume,
+            };
+            *run = Some(webhook::Run {
+                command: "push",
+                config: config.clone(),
+            });
+            handle_push(config, path, options).await
+        }
+        Commands::Download {
+            path,
+            name_template,
+            key_style,
+            yes,
+        } => {
+            let mut config = load_config(offline, env)?;
+            if name_template.is_some() {
+                config.name_template = name_template;
+            }
+            if let Some(style) = key_style.key_style() {
+                config.key_style = Some(style);
+            }
+            handle_download(config, path, yes).await
+        }
+        Commands::Promote {
+            from,
+            to,
+            keys,
+            dry_run,
+            yes_production,
+        } => {
+            let config = load_config(offline, env)?;
+            handle_promote(config, &from, &to, &keys, dry_run, yes_production).await
+        }
         Commands::Update => {
+            if offline {
+                return Err(AppError::Validation(
+                    "update 命令需要网络连接，请去掉 --offline 参数后重试".to_string(),
+                )
+                .into());
+            }
             if update::update().await? {
-                std::process::exit(0);
+                std::process::exit(exit_code::SUCCESS);
             }
             Ok(())
         }
-        Commands::Pull => handle_pull().await,
+        Commands::Version { json } => version::run(json),
+        Commands::GenDocs { format, out } => docs::run(format, out.as_deref()),
+        Commands::Pull {
+            create_missing,
+            key_style,
+            interactive,
+            materialize_fallbacks,
+            force,
+        } => {
+            let mut config = load_config(offline, env)?;
+            if let Some(style) = key_style.key_style() {
+                config.key_style = Some(style);
+            }
+            let options = PullOptions {
+                create_missing,
+                interactive,
+                materialize_fallbacks,
+                force,
+            };
+            *run = Some(webhook::Run {
+                command: "pull",
+                config: config.clone(),
+            });
+            handle_pull(config, options).await
+        }
+        Commands::Logs { last } => logs::show_logs(last),
+        Commands::History { limit } => history::show_history(limit),
+        Commands::Audit { since, format } => audit::show_audit(since, format),
+        Commands::Languages => handle_languages(load_config(offline, env)?).await,
+        Commands::Stats { since, top } => {
+            handle_stats(load_config(offline, env)?, since.as_deref(), top)
+        }
+        Commands::Estimate {
+            rate_per_word,
+            langs,
+        } => handle_estimate(load_config(offline, env)?, &langs, rate_per_word),
+        Commands::Export { lang, file, only } => {
+            handle_export(load_config(offline, env)?, &lang, &file, only)
+        }
+        Commands::Import { lang, file, yes } => {
+            handle_import(load_config(offline, env)?, &lang, &file, yes)
+        }
+        Commands::Changes => handle_changes(load_config(offline, env)?).await,
+        Commands::Daemon => daemon::run(load_config(offline, env)?).await,
+        Commands::Snapshot { action } => handle_snapshot(load_config(offline, env)?, action),
+        Commands::Diff { path } => handle_diff(load_config(offline, env)?, path),
+        Commands::Explain { key } => handle_explain(load_config(offline, env)?, &key),
+        Commands::Replace {
+            find,
+            replace,
+            lang,
+            regex,
+            yes,
+        } => handle_replace(
+            load_config(offline, env)?,
+            &find,
+            &replace,
+            &lang,
+            regex,
+            yes,
+        ),
+        Commands::Get { key, lang } => {
+            handle_get(load_config(offline, env)?, &key, lang.as_deref())
+        }
+        Commands::Set {
+            key,
+            value,
+            lang,
+            sub_system,
+            push,
+            yes_production,
+        } => {
+            // 只有这个 key 会覆盖服务器上不同的值，见 handle_set
+            let options = push.then_some(PushOptions {
+                overwrite_changed: false,
+                yes: false,
+                yes_production,
+                fail_fast: false,
+                resume: false,
+            });
+            let config = load_config(offline, env)?;
+            if push {
+                *run = Some(webhook::Run {
+                    command: "push",
+                    config: config.clone(),
+                });
+            }
+            handle_set(config, &key, &lang, &value, sub_system.as_deref(), options).await
+        }
+        Commands::Grep {
+            pattern,
+            lang,
+            keys_only,
+            fixed_strings,
+            ignore_case,
+        } => handle_grep(
+            load_config(offline, env)?,
+            grep::GrepOptions {
+                pattern,
+                lang,
+                keys_only,
+                fixed_strings,
+                ignore_case,
+            },
+        ),
+        Commands::Check {
+            duplicates,
+            spelling,
+            missing,
+            empty_base,
+            format,
+        } => handle_check(
+            load_config(offline, env)?,
+            check::CheckOptions::new(duplicates, spelling, missing, empty_base, format),
+        ),
+        Commands::Fmt { check } => handle_fmt(load_config(offline, env)?, check),
+        Commands::Reorganize {
+            by_prefix: _,
+            merge,
+            dry_run,
+        } => handle_reorganize(load_config(offline, env)?, merge, dry_run),
+        Commands::Lint {
+            write_baseline,
+            format,
+            out,
+        } => handle_lint(load_config(offline, env)?, write_baseline, format, out),
+        Commands::Lsp => lsp::run(),
+        Commands::Auth { action } => handle_auth(action, offline, env).await,
+        Commands::Whoami => handle_auth(AuthAction::Status, offline, env).await,
+        Commands::Review { action } => handle_review(load_config(offline, env)?, action),
+        Commands::Bundle {
+            out,
+            gzip,
+            brotli,
+            split_namespaces,
+        } => handle_bundle(
+            load_config(offline, env)?,
+            &out,
+            gzip,
+            brotli,
+            split_namespaces,
+        ),
+        Commands::Serve { port, pull } => {
+            handle_serve(load_config(offline, env)?, port, pull).await
+        }
+        Commands::Codegen { target, watch } => {
+            handle_codegen(load_config(offline, env)?, target, watch).await
+        }
+        Commands::Cache { action } => handle_cache(load_config(offline, env)?, action),
+        Commands::Clean {
+            cache,
+            logs,
+            backups,
+            all,
+        } => workspace::clean(workspace::CleanTargets::new(cache, logs, backups, all)),
+        Commands::External(args) => plugin::run(&args, offline),
+    }
+}
+
+/// 配置开启 history 时把本次运行的结果追加到本地历史文件
+fn record_history(command: &str, started: Instant, result: &Result<()>) {
+    if command == "history" || !Config::try_load().is_some_and(|c| c.history) {
+        return;
+    }
+
+    let entry = history::HistoryEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        command: command.to_string(),
+        args: std::env::args().skip(1).collect(),
+        duration_ms: started.elapsed().as_millis(),
+        keys_uploaded: history::keys_uploaded(),
+        keys_downloaded: history::keys_downloaded(),
+        success: result.is_ok(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    if let Err(e) = history::append_entry(&history::history_file(), &entry) {
+        tracing::warn!("Failed to record history: {}", e);
     }
 }
 
+fn load_config(offline: bool, env: Option<&str>) -> Result<Config> {
+    let mut config =
+        Config::load().map_err(|e| e.context(AppError::Config("加载配置文件失败".to_string())))?;
+    config.offline = offline;
+    if let Some(name) = env {
+        config
+            .apply_environment(name)
+            .map_err(|e| AppError::Config(e.to_string()))?;
+        tracing::info!("使用环境 {}（{}）", name, config.host);
+    }
+    Ok(config)
+}
+
 fn handle_init() -> Result<()> {
     match Config::init() {
         Ok(()) => {
             tracing::info!("Configuration file created successfully");
             tracing::info!("Please update the configuration file with your settings");
+            // 避免把缓存和日志提交到仓库
+            if workspace::ensure_root_gitignore(Path::new("."))? {
+                output::status(output::Status::Ok, "added .i18n-app/ to .gitignore");
+            }
             Ok(())
         }
         Err(e) => {
@@ -99,20 +520,996 @@ fn handle_init() -> Result<()> {
     }
 }
 
-async fn handle_push(path: Option<String>) -> Result<()> {
-    let config = Config::load()?;
-    let service = TranslationService::new(config);
-    service.push_translations(path).await
+async fn handle_push(config: Config, path: Option<String>, options: PushOptions) -> Result<()> {
+    hooks::run_pre(&config, "push")?;
+    if config.push_versions.is_empty() {
+        for config in config.split_by_sub_system() {
+            tracing::info!("Pushing sub system: {}", config.sub_system_name());
+            let service = TranslationService::new(config);
+            service.push_translations(path.clone(), options).await?;
+        }
+        return hooks::run_post(&config, "push");
+    }
+
+    // 多个版本依次上传，某个版本失败时不再上传后面的版本，最后输出每个版本的结果
+    let mut versions: Vec<&String> = Vec::new();
+    for version in &config.push_versions {
+        if !versions.contains(&version) {
+            versions.push(version);
+        }
+    }
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut failure = None;
+    for version in &versions {
+        if failure.is_some() {
+            rows.push(vec![
+                version.to_string(),
+                String::new(),
+                "skipped".to_string(),
+            ]);
+            continue;
+        }
+        tracing::info!("Pushing to version {}", version);
+        let mut uploaded = 0;
+        for sub_config in config.split_by_sub_system() {
+            tracing::info!(
+                "Pushing sub system {} to version {}",
+                sub_config.sub_system_name(),
+                version
+            );
+            let service = TranslationService::new(sub_config.for_version(version));
+            match service.push_translations(path.clone(), options).await {
+                Ok(keys) => uploaded += keys,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+        let result = if failure.is_some() { "failed" } else { "ok" };
+        rows.push(vec![
+            version.to_string(),
+            uploaded.to_string(),
+            result.to_string(),
+        ]);
+    }
+    output::table(["Version", "Uploaded Keys", "Result"], rows);
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    hooks::run_post(&config, "push")
+}
+
+async fn handle_download(config: Config, path: Option<String>, yes: bool) -> Result<()> {
+    let multiple = config.sub_systems.len() > 1;
+    for config in config.split_by_sub_system() {
+        // 多个子系统时分别下载到以子系统命名的子目录，避免同名语言文件互相覆盖
+        let path = if multiple {
+            let base = path
+                .clone()
+                .unwrap_or_else(|| workspace::preview_dir().display().to_string());
+            Some(format!("{}/{}", base, config.sub_system_name()))
+        } else {
+            path.clone()
+        };
+        tracing::info!("Downloading sub system: {}", config.sub_system_name());
+        let service = TranslationService::new(config);
+        service.download_translations(path, yes).await?;
+    }
+    Ok(())
 }
 
-async fn handle_download(path: Option<String>) -> Result<()> {
-    let config = Config::load()?;
-    let service = TranslationService::new(config);
-    service.download_translations(path).await
+async fn handle_promote(
+    config: Config,
+    from: &str,
+    to: &str,
+    keys: &[String],
+    dry_run: bool,
+    yes_production: bool,
+) -> Result<()> {
+    if from == to {
+        return Err(AppError::Validation("--from 和 --to 不能是同一个版本".to_string()).into());
+    }
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut total = 0;
+    for config in config.split_by_sub_system() {
+        let sub_system = config.sub_system_name().to_string();
+        tracing::info!(
+            "Promoting sub system {} from {} to {}",
+            sub_system,
+            from,
+            to
+        );
+        let source = TranslationService::new(config.for_version(from));
+        let target = TranslationService::new(config.for_version(to));
+        for (lang, count) in target
+            .promote_from(&source, keys, dry_run, yes_production)
+            .await?
+        {
+            total += count;
+            rows.push(vec![sub_system.clone(), lang, count.to_string()]);
+        }
+    }
+
+    if dry_run && !rows.is_empty() {
+        output::table(["Sub System", "Language", "Keys"], rows);
+    }
+    let message = if dry_run {
+        format!("{} keys would be promoted from {} to {}", total, from, to)
+    } else {
+        format!("Promoted {} keys from {} to {}", total, from, to)
+    };
+    output::status(output::Status::Ok, message);
+    Ok(())
+}
+
+async fn handle_pull(config: Config, options: PullOptions) -> Result<()> {
+    hooks::run_pre(&config, "pull")?;
+    for config in config.split_by_sub_system() {
+        tracing::info!("Pulling sub system: {}", config.sub_system_name());
+        let service = TranslationService::new(config);
+        service.sync_translations(options).await?;
+    }
+    hooks::run_post(&config, "pull")
+}
+
+async fn handle_languages(config: Config) -> Result<()> {
+    for config in config.split_by_sub_system() {
+        tracing::info!("Languages of sub system: {}", config.sub_system_name());
+        let service = TranslationService::new(config);
+        service.list_languages().await?;
+    }
+    Ok(())
+}
+
+fn handle_stats(config: Config, since: Option<&str>, top: usize) -> Result<()> {
+    if let Some(git_ref) = since {
+        stats::verify_ref(git_ref)?;
+    }
+    for config in config.split_by_sub_system() {
+        tracing::info!("Stats of sub system: {}", config.sub_system_name());
+        let service = TranslationService::new(config);
+        service.print_stats(since, top)?;
+    }
+    Ok(())
+}
+
+fn handle_estimate(config: Config, langs: &[String], rate_per_word: Option<f64>) -> Result<()> {
+    // 多个子系统时按语言汇总
+    let mut estimates: Vec<stats::CostEstimate> = Vec::new();
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        for estimate in service.estimate(langs, rate_per_word)? {
+            match estimates.iter_mut().find(|e| e.lang == estimate.lang) {
+                Some(existing) => {
+                    existing.keys += estimate.keys;
+                    existing.words += estimate.words;
+                }
+                None => estimates.push(estimate),
+            }
+        }
+    }
+
+    let total_words: usize = estimates.iter().map(|e| e.words).sum();
+    let total_cost: f64 = estimates.iter().map(stats::CostEstimate::cost).sum();
+    let mut rows: Vec<Vec<String>> = estimates
+        .iter()
+        .map(|e| {
+            vec![
+                e.lang.clone(),
+                e.keys.to_string(),
+                e.words.to_string(),
+                format!("{:.4}", e.rate),
+                format!("{:.2}", e.cost()),
+            ]
+        })
+        .collect();
+    rows.push(vec![
+        "Total".to_string(),
+        estimates.iter().map(|e| e.keys).sum::<usize>().to_string(),
+        total_words.to_string(),
+        String::new(),
+        format!("{:.2}", total_cost),
+    ]);
+    output::table(
+        ["Language", "Untranslated Keys", "Words", "Rate", "Cost"],
+        rows,
+    );
+    Ok(())
+}
+
+/// API token 按当前环境的 host 保存，GitHub token 不需要项目配置
+fn auth_credential(github: bool, offline: bool, env: Option<&str>) -> Result<Credential> {
+    if github {
+        return Ok(Credential::Github);
+    }
+    Ok(Credential::Api {
+        host: load_config(offline, env)?.host,
+    })
+}
+
+fn read_token(with_token: bool) -> Result<String> {
+    let token = if with_token {
+        std::io::read_to_string(std::io::stdin())?
+    } else if console::user_attended() {
+        let term = console::Term::stdout();
+        term.write_str("Token: ")?;
+        term.read_secure_line()?
+    } else {
+        return Err(AppError::Validation(
+            "非交互环境请使用 --with-token 从标准输入读取 token".to_string(),
+        )
+        .into());
+    };
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err(AppError::Validation("token 不能为空".to_string()).into());
+    }
+    Ok(token)
+}
+
+async fn handle_auth(action: AuthAction, offline: bool, env: Option<&str>) -> Result<()> {
+    match action {
+        AuthAction::Login { github, with_token } => {
+            let credential = auth_credential(github, offline, env)?;
+            let token = read_token(with_token)?;
+            credentials::store(&credential, &token)?;
+            output::status(
+                output::Status::Ok,
+                describe_credential(&credential, "saved to keyring"),
+            );
+            if github && Config::remove_plaintext_github_token()? {
+                output::status(
+                    output::Status::Ok,
+                    format!(
+                        "removed plaintext token from {}",
+                        config::user_config_file().display()
+                    ),
+                );
+            }
+        }
+        AuthAction::Logout { github } => {
+            let credential = auth_credential(github, offline, env)?;
+            let message = if credentials::delete(&credential)? {
+                "removed from keyring"
+            } else {
+                "not found in keyring"
+            };
+            output::status(
+                output::Status::Ok,
+                describe_credential(&credential, message),
+            );
+        }
+        AuthAction::Status => {
+            let service = TranslationService::new(load_config(offline, env)?);
+            service.auth_status().await?;
+        }
+    }
+    Ok(())
+}
+
+fn describe_credential(credential: &Credential, message: &str) -> String {
+    match credential {
+        Credential::Api { host } => format!("API token for {} {}", host, message),
+        Credential::Github => format!("GitHub token {}", message),
+    }
+}
+
+fn handle_export(config: Config, lang: &str, file: &Path, only: Option<ReviewState>) -> Result<()> {
+    let configs = config.split_by_sub_system();
+    let multiple = configs.len() > 1;
+    for config in configs {
+        // 多个子系统时每个子系统导出到单独的文件，如 delivery.app.xlsx
+        let path = if multiple {
+            let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+            let extension = file.extension().unwrap_or_default().to_string_lossy();
+            file.with_file_name(format!(
+                "{}.{}.{}",
+                stem,
+                config.sub_system_name(),
+                extension
+            ))
+        } else {
+            file.to_path_buf()
+        };
+        let service = TranslationService::new(config);
+        service.export(lang, &path, only)?;
+    }
+    Ok(())
+}
+
+fn handle_import(config: Config, lang: &str, file: &Path, yes: bool) -> Result<()> {
+    // 每个子系统只导入其基准语言中存在的 key，所有子系统都没有的 key 才提示
+    let mut unknown: Option<BTreeSet<String>> = None;
+    for config in config.split_by_sub_system() {
+        tracing::info!("Importing into sub system: {}", config.sub_system_name());
+        let service = TranslationService::new(config);
+        let skipped = service.import(lang, file, yes)?;
+        unknown = Some(match unknown {
+            Some(unknown) => unknown.intersection(&skipped).cloned().collect(),
+            None => skipped,
+        });
+    }
+    if let Some(unknown) = unknown.filter(|unknown| !unknown.is_empty()) {
+        output::status(
+            output::Status::Skipped,
+            format!(
+                "{}: {} keys not found in the base language: {}",
+                lang,
+                unknown.len(),
+                unknown.into_iter().collect::<Vec<_>>().join(", ")
+            ),
+        );
+    }
+    Ok(())
+}
+
+async fn handle_changes(config: Config) -> Result<()> {
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        service.changes().await?;
+    }
+    Ok(())
+}
+
+fn handle_check(config: Config, options: check::CheckOptions) -> Result<()> {
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        service.check(&options)?;
+    }
+    Ok(())
 }
 
-async fn handle_pull() -> Result<()> {
-    let config = Config::load()?;
-    let service = TranslationService::new(config);
-    service.sync_translations().await
+fn handle_fmt(config: Config, check: bool) -> Result<()> {
+    let mut unformatted = Vec::new();
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        if check {
+            unformatted.extend(service.unformatted_files()?);
+        } else {
+            for path in service.format_files()? {
+                output::status(output::Status::Ok, format!("formatted {}", path.display()));
+            }
+        }
+    }
+    if check {
+        check::print_unformatted(&unformatted);
+        if !unformatted.is_empty() {
+            return Err(AppError::Validation(format!(
+                "{} 个翻译文件不是规范格式，运行 'i18n-app fmt' 修复",
+                unformatted.len()
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn handle_reorganize(config: Config, merge: bool, dry_run: bool) -> Result<()> {
+    let mut moved = 0;
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        for (lang, from, to) in service.reorganize(merge, dry_run)? {
+            output::status(output::Status::Ok, format!("{}: {} → {}", lang, from, to));
+            moved += 1;
+        }
+    }
+
+    // include 使用配置文件中的原始模式，保留 {versionNo} 等变量
+    let raw = Config::from_file(config::DEFAULT_CONFIG_FILE)?;
+    let mut replacements: Vec<(String, String)> = Vec::new();
+    for pattern in raw
+        .include
+        .iter()
+        .chain(raw.sub_system_include.values().flatten())
+    {
+        let split = pattern.contains(translation::NAMESPACE_VAR);
+        let new = match (merge, split) {
+            (false, false) => translation::split_namespace_path(pattern, translation::LANG_VAR),
+            (true, true) => translation::merge_namespace_path(pattern),
+            _ => continue,
+        };
+        if !replacements.iter().any(|(old, _)| old == pattern) {
+            replacements.push((pattern.clone(), new));
+        }
+    }
+    for (old, new) in &replacements {
+        output::status(output::Status::Ok, format!("include: {} → {}", old, new));
+    }
+    if dry_run {
+        output::status(
+            output::Status::Skipped,
+            format!("dry run: {} files not changed", moved),
+        );
+        return Ok(());
+    }
+    Config::replace_include_patterns(config::DEFAULT_CONFIG_FILE, &replacements)
+}
+
+fn handle_lint(
+    config: Config,
+    write_baseline: bool,
+    format: Option<LintFormat>,
+    out: Option<String>,
+) -> Result<()> {
+    // --out 只写 SARIF，没有指定格式时按 SARIF 输出
+    let format = match (format, &out) {
+        (Some(LintFormat::Text), Some(_)) => {
+            return Err(AppError::Validation(
+                "--out 只能写入 SARIF，不能和 --format text 一起使用".to_string(),
+            )
+            .into())
+        }
+        (Some(format), _) => format,
+        (None, Some(_)) => LintFormat::Sarif,
+        (None, None) => LintFormat::Text,
+    };
+    let path = lint::baseline_path();
+    if write_baseline {
+        let mut baseline = lint::Baseline::default();
+        for config in config.split_by_sub_system() {
+            let sub_system = config.sub_system_name().to_string();
+            let service = TranslationService::new(config);
+            baseline.record(&sub_system, &service.lint()?);
+        }
+        baseline.save(&path)?;
+        output::status(
+            output::Status::Ok,
+            format!("recorded {} issues in {}", baseline.len(), path.display()),
+        );
+        return Ok(());
+    }
+
+    let baseline = lint::Baseline::load(&path)?;
+    let mut errors = 0;
+    let mut known = 0;
+    let mut all = Vec::new();
+    for config in config.split_by_sub_system() {
+        let sub_system = config.sub_system_name().to_string();
+        let service = TranslationService::new(config);
+        let (diagnostics, baselined) = baseline.filter(&sub_system, service.lint()?);
+        known += baselined;
+        match format {
+            LintFormat::Text => errors += lint::print(&diagnostics),
+            LintFormat::Sarif => all.extend(diagnostics),
+        }
+    }
+    if format == LintFormat::Sarif {
+        errors = all.iter().filter(|d| d.severity == Severity::Error).count();
+        let sarif = serde_json::to_string_pretty(&lint::to_sarif(&lint::rules(), &all))?;
+        match out {
+            Some(out) => {
+                std::fs::write(&out, sarif + "\n")
+                    .with_context(|| format!("写入 SARIF 文件 {} 失败", out))?;
+                output::status(
+                    output::Status::Ok,
+                    format!("wrote {} lint results to {}", all.len(), out),
+                );
+            }
+            None => println!("{}", sarif),
+        }
+    } else if known > 0 {
+        output::status(
+            output::Status::Skipped,
+            format!("{} known issues ignored by {}", known, path.display()),
+        );
+    }
+    if errors > 0 {
+        return Err(
+            AppError::Validation(format!("lint 发现 {} 个 error 级别的问题", errors)).into(),
+        );
+    }
+    Ok(())
+}
+
+fn handle_bundle(
+    config: Config,
+    out: &str,
+    gzip: bool,
+    brotli: bool,
+    split_namespaces: bool,
+) -> Result<()> {
+    // 多个子系统时每个子系统输出到单独的子目录
+    let per_sub_system = config.sub_systems.len() > 1;
+    for config in config.split_by_sub_system() {
+        let mut out = std::path::PathBuf::from(out);
+        if per_sub_system {
+            out.push(config.sub_system_name());
+        }
+        let service = TranslationService::new(config);
+        service.bundle(&bundle::BundleOptions {
+            out,
+            gzip,
+            brotli,
+            split_namespaces,
+        })?;
+    }
+    Ok(())
+}
+
+async fn handle_serve(config: Config, port: u16, pull: bool) -> Result<()> {
+    if pull {
+        handle_pull(config.clone(), PullOptions::default()).await?;
+    }
+    let per_sub_system = config.sub_systems.len() > 1;
+    let sources = config
+        .split_by_sub_system()
+        .into_iter()
+        .map(|config| serve::ServeSource {
+            prefix: per_sub_system.then(|| config.sub_system_name().to_string()),
+            service: TranslationService::new(config),
+        })
+        .collect();
+    serve::serve(port, sources).await
+}
+
+async fn handle_codegen(config: Config, target: CodegenTarget, watch: bool) -> Result<()> {
+    let (out, target) = match target {
+        CodegenTarget::Ts { out, params } => (out, codegen::Target::TypeScript { params }),
+        CodegenTarget::Rust { out } => (out, codegen::Target::Rust),
+        CodegenTarget::Dart { out } => (out, codegen::Target::Dart),
+        CodegenTarget::Kotlin { out } => (out, codegen::Target::Kotlin),
+        CodegenTarget::Swift { out } => (out, codegen::Target::Swift),
+        CodegenTarget::Snippets { out } => (out, codegen::Target::Snippets),
+    };
+    // 多个子系统时每个子系统输出到单独的子目录
+    let per_sub_system = config.sub_systems.len() > 1;
+    let services: Vec<(std::path::PathBuf, TranslationService)> = config
+        .split_by_sub_system()
+        .into_iter()
+        .map(|config| {
+            let out = std::path::PathBuf::from(&out);
+            let out = match (per_sub_system, out.parent(), out.file_name()) {
+                (true, Some(parent), Some(name)) => {
+                    parent.join(config.sub_system_name()).join(name)
+                }
+                _ => out,
+            };
+            (out, TranslationService::new(config))
+        })
+        .collect();
+    for (out, service) in &services {
+        service.codegen(target, out)?;
+    }
+    if !watch {
+        return Ok(());
+    }
+
+    // 与 serve 相同，轮询翻译文件的修改时间
+    tracing::info!("Watching translation files, press Ctrl+C to stop");
+    let watched_files = || -> Vec<(String, std::path::PathBuf)> {
+        services
+            .iter()
+            .flat_map(|(_, service)| service.local_language_files().unwrap_or_default())
+            .collect()
+    };
+    let mut files = watched_files();
+    let mut previous = serve::fingerprint(&files);
+    let cancel = shutdown::token();
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return Err(AppError::Cancelled.into()),
+            _ = tokio::time::sleep(serve::WATCH_INTERVAL) => {}
+        }
+        if serve::fingerprint(&files) == previous {
+            continue;
+        }
+        // 有变化时重新扫描，包括新增的语言文件
+        files = watched_files();
+        previous = serve::fingerprint(&files);
+        for (out, service) in &services {
+            // 编辑过程中文件可能暂时无法解析，报告后继续监视
+            if let Err(e) = service.codegen(target, out) {
+                output::status(output::Status::Failed, format!("{:#}", e));
+            }
+        }
+    }
+}
+
+fn handle_review(config: Config, action: ReviewAction) -> Result<()> {
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        match &action {
+            ReviewAction::Set {
+                lang,
+                state,
+                keys,
+                all,
+            } => service.review_set(lang, *state, keys, *all)?,
+            ReviewAction::List { lang, only } => service.review_list(lang.as_deref(), *only)?,
+        }
+    }
+    Ok(())
+}
+
+fn handle_cache(config: Config, action: CacheAction) -> Result<()> {
+    let configs = config.split_by_sub_system();
+    match action {
+        CacheAction::Ls => {
+            let mut rows = Vec::new();
+            for config in &configs {
+                for cached in cache::list_languages(&RealFs, config)? {
+                    let lang = if configs.len() > 1 {
+                        format!("{}/{}", config.sub_system_name(), cached.lang)
+                    } else {
+                        cached.lang
+                    };
+                    let status = match cached.status {
+                        cache::CacheStatus::Fresh => "ok",
+                        cache::CacheStatus::Corrupt => "corrupt",
+                        _ => "unverified",
+                    };
+                    rows.push(vec![
+                        lang,
+                        cached
+                            .keys
+                            .map_or_else(|| "invalid JSON".to_string(), |k| k.to_string()),
+                        cached.size.to_string(),
+                        cached
+                            .downloaded_at
+                            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                            .unwrap_or_default(),
+                        status.to_string(),
+                    ]);
+                }
+            }
+            if rows.is_empty() {
+                output::status(
+                    output::Status::Skipped,
+                    "cache is empty, run push or pull to download server translations",
+                );
+                return Ok(());
+            }
+            output::table(
+                ["Language", "Keys", "Bytes", "Downloaded", "Checksum"],
+                rows,
+            );
+        }
+        CacheAction::Show {
+            lang,
+            flat,
+            sub_system,
+        } => {
+            let mut candidates = Vec::new();
+            for config in &configs {
+                if sub_system
+                    .as_ref()
+                    .is_some_and(|name| name != config.sub_system_name())
+                {
+                    continue;
+                }
+                let path = cache::language_file(config, &lang)?;
+                if path.exists() {
+                    candidates.push((config, path));
+                }
+            }
+            let path = match candidates.as_slice() {
+                [(_, path)] => path.clone(),
+                [] => {
+                    return Err(AppError::Validation(format!(
+                        "缓存中没有语言 {}，先运行 push 或 pull 下载服务器翻译",
+                        lang
+                    ))
+                    .into())
+                }
+                _ => {
+                    return Err(AppError::Validation(format!(
+                        "语言 {} 在多个子系统的缓存中（{}），用 --sub-system 指定",
+                        lang,
+                        candidates
+                            .iter()
+                            .map(|(c, _)| c.sub_system_name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                    .into())
+                }
+            };
+            let value = translation::read_json_file(&path)
+                .with_context(|| format!("读取缓存文件 {} 失败", path.display()))?;
+            let value = if flat {
+                let flattened: BTreeMap<String, String> =
+                    translation::flatten_json(&value).into_iter().collect();
+                serde_json::to_value(flattened)?
+            } else {
+                value
+            };
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        CacheAction::Path => {
+            for config in &configs {
+                println!("{}", cache::target_dir(config).display());
+            }
+        }
+        CacheAction::Clear => {
+            for config in &configs {
+                let dir = cache::target_dir(config);
+                if cache::clear(&RealFs, config)? {
+                    output::status(output::Status::Ok, format!("removed {}", dir.display()));
+                } else {
+                    output::status(
+                        output::Status::Skipped,
+                        format!("{} does not exist", dir.display()),
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 当前本地翻译，按子系统合并
+fn local_catalog(config: Config) -> Result<release::Catalog> {
+    let mut catalog = release::Catalog::new();
+    for config in config.split_by_sub_system() {
+        let sub_system = config.sub_system_name().to_string();
+        let service = TranslationService::new(config);
+        catalog.insert(sub_system, service.local_catalog()?);
+    }
+    Ok(catalog)
+}
+
+fn handle_snapshot(config: Config, action: SnapshotAction) -> Result<()> {
+    let dir = workspace::releases_dir();
+    match action {
+        SnapshotAction::Save { name, force } => {
+            let catalog = local_catalog(config)?;
+            let keys: usize = catalog
+                .values()
+                .flat_map(|l| l.values())
+                .map(|c| c.len())
+                .sum();
+            let path = release::ReleaseSnapshot::new(&name, catalog).save(&dir, force)?;
+            workspace::ensure_work_dir_gitignore()?;
+            output::status(
+                output::Status::Ok,
+                format!(
+                    "Saved snapshot {} ({} strings) to {}",
+                    name,
+                    keys,
+                    path.display()
+                ),
+            );
+        }
+        SnapshotAction::Diff { from, to, lang } => {
+            let old = release::ReleaseSnapshot::load(&dir, &from)?.catalog;
+            let (new, to) = match to {
+                Some(to) => (release::ReleaseSnapshot::load(&dir, &to)?.catalog, to),
+                None => (local_catalog(config)?, "local files".to_string()),
+            };
+            let multiple_sub_systems = old.len().max(new.len()) > 1;
+            let changes: Vec<release::Change> = release::diff(&old, &new)
+                .into_iter()
+                .filter(|c| lang.as_ref().is_none_or(|lang| c.lang == *lang))
+                .collect();
+            if changes.is_empty() {
+                output::status(
+                    output::Status::Ok,
+                    format!("No changes between {} and {}", from, to),
+                );
+                return Ok(());
+            }
+
+            let show = |value: &Option<String>| {
+                value
+                    .as_deref()
+                    .map(|v| stats::preview(v, 40))
+                    .unwrap_or_default()
+            };
+            let rows = changes.iter().map(|c| {
+                let lang = if multiple_sub_systems {
+                    format!("{}/{}", c.sub_system, c.lang)
+                } else {
+                    c.lang.clone()
+                };
+                vec![
+                    lang,
+                    c.key.clone(),
+                    c.kind().to_string(),
+                    show(&c.old),
+                    show(&c.new),
+                ]
+            });
+            output::table(["Language", "Key", "Change", "Old", "New"], rows);
+            let count = |kind: &str| changes.iter().filter(|c| c.kind() == kind).count();
+            output::status(
+                output::Status::Ok,
+                format!(
+                    "{} changes between {} and {}: {} added, {} changed, {} removed",
+                    changes.len(),
+                    from,
+                    to,
+                    count("added"),
+                    count("changed"),
+                    count("removed")
+                ),
+            );
+        }
+        SnapshotAction::List => {
+            let snapshots = release::ReleaseSnapshot::list(&dir)?;
+            if snapshots.is_empty() {
+                output::status(output::Status::Skipped, "No snapshots saved yet");
+                return Ok(());
+            }
+            let rows = snapshots.iter().map(|s| {
+                let languages: std::collections::BTreeSet<&String> =
+                    s.catalog.values().flat_map(|l| l.keys()).collect();
+                let keys: usize = s
+                    .catalog
+                    .values()
+                    .flat_map(|l| l.values())
+                    .map(|c| c.len())
+                    .sum();
+                vec![
+                    s.name.clone(),
+                    s.created_at.clone(),
+                    languages.len().to_string(),
+                    keys.to_string(),
+                ]
+            });
+            output::table(["Name", "Created", "Languages", "Strings"], rows);
+        }
+    }
+    Ok(())
+}
+
+fn handle_diff(config: Config, path: Option<String>) -> Result<()> {
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        service.diff_with_cache(path.clone())?;
+    }
+    Ok(())
+}
+
+fn handle_grep(config: Config, options: grep::GrepOptions) -> Result<()> {
+    let regex = options.regex()?;
+    let mut count = 0;
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        count += service.grep(&options, &regex)?;
+    }
+    if count == 0 {
+        output::status(output::Status::Skipped, "no matches");
+    }
+    Ok(())
+}
+
+fn handle_get(config: Config, key: &str, lang: Option<&str>) -> Result<()> {
+    let mut rows = Vec::new();
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        for (language, value) in service.local_values(key)? {
+            match lang {
+                // 只输出值本身，便于在脚本中使用
+                Some(lang) if lang == language => println!("{}", value),
+                Some(_) => continue,
+                None => {}
+            }
+            rows.push(vec![language, value.to_string()]);
+        }
+    }
+    if rows.is_empty() {
+        let message = match lang {
+            Some(lang) => format!("语言 {} 的本地翻译中没有 key {}", lang, key),
+            None => format!("本地翻译中没有 key {}", key),
+        };
+        return Err(AppError::Validation(message).into());
+    }
+    if lang.is_none() {
+        output::table(["Language", "Value"], rows);
+    }
+    Ok(())
+}
+
+/// 修改本地文件中一个 key 的值，`push` 不为 None 时随后上传
+async fn handle_set(
+    config: Config,
+    key: &str,
+    lang: &str,
+    value: &str,
+    sub_system: Option<&str>,
+    push: Option<PushOptions>,
+) -> Result<()> {
+    if push.is_some() && config.offline {
+        return Err(AppError::Validation(
+            "set --push 需要访问服务器，离线模式下无法执行".to_string(),
+        )
+        .into());
+    }
+    let configs: Vec<Config> = config
+        .split_by_sub_system()
+        .into_iter()
+        .filter(|c| sub_system.is_none_or(|name| name == c.sub_system_name()))
+        .collect();
+    if configs.is_empty() {
+        return Err(AppError::Validation(format!(
+            "配置中没有子系统 {}",
+            sub_system.unwrap_or_default()
+        ))
+        .into());
+    }
+    // 只修改一个子系统：已有这个 key 的子系统，否则只配置了一个子系统时新增
+    let mut candidates = Vec::new();
+    for config in &configs {
+        if TranslationService::new(config.clone()).has_local_key(lang, key)? {
+            candidates.push(config);
+        }
+    }
+    let config = match (candidates.as_slice(), configs.as_slice()) {
+        ([config], _) => (*config).clone(),
+        ([], [config]) => config.clone(),
+        ([], _) => {
+            return Err(AppError::Validation(format!(
+                "本地没有 key {}，用 --sub-system 指定要新增到哪个子系统",
+                key
+            ))
+            .into())
+        }
+        _ => {
+            return Err(AppError::Validation(format!(
+                "key {} 在多个子系统中（{}），用 --sub-system 指定",
+                key,
+                candidates
+                    .iter()
+                    .map(|c| c.sub_system_name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .into())
+        }
+    };
+
+    let service = TranslationService::new(config.clone());
+    let message = match service.set_local_value(lang, key, value)? {
+        None => format!("{}: added {}", lang, key),
+        Some(previous) if *previous == *value => format!("{}: {} unchanged", lang, key),
+        Some(previous) => format!(
+            "{}: {} changed from {:?} to {:?}",
+            lang, key, previous, value
+        ),
+    };
+    output::status(output::Status::Ok, message);
+    match push {
+        Some(options) => {
+            service.record_pending_changes(lang, [key])?;
+            handle_push(config, None, options).await
+        }
+        None => Ok(()),
+    }
+}
+
+fn handle_replace(
+    config: Config,
+    find: &str,
+    replacement: &str,
+    lang: &str,
+    regex: bool,
+    yes: bool,
+) -> Result<()> {
+    let pattern = grep::GrepOptions {
+        pattern: find.to_string(),
+        fixed_strings: !regex,
+        ..Default::default()
+    }
+    .regex()?;
+    let lang = (lang != "all").then_some(lang);
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        service.replace(&pattern, replacement, !regex, lang, yes)?;
+    }
+    Ok(())
+}
+
+fn handle_explain(config: Config, key: &str) -> Result<()> {
+    let mut found = false;
+    for config in config.split_by_sub_system() {
+        let service = TranslationService::new(config);
+        found |= service.explain(key)?;
+    }
+    if !found {
+        return Err(
+            AppError::Validation(format!("本地翻译和服务器缓存中都没有 key {}", key)).into(),
+        );
+    }
+    Ok(())
 }
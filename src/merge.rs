@@ -0,0 +1,231 @@
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+/// 本地和服务器都修改了同一个 key，且修改结果不同
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub key: String,
+    pub base: Option<Value>,
+    pub local: Option<Value>,
+    pub remote: Option<Value>,
+}
+
+/// 冲突的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Local,
+    Remote,
+    /// 未解决，记录到冲突列表中
+    Unresolved,
+}
+
+/// 三方合并的结果，冲突的 key 保留本地值
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeResult {
+    pub merged: Value,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// 以上次同步的快照为基准合并本地和服务器的翻译：
+/// 只有一方修改时采用修改的一方，双方修改且结果不同时记为冲突。
+/// 服务器上缺失或为空的值不会覆盖本地值。
+/// 冲突先交给 `resolve` 处理（如交互式选择），未解决的冲突保留本地值并记录下来
+pub fn merge_three_way(
+    base: &Value,
+    local: &Value,
+    remote: &Value,
+    resolve: &mut dyn FnMut(&Conflict) -> Result<Resolution>,
+) -> Result<MergeResult> {
+    let mut merger = Merger {
+        conflicts: Vec::new(),
+        path: String::new(),
+        resolve,
+    };
+    let merged = merger
+        .merge_value(Some(base), Some(local), Some(remote))?
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    Ok(MergeResult {
+        merged,
+        conflicts: merger.conflicts,
+    })
+}
+
+/// 冲突文件内容：key -> {local, remote, base}，缺失的值为 null
+pub fn conflicts_to_json(conflicts: &[Conflict]) -> Value {
+    let entries = conflicts.iter().map(|c| {
+        let value = |v: &Option<Value>| v.clone().unwrap_or(Value::Null);
+        let entry = serde_json::json!({
+            "local": value(&c.local),
+            "remote": value(&c.remote),
+            "base": value(&c.base),
+        });
+        (c.key.clone(), entry)
+    });
+    Value::Object(entries.collect())
+}
+
+fn is_empty_remote(value: Option<&Value>) -> bool {
+    match value {
+        None => true,
+        Some(Value::String(s)) => s.trim().is_empty(),
+        Some(_) => false,
+    }
+}
+
+struct Merger<'a> {
+    conflicts: Vec<Conflict>,
+    /// 当前 key 的路径，递归时复用同一个缓冲区
+    path: String,
+    resolve: &'a mut dyn FnMut(&Conflict) -> Result<Resolution>,
+}
+
+impl Merger<'_> {
+    fn merge_value(
+        &mut self,
+        base: Option<&Value>,
+        local: Option<&Value>,
+        remote: Option<&Value>,
+    ) -> Result<Option<Value>> {
+        // 两侧都是对象时逐个 key 合并
+        if let (Some(Value::Object(local_map)), Some(Value::Object(remote_map))) = (local, remote) {
+            let base_map = base.and_then(Value::as_object);
+            let mut merged = Map::with_capacity(local_map.len());
+            let keys = local_map
+                .keys()
+                .chain(remote_map.keys().filter(|k| !local_map.contains_key(*k)));
+            for key in keys {
+                let len = self.path.len();
+                if len > 0 {
+                    self.path.push('.');
+                }
+                self.path.push_str(key);
+                let child = self.merge_value(
+                    base_map.and_then(|m| m.get(key)),
+                    local_map.get(key),
+                    remote_map.get(key),
+                );
+                self.path.truncate(len);
+                if let Some(child) = child? {
+                    merged.insert(key.clone(), child);
+                }
+            }
+            return Ok(Some(Value::Object(merged)));
+        }
+
+        if is_empty_remote(remote) || local == remote || remote == base {
+            return Ok(local.cloned());
+        }
+        if local == base {
+            return Ok(remote.cloned());
+        }
+
+        let conflict = Conflict {
+            key: self.path.clone(),
+            base: base.cloned(),
+            local: local.cloned(),
+            remote: remote.cloned(),
+        };
+        match (self.resolve)(&conflict)? {
+            Resolution::Local => Ok(local.cloned()),
+            Resolution::Remote => Ok(remote.cloned()),
+            Resolution::Unresolved => {
+                self.conflicts.push(conflict);
+                Ok(local.cloned())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_three_way() -> Result<()> {
+        let base = json!({
+            "home": {"title": "Home", "subtitle": "Welcome", "footer": "Footer"},
+            "removed": "Removed locally"
+        });
+        let local = json!({
+            "home": {"title": "Home page", "subtitle": "Welcome!", "footer": "Footer"},
+            "local_only": "Local"
+        });
+        let remote = json!({
+            "home": {"title": "Home", "subtitle": "Welcome back", "footer": "Page footer", "new": "New"},
+            "removed": "Removed locally",
+            "local_only": ""
+        });
+
+        let result = merge_three_way(&base, &local, &remote, &mut |_| Ok(Resolution::Unresolved))?;
+        assert_eq!(
+            result.merged,
+            json!({
+                "home": {
+                    "title": "Home page",
+                    "subtitle": "Welcome!",
+                    "footer": "Page footer",
+                    "new": "New"
+                },
+                "local_only": "Local"
+            })
+        );
+        assert_eq!(
+            result.conflicts,
+            vec![Conflict {
+                key: "home.subtitle".to_string(),
+                base: Some(json!("Welcome")),
+                local: Some(json!("Welcome!")),
+                remote: Some(json!("Welcome back")),
+            }]
+        );
+        assert_eq!(
+            conflicts_to_json(&result.conflicts),
+            json!({"home.subtitle": {"local": "Welcome!", "remote": "Welcome back", "base": "Welcome"}})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_conflicts() -> Result<()> {
+        let base = json!({"a": "1", "b": "1"});
+        let local = json!({"a": "local", "b": "local"});
+        let remote = json!({"a": "remote", "b": "remote"});
+
+        let result = merge_three_way(&base, &local, &remote, &mut |c| {
+            Ok(if c.key == "a" {
+                Resolution::Remote
+            } else {
+                Resolution::Unresolved
+            })
+        })?;
+        assert_eq!(result.merged, json!({"a": "remote", "b": "local"}));
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].key, "b");
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_golden() -> Result<()> {
+        for (case, dir) in crate::test_util::fixture_cases("merge") {
+            let read = |name: &str| crate::test_util::read_fixture(dir.join(name));
+            let (base, local, remote) =
+                (read("base.json"), read("local.json"), read("remote.json"));
+            for (name, resolution) in [
+                ("local", Resolution::Local),
+                ("remote", Resolution::Remote),
+                ("unresolved", Resolution::Unresolved),
+            ] {
+                let result = merge_three_way(&base, &local, &remote, &mut |_| Ok(resolution))?;
+                insta::assert_json_snapshot!(
+                    format!("merge_{}_{}", case, name),
+                    json!({
+                        "merged": result.merged,
+                        "conflicts": conflicts_to_json(&result.conflicts),
+                    })
+                );
+            }
+        }
+        Ok(())
+    }
+}
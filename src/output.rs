@@ -0,0 +1,59 @@
+use comfy_table::{presets::UTF8_FULL_CONDENSED, Table};
+use console::style;
+
+/// 面向用户的结果输出使用的 tracing target，标准输出中会过滤掉它，只写入日志文件
+pub const OUTPUT_TARGET: &str = "i18n_app::output";
+
+/// 单项操作的结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// ✓ 成功
+    Ok,
+    /// ~ 跳过或无变化
+    Skipped,
+    /// ✗ 失败
+    Failed,
+}
+
+/// 根据 `--no-color` 参数和 `NO_COLOR` 环境变量决定是否输出颜色
+pub fn init(no_color: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color || no_color_env {
+        console::set_colors_enabled(false);
+    }
+}
+
+pub fn colors_enabled() -> bool {
+    console::colors_enabled()
+}
+
+/// 输出一行带状态符号的结果，如 `✓ en-US: uploaded 12 keys`
+pub fn status(status: Status, message: impl AsRef<str>) {
+    let message = message.as_ref();
+    let symbol = match status {
+        Status::Ok => style("✓").green(),
+        Status::Skipped => style("~").yellow(),
+        Status::Failed => style("✗").red(),
+    };
+    println!("{} {}", symbol, message);
+    tracing::info!(target: OUTPUT_TARGET, "[{:?}] {}", status, message);
+}
+
+/// 输出对齐的表格
+pub fn table<H, R>(header: H, rows: R)
+where
+    H: IntoIterator,
+    H::Item: Into<comfy_table::Cell>,
+    R: IntoIterator<Item = Vec<String>>,
+{
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED).set_header(header);
+    if !colors_enabled() {
+        table.force_no_tty();
+    }
+    for row in rows {
+        tracing::info!(target: OUTPUT_TARGET, "{}", row.join(" | "));
+        table.add_row(row);
+    }
+    println!("{}", table);
+}
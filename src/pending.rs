@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::journal;
+use crate::vfs::FileSystem;
+use crate::workspace;
+
+pub fn pending_file() -> PathBuf {
+    workspace::work_dir().join("pending-changes.json")
+}
+
+/// 修改需要上传到的目标：当前配置以及 `environments` 中的每个环境，每个目标再按 pushVersions 展开
+fn push_targets(config: &Config) -> BTreeSet<String> {
+    let mut configs = vec![config.clone()];
+    let mut environments: Vec<&String> = config.environments.keys().collect();
+    environments.sort();
+    for name in environments {
+        let mut env_config = config.clone();
+        if env_config.apply_environment(name).is_ok() {
+            configs.push(env_config);
+        }
+    }
+    let mut targets = BTreeSet::new();
+    for config in configs {
+        targets.insert(journal::push_key(&config));
+        for version in &config.push_versions {
+            targets.insert(journal::push_key(&config.for_version(version)));
+        }
+    }
+    targets
+}
+
+/// 在本地确认过修改、下次 push 时即使与服务器的值不同也要上传的 key：上传目标 -> 语言 -> key。
+/// 上传目标与上传计划相同（`子系统@host#版本`），replace 等命令写入时记录到所有已配置的目标，
+/// 某个目标 push 全部成功后只清除该目标的记录，推送到其他环境时仍会上传
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PendingChanges {
+    #[serde(flatten)]
+    targets: BTreeMap<String, BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl PendingChanges {
+    /// 读取记录，文件不存在时返回空记录
+    pub fn load(fs: &dyn FileSystem, path: &Path) -> Result<Self> {
+        if !fs.exists(path) {
+            return Ok(Self::default());
+        }
+        let content = fs
+            .read_to_string(path)
+            .with_context(|| format!("读取待上传的修改 {} 失败", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("解析待上传的修改 {} 失败", path.display()))
+    }
+
+    /// 没有记录时删除文件
+    pub fn save(&self, fs: &dyn FileSystem, path: &Path) -> Result<()> {
+        if self.targets.is_empty() {
+            if fs.exists(path) {
+                fs.remove_file(path)
+                    .with_context(|| format!("删除待上传的修改 {} 失败", path.display()))?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)? + "\n";
+        fs.write(path, content.as_bytes())
+            .with_context(|| format!("写入待上传的修改 {} 失败", path.display()))
+    }
+
+    /// 为 `config`（单个子系统）的所有上传目标记录修改
+    pub fn add<'a>(
+        &mut self,
+        config: &Config,
+        lang: &str,
+        keys: impl IntoIterator<Item = &'a str>,
+    ) {
+        let keys: Vec<&str> = keys.into_iter().collect();
+        for target in push_targets(config) {
+            self.targets
+                .entry(target)
+                .or_default()
+                .entry(lang.to_string())
+                .or_default()
+                .extend(keys.iter().map(|key| key.to_string()));
+        }
+    }
+
+    /// 推送到 `config` 的目标时是否需要上传这个 key
+    pub fn contains(&self, config: &Config, lang: &str, key: &str) -> bool {
+        self.targets
+            .get(&journal::push_key(config))
+            .and_then(|langs| langs.get(lang))
+            .is_some_and(|keys| keys.contains(key))
+    }
+
+    /// 清除 `config` 的目标的记录，其他目标不受影响
+    pub fn clear(&mut self, config: &Config) {
+        self.targets.remove(&journal::push_key(config));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EnvironmentConfig;
+    use crate::vfs::RealFs;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pending_changes_roundtrip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir
+            .path()
+            .join(".i18n-app")
+            .join("pending-changes.json");
+        let app = Config {
+            sub_systems: vec!["app".to_string()],
+            ..Default::default()
+        };
+        let admin = Config {
+            sub_systems: vec!["admin".to_string()],
+            ..Default::default()
+        };
+
+        let mut pending = PendingChanges::load(&RealFs, &path)?;
+        pending.add(&app, "de-DE", ["brand.name", "footer.legal"]);
+        pending.add(&admin, "de-DE", ["brand.name"]);
+        pending.save(&RealFs, &path)?;
+
+        let mut loaded = PendingChanges::load(&RealFs, &path)?;
+        assert_eq!(loaded, pending);
+        assert!(loaded.contains(&app, "de-DE", "footer.legal"));
+        assert!(!loaded.contains(&app, "fr-FR", "footer.legal"));
+        assert!(!loaded.contains(&admin, "de-DE", "footer.legal"));
+
+        loaded.clear(&app);
+        loaded.clear(&admin);
+        loaded.save(&RealFs, &path)?;
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear_only_pushed_target() {
+        let env = |host: &str| EnvironmentConfig {
+            host: Some(host.to_string()),
+            ..Default::default()
+        };
+        let config = Config {
+            sub_systems: vec!["app".to_string()],
+            host: "https://test.com".to_string(),
+            environments: HashMap::from([
+                ("staging".to_string(), env("https://staging.test.com")),
+                ("prod".to_string(), env("https://prod.test.com")),
+            ]),
+            ..Default::default()
+        };
+        let mut staging = config.clone();
+        staging.apply_environment("staging").unwrap();
+        let mut prod = config.clone();
+        prod.apply_environment("prod").unwrap();
+
+        let mut pending = PendingChanges::default();
+        pending.add(&config, "de-DE", ["brand.name"]);
+        // 推送到 staging 后，prod 和其他版本仍然需要上传
+        pending.clear(&staging);
+        assert!(!pending.contains(&staging, "de-DE", "brand.name"));
+        assert!(pending.contains(&prod, "de-DE", "brand.name"));
+        assert!(pending.contains(&config, "de-DE", "brand.name"));
+        assert!(!pending.contains(&config.for_version("2.0.0"), "de-DE", "brand.name"));
+    }
+}
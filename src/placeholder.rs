@@ -0,0 +1,282 @@
+use serde_json::Value;
+
+use crate::config::PlaceholderStyle;
+
+/// 按占位符拆分的文本片段
+#[derive(Debug, PartialEq)]
+enum Token {
+    Text(String),
+    Named(String),
+    /// 位置占位符，从 1 开始
+    Positional(usize),
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// 以 `{` 开头的文本中配对的右括号之后的位置
+fn balanced_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn push_text(tokens: &mut Vec<Token>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match tokens.last_mut() {
+        Some(Token::Text(last)) => last.push_str(text),
+        _ => tokens.push(Token::Text(text.to_string())),
+    }
+}
+
+/// 解析 `{name}`（`double` 时为 `{{name}}`），ICU 的 `{count, plural, ...}` 等其他花括号内容原样保留
+fn parse_braces(text: &str, double: bool) -> Vec<Token> {
+    let (open, close) = if double { ("{{", "}}") } else { ("{", "}") };
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        push_text(&mut tokens, &rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = balanced_end(rest) else {
+            break;
+        };
+        let block = &rest[..end];
+        match block
+            .strip_prefix(open)
+            .and_then(|inner| inner.strip_suffix(close))
+        {
+            Some(name) if is_name(name) => tokens.push(Token::Named(name.to_string())),
+            _ => push_text(&mut tokens, block),
+        }
+        rest = &rest[end..];
+    }
+    push_text(&mut tokens, rest);
+    tokens
+}
+
+/// 解析 `%@`/`%1$@`（`marker` 为 `@`）或 `%s`/`%1$s`（`marker` 为 `s`），`%%` 解析为 `%`
+fn parse_positional(text: &str, marker: char) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut next = 1;
+    let mut rest = text;
+    while let Some(start) = rest.find('%') {
+        push_text(&mut tokens, &rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(after) = after.strip_prefix('%') {
+            push_text(&mut tokens, "%");
+            rest = after;
+            continue;
+        }
+        if let Some(after) = after.strip_prefix(marker) {
+            tokens.push(Token::Positional(next));
+            next += 1;
+            rest = after;
+            continue;
+        }
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let index = after[..digits].parse::<usize>().ok().filter(|i| *i > 0);
+        match (index, after[digits..].strip_prefix('$')) {
+            (Some(index), Some(spec)) if spec.starts_with(marker) => {
+                tokens.push(Token::Positional(index));
+                rest = &spec[marker.len_utf8()..];
+            }
+            // 不支持的格式（如 `%d`）原样保留
+            _ => {
+                push_text(&mut tokens, "%");
+                rest = after;
+            }
+        }
+    }
+    push_text(&mut tokens, rest);
+    tokens
+}
+
+/// 位置占位符使用的格式字符
+fn positional_marker(style: PlaceholderStyle) -> Option<char> {
+    match style {
+        PlaceholderStyle::Ios => Some('@'),
+        PlaceholderStyle::Android => Some('s'),
+        PlaceholderStyle::Braces | PlaceholderStyle::DoubleBraces => None,
+    }
+}
+
+/// 服务器文本（`{name}`）中可以转换的占位符，按出现顺序去重。
+/// ICU 的 `{count, plural, ...}` 等不转换，不包含在内
+pub fn server_names(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for token in parse_braces(text, false) {
+        if let Token::Named(name) = token {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// 把服务器的 `{name}` 转换为本地语法。位置占位符按 `base_names`（基准语言文本中占位符的顺序）编号，
+/// 保证各语言的参数顺序一致；不在其中的占位符排在后面
+pub fn to_local(text: &str, style: PlaceholderStyle, base_names: &[String]) -> String {
+    if style == PlaceholderStyle::Braces {
+        return text.to_string();
+    }
+    let tokens = parse_braces(text, false);
+    let mut names = base_names.to_vec();
+    for token in &tokens {
+        if let Token::Named(name) = token {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+
+    let mut output = String::new();
+    for token in tokens {
+        match (token, positional_marker(style)) {
+            (Token::Text(text), Some(_)) => output.push_str(&text.replace('%', "%%")),
+            (Token::Text(text), None) => output.push_str(&text),
+            (Token::Named(_), Some(marker)) if names.len() == 1 => {
+                output.push('%');
+                output.push(marker);
+            }
+            (Token::Named(name), Some(marker)) => {
+                let position = names.iter().position(|n| *n == name).unwrap_or(0) + 1;
+                output.push_str(&format!("%{}${}", position, marker));
+            }
+            (Token::Named(name), None) => output.push_str(&format!("{{{{{}}}}}", name)),
+            (Token::Positional(_), _) => {}
+        }
+    }
+    output
+}
+
+/// 把本地语法转换为服务器的 `{name}`。位置占位符按 `base_names` 还原名称，
+/// 超出的位置命名为 `arg<序号>`
+pub fn to_server(text: &str, style: PlaceholderStyle, base_names: &[String]) -> String {
+    let tokens = match (style, positional_marker(style)) {
+        (PlaceholderStyle::Braces, _) => return text.to_string(),
+        (_, Some(marker)) => parse_positional(text, marker),
+        (_, None) => parse_braces(text, true),
+    };
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            Token::Text(text) => text,
+            Token::Named(name) => format!("{{{}}}", name),
+            Token::Positional(index) => match base_names.get(index - 1) {
+                Some(name) => format!("{{{}}}", name),
+                None => format!("{{arg{}}}", index),
+            },
+        })
+        .collect()
+}
+
+/// 转换 JSON 中的所有字符串，`convert` 的参数为扁平 key（以 `.` 连接）和文本
+pub fn convert_json(value: &Value, convert: &dyn Fn(&str, &str) -> String) -> Value {
+    convert_json_inner(value, "", convert)
+}
+
+fn convert_json_inner(
+    value: &Value,
+    prefix: &str,
+    convert: &dyn Fn(&str, &str) -> String,
+) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    (key.clone(), convert_json_inner(value, &path, convert))
+                })
+                .collect(),
+        ),
+        Value::String(text) => Value::String(convert(prefix, text)),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_round_trip_styles() {
+        let server = "Hi {name}, 100% of {count} {n, plural, one {# item} other {# items}}";
+        let base = names(&["name", "count"]);
+
+        let double = to_local(server, PlaceholderStyle::DoubleBraces, &base);
+        assert_eq!(
+            double,
+            "Hi {{name}}, 100% of {{count}} {n, plural, one {# item} other {# items}}"
+        );
+        let ios = to_local(server, PlaceholderStyle::Ios, &base);
+        assert_eq!(
+            ios,
+            "Hi %1$@, 100%% of %2$@ {n, plural, one {# item} other {# items}}"
+        );
+        let android = to_local(server, PlaceholderStyle::Android, &base);
+        assert_eq!(
+            android,
+            "Hi %1$s, 100%% of %2$s {n, plural, one {# item} other {# items}}"
+        );
+        for (local, style) in [
+            (double, PlaceholderStyle::DoubleBraces),
+            (ios, PlaceholderStyle::Ios),
+            (android, PlaceholderStyle::Android),
+        ] {
+            assert_eq!(to_server(&local, style, &base), server);
+        }
+        assert_eq!(to_local(server, PlaceholderStyle::Braces, &base), server);
+    }
+
+    #[test]
+    fn test_positional_follows_base_order() {
+        let base = names(&["name", "count"]);
+        // 译文调整了占位符顺序，编号仍与基准语言一致
+        assert_eq!(
+            to_local("{count} pour {name}", PlaceholderStyle::Ios, &base),
+            "%2$@ pour %1$@"
+        );
+        assert_eq!(
+            to_local("Hello {name}", PlaceholderStyle::Ios, &names(&["name"])),
+            "Hello %@"
+        );
+        assert_eq!(
+            to_server("%@ and %@ at %d", PlaceholderStyle::Ios, &base),
+            "{name} and {count} at %d"
+        );
+        assert_eq!(
+            to_server("%1$s, %3$s", PlaceholderStyle::Android, &base),
+            "{name}, {arg3}"
+        );
+        assert_eq!(
+            server_names("{a} {b} {a} {{c}} {n, plural}"),
+            names(&["a", "b"])
+        );
+    }
+}
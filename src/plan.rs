@@ -0,0 +1,355 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::api::FileDownloadInfo;
+use crate::journal::PlannedUpload;
+use crate::translation::{self, Content, TranslationDelta, TranslationFile};
+
+/// push 要执行的上传，只根据本地和服务器的翻译计算，不访问服务器也不修改文件
+#[derive(Debug, Default, PartialEq)]
+pub struct PushPlan {
+    pub uploads: Vec<PlannedUpload>,
+    /// 没有需要上传的 key 的语言
+    pub unchanged: Vec<String>,
+}
+
+impl PushPlan {
+    pub fn key_count(&self) -> usize {
+        self.uploads.iter().map(|u| u.delta.key_count()).sum()
+    }
+}
+
+/// 本地 key 在服务器上的值
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteValue {
+    pub key: Arc<str>,
+    pub remote: String,
+}
+
+/// 一种语言本地与服务器的差异，只保存有差异的 key，各列表按 key 排序
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LanguageDiff {
+    /// 服务器上没有的 key
+    pub added: Vec<Arc<str>>,
+    /// 服务器上值为空的 key
+    pub empty: Vec<RemoteValue>,
+    /// 服务器上值不为空且与本地不同的 key
+    pub changed: Vec<RemoteValue>,
+}
+
+/// 流式读取服务器上一种语言的 JSON，边解析边与本地比较，不构建服务器翻译的完整 map
+pub fn diff_language<R: Read>(local: &TranslationFile, remote: R) -> Result<LanguageDiff> {
+    let mut diff = LanguageDiff::default();
+    let mut seen: HashSet<Arc<str>> = HashSet::new();
+    translation::for_each_flat_entry(remote, &mut |key, remote| {
+        let Some((key, local_value)) = local.content.get_key_value(key.as_str()) else {
+            return;
+        };
+        seen.insert(key.clone());
+        let entry = RemoteValue {
+            key: key.clone(),
+            remote,
+        };
+        if entry.remote.trim().is_empty() {
+            diff.empty.push(entry);
+        } else if **local_value != *entry.remote {
+            diff.changed.push(entry);
+        }
+    })?;
+    diff.added = local
+        .content
+        .keys()
+        .filter(|key| !seen.contains(*key))
+        .cloned()
+        .collect();
+    diff.added.sort();
+    diff.empty.sort_by(|a, b| a.key.cmp(&b.key));
+    diff.changed.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(diff)
+}
+
+/// 计算每种语言需要上传的内容：`diffs` 中没有的语言是服务器上还没有的语言，上传全部内容；其余语言只上传
+/// 服务器上没有或值为空的 key，值与服务器不同的 key 只在 `overwrite_changed(语言, key)` 返回 true 时上传。
+/// `display_path` 返回输出中显示的本地文件路径
+pub fn plan_push(
+    local_translations: Vec<TranslationFile>,
+    diffs: &HashMap<String, LanguageDiff>,
+    overwrite_changed: impl Fn(&str, &str) -> bool,
+    display_path: impl Fn(&TranslationFile) -> String,
+) -> PushPlan {
+    let mut plan = PushPlan::default();
+    for local_translation in local_translations {
+        let lang_code = local_translation.language_code.clone();
+        let full_path = display_path(&local_translation);
+
+        let Some(diff) = diffs.get(&lang_code) else {
+            plan.uploads.push(PlannedUpload {
+                full_path,
+                first_upload: true,
+                delta: TranslationDelta::new(&local_translation, &TranslationFile::default()),
+                done: false,
+            });
+            continue;
+        };
+
+        let local_value = |key: &Arc<str>| local_translation.content[key].clone();
+        // 服务器支持时只发送变化的 key 和原值的哈希
+        let mut delta = TranslationDelta {
+            language_code: lang_code.clone(),
+            relative_path: local_translation.relative_path.clone(),
+            added: Content::with_capacity(diff.added.len()),
+            changed: Content::new(),
+            base_hashes: HashMap::new(),
+        };
+        for key in &diff.added {
+            // 远程没有的键
+            delta.added.insert(key.clone(), local_value(key));
+        }
+        for entry in &diff.empty {
+            // 远程值为空的键
+            delta
+                .changed
+                .insert(entry.key.clone(), local_value(&entry.key));
+            delta
+                .base_hashes
+                .insert(entry.key.clone(), translation::text_hash(&entry.remote));
+        }
+        // 值不同的键只有确认覆盖时才上传
+        for entry in &diff.changed {
+            if overwrite_changed(&lang_code, &entry.key) {
+                // 用户确认覆盖的值不同的键
+                delta
+                    .changed
+                    .insert(entry.key.clone(), local_value(&entry.key));
+                delta
+                    .base_hashes
+                    .insert(entry.key.clone(), translation::text_hash(&entry.remote));
+            }
+        }
+
+        if delta.key_count() == 0 {
+            plan.unchanged.push(lang_code);
+            continue;
+        }
+        plan.uploads.push(PlannedUpload {
+            full_path,
+            first_upload: false,
+            delta,
+            done: false,
+        });
+    }
+    plan
+}
+
+/// pull 对一种语言执行的操作，下载 `url` 后写入本地文件
+#[derive(Debug, Clone, PartialEq)]
+pub enum PullAction {
+    /// 与已有的本地文件合并，`relative_path` 相对本地翻译目录
+    Merge {
+        lang: String,
+        url: String,
+        relative_path: String,
+    },
+    /// 为服务器独有的语言创建本地文件
+    Create {
+        lang: String,
+        url: String,
+        relative_path: PathBuf,
+    },
+}
+
+impl PullAction {
+    pub fn lang(&self) -> &str {
+        match self {
+            PullAction::Merge { lang, .. } | PullAction::Create { lang, .. } => lang,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            PullAction::Merge { url, .. } | PullAction::Create { url, .. } => url,
+        }
+    }
+}
+
+/// pull 要执行的下载，只根据本地文件和服务器的文件列表计算
+#[derive(Debug, Default, PartialEq)]
+pub struct PullPlan {
+    pub actions: Vec<PullAction>,
+    /// 服务器上没有的本地语言
+    pub missing_remote: Vec<String>,
+    /// 服务器独有、不创建本地文件的语言
+    pub remote_only: Vec<String>,
+}
+
+/// 计算 pull 对每种语言的操作：本地已有的语言与服务器上同一语言的第一个文件合并；服务器独有的语言在
+/// `create_path` 不为 None 时创建本地文件，`create_path` 返回新文件相对本地翻译目录的路径
+pub fn plan_pull(
+    local_files: &[TranslationFile],
+    remote_files: &[FileDownloadInfo],
+    create_path: Option<&dyn Fn(&str) -> PathBuf>,
+) -> PullPlan {
+    let mut plan = PullPlan::default();
+    for local_file in local_files {
+        let lang = &local_file.language_code;
+        match remote_files.iter().find(|f| &f.lang == lang) {
+            Some(remote) => plan.actions.push(PullAction::Merge {
+                lang: lang.clone(),
+                url: remote.url.clone(),
+                relative_path: local_file.relative_path.clone(),
+            }),
+            None => plan.missing_remote.push(lang.clone()),
+        }
+    }
+    for remote in remote_files
+        .iter()
+        .filter(|f| !local_files.iter().any(|l| l.language_code == f.lang))
+    {
+        match create_path {
+            Some(create_path) => plan.actions.push(PullAction::Create {
+                lang: remote.lang.clone(),
+                url: remote.url.clone(),
+                relative_path: create_path(&remote.lang),
+            }),
+            None => plan.remote_only.push(remote.lang.clone()),
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::translation_file;
+
+    fn full_path(file: &TranslationFile) -> String {
+        format!("locales/{}", file.relative_path)
+    }
+
+    #[test]
+    fn test_plan_push() {
+        let local = vec![
+            translation_file(
+                "en-US",
+                &[("save", "Save"), ("new", "New"), ("empty", "Empty")],
+            ),
+            translation_file("de", &[("save", "Speichern")]),
+            translation_file("fr", &[("save", "Enregistrer")]),
+        ];
+        let remote = HashMap::from([
+            (
+                "en-US".to_string(),
+                diff_language(&local[0], &br#"{"save": "Save it", "empty": " "}"#[..]).unwrap(),
+            ),
+            (
+                "de".to_string(),
+                diff_language(&local[1], &br#"{"save": "Speichern", "old": "Alt"}"#[..]).unwrap(),
+            ),
+        ]);
+
+        let plan = plan_push(local.clone(), &remote, |_, _| false, full_path);
+        assert_eq!(plan.unchanged, vec!["de"]);
+        assert_eq!(plan.uploads.len(), 2);
+        let en = &plan.uploads[0];
+        assert!(!en.first_upload);
+        assert_eq!(en.full_path, "locales/en-US.json");
+        let mut added: Vec<&str> = en.delta.added.keys().map(|k| k.as_ref()).collect();
+        added.sort();
+        assert_eq!(added, vec!["new"]);
+        // 服务器上为空的值作为修改上传
+        assert!(en.delta.changed.contains_key("empty"));
+        assert!(!en.delta.changed.contains_key("save"));
+        let fr = &plan.uploads[1];
+        assert!(fr.first_upload);
+        assert_eq!(plan.key_count(), 3);
+
+        let plan = plan_push(local.clone(), &remote, |_, _| true, full_path);
+        assert!(plan.uploads[0].delta.changed.contains_key("save"));
+        assert_eq!(
+            plan.uploads[0].delta.base_hashes["save"],
+            translation::text_hash("Save it")
+        );
+
+        // 只覆盖指定语言的 key
+        let plan = plan_push(
+            local,
+            &remote,
+            |lang, key| lang == "de" && key == "save",
+            full_path,
+        );
+        assert!(!plan.uploads[0].delta.changed.contains_key("save"));
+    }
+
+    #[test]
+    fn test_diff_language() -> Result<()> {
+        let local = translation_file(
+            "en-US",
+            &[("a.b", "B"), ("a.c", "C"), ("d", "D"), ("e", "E")],
+        );
+        let diff = diff_language(
+            &local,
+            &br#"{"a": {"b": "B", "c": "Old"}, "d": "", "x": "X"}"#[..],
+        )?;
+        assert_eq!(diff.added, vec![Arc::from("e")]);
+        assert_eq!(
+            diff.empty,
+            vec![RemoteValue {
+                key: Arc::from("d"),
+                remote: String::new()
+            }]
+        );
+        assert_eq!(
+            diff.changed,
+            vec![RemoteValue {
+                key: Arc::from("a.c"),
+                remote: "Old".to_string()
+            }]
+        );
+        assert!(diff_language(&local, &b"{"[..]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_pull() {
+        let local = vec![
+            translation_file("en-US", &[("save", "Save")]),
+            translation_file("de", &[("save", "Speichern")]),
+        ];
+        let remote = |lang: &str| FileDownloadInfo {
+            sub_system: "app".to_string(),
+            lang: lang.to_string(),
+            internal_url: String::new(),
+            url: format!("https://cdn/{}.json", lang),
+        };
+        let remote_files = vec![remote("en-US"), remote("fr"), remote("ja")];
+
+        let plan = plan_pull(&local, &remote_files, None);
+        assert_eq!(
+            plan.actions,
+            vec![PullAction::Merge {
+                lang: "en-US".to_string(),
+                url: "https://cdn/en-US.json".to_string(),
+                relative_path: "en-US.json".to_string(),
+            }]
+        );
+        assert_eq!(plan.missing_remote, vec!["de"]);
+        assert_eq!(plan.remote_only, vec!["fr", "ja"]);
+
+        let create_path = |lang: &str| PathBuf::from(format!("{}.json", lang));
+        let plan = plan_pull(&local, &remote_files, Some(&create_path));
+        assert!(plan.remote_only.is_empty());
+        let created: Vec<&str> = plan.actions[1..].iter().map(PullAction::lang).collect();
+        assert_eq!(created, vec!["fr", "ja"]);
+        assert_eq!(
+            plan.actions[2],
+            PullAction::Create {
+                lang: "ja".to_string(),
+                url: "https://cdn/ja.json".to_string(),
+                relative_path: PathBuf::from("ja.json"),
+            }
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 当前平台在 GitHub Releases 中对应的可执行文件，与 build.yml 中的 artifact_name 一致。
+/// 没有发布对应文件的平台返回 None
+pub const RELEASE_ASSET: Option<&str> = if cfg!(all(windows, target_arch = "x86_64")) {
+    Some("i18n-app-windows-x86_64.exe")
+} else if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+    Some("i18n-app-darwin-aarch64")
+} else {
+    None
+};
+
+/// 用系统的 shell 执行命令：Windows 使用 `cmd /C`，其他系统使用 `sh -c`
+pub fn shell(script: &str) -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", script]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+}
+
+/// 设置文件为可执行，Windows 按扩展名判断是否可执行，不需要修改
+pub fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(path, perms)?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// 上次替换时改名保留的旧可执行文件
+fn old_exe_path(exe: &Path) -> PathBuf {
+    exe.with_extension("old")
+}
+
+/// 用新内容替换可执行文件。Windows 不能覆盖正在运行的文件，但可以改名，
+/// 所以先把它改名为 `.old`，下次替换时再删除
+pub fn replace_executable(exe: &Path, content: &[u8]) -> Result<()> {
+    let old = old_exe_path(exe);
+    if old.exists() {
+        std::fs::remove_file(&old).with_context(|| format!("删除旧文件 {} 失败", old.display()))?;
+    }
+
+    let dir = exe.parent().unwrap_or(Path::new("."));
+    let mut temp = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("无法在 {} 中创建文件", dir.display()))?;
+    temp.write_all(content)?;
+    make_executable(temp.path())?;
+
+    if cfg!(windows) {
+        std::fs::rename(exe, &old).with_context(|| format!("重命名 {} 失败", exe.display()))?;
+    }
+    temp.persist(exe)
+        .with_context(|| format!("写入 {} 失败", exe.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_replace_executable() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let exe = temp_dir.path().join("i18n-app.exe");
+        std::fs::write(&exe, "v1")?;
+
+        replace_executable(&exe, b"v2")?;
+        assert_eq!(std::fs::read_to_string(&exe)?, "v2");
+        replace_executable(&exe, b"v3")?;
+        assert_eq!(std::fs::read_to_string(&exe)?, "v3");
+        assert_eq!(
+            std::fs::read_dir(temp_dir.path())?.count(),
+            if cfg!(windows) { 2 } else { 1 }
+        );
+
+        let output = shell("echo hello").output()?;
+        assert!(String::from_utf8_lossy(&output.stdout).contains("hello"));
+        Ok(())
+    }
+}
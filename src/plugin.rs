@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::service::TranslationService;
+
+/// 插件可执行文件的前缀，`i18n-app foo` 执行 PATH 中的 `i18n-app-foo`
+const PLUGIN_PREFIX: &str = "i18n-app-";
+
+/// 通过 stdin 传给插件的上下文
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginContext {
+    version: &'static str,
+    offline: bool,
+    /// 插件名之后的参数
+    args: Vec<String>,
+    /// 当前目录的配置，没有配置文件时为 null
+    config: Option<Config>,
+    /// 按 include 规则找到的本地翻译文件
+    files: Vec<PluginFile>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginFile {
+    sub_system: String,
+    lang: String,
+    path: PathBuf,
+}
+
+/// 在 PATH 中查找插件
+fn find_plugin(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    let file_name = format!("{}{}{}", PLUGIN_PREFIX, name, std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+fn resolve_files(config: &Config) -> Result<Vec<PluginFile>> {
+    let mut files = Vec::new();
+    for config in config.split_by_sub_system() {
+        let sub_system = config.sub_system_name().to_string();
+        let service = TranslationService::new(config);
+        for (lang, path) in service.local_language_files()? {
+            files.push(PluginFile {
+                sub_system: sub_system.clone(),
+                lang,
+                path,
+            });
+        }
+    }
+    Ok(files)
+}
+
+/// 执行外部插件，插件的退出码作为本程序的退出码
+pub fn run(args: &[String], offline: bool) -> Result<()> {
+    let Some((name, plugin_args)) = args.split_first() else {
+        return Err(AppError::Validation("缺少命令".to_string()).into());
+    };
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let Some(executable) = find_plugin(name, &path_var) else {
+        return Err(AppError::Validation(format!(
+            "未知命令 '{}'，PATH 中也没有找到插件 {}{}",
+            name, PLUGIN_PREFIX, name
+        ))
+        .into());
+    };
+
+    let config = Config::try_load().map(|mut config| {
+        config.offline = offline;
+        config
+    });
+    let files = match &config {
+        Some(config) => resolve_files(config)?,
+        None => Vec::new(),
+    };
+    let context = PluginContext {
+        version: env!("CARGO_PKG_VERSION"),
+        offline,
+        args: plugin_args.to_vec(),
+        config,
+        files,
+    };
+
+    tracing::debug!("Running plugin {}", executable.display());
+    let mut child = Command::new(&executable)
+        .args(plugin_args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("无法执行插件 {}", executable.display()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // 插件可能不读取 stdin，提前退出时忽略写入失败
+        let _ = stdin.write_all(serde_json::to_string(&context)?.as_bytes());
+    }
+    let status = child.wait()?;
+    if status.success() {
+        return Ok(());
+    }
+    Err(AppError::Plugin {
+        name: name.clone(),
+        code: status.code().unwrap_or(1),
+    }
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_plugin() -> Result<()> {
+        let empty = TempDir::new()?;
+        let bin = TempDir::new()?;
+        let plugin = bin
+            .path()
+            .join(format!("i18n-app-lint{}", std::env::consts::EXE_SUFFIX));
+        std::fs::write(&plugin, "")?;
+
+        let path_var = std::env::join_paths([empty.path(), bin.path()])?;
+        assert_eq!(find_plugin("lint", &path_var), Some(plugin));
+        assert_eq!(find_plugin("missing", &path_var), None);
+        Ok(())
+    }
+}
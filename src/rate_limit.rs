@@ -0,0 +1,109 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 服务器返回 429 且没有 Retry-After 时的默认等待时间
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+/// Retry-After 的最长等待时间，避免服务器返回异常值导致程序长时间挂起
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// 令牌桶限流器，桶容量等于每秒请求数（至少为 1）
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(requests_per_second: f64) -> Self {
+        let capacity = requests_per_second.max(1.0);
+        Self {
+            rate: requests_per_second,
+            capacity,
+            tokens: capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// 取出一个令牌，返回需要等待的时间（令牌充足时为零）
+    pub fn take(&mut self, now: Instant) -> Duration {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+static BUCKET: Mutex<Option<TokenBucket>> = Mutex::new(None);
+
+/// 按配置的每秒请求数等待，`None` 或非正数表示不限流
+pub async fn acquire(requests_per_second: Option<f64>) {
+    let rate = match requests_per_second {
+        Some(rate) if rate > 0.0 => rate,
+        _ => return,
+    };
+
+    let wait = {
+        let mut guard = BUCKET.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = match guard.as_mut() {
+            Some(bucket) if bucket.rate == rate => bucket,
+            _ => guard.insert(TokenBucket::new(rate)),
+        };
+        bucket.take(Instant::now())
+    };
+
+    if !wait.is_zero() {
+        tracing::debug!("Rate limit reached, waiting {:?}", wait);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// 解析 Retry-After 头（仅支持秒数形式），结果不超过 `MAX_RETRY_AFTER`
+pub fn parse_retry_after(value: Option<&str>) -> Duration {
+    value
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+        .min(MAX_RETRY_AFTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_allows_burst_then_waits() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0);
+
+        assert_eq!(bucket.take(start), Duration::ZERO);
+        assert_eq!(bucket.take(start), Duration::ZERO);
+        assert_eq!(bucket.take(start), Duration::from_millis(500));
+
+        // 一秒后令牌补满，之前透支的一个令牌也被归还
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after(Some("3")), Duration::from_secs(3));
+        assert_eq!(parse_retry_after(Some("0.5")), Duration::from_millis(500));
+        assert_eq!(parse_retry_after(Some("3600")), MAX_RETRY_AFTER);
+        assert_eq!(
+            parse_retry_after(Some("Wed, 21 Oct 2015 07:28:00 GMT")),
+            DEFAULT_RETRY_AFTER
+        );
+        assert_eq!(parse_retry_after(None), DEFAULT_RETRY_AFTER);
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+/// 一种语言扁平化后的全部翻译
+pub type LanguageCatalog = BTreeMap<String, String>;
+/// 子系统 -> 语言 -> 翻译
+pub type Catalog = BTreeMap<String, BTreeMap<String, LanguageCatalog>>;
+
+/// 某个发布版本的完整翻译快照，保存在 `.i18n-app/releases/<name>.json`，可以提交到仓库
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseSnapshot {
+    pub name: String,
+    pub created_at: String,
+    pub catalog: Catalog,
+}
+
+/// 快照名称会作为文件名使用，只允许字母、数字和 `.`、`-`、`_`
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "无效的快照名称 '{}'，只能包含字母、数字和 . - _",
+            name
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+pub fn snapshot_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+impl ReleaseSnapshot {
+    pub fn new(name: &str, catalog: Catalog) -> Self {
+        Self {
+            name: name.to_string(),
+            created_at: chrono::Local::now().to_rfc3339(),
+            catalog,
+        }
+    }
+
+    /// 保存到 `dir`，同名快照已存在且没有 `overwrite` 时返回错误
+    pub fn save(&self, dir: &Path, overwrite: bool) -> Result<PathBuf> {
+        let path = snapshot_path(dir, &self.name)?;
+        if path.exists() && !overwrite {
+            return Err(AppError::Validation(format!(
+                "快照 {} 已存在，使用 --force 覆盖",
+                self.name
+            ))
+            .into());
+        }
+        fs::create_dir_all(dir).with_context(|| format!("创建目录 {} 失败", dir.display()))?;
+        fs::write(&path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("写入快照 {} 失败", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let path = snapshot_path(dir, name)?;
+        if !path.exists() {
+            return Err(AppError::Validation(format!(
+                "快照 {} 不存在，先运行 `i18n-app snapshot save {}`",
+                name, name
+            ))
+            .into());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("读取快照 {} 失败", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("解析快照 {} 失败", path.display()))
+    }
+
+    /// 按名称排序的所有快照
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = fs::read_to_string(&path)?;
+                match serde_json::from_str::<Self>(&content) {
+                    Ok(snapshot) => snapshots.push(snapshot),
+                    Err(e) => tracing::warn!("跳过无法解析的快照 {}: {}", path.display(), e),
+                }
+            }
+        }
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(snapshots)
+    }
+}
+
+/// 一个 key 在两个版本之间的变化，`old`/`new` 为 None 表示新增/删除
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub sub_system: String,
+    pub lang: String,
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl Change {
+    pub fn kind(&self) -> &'static str {
+        match (&self.old, &self.new) {
+            (None, _) => "added",
+            (_, None) => "removed",
+            _ => "changed",
+        }
+    }
+}
+
+/// 比较两个版本的翻译，按子系统、语言、key 排序
+pub fn diff(old: &Catalog, new: &Catalog) -> Vec<Change> {
+    let empty_languages = BTreeMap::new();
+    let empty_catalog = LanguageCatalog::new();
+    let mut changes = Vec::new();
+    let sub_systems: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for sub_system in sub_systems {
+        let old_languages = old.get(sub_system).unwrap_or(&empty_languages);
+        let new_languages = new.get(sub_system).unwrap_or(&empty_languages);
+        let languages: BTreeSet<&String> =
+            old_languages.keys().chain(new_languages.keys()).collect();
+        for lang in languages {
+            let old_values = old_languages.get(lang).unwrap_or(&empty_catalog);
+            let new_values = new_languages.get(lang).unwrap_or(&empty_catalog);
+            let keys: BTreeSet<&String> = old_values.keys().chain(new_values.keys()).collect();
+            for key in keys {
+                let (old_value, new_value) = (old_values.get(key), new_values.get(key));
+                if old_value != new_value {
+                    changes.push(Change {
+                        sub_system: sub_system.clone(),
+                        lang: lang.clone(),
+                        key: key.clone(),
+                        old: old_value.cloned(),
+                        new: new_value.cloned(),
+                    });
+                }
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn catalog(entries: &[(&str, &str, &str)]) -> Catalog {
+        let mut catalog = Catalog::new();
+        for (lang, key, value) in entries {
+            catalog
+                .entry("app".to_string())
+                .or_default()
+                .entry(lang.to_string())
+                .or_default()
+                .insert(key.to_string(), value.to_string());
+        }
+        catalog
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = catalog(&[
+            ("en-US", "save", "Save"),
+            ("en-US", "old", "Old"),
+            ("de", "save", "Speichern"),
+        ]);
+        let new = catalog(&[
+            ("en-US", "save", "Save changes"),
+            ("en-US", "new", "New"),
+            ("de", "save", "Speichern"),
+        ]);
+        let changes: Vec<String> = diff(&old, &new)
+            .iter()
+            .map(|c| format!("{} {} {}", c.lang, c.key, c.kind()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec!["en-US new added", "en-US old removed", "en-US save changed"]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir = temp_dir.path().join("releases");
+        let snapshot = ReleaseSnapshot::new("v2.3.0", catalog(&[("en-US", "save", "Save")]));
+        snapshot.save(&dir, false)?;
+        assert!(snapshot.save(&dir, false).is_err());
+        snapshot.save(&dir, true)?;
+
+        assert_eq!(ReleaseSnapshot::load(&dir, "v2.3.0")?, snapshot);
+        assert!(ReleaseSnapshot::load(&dir, "v9").is_err());
+        assert!(ReleaseSnapshot::load(&dir, "../x").is_err());
+        assert_eq!(ReleaseSnapshot::list(&dir)?.len(), 1);
+        Ok(())
+    }
+}
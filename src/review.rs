@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::vfs::FileSystem;
+
+/// 审核状态文件，放在项目根目录，可以提交到仓库与 QA 共享
+pub const REVIEW_FILE: &str = ".i18n-review.json";
+
+/// 单个 key 的审核状态，没有记录的 key 视为 translated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReviewState {
+    Translated,
+    Reviewed,
+    NeedsReview,
+}
+
+impl std::fmt::Display for ReviewState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ReviewState::Translated => "translated",
+            ReviewState::Reviewed => "reviewed",
+            ReviewState::NeedsReview => "needs-review",
+        };
+        f.write_str(name)
+    }
+}
+
+type LanguageStates = BTreeMap<String, ReviewState>;
+
+/// 审核状态：子系统 -> 语言 -> key -> 状态
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReviewStore {
+    #[serde(flatten)]
+    sub_systems: BTreeMap<String, BTreeMap<String, LanguageStates>>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl ReviewStore {
+    pub fn load(fs: &dyn FileSystem) -> Result<Self> {
+        Self::load_from(fs, Path::new(REVIEW_FILE))
+    }
+
+    pub fn load_from(fs: &dyn FileSystem, path: &Path) -> Result<Self> {
+        let mut store = if fs.exists(path) {
+            let content = fs
+                .read_to_string(path)
+                .with_context(|| format!("读取审核状态文件 {} 失败", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("解析审核状态文件 {} 失败", path.display()))?
+        } else {
+            ReviewStore::default()
+        };
+        store.path = path.to_path_buf();
+        Ok(store)
+    }
+
+    pub fn save(&self, fs: &dyn FileSystem) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)? + "\n";
+        fs.write(&self.path, content.as_bytes())
+            .with_context(|| format!("写入审核状态文件 {} 失败", self.path.display()))
+    }
+
+    pub fn state(&self, sub_system: &str, lang: &str, key: &str) -> ReviewState {
+        self.sub_systems
+            .get(sub_system)
+            .and_then(|langs| langs.get(lang))
+            .and_then(|keys| keys.get(key))
+            .copied()
+            .unwrap_or(ReviewState::Translated)
+    }
+
+    /// 设置 key 的审核状态，translated 是默认状态，不单独记录
+    pub fn set(&mut self, sub_system: &str, lang: &str, key: &str, state: ReviewState) {
+        let langs = self.sub_systems.entry(sub_system.to_string()).or_default();
+        let keys = langs.entry(lang.to_string()).or_default();
+        if state == ReviewState::Translated {
+            keys.remove(key);
+        } else {
+            keys.insert(key.to_string(), state);
+        }
+    }
+
+    /// 把导入或机器翻译写入的 key 标记为需要审核
+    pub fn mark_needs_review<'a>(
+        &mut self,
+        sub_system: &str,
+        lang: &str,
+        keys: impl IntoIterator<Item = &'a String>,
+    ) {
+        for key in keys {
+            self.set(sub_system, lang, key, ReviewState::NeedsReview);
+        }
+    }
+
+    /// 统计某个语言中处于指定状态的 key 数量
+    pub fn count(&self, sub_system: &str, lang: &str, state: ReviewState) -> usize {
+        self.sub_systems
+            .get(sub_system)
+            .and_then(|langs| langs.get(lang))
+            .map(|keys| keys.values().filter(|s| **s == state).count())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::RealFs;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_review_store_roundtrip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(REVIEW_FILE);
+
+        let mut store = ReviewStore::load_from(&RealFs, &path)?;
+        let keys = vec!["home.title".to_string(), "home.subtitle".to_string()];
+        store.mark_needs_review("app", "zh-CN", &keys);
+        store.set("app", "zh-CN", "home.subtitle", ReviewState::Reviewed);
+        store.save(&RealFs)?;
+
+        let content = fs::read_to_string(&path)?;
+        assert!(content.contains(r#""home.title": "needs-review""#));
+
+        let store = ReviewStore::load_from(&RealFs, &path)?;
+        assert_eq!(
+            store.state("app", "zh-CN", "home.title"),
+            ReviewState::NeedsReview
+        );
+        assert_eq!(
+            store.state("app", "zh-CN", "home.subtitle"),
+            ReviewState::Reviewed
+        );
+        assert_eq!(
+            store.state("app", "en-US", "home.title"),
+            ReviewState::Translated
+        );
+        assert_eq!(store.count("app", "zh-CN", ReviewState::NeedsReview), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+
+use crate::config::Config;
+use crate::error::AppError;
+
+/// 错误信息中最多列出的不符合 schema 的位置
+const MAX_REPORTED_ERRORS: usize = 5;
+
+/// 配置的 `translationSchema`，pull/download 写入本地之前用它校验服务器返回的每种语言的 JSON
+pub struct TranslationSchema {
+    path: String,
+    validator: jsonschema::Validator,
+}
+
+impl TranslationSchema {
+    /// 读取并编译配置的 schema，相对路径相对于配置文件所在的目录，没有配置时返回 None；
+    /// 文件无法读取或不是有效的 schema 时返回配置错误
+    pub fn load(config: &Config) -> Result<Option<Self>> {
+        let Some(path) = &config.translation_schema else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(config.resolve_path(path))
+            .map_err(|e| AppError::Config(format!("读取 schema {} 失败: {}", path, e)))?;
+        let schema: Value = serde_json::from_str(&content)
+            .map_err(|e| AppError::Config(format!("schema {} 不是有效的 JSON: {}", path, e)))?;
+        Self::new(path, &schema).map(Some)
+    }
+
+    pub fn new(path: &str, schema: &Value) -> Result<Self> {
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| AppError::Config(format!("schema {} 无效: {}", path, e)))?;
+        Ok(Self {
+            path: path.to_string(),
+            validator,
+        })
+    }
+
+    /// 校验一种语言的内容，不符合时返回 Validation 错误，列出前几个不符合的位置
+    pub fn validate(&self, lang: &str, value: &Value) -> Result<()> {
+        let errors: Vec<String> = self
+            .validator
+            .iter_errors(value)
+            .map(|e| {
+                let location = e.instance_path.to_string();
+                let location = if location.is_empty() { "/" } else { &location };
+                format!("{}: {}", location, e)
+            })
+            .collect();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let mut message = format!(
+            "服务器返回的 {} 翻译不符合 {}，未写入本地（{} 处错误）：{}",
+            lang,
+            self.path,
+            errors.len(),
+            errors
+                .iter()
+                .take(MAX_REPORTED_ERRORS)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+        if errors.len() > MAX_REPORTED_ERRORS {
+            message.push_str("; ...");
+        }
+        Err(AppError::Validation(message).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() -> Result<()> {
+        // 顶层只能是命名空间对象，叶子节点必须是字符串
+        let schema = TranslationSchema::new(
+            "schema.json",
+            &json!({
+                "type": "object",
+                "required": ["common"],
+                "additionalProperties": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" }
+                }
+            }),
+        )?;
+        schema.validate("en-US", &json!({"common": {"save": "Save"}}))?;
+
+        let flat = json!({"common.save": "Save", "common": {"cancel": 1}});
+        let error = schema.validate("de-DE", &flat).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("de-DE"));
+        assert!(message.contains("/common.save"));
+        assert!(message.contains("/common/cancel"));
+        assert!(matches!(
+            error.downcast_ref::<AppError>(),
+            Some(AppError::Validation(_))
+        ));
+
+        assert!(TranslationSchema::new("bad.json", &json!({"type": 1})).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load() -> Result<()> {
+        assert!(TranslationSchema::load(&Config::default())?.is_none());
+
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("schema.json");
+        fs::write(&path, r#"{"type": "object"}"#)?;
+        let config = Config {
+            translation_schema: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let schema = TranslationSchema::load(&config)?;
+        assert!(schema.is_some_and(|s| s.validate("en-US", &json!([])).is_err()));
+
+        // 相对路径相对于配置文件所在的目录，而不是当前目录
+        let config = Config {
+            translation_schema: Some("schema.json".to_string()),
+            root: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        assert!(TranslationSchema::load(&config)?.is_some());
+        let missing = Config {
+            translation_schema: Some("missing.json".to_string()),
+            ..config
+        };
+        let error = TranslationSchema::load(&missing).err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<AppError>(),
+            Some(AppError::Config(_))
+        ));
+        Ok(())
+    }
+}
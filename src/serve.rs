@@ -0,0 +1,653 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::error::AppError;
+use crate::output::{self, Status};
+use crate::service::TranslationService;
+use crate::translation;
+
+/// 检查本地文件变化的间隔
+pub const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// 没有变化时发送 SSE 注释的间隔，避免代理关闭空闲连接，也借此发现已经断开的客户端
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// 请求头的最大长度，超过时直接关闭连接
+const MAX_REQUEST_SIZE: usize = 16 * 1024;
+/// 请求体的最大长度
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+/// 查看完成度、待上传修改和编辑翻译的页面
+const REVIEW_PAGE: &str = include_str!("../assets/review.html");
+
+/// 一个子系统的翻译来源，多个子系统时通过 `/i18n/<子系统>/<语言>.json` 区分
+pub struct ServeSource {
+    pub prefix: Option<String>,
+    pub service: TranslationService,
+}
+
+impl ServeSource {
+    fn route_prefix(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("/i18n/{}/", prefix),
+            None => "/i18n/".to_string(),
+        }
+    }
+
+    /// 多个子系统时语言名带上子系统，如 `admin/en-US`
+    fn language_name(&self, lang: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{}/{}", prefix, lang),
+            None => lang.to_string(),
+        }
+    }
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            content_type: "application/json; charset=utf-8",
+            body: body.into_bytes(),
+        }
+    }
+
+    fn error(status: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: message.into().into_bytes(),
+        }
+    }
+}
+
+/// 启动本地预览服务器，直到进程被终止
+pub async fn serve(port: u16, sources: Vec<ServeSource>) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("监听端口 {} 失败", port))?;
+    let sources = Arc::new(sources);
+    for source in sources.iter() {
+        for (lang, _) in source.service.local_language_files()? {
+            output::status(
+                Status::Ok,
+                format!(
+                    "{}: http://localhost:{}{}{}.json",
+                    source.language_name(&lang),
+                    port,
+                    source.route_prefix(),
+                    lang
+                ),
+            );
+        }
+    }
+    output::status(
+        Status::Ok,
+        format!(
+            "Serving translations on http://localhost:{} (review page: /, changes: /i18n/events), press Ctrl+C to stop",
+            port
+        ),
+    );
+    accept_loop(listener, sources).await
+}
+
+async fn accept_loop(listener: TcpListener, sources: Arc<Vec<ServeSource>>) -> Result<()> {
+    let port = listener.local_addr()?.port();
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let sources = Arc::clone(&sources);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &sources, port).await {
+                tracing::debug!("Connection error: {}", e);
+            }
+        });
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    host: Option<String>,
+    origin: Option<String>,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// 请求是否来自本机的页面：Host 必须是监听的地址，有 Origin 时也必须是这个地址。
+    /// 其他网站通过 DNS rebinding 或表单提交发来的请求会被拒绝
+    fn is_local(&self, port: u16) -> bool {
+        let allowed = |host: &str| {
+            host == format!("localhost:{}", port) || host == format!("127.0.0.1:{}", port)
+        };
+        self.host.as_deref().is_some_and(allowed)
+            && self
+                .origin
+                .as_deref()
+                .is_none_or(|origin| origin.strip_prefix("http://").is_some_and(allowed))
+    }
+}
+
+/// 读取请求头和请求体，连接提前关闭或请求过大时返回 None
+async fn read_request(stream: &mut TcpStream) -> Result<Option<Request>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let header_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 || buffer.len() > MAX_REQUEST_SIZE {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let Some((method, path)) = parse_request_line(&head) else {
+        return Ok(None);
+    };
+    let header = |name: &str| {
+        head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_string())
+        })
+    };
+    let content_length: usize = header("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if content_length > MAX_BODY_SIZE {
+        return Ok(None);
+    }
+
+    let mut body = buffer[header_end..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    Ok(Some(Request {
+        method,
+        path,
+        host: header("host"),
+        origin: header("origin"),
+        content_type: header("content-type"),
+        body,
+    }))
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    sources: &[ServeSource],
+    port: u16,
+) -> Result<()> {
+    let Some(request) = read_request(&mut stream).await? else {
+        return write_response(
+            &mut stream,
+            Response::error("400 Bad Request", "bad request"),
+            false,
+        )
+        .await;
+    };
+    tracing::debug!("{} {}", request.method, request.path);
+
+    let response = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/i18n/events") => {
+            return stream_changes(stream, sources, KEEP_ALIVE_INTERVAL).await
+        }
+        ("GET", "/") => Response {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            body: REVIEW_PAGE.as_bytes().to_vec(),
+        },
+        // 审阅接口只给本机的审阅页面使用，避免其他网站读取未推送的修改
+        ("GET", "/api/coverage" | "/api/changes") if !request.is_local(port) => {
+            Response::error("403 Forbidden", "requests must come from the review page")
+        }
+        ("GET", "/api/coverage") => api_response(coverage(sources)),
+        ("GET", "/api/changes") => api_response(pending_changes(sources)),
+        ("POST", "/api/translations") => {
+            // 只接受 JSON 请求体：跨域的 JSON 请求需要预检，其他网页无法借用户的浏览器修改本地文件
+            let is_json = request
+                .content_type
+                .as_deref()
+                .is_some_and(|t| t.starts_with("application/json"));
+            if !request.is_local(port) {
+                Response::error("403 Forbidden", "requests must come from the review page")
+            } else if is_json {
+                api_response(update_translation(sources, &request.body))
+            } else {
+                Response::error(
+                    "415 Unsupported Media Type",
+                    "content type must be application/json",
+                )
+            }
+        }
+        ("GET", path) => route(path, sources),
+        _ => Response::error("405 Method Not Allowed", "method not allowed"),
+    };
+    let cors = request.path.starts_with("/i18n/");
+    write_response(&mut stream, response, cors).await
+}
+
+fn api_response(result: Result<serde_json::Value>) -> Response {
+    match result {
+        Ok(value) => Response::json(value.to_string()),
+        Err(e) if e.downcast_ref::<AppError>().is_some() => {
+            Response::error("400 Bad Request", format!("{:#}", e))
+        }
+        Err(e) => Response::error("500 Internal Server Error", format!("{:#}", e)),
+    }
+}
+
+fn coverage(sources: &[ServeSource]) -> Result<serde_json::Value> {
+    let mut result = Vec::new();
+    for source in sources {
+        for coverage in source.service.local_coverage()? {
+            result.push(serde_json::json!({
+                "subSystem": source.prefix,
+                "lang": coverage.lang,
+                "keys": coverage.keys,
+                "completion": coverage.completion,
+            }));
+        }
+    }
+    Ok(serde_json::Value::Array(result))
+}
+
+fn pending_changes(sources: &[ServeSource]) -> Result<serde_json::Value> {
+    let mut result = Vec::new();
+    for source in sources {
+        for change in source.service.pending_changes()? {
+            let mut value = serde_json::to_value(&change)?;
+            value["subSystem"] = serde_json::json!(source.prefix);
+            result.push(value);
+        }
+    }
+    Ok(serde_json::Value::Array(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateRequest {
+    #[serde(default)]
+    sub_system: Option<String>,
+    lang: String,
+    key: String,
+    value: String,
+}
+
+fn update_translation(sources: &[ServeSource], body: &[u8]) -> Result<serde_json::Value> {
+    let request: UpdateRequest = serde_json::from_slice(body)
+        .map_err(|e| AppError::Validation(format!("请求格式错误: {}", e)))?;
+    let source = sources
+        .iter()
+        .find(|s| s.prefix == request.sub_system)
+        .ok_or_else(|| AppError::Validation("未知的子系统".to_string()))?;
+    source
+        .service
+        .set_local_value(&request.lang, &request.key, &request.value)?;
+    Ok(serde_json::json!({ "ok": true }))
+}
+
+/// 解析请求行，返回方法和去掉查询参数的路径
+fn parse_request_line(request: &str) -> Option<(String, String)> {
+    let mut parts = request.lines().next()?.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+    let path = target
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(target)
+        .to_string();
+    Some((method, path))
+}
+
+fn route(path: &str, sources: &[ServeSource]) -> Response {
+    if path == "/i18n/index.json" || path == "/i18n/" {
+        return match language_index(sources) {
+            Ok(index) => Response::json(index),
+            Err(e) => Response::error("500 Internal Server Error", e.to_string()),
+        };
+    }
+
+    for source in sources {
+        let Some(lang) = path
+            .strip_prefix(&source.route_prefix())
+            .and_then(|rest| rest.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        if lang.contains('/') {
+            continue;
+        }
+        return match read_language(source, lang) {
+            Ok(Some(body)) => Response::json(body),
+            Ok(None) => Response::error("404 Not Found", format!("language {} not found", lang)),
+            Err(e) => Response::error("500 Internal Server Error", format!("{:#}", e)),
+        };
+    }
+    Response::error("404 Not Found", "not found")
+}
+
+/// 每次请求都重新读取本地文件，保证返回的是最新内容
+fn read_language(source: &ServeSource, lang: &str) -> Result<Option<String>> {
+    let files = source.service.local_language_files()?;
+    let Some((_, path)) = files.into_iter().find(|(l, _)| l == lang) else {
+        return Ok(None);
+    };
+    let value = translation::read_json_file(&path)
+        .with_context(|| format!("读取文件 {} 失败", path.display()))?;
+    Ok(Some(serde_json::to_string(&value)?))
+}
+
+fn language_index(sources: &[ServeSource]) -> Result<String> {
+    let mut index = BTreeMap::new();
+    for source in sources {
+        for (lang, _) in source.service.local_language_files()? {
+            index.insert(
+                source.language_name(&lang),
+                format!("{}{}.json", source.route_prefix(), lang),
+            );
+        }
+    }
+    Ok(serde_json::to_string_pretty(&index)?)
+}
+
+/// `cors` 为 true 时允许任意网页跨域读取，只用于 `/i18n/*` 的预览路由
+async fn write_response(stream: &mut TcpStream, response: Response, cors: bool) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len(),
+        if cors {
+            "Access-Control-Allow-Origin: *\r\n"
+        } else {
+            ""
+        }
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// 被监视的文件及其所在目录的修改时间，目录的修改时间用于发现新增的文件
+pub fn fingerprint(files: &[(String, PathBuf)]) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    let mtime = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut result = BTreeMap::new();
+    for (_, path) in files {
+        // 按命名空间拆分的语言监视每个命名空间文件
+        for (_, file) in translation::namespace_files(path).unwrap_or_default() {
+            let file_mtime = mtime(&file);
+            result.insert(file, file_mtime);
+        }
+        if let Some(parent) = path.parent() {
+            let parent = parent.to_path_buf();
+            let parent_mtime = mtime(&parent);
+            result.insert(parent, parent_mtime);
+        }
+    }
+    result
+}
+
+fn watched_files(sources: &[ServeSource]) -> Vec<(String, PathBuf)> {
+    sources
+        .iter()
+        .flat_map(|source| {
+            source
+                .service
+                .local_language_files()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(lang, path)| (source.language_name(&lang), path))
+        })
+        .collect()
+}
+
+/// 以 Server-Sent Events 推送本地文件的变化，每次变化发送一个 `change` 事件，数据为变化的语言。
+/// 客户端断开或写入失败时停止监视
+async fn stream_changes(
+    mut stream: TcpStream,
+    sources: &[ServeSource],
+    keep_alive: Duration,
+) -> Result<()> {
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nAccess-Control-Allow-Origin: *\r\nConnection: keep-alive\r\n\r\n: connected\n\n",
+        )
+        .await?;
+
+    let mut files = watched_files(sources);
+    let mut previous = fingerprint(&files);
+    let mut last_sent = tokio::time::Instant::now();
+    let mut buffer = [0u8; 256];
+    loop {
+        // EventSource 发送请求后不会再写入数据，读到 EOF 或出错说明客户端已经断开
+        tokio::select! {
+            read = stream.read(&mut buffer) => {
+                if matches!(read, Ok(0) | Err(_)) {
+                    tracing::debug!("Change stream client disconnected");
+                    return Ok(());
+                }
+                continue;
+            }
+            _ = tokio::time::sleep(WATCH_INTERVAL) => {}
+        }
+
+        let mut events = String::new();
+        let current = fingerprint(&files);
+        if current != previous {
+            // 有变化时重新扫描，包括新增的语言文件
+            files = watched_files(sources);
+            let current = fingerprint(&files);
+            for (lang, path) in &files {
+                if previous.get(path) != current.get(path) {
+                    tracing::info!("Translation changed: {}", lang);
+                    events.push_str(&format!("event: change\ndata: {}\n\n", lang));
+                }
+            }
+            previous = current;
+        }
+        if events.is_empty() && last_sent.elapsed() >= keep_alive {
+            events.push_str(": keep-alive\n\n");
+        }
+        if events.is_empty() {
+            continue;
+        }
+        if let Err(e) = stream.write_all(events.as_bytes()).await {
+            tracing::debug!("Change stream write failed, stop watching: {}", e);
+            return Ok(());
+        }
+        last_sent = tokio::time::Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::vfs::{FileSystem, MemoryFs};
+    use tempfile::TempDir;
+
+    /// 在随机端口启动服务器，返回端口
+    async fn start(sources: Vec<ServeSource>) -> Result<u16> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let port = listener.local_addr()?.port();
+        tokio::spawn(accept_loop(listener, Arc::new(sources)));
+        Ok(port)
+    }
+
+    /// 发送原始请求，返回完整响应
+    async fn send_raw(port: u16, request: String) -> Result<String> {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        Ok(response)
+    }
+
+    /// 发送原始请求，返回状态行
+    async fn send(port: u16, request: String) -> Result<String> {
+        let response = send_raw(port, request).await?;
+        Ok(response.lines().next().unwrap_or_default().to_string())
+    }
+
+    fn get(path: &str, host: &str) -> String {
+        format!("GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, host)
+    }
+
+    fn post(host: &str, headers: &str, body: &str) -> String {
+        format!(
+            "POST /api/translations HTTP/1.1\r\nHost: {}\r\n{}Content-Length: {}\r\n\r\n{}",
+            host,
+            headers,
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn test_update_translation_endpoint() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        let config = Config {
+            sub_systems: vec!["app".to_string()],
+            base_language: "en-US".to_string(),
+            base_path: Some(temp_dir.path().display().to_string()),
+            include: vec!["*.json".to_string()],
+            offline: true,
+            ..Default::default()
+        };
+        let fs = Arc::new(MemoryFs::new());
+        let service = TranslationService::new(config).with_fs(fs.clone());
+        let port = start(vec![ServeSource {
+            prefix: None,
+            service,
+        }])
+        .await?;
+        let body = r#"{"lang": "en-US", "key": "save", "value": "Save all"}"#;
+        let json = "Content-Type: application/json\r\n";
+        let local = format!("localhost:{}", port);
+
+        // 其他网站发来的请求：Host 不是监听的地址（DNS rebinding）或 Origin 是其他网站
+        assert_eq!(
+            send(port, post("evil.example.com", json, body)).await?,
+            "HTTP/1.1 403 Forbidden"
+        );
+        let origin = format!("{}Origin: http://evil.example.com\r\n", json);
+        assert_eq!(
+            send(port, post(&local, &origin, body)).await?,
+            "HTTP/1.1 403 Forbidden"
+        );
+        // 表单提交不需要预检，只接受 JSON
+        let form = "Content-Type: text/plain\r\n";
+        assert_eq!(
+            send(port, post(&local, form, body)).await?,
+            "HTTP/1.1 415 Unsupported Media Type"
+        );
+        assert!(fs.paths().is_empty());
+
+        let origin = format!("{}Origin: http://{}\r\n", json, local);
+        assert_eq!(
+            send(port, post(&local, &origin, body)).await?,
+            "HTTP/1.1 200 OK"
+        );
+        let written = fs.read_to_string(&temp_dir.path().join("en-US.json"))?;
+        assert!(written.contains("Save all"));
+
+        let invalid = r#"{"lang": "fr-FR", "key": "save", "value": "Enregistrer"}"#;
+        assert_eq!(
+            send(port, post(&local, json, invalid)).await?,
+            "HTTP/1.1 400 Bad Request"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_routes_are_local_only() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        let config = Config {
+            sub_systems: vec!["app".to_string()],
+            base_language: "en-US".to_string(),
+            base_path: Some(temp_dir.path().display().to_string()),
+            include: vec!["*.json".to_string()],
+            offline: true,
+            ..Default::default()
+        };
+        let port = start(vec![ServeSource {
+            prefix: None,
+            service: TranslationService::new(config),
+        }])
+        .await?;
+        let local = format!("localhost:{}", port);
+        let cors = "Access-Control-Allow-Origin: *";
+
+        for path in ["/api/coverage", "/api/changes"] {
+            assert_eq!(
+                send(port, get(path, "evil.example.com")).await?,
+                "HTTP/1.1 403 Forbidden"
+            );
+            let response = send_raw(port, get(path, &local)).await?;
+            assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+            assert!(!response.contains(cors), "{}", response);
+        }
+        // 预览路由允许其他端口上的开发服务器跨域读取
+        let response = send_raw(port, get("/i18n/en-US.json", &local)).await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.contains(cors), "{}", response);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_change_stream_stops_when_client_disconnects() -> Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let mut client = TcpStream::connect(listener.local_addr()?).await?;
+        let (stream, _) = listener.accept().await?;
+        let task =
+            tokio::spawn(
+                async move { stream_changes(stream, &[], Duration::from_millis(100)).await },
+            );
+
+        // 没有变化时定期收到 keep-alive
+        let mut received = String::new();
+        let mut buffer = [0u8; 1024];
+        while !received.contains(": keep-alive") {
+            let read =
+                tokio::time::timeout(Duration::from_secs(5), client.read(&mut buffer)).await??;
+            assert!(read > 0);
+            received.push_str(&String::from_utf8_lossy(&buffer[..read]));
+        }
+        assert!(received.starts_with("HTTP/1.1 200 OK"));
+
+        drop(client);
+        tokio::time::timeout(Duration::from_secs(5), task).await???;
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_request_line() {
+        assert_eq!(
+            parse_request_line("GET /i18n/en-US.json?v=1 HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+            Some(("GET".to_string(), "/i18n/en-US.json".to_string()))
+        );
+        assert_eq!(parse_request_line(""), None);
+    }
+}
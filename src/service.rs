@@ -1,135 +1,535 @@
 use anyhow::{ensure, Context, Result};
-use std::collections::HashMap;
-use std::fs::{self, File};
+use chrono::{DateTime, FixedOffset};
+use futures_util::{stream, StreamExt};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    api,
-    config::Config,
-    translation::{self, flatten_json_inner, read_translation_files, TranslationFile},
+    api::{
+        self, ApiError, ApiResult, ChangedTerm, FileDownloadInfo, LongPollingData, UploadSummary,
+    },
+    audit,
+    backend::{self, TranslationBackend},
+    bundle::{self, BundleOptions},
+    cache::{self, CacheEntry, CacheManifest, CacheStatus},
+    check::{self, CheckOptions},
+    codegen,
+    config::{
+        Config, EmptyBasePolicy, FillMissing, KeyStyle, LintConfig, PlaceholderStyle, Severity,
+    },
+    credentials::{self, Credential},
+    error::AppError,
+    grep::{self, GrepOptions},
+    history, import,
+    journal::{self, PlannedUpload, PushJournal},
+    lint, lsp, merge,
+    output::{self, Status},
+    pending::{self, PendingChanges},
+    placeholder,
+    plan::{self, LanguageDiff, PullAction},
+    release,
+    review::{ReviewState, ReviewStore},
+    schema::TranslationSchema,
+    shutdown,
+    spell::SpellChecker,
+    stats,
+    sync_state::{self, SyncEntry, SyncState},
+    translation::{
+        self, flatten_json_inner, read_translation_files, Content, TranslationDelta,
+        TranslationFile,
+    },
+    vfs::{FileSystem, RealFs},
+    workspace,
 };
 
+/// pull 命令的选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PullOptions {
+    /// 为远程独有的语言创建本地文件
+    pub create_missing: bool,
+    /// 逐个询问冲突的处理方式
+    pub interactive: bool,
+    /// 把回退链中的翻译写入本地文件
+    pub materialize_fallbacks: bool,
+    /// 忽略上次同步的 taskHash，服务器没有变化时也下载
+    pub force: bool,
+}
+
+/// push 命令的选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PushOptions {
+    /// 同时上传本地值与服务器不同的 key
+    pub overwrite_changed: bool,
+    /// 不再询问，直接覆盖
+    pub yes: bool,
+    /// 推送到受保护环境时不再确认
+    pub yes_production: bool,
+    /// 第一种语言上传失败时停止，不再上传其余语言
+    pub fail_fast: bool,
+    /// 继续上次中断的 push
+    pub resume: bool,
+}
+
+/// 一种语言的上传结果，`error` 为 None 表示成功
+struct UploadOutcome {
+    lang: String,
+    keys: usize,
+    error: Option<String>,
+}
+
 pub struct TranslationService {
     config: Config,
+    backend: Box<dyn TranslationBackend>,
+    /// 写入翻译文件和上传计划使用的文件系统
+    fs: Arc<dyn FileSystem>,
+    /// 取消后在下一次请求之前返回 [`AppError::Cancelled`]，正在进行的请求被放弃
+    cancel: CancellationToken,
+}
+
+/// 本地语言文件的完成度，以本地基准语言为准，回退链中的翻译也计入
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageCoverage {
+    pub lang: String,
+    pub keys: usize,
+    pub completion: f64,
+}
+
+/// 本地与最近一次缓存的服务器翻译不同的 key
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChange {
+    pub lang: String,
+    pub key: String,
+    pub local: String,
+    pub server: Option<String>,
+    pub status: ChangeStatus,
+}
+
+/// push 时如何处理本地与服务器不同的 key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeStatus {
+    /// 服务器上没有这个 key 或值为空，push 时上传
+    New,
+    /// replace、set --push 等记录过的修改，push 时覆盖服务器上的值
+    Confirmed,
+    /// 值与服务器不同，只有 push --overwrite-changed 确认后才上传
+    Changed,
 }
 
 impl TranslationService {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let config = config.with_path_vars();
+        let backend = backend::from_config(&config);
+        Self::with_backend(config, backend)
+    }
+
+    /// 使用指定的翻译后端创建服务
+    pub fn with_backend(config: Config, backend: Box<dyn TranslationBackend>) -> Self {
+        tracing::debug!("Using translation backend: {}", backend.name());
+        Self {
+            config,
+            backend,
+            fs: Arc::new(RealFs),
+            cancel: shutdown::token(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// 等待 `future`，取消信号先到达时放弃它并返回 [`AppError::Cancelled`]
+    async fn cancellable<T>(&self, future: impl std::future::Future<Output = T>) -> Result<T> {
+        tokio::select! {
+            biased;
+            _ = self.cancel.cancelled() => Err(AppError::Cancelled.into()),
+            value = future => Ok(value),
+        }
+    }
+
+    /// 使用内存文件系统写入翻译文件和上传计划，测试不会在当前目录留下文件
+    #[cfg(test)]
+    pub fn with_fs(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// 服务器翻译的本地缓存目录，按子系统、host 和版本区分，离线模式下 diff 依赖这里的内容
+    fn cache_dir(&self) -> PathBuf {
+        cache::target_dir(&self.config)
     }
 
     pub async fn download_to_cache(&self) -> Result<HashMap<String, TranslationFile>> {
-        let cache_dir = PathBuf::from(".i18n-app").join("cache");
-        self.prepare_cache_dir(&cache_dir)?;
+        let partial_dir = self.prepare_partial_cache()?;
+        let (translations, task_hash) = self.fetch_translations(Some(&partial_dir)).await?;
+        self.commit_partial_cache(&partial_dir)?;
+        self.record_cache_manifest(translations.keys(), task_hash.as_deref(), BTreeMap::new())?;
+        Ok(translations)
+    }
+
+    /// 只把服务器翻译下载到缓存文件，不在内存中保留翻译内容，返回服务器上有的语言。
+    /// 清单中校验和与服务器版本都一致的语言直接使用缓存，只重新下载过期、损坏或缺失的语言
+    async fn refresh_cache(&self) -> Result<HashSet<String>> {
+        let config_response = self.backend.fetch_config().await?;
+        let task_hash = config_response.data.task_hash;
+        let files = config_response.data.files.unwrap_or_default();
+        let cache_dir = self.cache_dir();
+        self.fs
+            .create_dir_all(&cache_dir)
+            .with_context(|| format!("创建缓存目录 {} 失败", cache_dir.display()))?;
+
+        let target = journal::push_key(&self.config);
+        let manifest = CacheManifest::load(self.fs.as_ref(), &cache::manifest_file());
+        let remote_languages: HashSet<&str> = files
+            .iter()
+            .filter(|f| !f.url.is_empty())
+            .map(|f| f.lang.as_str())
+            .collect();
+        let mut fresh = BTreeMap::new();
+        let mut languages = HashSet::new();
+        for lang in &remote_languages {
+            let path = self.cached_language_path(lang);
+            match manifest.check(self.fs.as_ref(), &target, lang, &path, task_hash.as_deref()) {
+                CacheStatus::Fresh => {
+                    tracing::debug!("Using cached translation for {}", lang);
+                    if let Some(entry) = manifest.entries(&target).and_then(|e| e.get(*lang)) {
+                        fresh.insert(lang.to_string(), entry.clone());
+                    }
+                    languages.insert(lang.to_string());
+                }
+                CacheStatus::Corrupt => {
+                    tracing::warn!("缓存 {} 与清单中的校验和不一致，重新下载", path.display());
+                }
+                status => tracing::debug!("Cache for {} is {:?}, downloading", lang, status),
+            }
+        }
+
+        // 服务器上已经没有的语言从缓存中删除
+        for path in self.fs.read_dir(&cache_dir).unwrap_or_default() {
+            let lang = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            if path.extension().is_some_and(|ext| ext == "json") && !remote_languages.contains(lang)
+            {
+                self.fs.remove_file(&path)?;
+            }
+        }
+
+        let outdated: Vec<FileDownloadInfo> = files
+            .into_iter()
+            .filter(|f| !fresh.contains_key(&f.lang))
+            .collect();
+        let outdated_languages: HashSet<String> = outdated.iter().map(|f| f.lang.clone()).collect();
+        let mut downloaded = Vec::new();
+        self.download_files(outdated, Some(&cache_dir), &mut |lang, json| {
+            let mut key_count = 0;
+            translation::for_each_flat_entry(json, &mut |_, _| key_count += 1)?;
+            history::record_downloaded(key_count);
+            downloaded.push(lang.to_string());
+            Ok(())
+        })
+        .await?;
+        // 下载失败的语言不保留旧的缓存文件
+        for file in &outdated_languages {
+            let path = self.cached_language_path(file);
+            if !downloaded.contains(file) && self.fs.exists(&path) {
+                self.fs.remove_file(&path)?;
+            }
+        }
+        languages.extend(downloaded.iter().cloned());
+        self.record_cache_manifest(downloaded.iter(), task_hash.as_deref(), fresh)?;
+        Ok(languages)
+    }
+
+    /// 用 `languages` 的缓存文件和沿用的 `entries` 替换清单中当前上传目标的记录
+    fn record_cache_manifest<'a>(
+        &self,
+        languages: impl IntoIterator<Item = &'a String>,
+        task_hash: Option<&str>,
+        entries: BTreeMap<String, CacheEntry>,
+    ) -> Result<()> {
+        let target = journal::push_key(&self.config);
+        CacheManifest::update(self.fs.as_ref(), &cache::manifest_file(), |manifest| {
+            manifest.set_entries(&target, entries);
+            for lang in languages {
+                let path = self.cached_language_path(lang);
+                manifest.record(self.fs.as_ref(), &target, lang, &path, task_hash)?;
+            }
+            Ok(())
+        })
+    }
 
+    /// 先下载到临时目录，全部完成后再替换缓存，下载失败或被中断时保留原来的缓存
+    fn prepare_partial_cache(&self) -> Result<PathBuf> {
+        let mut partial_dir = self.cache_dir().into_os_string();
+        partial_dir.push(".partial");
+        let partial_dir = PathBuf::from(partial_dir);
+        self.prepare_cache_dir(&partial_dir)?;
+        Ok(partial_dir)
+    }
+
+    fn commit_partial_cache(&self, partial_dir: &Path) -> Result<()> {
+        let cache_dir = self.cache_dir();
+        workspace::remove_dir_safely_with(self.fs.as_ref(), &cache_dir)?;
+        self.fs
+            .rename(partial_dir, &cache_dir)
+            .with_context(|| format!("写入缓存 {} 失败", cache_dir.display()))
+    }
+
+    /// 缓存中一种语言的 JSON 文件
+    fn cached_language_path(&self, lang: &str) -> PathBuf {
+        self.cache_dir().join(format!("{}.json", lang))
+    }
+
+    /// 流式读取缓存中的一种语言并扁平化
+    fn read_cached_language(&self, path: &Path) -> Result<HashMap<String, String>> {
+        let file = self
+            .fs
+            .open(path)
+            .with_context(|| format!("读取缓存 {} 失败", path.display()))?;
+        translation::flatten_json_reader(file)
+            .with_context(|| format!("解析缓存 {} 失败", path.display()))
+    }
+
+    /// 下载服务器翻译，`cache_dir` 不为 None 时同时写入缓存，同时返回服务器的 taskHash
+    async fn fetch_translations(
+        &self,
+        cache_dir: Option<&Path>,
+    ) -> Result<(HashMap<String, TranslationFile>, Option<String>)> {
         let mut cached_files: HashMap<String, TranslationFile> = HashMap::new();
-        let config_response = api::get_translation_config(&self.config).await?;
+        let task_hash = self
+            .download_languages(cache_dir, &mut |lang, json| {
+                let flattened = translation::flatten_json_reader(json)?;
+                let flattened_len = flattened.len();
+                history::record_downloaded(flattened_len);
 
-        if let Some(files_to_download) = config_response.data.files {
-            for file_info in files_to_download {
-                if file_info.url.is_empty() {
-                    tracing::warn!("No download url found for language: {}", file_info.lang);
-                    continue;
+                if let Some(existing_translation_file) = cached_files.get_mut(lang) {
+                    existing_translation_file
+                        .content
+                        .extend(translation::to_content(flattened));
+                    tracing::debug!("Merged {} new keys for language {}", flattened_len, lang);
+                } else {
+                    let translation = TranslationFile::from_content(
+                        lang.to_string(),
+                        format!("{}.json", lang),
+                        flattened,
+                    );
+                    tracing::debug!(
+                        "Created new translation for language {} with {} keys",
+                        lang,
+                        translation.content.len()
+                    );
+                    cached_files.insert(lang.to_string(), translation);
                 }
+                Ok(())
+            })
+            .await?;
+        Ok((cached_files, task_hash))
+    }
 
-                match api::download_translation(&self.config, &file_info.url).await {
-                    Ok(raw_content_string) => {
-                        let full_json_value: serde_json::Value =
-                            serde_json::from_str(&raw_content_string)?;
-                        let lang_key = format!("{}/languages", self.config.path_prefix);
+    /// 下载服务器上的全部语言文件，返回服务器的 taskHash
+    async fn download_languages(
+        &self,
+        cache_dir: Option<&Path>,
+        on_language: &mut dyn FnMut(&str, &mut dyn Read) -> Result<()>,
+    ) -> Result<Option<String>> {
+        let config_response = self.backend.fetch_config().await?;
+        if let Some(files) = config_response.data.files {
+            self.download_files(files, cache_dir, on_language).await?;
+        }
+        Ok(config_response.data.task_hash)
+    }
 
-                        if let Some(lang_specific_json_value) = full_json_value.get(&lang_key) {
-                            let mut flattened = HashMap::new();
-                            // 使用提取出的 lang_specific_json_value 进行扁平化
-                            flatten_json_inner(
-                                lang_specific_json_value,
-                                String::new(),
-                                &mut flattened,
-                            );
-                            let flattened_len = flattened.len();
-
-                            if let Some(existing_translation_file) =
-                                cached_files.get_mut(&file_info.lang)
-                            {
-                                existing_translation_file.content.extend(flattened);
-                                tracing::debug!(
-                                    "Merged {} new keys for language {}",
-                                    flattened_len,
-                                    file_info.lang
-                                );
-                            } else {
-                                let translation = TranslationFile::from_content(
-                                    file_info.lang.clone(),
-                                    format!("{}.json", file_info.lang),
-                                    flattened,
-                                );
-                                tracing::debug!(
-                                    "Created new translation for language {} with {} keys",
-                                    file_info.lang,
-                                    translation.content.len()
-                                );
-                                cached_files.insert(file_info.lang.clone(), translation);
-                            }
+    /// 逐个下载语言文件，把每种语言的 JSON 交给 `on_language`，`cache_dir` 不为 None 时同时写入缓存。
+    /// 下载内容先写入缓存目录中的临时文件，再流式取出语言字段，大文件不会完整读入内存。
+    /// 同一语言有多个文件时缓存中保存合并后的内容
+    async fn download_files(
+        &self,
+        files_to_download: Vec<FileDownloadInfo>,
+        cache_dir: Option<&Path>,
+        on_language: &mut dyn FnMut(&str, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        let scratch_dir = cache_dir.map_or_else(|| self.cache_dir(), Path::to_path_buf);
+        self.fs
+            .create_dir_all(&scratch_dir)
+            .with_context(|| format!("创建缓存目录 {} 失败", scratch_dir.display()))?;
+        let lang_key = format!("{}/languages", self.config.path_prefix);
+        let mut cached_languages = HashSet::new();
+        for file_info in files_to_download {
+            if file_info.url.is_empty() {
+                tracing::warn!("No download url found for language: {}", file_info.lang);
+                continue;
+            }
 
-                            let target_file = cache_dir.join(format!("{}.json", file_info.lang));
-                            // 将提取出的 lang_specific_json_value 写入缓存文件
-                            std::fs::write(
-                                &target_file,
-                                serde_json::to_string_pretty(lang_specific_json_value)?,
-                            )?;
-                            tracing::debug!(
-                                "Cached translation for {} to {}",
-                                file_info.lang,
-                                target_file.display()
-                            );
-                        } else {
-                            tracing::error!(
-                                "Key '{}' not found in downloaded content for language: {}. Raw content: {}",
-                                lang_key,
-                                file_info.lang,
-                                raw_content_string
-                            );
-                        }
+            let download_path = scratch_dir.join(format!("{}.download", file_info.lang));
+            let field_path = scratch_dir.join(format!("{}.json.part", file_info.lang));
+            let result = self
+                .cancellable(self.download_language(
+                    &file_info.url,
+                    &file_info.lang,
+                    &download_path,
+                    &field_path,
+                ))
+                .await
+                .and_then(|result| result);
+            if self.fs.exists(&download_path) {
+                self.fs.remove_file(&download_path)?;
+            }
+            match result {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::error!(
+                        "Key '{}' not found in downloaded content for language: {}",
+                        lang_key,
+                        file_info.lang
+                    );
+                    self.fs.remove_file(&field_path)?;
+                    continue;
+                }
+                Err(e) => {
+                    if self.fs.exists(&field_path) {
+                        self.fs.remove_file(&field_path)?;
                     }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to download translation for {}: {}",
-                            file_info.lang,
-                            e
-                        );
+                    if !is_skippable_download_error(&e) {
+                        return Err(e);
                     }
+                    tracing::error!(
+                        "Failed to download translation for {}: {}",
+                        file_info.lang,
+                        e
+                    );
+                    continue;
                 }
             }
-        }
 
-        Ok(cached_files)
+            let result = on_language(&file_info.lang, &mut self.fs.open(&field_path)?);
+            let result = result.and_then(|()| match cache_dir {
+                Some(cache_dir) => {
+                    let target_file = cache_dir.join(format!("{}.json", file_info.lang));
+                    if cached_languages.insert(file_info.lang.clone()) {
+                        self.fs.rename(&field_path, &target_file)?;
+                    } else {
+                        let mut merged = self.read_cached_language(&target_file)?;
+                        merged.extend(self.read_cached_language(&field_path)?);
+                        self.fs.write(
+                            &target_file,
+                            serde_json::to_string(&translation::unflatten_json(&merged)?)?
+                                .as_bytes(),
+                        )?;
+                    }
+                    tracing::debug!(
+                        "Cached translation for {} to {}",
+                        file_info.lang,
+                        target_file.display()
+                    );
+                    Ok(())
+                }
+                None => Ok(()),
+            });
+            if self.fs.exists(&field_path) {
+                self.fs.remove_file(&field_path)?;
+            }
+            result?;
+        }
+        Ok(())
     }
 
-    pub async fn push_translations(&self, path: Option<String>) -> Result<()> {
-        // 1. 读取本地翻译文件
-        let (base_path, mut local_translations) = self.read_local_translations(path)?;
+    /// 下载一个语言文件到 `download_path`，再把其中的语言字段写入 `field_path`，返回是否找到语言字段
+    async fn download_language(
+        &self,
+        url: &str,
+        lang: &str,
+        download_path: &Path,
+        field_path: &Path,
+    ) -> Result<bool> {
+        let mut out = self
+            .fs
+            .create(download_path)
+            .with_context(|| format!("创建文件 {} 失败", download_path.display()))?;
+        self.backend.download_to(url, &mut *out).await?;
+        out.flush()?;
+        drop(out);
 
-        // 2. 找到基准语言翻译
-        let base_translation = local_translations
-            .iter()
-            .find(|t| t.language_code == self.config.base_language)
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Base language {} not found in local translations",
-                    self.config.base_language
-                )
-            })?
-            .clone();
+        let mut field = self
+            .fs
+            .create(field_path)
+            .with_context(|| format!("创建文件 {} 失败", field_path.display()))?;
+        let lang_key = format!("{}/languages", self.config.path_prefix);
+        let found =
+            translation::copy_json_field(self.fs.open(download_path)?, &lang_key, &mut *field)
+                .with_context(|| format!("解析 {} 的下载内容失败", lang))?;
+        field.flush()?;
+        Ok(found)
+    }
 
-        // 3. 先处理本地文件的缺失key
-        for translation in &mut local_translations {
+    /// 通过缓存目录中的临时文件下载一种语言，流式取出语言字段后解析，返回语言字段的内容；下载内容中没有
+    /// 语言字段时返回 None。`name` 区分同时进行的下载，临时文件在返回前删除
+    async fn download_language_json(
+        &self,
+        url: &str,
+        lang: &str,
+        name: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        let scratch_dir = self.cache_dir();
+        self.fs
+            .create_dir_all(&scratch_dir)
+            .with_context(|| format!("创建缓存目录 {} 失败", scratch_dir.display()))?;
+        let download_path = scratch_dir.join(format!("{}.download", name));
+        let field_path = scratch_dir.join(format!("{}.json.part", name));
+        let _temp_files = TempFiles {
+            fs: self.fs.as_ref(),
+            paths: [download_path.clone(), field_path.clone()],
+        };
+        if !self
+            .download_language(url, lang, &download_path, &field_path)
+            .await?
+        {
+            return Ok(None);
+        }
+        let value = serde_json::from_reader(self.fs.open(&field_path)?)
+            .with_context(|| format!("解析 {} 的下载内容失败", lang))?;
+        Ok(Some(value))
+    }
+
+    /// 按 `fill_missing` 为非基准语言补全缺失的 key 并保存本地文件
+    fn fill_missing_keys(
+        &self,
+        base_path: &Path,
+        local_translations: &mut [TranslationFile],
+        base_translation: &TranslationFile,
+        fill_missing: FillMissing,
+    ) -> Result<()> {
+        for translation in local_translations {
             // 跳过基准语言
             if translation.language_code == self.config.base_language {
                 continue;
             }
 
             // 获取缺失的键
-            let missing_keys = translation::get_missing_keys(&base_translation, translation);
+            let missing_keys: Content =
+                translation::get_missing_keys(base_translation, translation)
+                    .into_iter()
+                    .map(|(key, base_value)| {
+                        let value = match fill_missing {
+                            FillMissing::Empty => Arc::from(""),
+                            FillMissing::Placeholder => {
+                                Arc::from(self.config.missing_placeholder.as_str())
+                            }
+                            FillMissing::Base => base_value,
+                        };
+                        (key, value)
+                    })
+                    .collect();
             if !missing_keys.is_empty() {
                 tracing::info!(
                     "Found {} missing keys in {} compared to base language {}",
@@ -152,451 +552,2914 @@ impl TranslationService {
                 );
             }
         }
+        Ok(())
+    }
+
+    /// 需要访问服务器的命令在离线模式下直接失败，避免先改动本地文件
+    fn require_online(&self, command: &str) -> Result<()> {
+        if self.config.offline {
+            return Err(AppError::Validation(format!(
+                "{} 命令需要访问服务器，离线模式下无法执行",
+                command
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// 上传本地新增的翻译，返回上传的 key 数量
+    pub async fn push_translations(
+        &self,
+        path: Option<String>,
+        options: PushOptions,
+    ) -> Result<usize> {
+        self.require_online("push")?;
+        if options.resume {
+            return self.resume_push(options).await;
+        }
+        // 1. 读取本地翻译文件
+        let (base_path, mut local_translations) = self.read_local_translations(path)?;
+
+        // 2. 找到基准语言翻译
+        let base_translation = local_translations
+            .iter()
+            .find(|t| t.language_code == self.config.base_language)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Base language {} not found in local translations",
+                    self.config.base_language
+                )
+            })?
+            .clone();
+        self.check_empty_base_values(&base_translation, self.config.empty_base_values.severity())?;
 
-        // 4. 下载当前服务器翻译到缓存
+        // 3. 配置了 fillMissingKeys 时先补全本地文件的缺失key
+        if let Some(fill_missing) = self.config.fill_missing_keys {
+            self.fill_missing_keys(
+                &base_path,
+                &mut local_translations,
+                &base_translation,
+                fill_missing,
+            )?;
+        }
+
+        // 4. 下载当前服务器翻译到缓存，只有基准语言完整读入内存
         tracing::info!("Downloading current translations to cache...");
-        let cached_translations = match self.download_to_cache().await {
-            Ok(translations) => translations,
+        let remote_languages = match self.refresh_cache().await {
+            Ok(languages) => languages,
             Err(e) => {
                 tracing::warn!("Failed to download current translations: {}", e);
-                HashMap::new()
+                HashSet::new()
             }
         };
+        let remote_base = if remote_languages.contains(&self.config.base_language) {
+            let path = self.cached_language_path(&self.config.base_language);
+            Some(TranslationFile::from_content(
+                self.config.base_language.clone(),
+                format!("{}.json", self.config.base_language),
+                self.read_cached_language(&path)?,
+            ))
+        } else {
+            None
+        };
+        let remote_base = remote_base.as_ref();
 
-        // 5. 处理每个翻译文件的上传
-        for local_translation in local_translations {
-            let lang_code = &local_translation.language_code;
-            let full_path = self.get_full_path(&local_translation, &base_path);
+        // 按 placeholderStyles 把本地的占位符转换为服务器使用的 {name}，之后再与服务器比较
+        for translation in &mut local_translations {
+            self.to_server_placeholders(translation, remote_base);
+        }
+        let base_translation = local_translations
+            .iter()
+            .find(|t| t.language_code == self.config.base_language)
+            .cloned()
+            .unwrap_or(base_translation);
 
-            match cached_translations.get(lang_code) {
-                None => {
-                    // 首次上传，上传全部内容
-                    tracing::info!(
-                        "First time upload for language {}, uploading all {} keys",
-                        lang_code,
-                        local_translation.content.len()
-                    );
-                    self.upload_translation(&local_translation, &full_path)
-                        .await?;
-                }
-                Some(cached_translation) => {
-                    let mut need_upload = HashMap::new();
-
-                    // 收集需要上传的键
-                    for (key, local_value) in &local_translation.content {
-                        match cached_translation.content.get(key) {
-                            None => {
-                                // 远程没有的键
-                                need_upload.insert(key.clone(), local_value.clone());
-                                tracing::debug!("New key found: {}", key);
-                            }
-                            Some(remote_value) if remote_value.trim().is_empty() => {
-                                // 远程值为空的键
-                                need_upload.insert(key.clone(), local_value.clone());
-                                tracing::debug!("Empty value key found: {}", key);
-                            }
-                            Some(remote_value) if remote_value != local_value => {
-                                // 值不同的键（仅记录，不上传）
-                                tracing::debug!(
-                                    "Different value for key {}: local='{}', remote='{}'",
-                                    key,
-                                    local_value,
-                                    remote_value
-                                );
-                            }
-                            _ => {}
-                        }
-                    }
+        // 提示与服务器已有文本相似的新 key，避免重复文案不断增加
+        if let Some(remote_base) = remote_base {
+            let new_keys: Content = base_translation
+                .content
+                .iter()
+                .filter(|(key, _)| {
+                    !remote_base.content.contains_key(*key)
+                        && !translation::key_matches_any(&self.config.ignored_keys, key)
+                })
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            let similar = check::find_similar(
+                &new_keys,
+                &remote_base.content,
+                self.config.fuzzy_match_threshold,
+            );
+            check::print_similar(&similar);
+        }
 
-                    if !need_upload.is_empty() {
-                        tracing::info!(
-                            "Uploading {} new/updated keys for language {}",
-                            need_upload.len(),
-                            lang_code
-                        );
+        // 5. 逐个语言流式读取缓存文件与本地比较，内存中只保留有差异的 key
+        for translation in &mut local_translations {
+            self.remove_ignored_keys(translation);
+        }
+        let mut diffs = HashMap::new();
+        for local in &local_translations {
+            if !remote_languages.contains(&local.language_code) {
+                continue;
+            }
+            let path = self.cached_language_path(&local.language_code);
+            let file = self
+                .fs
+                .open(&path)
+                .with_context(|| format!("读取缓存 {} 失败", path.display()))?;
+            let diff = plan::diff_language(local, file)
+                .with_context(|| format!("解析缓存 {} 失败", path.display()))?;
+            diffs.insert(local.language_code.clone(), diff);
+        }
 
-                        // 打印要上传的键值对
-                        for (key, value) in &need_upload {
-                            tracing::info!("  + {}: {}", key, value);
-                        }
+        // 值与服务器不同的键默认只记录，--overwrite-changed 确认后才上传
+        let overwrite_changed = options.overwrite_changed
+            && self.confirm_overwrite_changed(&local_translations, &diffs, options.yes)?;
 
-                        let upload_translation = TranslationFile::from_content(
-                            local_translation.language_code.clone(),
-                            local_translation.relative_path.clone(),
-                            need_upload,
-                        );
-                        self.upload_translation(&upload_translation, &full_path)
-                            .await?;
-                    } else {
-                        tracing::info!("No new keys to upload for language {}", lang_code);
-                    }
+        // 计算每个翻译文件需要上传的内容，首次上传的语言上传全部内容
+        // replace 等命令记录的修改已经确认过，不需要 --overwrite-changed 也会上传
+        let pending = PendingChanges::load(self.fs.as_ref(), &pending::pending_file())?;
+        let plan = plan::plan_push(
+            local_translations,
+            &diffs,
+            |lang, key| overwrite_changed || pending.contains(&self.config, lang, key),
+            |t| self.get_full_path(t, &base_path),
+        );
+        for lang in &plan.unchanged {
+            output::status(Status::Skipped, format!("{}: no new keys to upload", lang));
+        }
+        for upload in &plan.uploads {
+            let delta = &upload.delta;
+            tracing::info!(
+                "Uploading {} new/updated keys for language {}{}",
+                delta.key_count(),
+                delta.language_code,
+                if upload.first_upload {
+                    " (first upload)"
+                } else {
+                    ""
                 }
+            );
+            for (key, value) in delta.added.iter().chain(&delta.changed) {
+                tracing::debug!("  + {}: {}", key, value);
             }
         }
 
-        // 6. 清理缓存目录
-        let cache_dir = PathBuf::from(".i18n-app").join("cache");
-        if cache_dir.exists() {
-            if let Err(e) = std::fs::remove_dir_all(&cache_dir) {
-                tracing::warn!("Failed to clean cache directory: {}", e);
-            } else {
-                tracing::info!("Cache directory cleaned successfully");
-            }
+        // 6. 推送到受保护环境前确认目标
+        let key_count = plan.key_count();
+        if key_count > 0 {
+            self.confirm_protected_push(key_count, options.yes_production)?;
         }
 
-        Ok(())
+        // 7. 先写入上传计划再上传，中断后可以用 --resume 继续；缓存目录保留到下次 push，供离线 diff 使用
+        let journal_path = journal::journal_file();
+        let mut journal = PushJournal::load(self.fs.as_ref(), &journal_path)?;
+        if journal.get(&self.config).is_some() {
+            tracing::warn!("Replacing the unfinished push recorded in the journal");
+        }
+        journal.start(&self.config, plan.uploads);
+        journal.save(self.fs.as_ref(), &journal_path)?;
+        self.run_planned_uploads(journal, &journal_path, options)
+            .await
     }
 
-    fn prepare_cache_dir(&self, cache_dir: &PathBuf) -> Result<()> {
-        if cache_dir.exists() {
-            std::fs::remove_dir_all(cache_dir)?;
+    /// 继续上次中断的 push，只上传上传计划中还没有完成的语言，不重新读取本地文件和比较
+    async fn resume_push(&self, options: PushOptions) -> Result<usize> {
+        let journal_path = journal::journal_file();
+        let journal = PushJournal::load(self.fs.as_ref(), &journal_path)?;
+        let Some(pending) = journal.get(&self.config) else {
+            output::status(
+                Status::Skipped,
+                format!(
+                    "{}: no interrupted push to resume",
+                    self.config.sub_system_name()
+                ),
+            );
+            return Ok(0);
+        };
+        output::status(
+            Status::Ok,
+            format!(
+                "Resuming push started at {}: {} of {} languages remaining",
+                pending.started_at,
+                pending.remaining(),
+                pending.uploads.len()
+            ),
+        );
+        let key_count = pending
+            .uploads
+            .iter()
+            .filter(|u| !u.done)
+            .map(|u| u.delta.key_count())
+            .sum();
+        if key_count > 0 {
+            self.confirm_protected_push(key_count, options.yes_production)?;
         }
-        std::fs::create_dir_all(cache_dir)?;
-        Ok(())
+        self.run_planned_uploads(journal, &journal_path, options)
+            .await
     }
 
-    fn read_local_translations(
+    /// 上传计划中没有完成的语言，同时最多上传 uploadConcurrency 种语言，每种语言成功后立即记录，
+    /// 全部成功后删除计划。某种语言失败时默认继续上传其余语言，最后汇总；
+    /// 提前返回时丢弃还在进行的上传，已完成的语言仍记录在计划中
+    async fn run_planned_uploads(
         &self,
-        path: Option<String>,
-    ) -> Result<(PathBuf, Vec<TranslationFile>)> {
-        let include_patterns: Vec<String> = if path.is_some() {
-            self.config
-                .include
-                .iter()
-                .map(|p| p.replace("fixtures/", ""))
-                .collect()
-        } else {
-            self.config.include.clone()
-        };
+        mut journal: PushJournal,
+        journal_path: &Path,
+        options: PushOptions,
+    ) -> Result<usize> {
+        let pending: Vec<(usize, PlannedUpload)> = journal
+            .get(&self.config)
+            .map(|pending| pending.uploads.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, upload)| !upload.done)
+            .collect();
+        let mut results = stream::iter(pending)
+            .map(|(index, upload)| async move {
+                let result = if upload.first_upload {
+                    self.backend
+                        .upload_terms(&upload.delta.to_translation_file())
+                        .await
+                } else {
+                    self.backend.upload_delta(&upload.delta).await
+                };
+                (index, upload, result)
+            })
+            .buffer_unordered(self.config.upload_concurrency.max(1));
 
-        let base_path = path
-            .map(PathBuf::from)
-            .unwrap_or_else(|| PathBuf::from("."));
+        let mut outcomes: Vec<UploadOutcome> = Vec::new();
+        let mut last_error = None;
+        // 取消时丢弃正在进行的上传，上传计划中只记录已经完成的语言，之后可以用 --resume 继续
+        while let Some((index, upload, result)) = self.cancellable(results.next()).await? {
+            // 认证失败或离线时其余语言也不可能成功
+            let fatal = result.as_ref().is_err_and(ApiError::is_fatal);
+            let translation = upload.delta.to_translation_file();
+            let error = match self.report_upload(&translation, &upload.full_path, result) {
+                Ok(()) => {
+                    journal.mark_done(&self.config, index);
+                    journal.save(self.fs.as_ref(), journal_path)?;
+                    None
+                }
+                Err(e) if options.fail_fast || fatal => return Err(e),
+                Err(e) => {
+                    let message = e.to_string();
+                    last_error = Some(e);
+                    Some(message)
+                }
+            };
+            outcomes.push(UploadOutcome {
+                lang: translation.language_code,
+                keys: translation.content.len(),
+                error,
+            });
+        }
+        outcomes.sort_by(|a, b| a.lang.cmp(&b.lang));
+        print_upload_summary(&outcomes);
 
-        let local_translations =
-            read_translation_files(&base_path, &include_patterns, &self.config.exclude)?;
+        match last_error {
+            Some(e) => {
+                let failed = outcomes.iter().filter(|o| o.error.is_some()).count();
+                Err(e.context(format!(
+                    "{} of {} languages failed to push",
+                    failed,
+                    outcomes.len()
+                )))
+            }
+            None => {
+                journal.finish(&self.config);
+                journal.save(self.fs.as_ref(), journal_path)?;
+                // 全部成功后清除这个目标的待上传修改，部分失败时保留，下次 push 继续上传
+                let pending_path = pending::pending_file();
+                let mut pending = PendingChanges::load(self.fs.as_ref(), &pending_path)?;
+                pending.clear(&self.config);
+                pending.save(self.fs.as_ref(), &pending_path)?;
+                Ok(outcomes.iter().map(|o| o.keys).sum())
+            }
+        }
+    }
 
-        if local_translations.is_empty() {
-            tracing::warn!(
-                "No translation files found matching patterns: {:?}",
-                include_patterns
-            );
-        } else {
-            tracing::info!("Found {} local translation files", local_translations.len());
+    /// 把服务器上 `source`（同一子系统的另一个版本）的翻译复制到当前版本，只上传值不同的 key，
+    /// `keys` 不为空时只复制匹配的 key。返回每种语言需要复制的 key 数量，`dry_run` 时不上传
+    pub async fn promote_from(
+        &self,
+        source: &TranslationService,
+        keys: &[String],
+        dry_run: bool,
+        yes_production: bool,
+    ) -> Result<Vec<(String, usize)>> {
+        self.require_online("promote")?;
+        let from = &source.config.version_no;
+        let (source_translations, _) = source.fetch_translations(None).await?;
+        if source_translations.is_empty() {
+            return Err(AppError::Validation(format!("版本 {} 在服务器上没有翻译", from)).into());
         }
+        let (target_translations, _) = self.fetch_translations(None).await?;
 
-        Ok((base_path, local_translations))
-    }
+        let mut languages: Vec<&String> = source_translations.keys().collect();
+        languages.sort();
+        let mut uploads: Vec<(TranslationFile, Option<&TranslationFile>)> = Vec::new();
+        for lang in languages {
+            let target = target_translations.get(lang);
+            let content: Content = source_translations[lang]
+                .content
+                .iter()
+                .filter(|(key, value)| {
+                    (keys.is_empty() || translation::key_matches_any(keys, key))
+                        && !value.is_empty()
+                        && target.and_then(|t| t.content.get(*key)) != Some(*value)
+                })
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            let mut file =
+                TranslationFile::from_content(lang.clone(), format!("{}.json", lang), content);
+            self.remove_ignored_keys(&mut file);
+            if file.content.is_empty() {
+                output::status(Status::Skipped, format!("{}: nothing to promote", lang));
+                continue;
+            }
+            uploads.push((file, target));
+        }
 
-    fn get_full_path(&self, translation: &TranslationFile, base_path: &Path) -> String {
-        if translation.relative_path.starts_with("fixtures/") {
-            translation.relative_path.clone()
-        } else {
-            format!("{}/{}", base_path.display(), translation.relative_path)
+        let summary: Vec<(String, usize)> = uploads
+            .iter()
+            .map(|(file, _)| (file.language_code.clone(), file.content.len()))
+            .collect();
+        let key_count = summary.iter().map(|(_, count)| count).sum();
+        if dry_run || key_count == 0 {
+            return Ok(summary);
+        }
+        self.confirm_protected_push(key_count, yes_production)?;
+
+        let label = format!("{} -> {}", from, self.config.version_no);
+        for (file, target) in uploads {
+            let result = match target {
+                Some(target) => {
+                    let delta = TranslationDelta::new(&file, target);
+                    self.cancellable(self.backend.upload_delta(&delta)).await?
+                }
+                None => self.cancellable(self.backend.upload_terms(&file)).await?,
+            };
+            self.report_upload(&file, &label, result)?;
         }
+        Ok(summary)
     }
 
-    async fn upload_translation(
+    /// 按 placeholderStyles 把本地文件的占位符转换为服务器使用的 `{name}`，
+    /// 位置占位符按服务器上基准语言文本中占位符的顺序还原名称
+    fn to_server_placeholders(
         &self,
-        translation: &TranslationFile,
-        full_path: &str,
-    ) -> Result<()> {
-        if let Err(e) = api::upload_translation(&self.config, translation).await {
-            tracing::error!("Failed to push {}: {}", full_path, e);
-            Err(e)
-        } else {
-            tracing::info!("Push {} success 🎉🎉🎉", full_path);
-            Ok(())
+        translation: &mut TranslationFile,
+        remote_base: Option<&TranslationFile>,
+    ) {
+        let style = self.config.placeholder_style(&translation.relative_path);
+        if style == PlaceholderStyle::Braces {
+            return;
+        }
+        for (key, value) in translation.content.iter_mut() {
+            let names = remote_base
+                .and_then(|base| base.content.get(key))
+                .map(|base_value| placeholder::server_names(base_value))
+                .unwrap_or_default();
+            *value = placeholder::to_server(value, style, &names).into();
         }
     }
 
-    pub async fn download_translations(&self, path: Option<String>) -> Result<()> {
-        let target_dir = path
-            .map(PathBuf::from)
-            .unwrap_or_else(|| PathBuf::from(".i18n-app").join("preview"));
+    /// 按 placeholderStyles 把服务器的 `{name}` 转换为本地文件使用的语法，
+    /// 位置占位符按服务器上基准语言文本中占位符的顺序编号
+    fn to_local_placeholders(
+        &self,
+        relative_path: &str,
+        value: &serde_json::Value,
+        remote_base: &HashMap<String, String>,
+    ) -> serde_json::Value {
+        let style = self.config.placeholder_style(relative_path);
+        if style == PlaceholderStyle::Braces {
+            return value.clone();
+        }
+        placeholder::convert_json(value, &|key, text| {
+            let names = placeholder::server_names(remote_base.get(key).map_or(text, |v| v));
+            placeholder::to_local(text, style, &names)
+        })
+    }
 
-        if target_dir.exists() {
-            tracing::info!("Cleaning target directory: {}", target_dir.display());
-            std::fs::remove_dir_all(&target_dir)?;
+    /// 服务器上基准语言的翻译，只在配置了位置占位符语法时下载，用于统一各语言的参数编号
+    async fn remote_base_for_placeholders(
+        &self,
+        files: &[api::FileDownloadInfo],
+    ) -> HashMap<String, String> {
+        let positional = self
+            .config
+            .placeholder_styles
+            .iter()
+            .any(|rule| rule.style.is_positional());
+        let Some(file) = files
+            .iter()
+            .find(|f| positional && f.lang == self.config.base_language)
+        else {
+            return HashMap::new();
+        };
+        let content = async {
+            let json = self
+                .cancellable(self.download_language_json(&file.url, &file.lang, &file.lang))
+                .await??;
+            Ok::<_, anyhow::Error>(
+                json.as_ref()
+                    .map(translation::flatten_json)
+                    .unwrap_or_default(),
+            )
+        };
+        content.await.unwrap_or_else(|e| {
+            tracing::warn!("下载基准语言翻译失败，位置占位符按各语言文本编号: {}", e);
+            HashMap::new()
+        })
+    }
+
+    /// 推送到 protectedEnvironments 中的环境前显示目标并请求确认，--yes-production 时跳过
+    fn confirm_protected_push(&self, key_count: usize, yes_production: bool) -> Result<()> {
+        let Some(env) = self.config.protected_environment() else {
+            return Ok(());
+        };
+        if yes_production {
+            tracing::warn!("推送 {} 个 key 到受保护环境 {}", key_count, env);
+            return Ok(());
+        }
+
+        output::table(
+            [
+                "Environment",
+                "Host",
+                "Product",
+                "Version",
+                "Sub System",
+                "Keys",
+            ],
+            [vec![
+                env.to_string(),
+                self.config.host.clone(),
+                self.config.product_code.clone(),
+                self.config.version_no.clone(),
+                self.config.sub_system_name().to_string(),
+                key_count.to_string(),
+            ]],
+        );
+        if workspace::confirm(&format!(
+            "确认推送 {} 个 key 到受保护环境 {}？",
+            key_count, env
+        ))? {
+            Ok(())
+        } else {
+            Err(AppError::Validation(format!(
+                "未确认推送到受保护环境 {}，已取消（非交互环境请使用 --yes-production）",
+                env
+            ))
+            .into())
+        }
+    }
+
+    /// 列出本地值与服务器不同的键并请求确认（--yes 时跳过），返回是否覆盖
+    fn confirm_overwrite_changed(
+        &self,
+        local_translations: &[TranslationFile],
+        diffs: &HashMap<String, LanguageDiff>,
+        yes: bool,
+    ) -> Result<bool> {
+        let mut rows = Vec::new();
+        for local in local_translations {
+            let Some(diff) = diffs.get(&local.language_code) else {
+                continue;
+            };
+            for entry in &diff.changed {
+                rows.push(vec![
+                    local.language_code.clone(),
+                    entry.key.to_string(),
+                    entry.remote.clone(),
+                    local.content[&entry.key].to_string(),
+                ]);
+            }
+        }
+
+        if rows.is_empty() {
+            tracing::info!("No changed values to overwrite");
+            return Ok(false);
+        }
+
+        let count = rows.len();
+        output::table(["Language", "Key", "Remote", "Local"], rows);
+        let confirmed =
+            yes || workspace::confirm(&format!("用本地值覆盖服务器上以上 {} 个 key？", count))?;
+        if !confirmed {
+            tracing::warn!("未确认覆盖，值不同的 key 不会上传");
+        }
+        Ok(confirmed)
+    }
+
+    fn prepare_cache_dir(&self, cache_dir: &Path) -> Result<()> {
+        workspace::remove_dir_safely_with(self.fs.as_ref(), cache_dir)?;
+        self.fs.create_dir_all(cache_dir)?;
+        Ok(())
+    }
+
+    fn read_local_translations(
+        &self,
+        path: Option<String>,
+    ) -> Result<(PathBuf, Vec<TranslationFile>)> {
+        let base_path = self.base_path(path);
+        let include_patterns = &self.config.include;
+
+        let mut local_translations =
+            read_translation_files(&base_path, include_patterns, &self.config.exclude)?;
+        if self.config.normalize_nfc {
+            local_translations
+                .iter_mut()
+                .for_each(TranslationFile::normalize_nfc);
+        }
+
+        if local_translations.is_empty() {
+            tracing::warn!(
+                "No translation files found matching patterns: {:?}",
+                include_patterns
+            );
+        } else {
+            tracing::info!("Found {} local translation files", local_translations.len());
+        }
+
+        Ok((base_path, local_translations))
+    }
+
+    /// 翻译文件所在的目录：命令行 `--path` 优先，其次是配置的 basePath，默认是当前目录
+    fn base_path(&self, path: Option<String>) -> PathBuf {
+        path.or_else(|| self.config.base_path.clone())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    fn get_full_path(&self, translation: &TranslationFile, base_path: &Path) -> String {
+        base_path
+            .join(&translation.relative_path)
+            .display()
+            .to_string()
+    }
+
+    /// 按 emptyBaseValues 处理基准语言中的空文本，避免空文本被上传后传播到所有语言
+    fn check_empty_base_values(&self, base: &TranslationFile, severity: Severity) -> Result<()> {
+        let config = LintConfig {
+            rules: HashMap::from([("empty-values".to_string(), severity)]),
+            ..Default::default()
+        };
+        let empty = lint::run(
+            &lint::LintContext {
+                config: &config,
+                base,
+                translations: &[],
+                identical_allowlist: None,
+            },
+            &["empty-values"],
+        )?;
+        if empty.is_empty() || lint::print(&empty) == 0 {
+            return Ok(());
+        }
+        let preview = empty
+            .iter()
+            .take(5)
+            .map(|d| d.key.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let more = if empty.len() > 5 { ", ..." } else { "" };
+        Err(AppError::Validation(format!(
+            "基准语言 {} 中有 {} 个 key 的文本为空: {}{}（可在配置中设置 emptyBaseValues 为 warn 或 allow）",
+            base.language_code,
+            empty.len(),
+            preview,
+            more
+        ))
+        .into())
+    }
+
+    /// 输出并记录一种语言的上传结果
+    fn report_upload(
+        &self,
+        translation: &TranslationFile,
+        full_path: &str,
+        result: ApiResult<UploadSummary>,
+    ) -> Result<()> {
+        let lang = translation.language_code.as_str();
+        match result {
+            Err(e) => {
+                tracing::error!("Failed to push {}: {}", full_path, e);
+                output::status(Status::Failed, format!("{}: push failed: {}", lang, e));
+                Err(e.into())
+            }
+            Ok(summary) => {
+                history::record_uploaded(summary.uploaded_keys);
+                history::record_language(lang);
+                audit::record(self.fs.as_ref(), &self.config, translation);
+                tracing::info!("Push {} success 🎉🎉🎉", full_path);
+                output::status(
+                    Status::Ok,
+                    format!(
+                        "{}: uploaded {} keys ({})",
+                        lang, summary.uploaded_keys, full_path
+                    ),
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// 下载目标是工作目录之外的已有目录时，经用户确认（或 --yes）才清空，
+    /// 包含非 JSON 文件的目录不会被清空，未确认时直接写入该目录
+    fn confirm_clear_user_dir(target_dir: &Path, yes: bool) -> Result<()> {
+        if std::fs::read_dir(target_dir)?.next().is_none() {
+            return Ok(());
+        }
+        if let Some(file) = workspace::find_non_json_file(target_dir)? {
+            tracing::warn!(
+                "{} 中包含非 JSON 文件 {}，不会清空该目录，下载的文件将直接写入",
+                target_dir.display(),
+                file.display()
+            );
+            return Ok(());
+        }
+
+        let confirmed = yes
+            || workspace::confirm(&format!(
+                "目录 {} 已存在，是否清空后再下载？",
+                target_dir.display()
+            ))?;
+        if confirmed {
+            tracing::info!("Cleaning target directory: {}", target_dir.display());
+            workspace::remove_user_dir(target_dir)?;
+        } else {
+            tracing::info!(
+                "保留 {} 中的现有文件，下载的文件将直接写入",
+                target_dir.display()
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn download_translations(&self, path: Option<String>, yes: bool) -> Result<()> {
+        self.require_online("download")?;
+        let schema = TranslationSchema::load(&self.config)?;
+        let target_dir = path
+            .map(PathBuf::from)
+            .unwrap_or_else(workspace::preview_dir);
+
+        // 只自动清空工作目录内的预览目录，用户指定的其他目录直接写入，不做删除
+        if workspace::is_inside(&target_dir, &workspace::work_dir()) {
+            tracing::info!("Cleaning target directory: {}", target_dir.display());
+            workspace::remove_dir_safely_with(self.fs.as_ref(), &target_dir)?;
+        } else if target_dir.exists() {
+            Self::confirm_clear_user_dir(&target_dir, yes)?;
+        }
+        self.fs.create_dir_all(&target_dir)?;
+
+        let name_template = self
+            .config
+            .name_template
+            .as_deref()
+            .unwrap_or(translation::DEFAULT_NAME_TEMPLATE);
+
+        tracing::info!("Fetching translation configuration...");
+        let config_response = self.backend.fetch_config().await?;
+
+        let mut success_count = 0;
+        let mut failed_count = 0;
+        let mut invalid_languages = Vec::new();
+
+        if let Some(files_to_download) = config_response.data.files {
+            for file_info in files_to_download {
+                match self
+                    .cancellable(self.download_language_json(
+                        &file_info.url,
+                        &file_info.lang,
+                        &file_info.lang,
+                    ))
+                    .await?
+                {
+                    Ok(Some(lang_specific_json_value)) => {
+                        if let Some(Err(e)) = schema.as_ref().map(|schema| {
+                            schema.validate(&file_info.lang, &lang_specific_json_value)
+                        }) {
+                            output::status(Status::Failed, format!("{}: {}", file_info.lang, e));
+                            invalid_languages.push(file_info.lang.clone());
+                            failed_count += 1;
+                            continue;
+                        }
+                        history::record_downloaded(count_keys(&lang_specific_json_value));
+                        let files = translation::apply_name_template(
+                            name_template,
+                            &file_info.lang,
+                            self.config.sub_system_name(),
+                            &self.normalize_key_style(&lang_specific_json_value)?,
+                        )
+                        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+                        // 按命名模板把提取出的 lang_specific_json_value 写入文件
+                        for (relative_path, content) in files {
+                            let target_file = target_dir.join(relative_path);
+                            if let Some(parent) = target_file.parent() {
+                                self.fs.create_dir_all(parent)?;
+                            }
+                            let content = self.apply_key_style(content)?;
+                            let content_to_write = self.to_json(&content)?;
+                            self.fs.write(&target_file, content_to_write.as_bytes())?;
+
+                            tracing::info!(
+                                "Downloaded translation for {} to {}",
+                                file_info.lang,
+                                target_file.display()
+                            );
+                        }
+                        success_count += 1;
+                    }
+                    Ok(None) => {
+                        tracing::error!(
+                            "Key '{}/languages' not found in downloaded content for language: {}",
+                            self.config.path_prefix,
+                            file_info.lang
+                        );
+                        failed_count += 1;
+                    }
+                    Err(e) if !is_skippable_download_error(&e) => return Err(e),
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to download translation for {}: {}",
+                            file_info.lang,
+                            e
+                        );
+                        failed_count += 1;
+                    }
+                }
+            }
+        } else {
+            tracing::error!("No files found in the configuration response");
+            return Ok(());
+        }
+
+        tracing::info!(
+            "Download completed: {} succeeded, {} failed, {} total",
+            success_count,
+            failed_count,
+            success_count + failed_count
+        );
+
+        if !invalid_languages.is_empty() {
+            return Err(AppError::Validation(format!(
+                "{} 种语言的服务器翻译不符合 {}，未写入：{}",
+                invalid_languages.len(),
+                self.config
+                    .translation_schema
+                    .as_deref()
+                    .unwrap_or_default(),
+                invalid_languages.join(", ")
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// 同步翻译文件（从服务器同步到本地）
+    pub async fn sync_translations(&self, options: PullOptions) -> Result<()> {
+        self.require_online("pull")?;
+        let schema = TranslationSchema::load(&self.config)?;
+        tracing::info!("正在下载最新翻译...");
+        // 上次完整同步的 taskHash，服务器据此判断是否有变化
+        let known_hash = if options.force {
+            None
+        } else {
+            self.sync_entry()?.task_hash
+        };
+        let config_response = self
+            .backend
+            .fetch_config_since(known_hash.as_deref())
+            .await
+            .context("获取翻译配置失败")?;
+
+        if known_hash.is_some() && config_response.data.task_hash == known_hash {
+            tracing::info!("服务器翻译自上次 pull 以来没有变化（taskHash 相同），跳过下载");
+            output::status(
+                Status::Skipped,
+                format!(
+                    "{}: no changes on server since last pull",
+                    self.config.sub_system_name()
+                ),
+            );
+            if options.materialize_fallbacks {
+                self.materialize_fallbacks()?;
+            }
+            return Ok(());
+        }
+
+        let files_to_download = config_response
+            .data
+            .files
+            .as_ref()
+            .and_then(|files| if files.is_empty() { None } else { Some(files) })
+            .with_context(|| {
+                format!(
+                    "未找到任何翻译文件。系统名称: '{}', 产品代码: '{}'",
+                    self.config.sub_system_name(),
+                    self.config.product_code
+                )
+            })?;
+
+        let (base_path, local_files) = self
+            .read_local_translations(None)
+            .context("读取本地翻译文件失败")?;
+
+        ensure!(
+            !local_files.is_empty(),
+            format!(
+                "未找到任何本地翻译文件。include 设置: {:?}",
+                self.config.include
+            )
+        );
+
+        let mut success_count = 0;
+        let mut failed_count = 0;
+        let mut conflict_files = Vec::new();
+        let mut invalid_languages = Vec::new();
+        let remote_base = self.remote_base_for_placeholders(files_to_download).await;
+
+        // 新语言的文件按基准语言文件的位置创建
+        let create_missing = options.create_missing || self.config.create_missing_languages;
+        let base_relative_path = local_files
+            .iter()
+            .find(|f| f.language_code == self.config.base_language)
+            .map(|f| f.relative_path.clone());
+        if create_missing && base_relative_path.is_none() {
+            tracing::warn!(
+                "未找到基准语言 {} 的本地文件，无法为远程独有的语言创建文件",
+                self.config.base_language
+            );
+        }
+        let create_path = base_relative_path
+            .filter(|_| create_missing)
+            .map(|base| move |lang: &str| missing_language_path(&self.config.include, &base, lang));
+        let plan = plan::plan_pull(
+            &local_files,
+            files_to_download,
+            create_path.as_ref().map(|f| f as &dyn Fn(&str) -> PathBuf),
+        );
+
+        for lang_code in &plan.missing_remote {
+            tracing::warn!("未找到语言 {} 的远程翻译，跳过同步", lang_code);
+            output::status(
+                Status::Skipped,
+                format!("{}: no remote translation", lang_code),
+            );
+        }
+        // 还没有 push 过的本地语言不算失败，不影响保存 taskHash
+        let skipped_count = plan.missing_remote.len();
+        if !plan.remote_only.is_empty() && !create_missing {
+            tracing::info!(
+                "远程存在本地没有的语言: {}，可使用 pull --create-missing 创建",
+                plan.remote_only.join(", ")
+            );
+        }
+
+        self.run_pull_plan(plan.actions, &mut |action, result| {
+            let remote_lang_json = match result {
+                Ok(Some(json)) => json,
+                Ok(None) => {
+                    let lang_key = format!("{}/languages", self.config.path_prefix);
+                    tracing::error!(
+                        "Key '{}' not found in downloaded content for language: {}",
+                        lang_key,
+                        action.lang()
+                    );
+                    output::status(
+                        Status::Failed,
+                        format!(
+                            "{}: '{}' not found in remote content",
+                            action.lang(),
+                            lang_key
+                        ),
+                    );
+                    failed_count += 1;
+                    return Ok(());
+                }
+                Err(e) if !is_skippable_download_error(&e) => return Err(e),
+                Err(e) => {
+                    let lang_code = action.lang();
+                    tracing::error!("下载语言 {} 的翻译失败: {:#}", lang_code, e);
+                    output::status(
+                        Status::Failed,
+                        format!("{}: download failed: {:#}", lang_code, e),
+                    );
+                    failed_count += 1;
+                    return Ok(());
+                }
+            };
+            match action {
+                PullAction::Merge {
+                    lang,
+                    relative_path,
+                    ..
+                } => {
+                    let lang_code = &lang;
+                    let target_path = base_path.join(&relative_path);
+                    tracing::info!("正在同步 {} 到 {}", lang_code, target_path.display());
+
+                    // 不符合 schema 的内容不写入缓存和本地文件
+                    if let Some(Err(e)) = schema
+                        .as_ref()
+                        .map(|schema| schema.validate(lang_code, &remote_lang_json))
+                    {
+                        output::status(Status::Failed, format!("{}: {}", lang_code, e));
+                        invalid_languages.push(lang_code.clone());
+                        failed_count += 1;
+                        return Ok(());
+                    }
+                    history::record_downloaded(count_keys(&remote_lang_json));
+                    history::record_language(lang_code);
+                    let local_json = translation::read_json_file(&target_path)
+                        .with_context(|| format!("读取本地文件 {} 失败", target_path.display()))?;
+                    if let Err(e) = self.write_cache_file(
+                        lang_code,
+                        &remote_lang_json,
+                        config_response.data.task_hash.as_deref(),
+                    ) {
+                        tracing::warn!("Failed to cache translation for {}: {}", lang_code, e);
+                    }
+
+                    let local_json = self.normalize_key_style(&local_json)?;
+                    let remote_json = self.to_local_placeholders(
+                        &relative_path,
+                        &self.normalize_key_style(&remote_lang_json)?,
+                        &remote_base,
+                    );
+                    let remote_lang_specific_json =
+                        &self.skip_protected_keys(lang_code, &local_json, &remote_json);
+                    self.print_json_diff(&local_json, remote_lang_specific_json, lang_code);
+
+                    let merge_result = self.merge_with_snapshot(
+                        lang_code,
+                        &local_json,
+                        remote_lang_specific_json,
+                        options.interactive,
+                    )?;
+
+                    // 有未解决的冲突时不写入该文件，冲突写入 <lang>.conflicts.json
+                    let conflicts_path = conflicts_file(&target_path, lang_code);
+                    if !merge_result.conflicts.is_empty() {
+                        let conflicts = serde_json::to_string_pretty(&merge::conflicts_to_json(
+                            &merge_result.conflicts,
+                        ))?;
+                        self.fs
+                            .write(&conflicts_path, conflicts.as_bytes())
+                            .with_context(|| {
+                                format!("写入冲突文件 {} 失败", conflicts_path.display())
+                            })?;
+                        output::status(
+                            Status::Failed,
+                            format!(
+                                "{}: {} conflict(s), not written, see {}",
+                                lang_code,
+                                merge_result.conflicts.len(),
+                                conflicts_path.display()
+                            ),
+                        );
+                        conflict_files.push(conflicts_path);
+                        return Ok(());
+                    }
+                    if self.fs.exists(&conflicts_path) {
+                        self.fs.remove_file(&conflicts_path).with_context(|| {
+                            format!("删除冲突文件 {} 失败", conflicts_path.display())
+                        })?;
+                    }
+
+                    self.write_language_json(&merge_result.merged, &target_path)?;
+
+                    if let Err(e) = self.write_snapshot(lang_code, &remote_json) {
+                        tracing::warn!("Failed to save sync snapshot for {}: {}", lang_code, e);
+                    }
+
+                    tracing::info!("成功同步 {}", target_path.display());
+                    output::status(
+                        Status::Ok,
+                        format!("{}: synced to {}", lang_code, target_path.display()),
+                    );
+                    success_count += 1;
+                }
+                PullAction::Create {
+                    lang,
+                    relative_path,
+                    ..
+                } => {
+                    let target_path = base_path.join(&relative_path);
+                    match self.create_language_file(
+                        &lang,
+                        &remote_lang_json,
+                        &relative_path,
+                        &target_path,
+                        &remote_base,
+                        schema.as_ref(),
+                    ) {
+                        Ok(()) => {
+                            output::status(
+                                Status::Ok,
+                                format!("{}: created {}", lang, target_path.display()),
+                            );
+                            success_count += 1;
+                        }
+                        Err(e) => {
+                            tracing::error!("创建语言 {} 的本地文件失败: {}", lang, e);
+                            output::status(
+                                Status::Failed,
+                                format!("{}: create failed: {}", lang, e),
+                            );
+                            failed_count += 1;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })
+        .await?;
+
+        if options.materialize_fallbacks {
+            self.materialize_fallbacks()?;
+        }
+
+        if !invalid_languages.is_empty() {
+            return Err(AppError::Validation(format!(
+                "{} 种语言的服务器翻译不符合 {}，未写入：{}",
+                invalid_languages.len(),
+                self.config
+                    .translation_schema
+                    .as_deref()
+                    .unwrap_or_default(),
+                invalid_languages.join(", ")
+            ))
+            .into());
+        }
+
+        if !conflict_files.is_empty() {
+            return Err(AppError::Conflicts(format!(
+                "{} 个文件存在未解决的冲突，未写入。请查看 {}，使用 pull --interactive 逐个选择保留本地值或采用服务器的值",
+                conflict_files.len(),
+                conflict_files
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .into());
+        }
+
+        ensure!(
+            success_count > 0,
+            format!(
+                "同步失败：成功 {} 个，失败 {} 个",
+                success_count, failed_count
+            )
+        );
+
+        tracing::info!(
+            "同步完成: {} 个成功, {} 个失败, {} 个跳过, 共 {} 个文件",
+            success_count,
+            failed_count,
+            skipped_count,
+            success_count + failed_count + skipped_count
+        );
+
+        // 有语言同步失败时不保存 taskHash，下次 pull 仍然完整下载
+        let task_hash = config_response.data.task_hash.filter(|_| failed_count == 0);
+        if let Err(e) = self.record_sync(task_hash) {
+            tracing::warn!("Failed to save sync state: {}", e);
+        }
+        Ok(())
+    }
+
+    /// 按 pull 计划下载服务器翻译，同时最多下载 uploadConcurrency 种语言，并按计划的顺序依次交给
+    /// `apply` 写入本地文件。`apply` 返回错误或收到取消信号时丢弃还在进行的下载
+    async fn run_pull_plan(
+        &self,
+        actions: Vec<PullAction>,
+        apply: &mut dyn FnMut(PullAction, Result<Option<serde_json::Value>>) -> Result<()>,
+    ) -> Result<()> {
+        // 并发下载时用序号区分临时文件，同一语言可能对应多个本地文件
+        let mut downloads = stream::iter(actions.into_iter().enumerate())
+            .map(|(index, action)| async move {
+                let name = format!("{}.{}", action.lang(), index);
+                let result = self
+                    .download_language_json(action.url(), action.lang(), &name)
+                    .await;
+                (action, result)
+            })
+            .buffered(self.config.upload_concurrency.max(1));
+        while let Some((action, result)) = self.cancellable(downloads.next()).await? {
+            apply(action, result)?;
+        }
+        Ok(())
+    }
+
+    /// 上次完整同步时服务器返回的 taskHash
+    pub fn synced_task_hash(&self) -> Result<Option<String>> {
+        Ok(self.sync_entry()?.task_hash)
+    }
+
+    /// 带 `known_hash` 请求服务器当前的文件列表和 taskHash，不下载文件
+    pub async fn poll_remote(&self, known_hash: Option<&str>) -> Result<LongPollingData> {
+        self.require_online("daemon")?;
+        Ok(self.backend.fetch_config_since(known_hash).await?.data)
+    }
+
+    fn sync_entry(&self) -> Result<SyncEntry> {
+        Ok(SyncState::load(self.fs.as_ref(), &sync_state::sync_state_file())?.get(&self.config))
+    }
+
+    /// 记录本次成功 pull 的时间和 taskHash，changes 命令只显示这之后的变化
+    fn record_sync(&self, task_hash: Option<String>) -> Result<()> {
+        let path = sync_state::sync_state_file();
+        let mut state = SyncState::load(self.fs.as_ref(), &path)?;
+        let entry = state.entry_mut(&self.config);
+        entry.last_sync = Some(chrono::Local::now().to_rfc3339());
+        entry.task_hash = task_hash;
+        state.save(self.fs.as_ref(), &path)
+    }
+
+    /// 列出服务器报告的有变化的 term；服务器提供修改时间时只显示最近一次 pull 之后的变化
+    pub async fn changes(&self) -> Result<()> {
+        self.require_online("changes")?;
+        let entry = self.sync_entry()?;
+        let response = self
+            .backend
+            .fetch_config_since(entry.task_hash.as_deref())
+            .await?;
+        let last_sync = entry
+            .last_sync
+            .and_then(|time| DateTime::parse_from_rfc3339(&time).ok());
+        if last_sync.is_none() {
+            tracing::info!("尚未成功 pull 过，显示服务器报告的所有变化");
+        }
+
+        let sub_system = self.config.sub_system_name();
+        let mut terms: Vec<(&ChangedTerm, Option<DateTime<FixedOffset>>)> = response
+            .data
+            .change_terms
+            .iter()
+            .filter(|term| term.sub_system.as_deref().is_none_or(|s| s == sub_system))
+            .map(|term| (term, term.changed_at.as_ref().and_then(parse_change_time)))
+            .filter(|(_, changed_at)| match (last_sync, changed_at) {
+                (Some(last_sync), Some(changed_at)) => *changed_at > last_sync,
+                _ => true,
+            })
+            .collect();
+        if terms.is_empty() {
+            output::status(
+                Status::Skipped,
+                format!("{}: no server-side changes since last sync", sub_system),
+            );
+            return Ok(());
+        }
+
+        terms.sort_by(|(a, _), (b, _)| (&a.term, &a.language).cmp(&(&b.term, &b.language)));
+        let count = terms.len();
+        let rows = terms.into_iter().map(|(term, changed_at)| {
+            vec![
+                term.term.clone(),
+                term.language.clone().unwrap_or_default(),
+                term.changed_by.clone().unwrap_or_default(),
+                match (changed_at, &term.changed_at) {
+                    (Some(time), _) => time.to_rfc3339(),
+                    (None, Some(serde_json::Value::String(raw))) => raw.clone(),
+                    (None, Some(raw)) => raw.to_string(),
+                    (None, None) => String::new(),
+                },
+            ]
+        });
+        output::table(["Term", "Language", "Changed By", "Changed At"], rows);
+        output::status(
+            Status::Ok,
+            format!("{}: {} changed terms", sub_system, count),
+        );
+        Ok(())
+    }
+
+    /// 按回退链补全一种语言的翻译，`files` 为可用的各语言翻译，没有基准语言时不补全
+    fn resolve_fallbacks(
+        &self,
+        file: &TranslationFile,
+        files: &HashMap<&str, &TranslationFile>,
+    ) -> TranslationFile {
+        let Some(base) = files.get(self.config.base_language.as_str()) else {
+            return file.clone();
+        };
+        let chain: Vec<&TranslationFile> = self
+            .config
+            .fallback_chain(&file.language_code)
+            .iter()
+            .filter_map(|lang| files.get(lang.as_str()).copied())
+            .collect();
+        translation::with_fallbacks(file, base, &chain)
+    }
+
+    /// 把回退链中的翻译写入本地文件中缺失或为空的 key，有未解决冲突的语言跳过
+    fn materialize_fallbacks(&self) -> Result<()> {
+        let (base_path, local_files) = self.read_local_translations(None)?;
+        let files_by_lang: HashMap<&str, &TranslationFile> = local_files
+            .iter()
+            .map(|f| (f.language_code.as_str(), f))
+            .collect();
+
+        for file in &local_files {
+            let chain = self.config.fallback_chain(&file.language_code);
+            if chain.is_empty() {
+                continue;
+            }
+            let target_path = base_path.join(&file.relative_path);
+            if self
+                .fs
+                .exists(&conflicts_file(&target_path, &file.language_code))
+            {
+                continue;
+            }
+
+            let resolved = self.resolve_fallbacks(file, &files_by_lang);
+            let filled = resolved
+                .content
+                .iter()
+                .filter(|(key, value)| file.content.get(*key) != Some(*value))
+                .count();
+            if filled == 0 {
+                continue;
+            }
+            self.save_translation_file(&resolved, &target_path)?;
+            output::status(
+                Status::Ok,
+                format!(
+                    "{}: filled {} keys from fallbacks {}",
+                    file.language_code,
+                    filled,
+                    chain.join(" → ")
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// 为远程独有的语言创建本地文件，只写入远程的非空翻译
+    fn create_language_file(
+        &self,
+        lang_code: &str,
+        remote_json: &serde_json::Value,
+        relative_path: &Path,
+        target_path: &Path,
+        remote_base: &HashMap<String, String>,
+        schema: Option<&TranslationSchema>,
+    ) -> Result<()> {
+        tracing::info!("正在创建 {} 到 {}", lang_code, target_path.display());
+
+        if let Some(schema) = schema {
+            schema.validate(lang_code, remote_json)?;
+        }
+        history::record_downloaded(count_keys(remote_json));
+        history::record_language(lang_code);
+
+        if let Err(e) = self.write_cache_file(lang_code, remote_json, None) {
+            tracing::warn!("Failed to cache translation for {}: {}", lang_code, e);
+        }
+
+        let empty = serde_json::Value::Object(serde_json::Map::new());
+        let relative_path = translation::relative_path_string(relative_path)?;
+        let remote_json = self.to_local_placeholders(&relative_path, remote_json, remote_base);
+        let content = Self::merge_json_content(&empty, &remote_json);
+        self.write_language_json(&content, target_path)?;
+
+        if let Err(e) = self.write_snapshot(lang_code, &self.apply_key_style(content)?) {
+            tracing::warn!("Failed to save sync snapshot for {}: {}", lang_code, e);
+        }
+
+        tracing::info!("成功创建 {}", target_path.display());
+        Ok(())
+    }
+
+    /// 显示当前凭据的来源，并向服务器验证凭据，输出对应的用户和权限
+    pub async fn auth_status(&self) -> Result<()> {
+        let api = Credential::Api {
+            host: self.config.host.clone(),
+        };
+        let github = credentials::source(&Credential::Github).or_else(|| {
+            Config::plaintext_github_token().map(|_| "plaintext config.toml".to_string())
+        });
+        let none = || "none".to_string();
+        output::table(
+            ["Item", "Value"],
+            [
+                vec!["Host".to_string(), self.config.host.clone()],
+                vec![
+                    "Environment".to_string(),
+                    self.config
+                        .environment
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                ],
+                vec!["Backend".to_string(), self.backend.name().to_string()],
+                vec![
+                    "API token".to_string(),
+                    credentials::source(&api).unwrap_or_else(none),
+                ],
+                vec!["GitHub token".to_string(), github.unwrap_or_else(none)],
+            ],
+        );
+
+        let identity = match self.backend.whoami().await {
+            Ok(Some(identity)) => identity,
+            Ok(None) => {
+                output::status(
+                    Status::Skipped,
+                    format!(
+                        "{} backend does not report user identity",
+                        self.backend.name()
+                    ),
+                );
+                return Ok(());
+            }
+            Err(e @ ApiError::Auth { .. }) => {
+                output::status(
+                    Status::Failed,
+                    format!("credentials were rejected by {}", self.config.host),
+                );
+                return Err(e.into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let user = identity
+            .user
+            .as_deref()
+            .or(identity.email.as_deref())
+            .unwrap_or("unknown user");
+        output::status(Status::Ok, format!("authenticated as {}", user));
+        if !identity.permissions.is_empty() {
+            println!("  permissions: {}", identity.permissions.join(", "));
+        }
+        // 服务器返回了可访问的子系统时，检查配置中的子系统是否都有权限
+        if !identity.sub_systems.is_empty() {
+            for sub_system in &self.config.sub_systems {
+                if !identity.sub_systems.contains(sub_system) {
+                    output::status(
+                        Status::Failed,
+                        format!("no access to sub system {}", sub_system),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 列出服务器和本地的所有语言，以及 key 数量和完成度
+    pub async fn list_languages(&self) -> Result<()> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let remote_files = if self.config.offline {
+            tracing::info!("离线模式，使用缓存中的服务器翻译");
+            self.read_cache()?
+        } else {
+            self.download_to_cache().await?
+        };
+
+        let local_by_lang: HashMap<&str, &TranslationFile> = local_files
+            .iter()
+            .map(|f| (f.language_code.as_str(), f))
+            .collect();
+        let mut languages: Vec<&str> = local_by_lang
+            .keys()
+            .copied()
+            .chain(remote_files.keys().map(String::as_str))
+            .collect();
+        languages.sort_unstable();
+        languages.dedup();
+
+        // 完成度以本地基准语言为准，本地没有基准语言时使用远程的
+        let base = local_by_lang
+            .get(self.config.base_language.as_str())
+            .copied()
+            .or_else(|| remote_files.get(&self.config.base_language));
+
+        // 回退链中的语言优先使用本地文件
+        let mut files_by_lang: HashMap<&str, &TranslationFile> = remote_files
+            .iter()
+            .map(|(lang, file)| (lang.as_str(), file))
+            .collect();
+        files_by_lang.extend(local_by_lang.iter().map(|(lang, file)| (*lang, *file)));
+
+        let review_store = ReviewStore::load(self.fs.as_ref())?;
+        let mut rows = Vec::new();
+        for lang in languages {
+            let local = local_by_lang.get(lang).copied();
+            let remote = remote_files.get(lang);
+            let completion = match (base, local.or(remote)) {
+                (Some(base), Some(file)) => {
+                    let resolved = self.resolve_fallbacks(file, &files_by_lang);
+                    let allowlist = self.config.identical_allowlist(lang);
+                    format!(
+                        "{:.1}%",
+                        translation::completion_ratio(base, &resolved, allowlist) * 100.0
+                    )
+                }
+                _ => "-".to_string(),
+            };
+            let count = |file: Option<&TranslationFile>| {
+                file.map(|f| f.content.len().to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            let mark = |exists: bool| if exists { "✓" } else { "✗" }.to_string();
+
+            rows.push(vec![
+                lang.to_string(),
+                mark(remote.is_some()),
+                mark(local.is_some()),
+                count(local),
+                count(remote),
+                completion,
+                review_store
+                    .count(
+                        self.config.sub_system_name(),
+                        lang,
+                        ReviewState::NeedsReview,
+                    )
+                    .to_string(),
+            ]);
+        }
+
+        output::table(
+            [
+                "Language",
+                "Remote",
+                "Local",
+                "Local keys",
+                "Remote keys",
+                "Completion",
+                "Needs review",
+            ],
+            rows,
+        );
+
+        Ok(())
+    }
+
+    /// 读取缓存目录中的服务器翻译，不发出网络请求
+    fn read_cache(&self) -> Result<HashMap<String, TranslationFile>> {
+        let cache_dir = self.cache_dir();
+        let mut cached_files = HashMap::new();
+        if !self.fs.exists(&cache_dir) {
+            tracing::warn!("未找到缓存目录 {}", cache_dir.display());
+            return Ok(cached_files);
+        }
+
+        for path in self.fs.read_dir(&cache_dir)? {
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(lang) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let file = TranslationFile::from_content(
+                lang.to_string(),
+                format!("{}.json", lang),
+                self.read_cached_language(&path)?,
+            );
+            cached_files.insert(lang.to_string(), file);
+        }
+        Ok(cached_files)
+    }
+
+    /// 设置本地翻译中 key 的审核状态
+    pub fn review_set(
+        &self,
+        lang: &str,
+        state: ReviewState,
+        keys: &[String],
+        all: bool,
+    ) -> Result<()> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let file = local_files
+            .iter()
+            .find(|f| f.language_code == lang)
+            .ok_or_else(|| AppError::Validation(format!("本地没有语言 {} 的翻译文件", lang)))?;
+
+        let mut keys: Vec<&str> = if all {
+            file.content.keys().map(|key| key.as_ref()).collect()
+        } else {
+            keys.iter().map(String::as_str).collect()
+        };
+        keys.sort();
+        if let Some(unknown) = keys.iter().find(|k| !file.content.contains_key(**k)) {
+            return Err(AppError::Validation(format!("{} 中不存在 key {}", lang, unknown)).into());
+        }
+
+        let mut store = ReviewStore::load(self.fs.as_ref())?;
+        for key in &keys {
+            store.set(self.config.sub_system_name(), lang, key, state);
+        }
+        store.save(self.fs.as_ref())?;
+        output::status(
+            Status::Ok,
+            format!("{}: marked {} keys as {}", lang, keys.len(), state),
+        );
+        Ok(())
+    }
+
+    /// 列出本地翻译中 key 的审核状态
+    pub fn review_list(&self, lang: Option<&str>, only: Option<ReviewState>) -> Result<()> {
+        let (_, mut local_files) = self.read_local_translations(None)?;
+        local_files.sort_by(|a, b| a.language_code.cmp(&b.language_code));
+        let store = ReviewStore::load(self.fs.as_ref())?;
+        let sub_system = self.config.sub_system_name();
+
+        let mut rows = Vec::new();
+        for file in local_files
+            .iter()
+            .filter(|f| lang.is_none_or(|l| f.language_code == l))
+        {
+            let mut keys: Vec<&Arc<str>> = file.content.keys().collect();
+            keys.sort();
+            for key in keys {
+                let state = store.state(sub_system, &file.language_code, key);
+                if only.is_none_or(|only| only == state) {
+                    rows.push(vec![
+                        file.language_code.clone(),
+                        key.to_string(),
+                        state.to_string(),
+                        file.content[key].to_string(),
+                    ]);
+                }
+            }
+        }
+
+        if rows.is_empty() {
+            output::status(Status::Skipped, "no matching keys");
+        } else {
+            output::table(["Language", "Key", "State", "Value"], rows);
+        }
+        Ok(())
+    }
+
+    /// 对本地翻译文件执行 check 命令的各项检查
+    pub fn check(&self, options: &CheckOptions) -> Result<()> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let base = self.local_base(&local_files)?;
+
+        // 重复和缺失的检查与 lint 使用相同的规则，级别按 lint 配置
+        let mut rules = Vec::new();
+        if options.duplicates {
+            rules.push("duplicates");
+        }
+        if options.missing {
+            rules.push("missing-keys");
+        }
+        if !rules.is_empty() {
+            let errors = lint::print(&self.run_lint(&local_files, &rules)?);
+            if errors > 0 {
+                return Err(AppError::Validation(format!(
+                    "check 发现 {} 个 error 级别的问题",
+                    errors
+                ))
+                .into());
+            }
+        }
+
+        // 全部检查时按 emptyBaseValues 决定是否检查，单独指定时总是检查
+        let severity = match self.config.empty_base_values {
+            EmptyBasePolicy::Allow if !options.all => Severity::Warning,
+            policy => policy.severity(),
+        };
+        if options.empty_base && severity != Severity::Off {
+            self.check_empty_base_values(base, severity)?;
+        }
+
+        if options.format {
+            let unformatted = self.unformatted_files()?;
+            check::print_unformatted(&unformatted);
+            if !unformatted.is_empty() {
+                return Err(AppError::Validation(format!(
+                    "{} 个翻译文件不是规范格式",
+                    unformatted.len()
+                ))
+                .into());
+            }
+        }
+
+        if options.spelling {
+            match &self.config.spell_check {
+                Some(spell_config) => {
+                    let checker = SpellChecker::from_config(spell_config)?;
+                    let mut issues = 0;
+                    for file in local_files
+                        .iter()
+                        .filter(|f| checker.has_dictionary(&f.language_code))
+                    {
+                        issues += check::print_misspellings(file, &checker.check(file));
+                    }
+                    if issues > 0 {
+                        return Err(AppError::Validation(format!(
+                            "拼写检查发现 {} 个 key 存在拼写错误",
+                            issues
+                        ))
+                        .into());
+                    }
+                }
+                None if options.all => {}
+                None => {
+                    return Err(AppError::Validation(
+                        "未配置 spellCheck，无法执行拼写检查".to_string(),
+                    )
+                    .into())
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn local_base<'a>(&self, local_files: &'a [TranslationFile]) -> Result<&'a TranslationFile> {
+        local_files
+            .iter()
+            .find(|f| f.language_code == self.config.base_language)
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Base language {} not found in local translations",
+                    self.config.base_language
+                ))
+                .into()
+            })
+    }
+
+    /// 按 lint 配置检查本地翻译文件，缺失的 key 先按回退链补全
+    pub fn lint(&self) -> Result<Vec<lint::Diagnostic>> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        self.run_lint(&local_files, &[])
+    }
+
+    /// 执行 lint 规则，`rules` 不为空时只执行其中的规则
+    fn run_lint(
+        &self,
+        local_files: &[TranslationFile],
+        rules: &[&str],
+    ) -> Result<Vec<lint::Diagnostic>> {
+        let base = self.local_base(local_files)?;
+        let files_by_lang: HashMap<&str, &TranslationFile> = local_files
+            .iter()
+            .map(|f| (f.language_code.as_str(), f))
+            .collect();
+        let translations: Vec<TranslationFile> = local_files
+            .iter()
+            .filter(|f| f.language_code != base.language_code)
+            .map(|f| self.resolve_fallbacks(f, &files_by_lang))
+            .collect();
+        let config = &self.config;
+        lint::run(
+            &lint::LintContext {
+                config: &config.lint,
+                base,
+                translations: &translations,
+                identical_allowlist: config
+                    .identical_as_untranslated
+                    .then_some(config.identical_allowlist.as_slice()),
+            },
+            rules,
+        )
+    }
+
+    /// 本地各语言的完成度
+    pub fn local_coverage(&self) -> Result<Vec<LanguageCoverage>> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let files_by_lang: HashMap<&str, &TranslationFile> = local_files
+            .iter()
+            .map(|f| (f.language_code.as_str(), f))
+            .collect();
+        let base = files_by_lang.get(self.config.base_language.as_str());
+
+        let mut coverage: Vec<LanguageCoverage> = local_files
+            .iter()
+            .map(|file| LanguageCoverage {
+                lang: file.language_code.clone(),
+                keys: file.content.len(),
+                completion: base
+                    .map(|base| {
+                        let resolved = self.resolve_fallbacks(file, &files_by_lang);
+                        let allowlist = self.config.identical_allowlist(&file.language_code);
+                        translation::completion_ratio(base, &resolved, allowlist)
+                    })
+                    .unwrap_or(0.0),
+            })
+            .collect();
+        coverage.sort_by(|a, b| a.lang.cmp(&b.lang));
+        Ok(coverage)
+    }
+
+    /// 本地每种语言的全部翻译，用于保存发布版本快照
+    pub fn local_catalog(&self) -> Result<BTreeMap<String, release::LanguageCatalog>> {
+        let (_, files) = self.read_local_translations(None)?;
+        Ok(files
+            .into_iter()
+            .map(|file| {
+                let catalog = file
+                    .content
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                (file.language_code, catalog)
+            })
+            .collect())
+    }
+
+    /// 输出本地翻译的统计：基准语言每个命名空间的 key 数量和词数，每种语言的 key 数量、词数和字符数
+    /// （`since` 为 git 引用时加上与当时相比增加的 key 数量），以及基准语言中最长的 `top` 个文本
+    pub fn print_stats(&self, since: Option<&str>, top: usize) -> Result<()> {
+        let (base_path, mut files) = self.read_local_translations(None)?;
+        files.sort_by(|a, b| a.language_code.cmp(&b.language_code));
+        let base = files
+            .iter()
+            .find(|f| f.language_code == self.config.base_language);
+
+        if let Some(base) = base {
+            let rows =
+                stats::namespace_counts(base)
+                    .into_iter()
+                    .map(|(namespace, (keys, words))| {
+                        vec![namespace.to_string(), keys.to_string(), words.to_string()]
+                    });
+            output::table(["Namespace", "Keys", "Words"], rows);
+        }
+
+        let mut header = vec![
+            "Language".to_string(),
+            "Keys".to_string(),
+            "Words".to_string(),
+            "Characters".to_string(),
+        ];
+        if let Some(git_ref) = since {
+            header.push(format!("Keys at {}", git_ref));
+            header.push("Growth".to_string());
+        }
+        let mut rows = Vec::new();
+        for file in &files {
+            let language = stats::LanguageStats::new(file);
+            let mut row = vec![
+                language.lang,
+                language.keys.to_string(),
+                language.words.to_string(),
+                language.characters.to_string(),
+            ];
+            if let Some(git_ref) = since {
+                let mut previous = 0;
+                for (_, path) in translation::namespace_files(&base_path.join(&file.relative_path))?
+                {
+                    previous += stats::keys_at_ref(git_ref, &path)?.unwrap_or(0);
+                }
+                row.push(previous.to_string());
+                row.push(format!("{:+}", language.keys as i64 - previous as i64));
+            }
+            rows.push(row);
+        }
+        output::table(header, rows);
+
+        if let Some(base) = base.filter(|_| top > 0) {
+            let rows = stats::largest_strings(base, top)
+                .into_iter()
+                .map(|(key, value, chars)| {
+                    vec![
+                        key.to_string(),
+                        chars.to_string(),
+                        stats::preview(value, 60),
+                    ]
+                });
+            output::table(["Key", "Characters", "Text"], rows);
+        }
+        Ok(())
+    }
+
+    /// 估算 `langs`（为空时是本地所有非基准语言）的翻译费用：未翻译 key 的基准语言词数乘以语言的单价。
+    /// 单价使用配置的 wordRates，没有配置的语言使用 `rate_per_word`；本地没有文件的语言按全部 key 计算
+    pub fn estimate(
+        &self,
+        langs: &[String],
+        rate_per_word: Option<f64>,
+    ) -> Result<Vec<stats::CostEstimate>> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let files_by_lang: HashMap<&str, &TranslationFile> = local_files
+            .iter()
+            .map(|f| (f.language_code.as_str(), f))
+            .collect();
+        let base = files_by_lang
+            .get(self.config.base_language.as_str())
+            .with_context(|| format!("未找到基准语言 {} 的本地文件", self.config.base_language))?;
+
+        let mut langs: Vec<&str> = if langs.is_empty() {
+            files_by_lang
+                .keys()
+                .copied()
+                .filter(|lang| *lang != self.config.base_language)
+                .collect()
+        } else {
+            langs.iter().map(String::as_str).collect()
+        };
+        langs.sort_unstable();
+        langs.dedup();
+
+        let mut estimates = Vec::new();
+        for lang in langs {
+            let rate = self
+                .config
+                .word_rates
+                .get(lang)
+                .copied()
+                .or(rate_per_word)
+                .ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "语言 {} 没有设置单价，使用 --rate-per-word 或在配置的 wordRates 中设置",
+                        lang
+                    ))
+                })?;
+            let (keys, words) = match files_by_lang.get(lang) {
+                Some(file) => {
+                    let resolved = self.resolve_fallbacks(file, &files_by_lang);
+                    let allowlist = self.config.identical_allowlist(lang);
+                    stats::untranslated_words(base, |key| {
+                        // fillMissingKeys 写入的占位文本也需要翻译
+                        resolved.content.get(key).is_some_and(|value| {
+                            value.as_ref() != self.config.missing_placeholder
+                                && translation::is_translated(
+                                    key,
+                                    &base.content[key],
+                                    value,
+                                    allowlist,
+                                )
+                        })
+                    })
+                }
+                None => stats::untranslated_words(base, |_| false),
+            };
+            estimates.push(stats::CostEstimate {
+                lang: lang.to_string(),
+                keys,
+                words,
+                rate,
+            });
+        }
+        Ok(estimates)
+    }
+
+    /// 与缓存的服务器翻译相比，本地新增或修改的非空 key（不含 ignoredKeys）以及 push 时是否会上传，
+    /// 不发出网络请求
+    pub fn pending_changes(&self) -> Result<Vec<PendingChange>> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let cached = self.read_cache()?;
+        let confirmed = PendingChanges::load(self.fs.as_ref(), &pending::pending_file())?;
+        let mut changes = Vec::new();
+        for file in &local_files {
+            let server = cached.get(&file.language_code);
+            for (key, local) in &file.content {
+                if local.trim().is_empty()
+                    || translation::key_matches_any(&self.config.ignored_keys, key)
+                {
+                    continue;
+                }
+                let server_value = server.and_then(|s| s.content.get(key));
+                if server_value == Some(local) {
+                    continue;
+                }
+                let status = match server_value {
+                    Some(value) if !value.trim().is_empty() => {
+                        if confirmed.contains(&self.config, &file.language_code, key) {
+                            ChangeStatus::Confirmed
+                        } else {
+                            ChangeStatus::Changed
+                        }
+                    }
+                    _ => ChangeStatus::New,
+                };
+                changes.push(PendingChange {
+                    lang: file.language_code.clone(),
+                    key: key.to_string(),
+                    local: local.to_string(),
+                    server: server_value.map(|value| value.to_string()),
+                    status,
+                });
+            }
+        }
+        changes.sort_by(|a, b| (&a.lang, &a.key).cmp(&(&b.lang, &b.key)));
+        Ok(changes)
+    }
+
+    /// 本地各语言中 `key` 的值，按语言排序，没有这个 key 的语言不返回
+    pub fn local_values(&self, key: &str) -> Result<Vec<(String, Arc<str>)>> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let mut values: Vec<(String, Arc<str>)> = local_files
+            .into_iter()
+            .filter_map(|f| {
+                let value = f.content.get(key)?.clone();
+                Some((f.language_code, value))
+            })
+            .collect();
+        values.sort();
+        Ok(values)
+    }
+
+    /// `lang` 中 `key` 所在的文件（相对路径）：已有这个 key 的文件，否则按 include 的布局写入与包含这个 key
+    /// 的基准语言文件对应的文件；两者都没有时返回 None
+    fn key_file_path(
+        &self,
+        local_files: &[TranslationFile],
+        lang: &str,
+        key: &str,
+    ) -> Result<Option<String>> {
+        let find = |lang: &str| {
+            local_files
+                .iter()
+                .find(|f| f.language_code == lang && f.content.contains_key(key))
+        };
+        if let Some(file) = find(lang) {
+            return Ok(Some(file.relative_path.clone()));
+        }
+        match find(&self.config.base_language) {
+            Some(base_file) => Ok(Some(translation::relative_path_string(
+                &missing_language_path(&self.config.include, &base_file.relative_path, lang),
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 本地 `lang` 或基准语言中是否有 `key`，set 据此选择子系统
+    pub fn has_local_key(&self, lang: &str, key: &str) -> Result<bool> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        Ok(self.key_file_path(&local_files, lang, key)?.is_some())
+    }
+
+    /// 修改本地翻译文件中一个 key 的值，返回原来的值；值没有变化时不写文件。key 写入的文件与 import 相同，
+    /// 本地没有这个 key 时只有 `lang` 只有一个文件才能新增
+    pub fn set_local_value(&self, lang: &str, key: &str, value: &str) -> Result<Option<Arc<str>>> {
+        if key.trim().is_empty() {
+            return Err(AppError::Validation("key 不能为空".to_string()).into());
+        }
+        let (base_path, local_files) = self.read_local_translations(None)?;
+        let lang_files: Vec<&TranslationFile> = local_files
+            .iter()
+            .filter(|f| f.language_code == lang)
+            .collect();
+        if lang_files.is_empty() {
+            return Err(AppError::Validation(format!("本地没有语言 {} 的翻译文件", lang)).into());
+        }
+        let relative_path = match (
+            self.key_file_path(&local_files, lang, key)?,
+            lang_files.as_slice(),
+        ) {
+            (Some(relative_path), _) => relative_path,
+            (None, [file]) => file.relative_path.clone(),
+            (None, _) => {
+                return Err(AppError::Validation(format!(
+                    "本地没有 key {}，语言 {} 有多个翻译文件，无法确定写入哪个文件",
+                    key, lang
+                ))
+                .into())
+            }
+        };
+        let mut file = lang_files
+            .into_iter()
+            .find(|f| f.relative_path == relative_path)
+            .cloned()
+            .unwrap_or_else(|| {
+                // 基准语言文件对应的位置还没有这种语言的文件
+                TranslationFile::from_content(
+                    lang.to_string(),
+                    relative_path.clone(),
+                    Content::new(),
+                )
+            });
+        let previous = file.content.insert(Arc::from(key), Arc::from(value));
+        if previous.as_deref() == Some(value) {
+            return Ok(previous);
+        }
+        let path = base_path.join(&file.relative_path);
+        self.save_translation_file(&file, &path)?;
+        tracing::info!("Updated {} in {}", key, path.display());
+        Ok(previous)
+    }
+
+    /// 记录本地确认过的修改，下次 push 时即使服务器的值不同也只上传这些 key
+    pub fn record_pending_changes<'a>(
+        &self,
+        lang: &str,
+        keys: impl IntoIterator<Item = &'a str>,
+    ) -> Result<()> {
+        let path = pending::pending_file();
+        let mut pending = PendingChanges::load(self.fs.as_ref(), &path)?;
+        pending.add(&self.config, lang, keys);
+        pending.save(self.fs.as_ref(), &path)
+    }
+
+    /// 把外部交付的翻译文件合并到本地 `lang` 的翻译文件：只导入基准语言中存在的 key，每个 key 写入已有这个 key
+    /// 的文件，或按 include 的布局写入与基准语言文件对应的文件。显示与本地不同的 key 并确认（--yes 时跳过）
+    /// 后写入，导入的 key 标记为需要审核。返回基准语言中不存在、没有导入的 key
+    pub fn import(&self, lang: &str, path: &Path, yes: bool) -> Result<BTreeSet<String>> {
+        let imported = import::read_external(path, lang)?;
+        let (base_path, mut local_files) = self.read_local_translations(None)?;
+        if !local_files.iter().any(|f| f.language_code == lang) {
+            return Err(AppError::Validation(format!("本地没有语言 {} 的翻译文件", lang)).into());
+        }
+
+        // 每个 key 写入哪个文件：已有这个 key 的文件，否则按 include 的布局写入与基准语言文件对应的文件
+        let base_files: HashMap<&str, &TranslationFile> = local_files
+            .iter()
+            .filter(|f| f.language_code == self.config.base_language)
+            .flat_map(|f| f.content.keys().map(move |key| (key.as_ref(), f)))
+            .collect();
+        let has_base = local_files
+            .iter()
+            .any(|f| f.language_code == self.config.base_language);
+        let lang_files: Vec<&TranslationFile> = local_files
+            .iter()
+            .filter(|f| f.language_code == lang)
+            .collect();
+
+        // 目标文件的相对路径 -> (key, 本地值, 导入的值)
+        let mut changes: BTreeMap<String, Vec<(String, Option<String>, String)>> = BTreeMap::new();
+        let (mut unknown, mut stale) = (BTreeSet::new(), Vec::new());
+        for (key, entry) in imported {
+            let base_file = base_files.get(key.as_str());
+            if has_base && base_file.is_none() {
+                unknown.insert(key);
+                continue;
+            }
+            // 导出后基准语言文本有变化，译文可能已经过时
+            if let (Some(base_file), Some(id)) = (base_file, &entry.stable_id) {
+                if *id != import::stable_id(&key, &base_file.content[key.as_str()]) {
+                    stale.push(key);
+                    continue;
+                }
+            }
+            let existing = lang_files
+                .iter()
+                .find(|f| f.content.contains_key(key.as_str()));
+            let relative_path = match self.key_file_path(&local_files, lang, &key)? {
+                Some(relative_path) => relative_path,
+                None => lang_files[0].relative_path.clone(),
+            };
+            let local = existing.map(|f| f.content[key.as_str()].to_string());
+            if local.as_deref() != Some(entry.value.as_str()) {
+                changes
+                    .entry(relative_path)
+                    .or_default()
+                    .push((key, local, entry.value));
+            }
+        }
+        if !stale.is_empty() {
+            stale.sort();
+            output::status(
+                Status::Skipped,
+                format!(
+                    "{}: {} stale keys whose source text changed since export: {}",
+                    lang,
+                    stale.len(),
+                    stale.join(", ")
+                ),
+            );
+        }
+        if changes.is_empty() {
+            output::status(
+                Status::Skipped,
+                format!("{}: nothing to import from {}", lang, path.display()),
+            );
+            return Ok(unknown);
+        }
+
+        let mut rows = Vec::new();
+        for (relative_path, entries) in &mut changes {
+            entries.sort();
+            for (key, local, value) in entries.iter() {
+                rows.push(vec![
+                    key.clone(),
+                    relative_path.clone(),
+                    local.clone().unwrap_or_default(),
+                    value.clone(),
+                ]);
+            }
+        }
+        let key_count = rows.len();
+        output::table(["Key", "File", "Local", "Imported"], rows);
+
+        let confirmed = yes
+            || workspace::confirm(&format!(
+                "把以上 {} 个 key 导入 {} 个文件？",
+                key_count,
+                changes.len()
+            ))?;
+        if !confirmed {
+            tracing::warn!("未确认导入，本地文件没有修改");
+            return Ok(unknown);
+        }
+
+        let mut reviews = ReviewStore::load(self.fs.as_ref())?;
+        for (relative_path, entries) in changes {
+            let index = match local_files
+                .iter()
+                .position(|f| f.language_code == lang && f.relative_path == relative_path)
+            {
+                Some(index) => index,
+                None => {
+                    // 基准语言文件对应的位置还没有这种语言的文件
+                    local_files.push(TranslationFile::from_content(
+                        lang.to_string(),
+                        relative_path.clone(),
+                        Content::new(),
+                    ));
+                    local_files.len() - 1
+                }
+            };
+            let file = &mut local_files[index];
+            let keys: Vec<String> = entries.iter().map(|(key, _, _)| key.clone()).collect();
+            file.content.extend(translation::to_content(
+                entries.into_iter().map(|(key, _, value)| (key, value)),
+            ));
+            let target = base_path.join(&relative_path);
+            self.save_translation_file(file, &target)?;
+            reviews.mark_needs_review(self.config.sub_system_name(), lang, &keys);
+
+            output::status(
+                Status::Ok,
+                format!(
+                    "{}: imported {} keys into {} (marked needs-review)",
+                    lang,
+                    keys.len(),
+                    target.display()
+                ),
+            );
+        }
+        reviews.save(self.fs.as_ref())?;
+        Ok(unknown)
+    }
+
+    /// 导出基准语言和 `lang` 的所有 key，交给翻译公司翻译后用 import 导回。`only` 不为 None 时
+    /// 只导出 `lang` 中处于该审核状态的 key
+    pub fn export(&self, lang: &str, path: &Path, only: Option<ReviewState>) -> Result<()> {
+        let local_files = self.local_translations()?;
+        let find = |code: &str| local_files.iter().find(|f| f.language_code == code);
+        let base = find(&self.config.base_language).ok_or_else(|| {
+            AppError::Validation(format!(
+                "本地没有基准语言 {} 的翻译文件",
+                self.config.base_language
+            ))
+        })?;
+        let target = find(lang);
+        let store = ReviewStore::load(self.fs.as_ref())?;
+        let sub_system = self.config.sub_system_name();
+
+        let mut rows: Vec<import::ExportRow> = base
+            .content
+            .iter()
+            .filter(|(key, _)| only.is_none_or(|only| store.state(sub_system, lang, key) == only))
+            .map(|(key, source)| import::ExportRow {
+                key: key.to_string(),
+                source: source.to_string(),
+                translation: target
+                    .and_then(|f| f.content.get(key))
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        rows.sort_by(|a, b| a.key.cmp(&b.key));
+        import::write_external(path, &self.config.base_language, lang, &rows)?;
+
+        output::status(
+            Status::Ok,
+            format!(
+                "{}: exported {} keys to {}",
+                lang,
+                rows.len(),
+                path.display()
+            ),
+        );
+        Ok(())
+    }
+
+    /// 按 include 规则读取本地翻译文件
+    pub fn local_translations(&self) -> Result<Vec<TranslationFile>> {
+        Ok(self.read_local_translations(None)?.1)
+    }
+
+    /// 本地各语言翻译文件的路径
+    pub fn local_language_files(&self) -> Result<Vec<(String, PathBuf)>> {
+        let (base_path, local_files) = self.read_local_translations(None)?;
+        Ok(local_files
+            .into_iter()
+            .map(|f| (f.language_code, base_path.join(f.relative_path)))
+            .collect())
+    }
+
+    /// 把本地翻译打包为运行时使用的静态文件，不发出网络请求
+    pub fn bundle(&self, options: &BundleOptions) -> Result<()> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        ensure!(
+            !local_files.is_empty(),
+            format!(
+                "未找到任何本地翻译文件。include 设置: {:?}",
+                self.config.include
+            )
+        );
+
+        let mut languages = BTreeMap::new();
+        for file in &local_files {
+            let value = self.apply_key_style(translation::unflatten_json(&file.content)?)?;
+            languages.insert(file.language_code.clone(), value);
+        }
+
+        let manifest = bundle::write_bundle(&languages, options)?;
+        for (lang, entry) in &manifest.languages {
+            let files = entry.file.iter().count() + entry.namespaces.len();
+            output::status(
+                Status::Ok,
+                format!("{}: {} keys in {} file(s)", lang, entry.keys, files),
+            );
+        }
+        output::status(
+            Status::Ok,
+            format!(
+                "manifest written to {}",
+                options.out.join(bundle::MANIFEST_FILE).display()
+            ),
+        );
+        Ok(())
+    }
+
+    /// 根据本地基准语言的 key 生成代码文件
+    pub fn codegen(&self, target: codegen::Target, out: &Path) -> Result<()> {
+        let (_, local_files) = self.read_local_translations(None)?;
+        let languages: codegen::Languages = local_files
+            .into_iter()
+            .map(|f| {
+                let content = f
+                    .content
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                (f.language_code, content)
+            })
+            .collect();
+        let keys = languages
+            .get(&self.config.base_language)
+            .map(BTreeMap::len)
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Base language {} not found in local translations",
+                    self.config.base_language
+                ))
+            })?;
+
+        if let Some(parent) = out.parent() {
+            self.fs.create_dir_all(parent)?;
+        }
+        let generated = target.generate(&self.config.base_language, &languages);
+        self.fs
+            .write(out, generated.as_bytes())
+            .with_context(|| format!("写入文件 {} 失败", out.display()))?;
+        output::status(
+            Status::Ok,
+            format!(
+                "{}: generated {} keys to {}",
+                target.name(),
+                keys,
+                out.display()
+            ),
+        );
+        Ok(())
+    }
+
+    /// 在本地翻译中搜索，每个匹配输出一行 `文件:key: 值`（`keys_only` 时为 `文件:key`），返回匹配数量
+    pub fn grep(&self, options: &GrepOptions, regex: &Regex) -> Result<usize> {
+        let (base_path, mut local_files) = self.read_local_translations(None)?;
+        local_files.sort_by(|a, b| a.language_code.cmp(&b.language_code));
+        let mut count = 0;
+        for file in local_files
+            .iter()
+            .filter(|f| options.lang.as_ref().is_none_or(|l| f.language_code == *l))
+        {
+            let path = self.get_full_path(file, &base_path);
+            for (key, value) in grep::find_matches(file, regex, options.keys_only) {
+                if options.keys_only {
+                    println!("{}:{}", path, grep::display(key, regex));
+                } else {
+                    println!(
+                        "{}:{}: {}",
+                        path,
+                        grep::display(key, regex),
+                        grep::display(value, regex)
+                    );
+                }
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// 替换本地翻译值中匹配 `regex` 的文本（`lang` 为 None 时替换所有语言）：先列出每个 key 替换前后的值，
+    /// 确认（`yes` 时跳过）后写入文件，并记录为待上传的修改，下次 push 时即使与服务器的值不同也会上传。
+    /// 返回替换的值的数量
+    pub fn replace(
+        &self,
+        regex: &Regex,
+        replacement: &str,
+        literal: bool,
+        lang: Option<&str>,
+        yes: bool,
+    ) -> Result<usize> {
+        let (base_path, mut local_files) = self.read_local_translations(None)?;
+        local_files.sort_by(|a, b| a.language_code.cmp(&b.language_code));
+        let sub_system = self.config.sub_system_name();
+
+        let mut changes = Vec::new();
+        let mut rows = Vec::new();
+        for (index, file) in local_files.iter().enumerate() {
+            if lang.is_some_and(|l| file.language_code != l) {
+                continue;
+            }
+            let replacements = grep::find_replacements(file, regex, replacement, literal);
+            for r in &replacements {
+                rows.push(vec![
+                    file.language_code.clone(),
+                    r.key.to_string(),
+                    r.before.to_string(),
+                    r.after.clone(),
+                ]);
+            }
+            if !replacements.is_empty() {
+                changes.push((index, replacements));
+            }
+        }
+        if rows.is_empty() {
+            output::status(
+                Status::Skipped,
+                format!("{}: no matching values", sub_system),
+            );
+            return Ok(0);
         }
-        std::fs::create_dir_all(&target_dir)?;
 
-        tracing::info!("Fetching translation configuration...");
-        let config_response = api::get_translation_config(&self.config).await?;
+        let count = rows.len();
+        output::table(["Language", "Key", "Before", "After"], rows);
+        if !yes && !workspace::confirm(&format!("替换以上 {} 个值？", count))? {
+            tracing::warn!("未确认替换，没有修改任何文件");
+            return Ok(0);
+        }
 
-        let mut success_count = 0;
-        let mut failed_count = 0;
+        let pending_path = pending::pending_file();
+        let mut pending = PendingChanges::load(self.fs.as_ref(), &pending_path)?;
+        for (index, replacements) in changes {
+            let file = &mut local_files[index];
+            for r in &replacements {
+                file.content
+                    .insert(r.key.clone(), Arc::from(r.after.as_str()));
+            }
+            self.save_translation_file(file, &base_path.join(&file.relative_path))?;
+            pending.add(
+                &self.config,
+                &file.language_code,
+                replacements.iter().map(|r| r.key.as_ref()),
+            );
+            output::status(
+                Status::Ok,
+                format!(
+                    "{}: replaced {} values",
+                    file.language_code,
+                    replacements.len()
+                ),
+            );
+        }
+        pending.save(self.fs.as_ref(), &pending_path)?;
+        Ok(count)
+    }
 
-        if let Some(files_to_download) = config_response.data.files {
-            for file_info in files_to_download {
-                match api::download_translation(&self.config, &file_info.url).await {
-                    Ok(raw_content_string) => {
-                        let full_json_value: serde_json::Value =
-                            serde_json::from_str(&raw_content_string)?;
-                        let lang_key = format!("{}/languages", self.config.path_prefix);
+    /// 显示 `key` 的全部信息：各语言的本地值、缓存的服务器值和审核状态，基准语言文件中的注释，
+    /// 审计日志中各语言最近一次上传，以及源码中的引用。本地和缓存中都没有这个 key 时不输出并返回 false
+    pub fn explain(&self, key: &str) -> Result<bool> {
+        let (base_path, mut local_files) = self.read_local_translations(None)?;
+        local_files.sort_by(|a, b| a.language_code.cmp(&b.language_code));
+        let cached = self.read_cache()?;
+        let sub_system = self.config.sub_system_name();
 
-                        if let Some(lang_specific_json_value) = full_json_value.get(&lang_key) {
-                            let target_file = target_dir.join(format!("{}.json", file_info.lang));
+        let languages: BTreeSet<&str> = local_files
+            .iter()
+            .map(|f| f.language_code.as_str())
+            .chain(cached.keys().map(String::as_str))
+            .collect();
+        let store = ReviewStore::load(self.fs.as_ref())?;
+        let mut found = false;
+        let mut rows = Vec::new();
+        for lang in languages {
+            let local = local_files
+                .iter()
+                .find(|f| f.language_code == lang)
+                .and_then(|f| f.content.get(key));
+            let remote = cached.get(lang).and_then(|f| f.content.get(key));
+            found |= local.is_some() || remote.is_some();
+            let review = match local {
+                Some(_) => store.state(sub_system, lang, key).to_string(),
+                None => "-".to_string(),
+            };
+            rows.push(vec![
+                lang.to_string(),
+                local.map_or("-".to_string(), |v| v.to_string()),
+                remote.map_or("-".to_string(), |v| v.to_string()),
+                review,
+            ]);
+        }
+        if !found {
+            return Ok(false);
+        }
 
-                            // 将提取出的 lang_specific_json_value 写入文件
-                            let content_to_write =
-                                serde_json::to_string_pretty(lang_specific_json_value)?;
-                            std::fs::write(&target_file, content_to_write)?;
+        output::status(Status::Ok, format!("{} ({})", key, sub_system));
+        output::table(["Language", "Local", "Remote (cache)", "Review"], rows);
 
-                            tracing::info!(
-                                "Downloaded translation for {} to {}",
-                                file_info.lang,
-                                target_file.display()
-                            );
-                            success_count += 1;
-                        } else {
-                            tracing::error!(
-                                "Key '{}' not found in downloaded content for language: {}. Raw content: {}",
-                                lang_key,
-                                file_info.lang,
-                                raw_content_string
-                            );
-                            failed_count += 1;
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to download translation for {}: {}",
-                            file_info.lang,
-                            e
-                        );
-                        failed_count += 1;
-                    }
-                }
+        // 基准语言文件中 key 前和同一行的注释作为说明
+        if let Some(base) = local_files
+            .iter()
+            .find(|f| f.language_code == self.config.base_language)
+        {
+            let comments =
+                translation::read_comments(self.fs.as_ref(), &base_path.join(&base.relative_path))?;
+            for comment in comments
+                .leading
+                .get(key)
+                .into_iter()
+                .chain(comments.trailing.get(key))
+                .flatten()
+            {
+                let text = comment
+                    .trim_start_matches("//")
+                    .trim_start_matches("/*")
+                    .trim_end_matches("*/")
+                    .trim();
+                println!("Description: {}", text);
             }
-        } else {
-            tracing::error!("No files found in the configuration response");
-            return Ok(());
         }
 
-        tracing::info!(
-            "Download completed: {} succeeded, {} failed, {} total",
-            success_count,
-            failed_count,
-            success_count + failed_count
-        );
+        let entries = audit::read_entries(self.fs.as_ref(), &audit::audit_file())?;
+        let pushes = audit::last_pushes(&entries, sub_system, key);
+        if pushes.is_empty() {
+            output::status(Status::Skipped, "no push of this key in the audit log");
+        } else {
+            let rows = pushes.into_iter().map(|entry| {
+                vec![
+                    entry.language.clone(),
+                    entry.timestamp.clone(),
+                    entry.user.clone(),
+                    entry.environment.clone().unwrap_or_default(),
+                ]
+            });
+            output::table(["Language", "Last push", "User", "Environment"], rows);
+        }
 
-        Ok(())
+        let references = lsp::find_references(Path::new("."), key);
+        if references.is_empty() {
+            output::status(Status::Skipped, "no references in source code");
+        } else {
+            for (path, line) in references {
+                println!("{}:{}", path.display(), line);
+            }
+        }
+        Ok(true)
     }
 
-    /// 同步翻译文件（从服务器同步到本地）
-    pub async fn sync_translations(&self) -> Result<()> {
-        tracing::info!("正在下载最新翻译...");
-        let config_response = api::get_translation_config(&self.config)
-            .await
-            .context("获取翻译配置失败")?;
-
-        let files_to_download = config_response
-            .data
-            .files
-            .as_ref()
-            .and_then(|files| if files.is_empty() { None } else { Some(files) })
-            .with_context(|| {
-                format!(
-                    "未找到任何翻译文件。系统名称: '{}', 产品代码: '{}'",
-                    self.config.sub_system_name, self.config.product_code
-                )
-            })?;
-
-        let (base_path, local_files) = self
-            .read_local_translations(None)
-            .context("读取本地翻译文件失败")?;
-
+    /// 对比本地翻译文件与最近一次缓存的服务器翻译，不发出任何网络请求
+    pub fn diff_with_cache(&self, path: Option<String>) -> Result<()> {
+        let cache_dir = self.cache_dir();
         ensure!(
-            !local_files.is_empty(),
+            self.fs.exists(&cache_dir),
             format!(
-                "未找到任何本地翻译文件。include 设置: {:?}",
-                self.config.include
+                "未找到缓存目录 {}，请先在联网状态下运行 push 或 pull",
+                cache_dir.display()
             )
         );
 
-        let mut success_count = 0;
-        let mut failed_count = 0;
-
+        let (base_path, local_files) = self.read_local_translations(path)?;
         for local_file in local_files {
             let lang_code = &local_file.language_code;
+            let cache_file = cache_dir.join(format!("{}.json", lang_code));
+            if !self.fs.exists(&cache_file) {
+                tracing::warn!("语言 {} 没有缓存，跳过对比", lang_code);
+                continue;
+            }
 
-            if let Some(remote_file_info) = files_to_download.iter().find(|f| &f.lang == lang_code)
-            {
-                let target_path = base_path.join(&local_file.relative_path);
-                tracing::info!("正在同步 {} 到 {}", lang_code, target_path.display());
-
-                match api::download_translation(&self.config, &remote_file_info.url).await {
-                    Ok(raw_content_string) => {
-                        let full_json_value: serde_json::Value =
-                            serde_json::from_str(&raw_content_string)?;
-                        let lang_key = format!("{}/languages", self.config.path_prefix);
-
-                        if let Some(remote_lang_specific_json) = full_json_value.get(&lang_key) {
-                            let local_content_string = std::fs::read_to_string(&target_path)
-                                .with_context(|| {
-                                    format!("读取本地文件 {} 失败", target_path.display())
-                                })?;
-                            let local_json: serde_json::Value =
-                                serde_json::from_str(&local_content_string)?;
-
-                            self.print_json_diff(&local_json, remote_lang_specific_json, lang_code);
-
-                            let merged_content =
-                                Self::merge_json_content(&local_json, remote_lang_specific_json);
-
-                            if let Some(parent) = target_path.parent() {
-                                std::fs::create_dir_all(parent).with_context(|| {
-                                    format!("创建目录 {} 失败", parent.display())
-                                })?;
-                            }
-
-                            let formatted_json = serde_json::to_string_pretty(&merged_content)?;
-                            std::fs::write(&target_path, formatted_json).with_context(|| {
-                                format!("写入文件 {} 失败", target_path.display())
-                            })?;
+            let local_path = base_path.join(&local_file.relative_path);
+            let local_json = translation::read_json_file(&local_path)
+                .with_context(|| format!("读取本地文件 {} 失败", local_path.display()))?;
+            let cached_json: serde_json::Value =
+                serde_json::from_reader(self.fs.open(&cache_file)?)
+                    .with_context(|| format!("读取缓存文件 {} 失败", cache_file.display()))?;
 
-                            tracing::info!("成功同步 {}", target_path.display());
-                            success_count += 1;
-                        } else {
-                            tracing::error!(
-                                "Key '{}' not found in downloaded content for language: {}. Raw content: {}",
-                                lang_key,
-                                lang_code,
-                                raw_content_string
-                            );
-                            failed_count += 1;
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("下载语言 {} 的翻译失败: {}", lang_code, e);
-                        failed_count += 1;
-                    }
-                }
+            tracing::info!(
+                "对比 {} 与缓存 {}",
+                local_path.display(),
+                cache_file.display()
+            );
+            let counts = self.print_json_diff(&local_json, &cached_json, lang_code);
+            if counts.is_empty() {
+                output::status(Status::Ok, format!("{}: up to date with cache", lang_code));
             } else {
-                tracing::warn!("未找到语言 {} 的远程翻译，跳过同步", lang_code);
-                failed_count += 1;
+                output::status(
+                    Status::Skipped,
+                    format!(
+                        "{}: {} local only, {} remote only, {} changed",
+                        lang_code, counts.local_only, counts.remote_only, counts.changed
+                    ),
+                );
             }
         }
 
-        ensure!(
-            success_count > 0,
-            format!(
-                "同步失败：成功 {} 个，失败 {} 个",
-                success_count, failed_count
+        Ok(())
+    }
+
+    /// 写入一种语言的缓存并更新清单，`task_hash` 为 None 时下次 push 会重新下载该语言
+    fn write_cache_file(
+        &self,
+        lang_code: &str,
+        content: &serde_json::Value,
+        task_hash: Option<&str>,
+    ) -> Result<()> {
+        let cache_dir = self.cache_dir();
+        self.fs.create_dir_all(&cache_dir)?;
+        let path = self.cached_language_path(lang_code);
+        self.fs
+            .write(&path, serde_json::to_string_pretty(content)?.as_bytes())?;
+        CacheManifest::update(self.fs.as_ref(), &cache::manifest_file(), |manifest| {
+            manifest.record(
+                self.fs.as_ref(),
+                &journal::push_key(&self.config),
+                lang_code,
+                &path,
+                task_hash,
             )
-        );
+        })
+    }
 
-        tracing::info!(
-            "同步完成: {} 个成功, {} 个失败, 共 {} 个文件",
-            success_count,
-            failed_count,
-            success_count + failed_count
-        );
+    /// 去掉配置中 ignoredKeys 匹配的 key，这些 key 不会上传到服务器
+    fn remove_ignored_keys(&self, translation: &mut TranslationFile) {
+        if self.config.ignored_keys.is_empty() {
+            return;
+        }
+        let before = translation.content.len();
+        translation
+            .content
+            .retain(|key, _| !translation::key_matches_any(&self.config.ignored_keys, key));
+        let ignored = before - translation.content.len();
+        if ignored > 0 {
+            tracing::info!(
+                "Ignored {} keys in {} matching ignoredKeys",
+                ignored,
+                translation.language_code
+            );
+        }
+    }
 
-        Ok(())
+    /// 上次 pull 时服务器内容的快照，作为下次三方合并的基准。和缓存一样按 host 和版本分开，
+    /// 切换环境或版本后不会拿另一份服务器内容做基准
+    fn snapshot_file(&self, lang_code: &str) -> PathBuf {
+        self.snapshot_lang_dir().join(format!("{}.json", lang_code))
     }
 
-    /// 添加新的辅助方法来保存翻译文件
-    fn save_translation_file(&self, translation: &TranslationFile, file_path: &Path) -> Result<()> {
-        // 将扁平的键值对转换为嵌套的 JSON 结构
-        let mut json_value = serde_json::Map::new();
-        for (key, value) in &translation.content {
-            let parts: Vec<&str> = key.split('.').collect();
-            let mut current = &mut json_value;
-
-            // 创建嵌套结构
-            for (i, part) in parts.iter().enumerate() {
-                if i == parts.len() - 1 {
-                    current.insert(
-                        (*part).to_string(),
-                        serde_json::Value::String(value.clone()),
-                    );
-                } else {
-                    current = current
-                        .entry((*part).to_string())
-                        .or_insert(serde_json::Value::Object(serde_json::Map::new()))
-                        .as_object_mut()
-                        .ok_or_else(|| anyhow::anyhow!("Failed to create nested structure"))?;
+    fn snapshot_lang_dir(&self) -> PathBuf {
+        cache::scoped_dir(&workspace::snapshot_dir(), &self.config)
+    }
+
+    /// 各语言快照的扁平内容，没有快照时为空
+    pub fn snapshots(&self) -> Result<HashMap<String, HashMap<String, String>>> {
+        let dir = self.snapshot_lang_dir();
+        let mut snapshots = HashMap::new();
+        if !self.fs.exists(&dir) {
+            return Ok(snapshots);
+        }
+        for path in self.fs.read_dir(&dir)? {
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(lang) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    let file = self.fs.open(&path)?;
+                    snapshots.insert(lang.to_string(), translation::flatten_json_reader(file)?);
                 }
             }
         }
+        Ok(snapshots)
+    }
 
-        // 创建父目录（如果不存在）
-        if let Some(parent) = file_path.parent() {
-            std::fs::create_dir_all(parent)?;
+    fn write_snapshot(&self, lang_code: &str, content: &serde_json::Value) -> Result<()> {
+        let file = self.snapshot_file(lang_code);
+        if let Some(parent) = file.parent() {
+            self.fs.create_dir_all(parent)?;
         }
-
-        // 将 JSON 写入文件
-        let json_str = serde_json::to_string_pretty(&json_value)?;
-        std::fs::write(file_path, json_str)?;
-
+        self.fs
+            .write(&file, serde_json::to_string_pretty(content)?.as_bytes())?;
         Ok(())
     }
 
-    // 修改为实例方法
-    fn print_json_diff(
+    /// 有快照时做三方合并，否则退回两方合并。`interactive` 时逐个询问冲突的处理方式，
+    /// 未解决的冲突保留本地值并在结果中返回
+    fn merge_with_snapshot(
         &self,
+        lang_code: &str,
         local: &serde_json::Value,
         remote: &serde_json::Value,
-        lang_code: &str,
-    ) {
-        let mut local_map = HashMap::new();
-        let mut remote_map = HashMap::new();
-
-        // 将 JSON 扁平化以便比较
-        flatten_json_inner(local, String::new(), &mut local_map);
-        flatten_json_inner(remote, String::new(), &mut remote_map);
+        interactive: bool,
+    ) -> Result<merge::MergeResult> {
+        let snapshot_file = self.snapshot_file(lang_code);
+        if !self.fs.exists(&snapshot_file) {
+            tracing::debug!("No sync snapshot for {}, using two-way merge", lang_code);
+            return Ok(merge::MergeResult {
+                merged: Self::merge_json_content(local, remote),
+                conflicts: Vec::new(),
+            });
+        }
 
-        // 找出本地独有的键（将被保留）
-        let mut local_only = Vec::new();
-        for key in local_map.keys() {
-            if !remote_map.contains_key(key) {
-                local_only.push(key);
+        let base: serde_json::Value = serde_json::from_reader(self.fs.open(&snapshot_file)?)
+            .with_context(|| format!("读取快照 {} 失败", snapshot_file.display()))?;
+        let base = self.normalize_key_style(&base)?;
+        let result = merge::merge_three_way(&base, local, remote, &mut |conflict| {
+            if interactive {
+                prompt_conflict(lang_code, conflict)
+            } else {
+                Ok(merge::Resolution::Unresolved)
             }
+        })?;
+        for conflict in &result.conflicts {
+            tracing::warn!(
+                "Conflict on {} in {}: base={}, local={}, remote={}",
+                conflict.key,
+                lang_code,
+                display_value(&conflict.base),
+                display_value(&conflict.local),
+                display_value(&conflict.remote)
+            );
+        }
+        Ok(result)
+    }
+
+    /// 从远程翻译中去掉受保护且与本地不同的 key，使合并时保留本地值
+    fn skip_protected_keys(
+        &self,
+        lang_code: &str,
+        local: &serde_json::Value,
+        remote: &serde_json::Value,
+    ) -> serde_json::Value {
+        let mut remote = remote.clone();
+        if self.config.protected_keys.is_empty() {
+            return remote;
         }
 
-        // 找出远程有但本地没有的键（新增的键）
-        let mut remote_only = Vec::new();
-        for key in remote_map.keys() {
-            if !local_map.contains_key(key) {
-                remote_only.push(key);
+        let local_flat = translation::flatten_json(local);
+        let mut remote_flat: Vec<(String, String)> =
+            translation::flatten_json(&remote).into_iter().collect();
+        remote_flat.sort();
+        for (key, remote_value) in remote_flat {
+            if !translation::key_matches_any(&self.config.protected_keys, &key) {
+                continue;
+            }
+            match local_flat.get(&key) {
+                Some(local_value) if *local_value != remote_value => {
+                    translation::remove_flat_key(&mut remote, &key);
+                    tracing::info!(
+                        "Skipped remote change for protected key {} in {}: local='{}', remote='{}'",
+                        key,
+                        lang_code,
+                        local_value,
+                        remote_value
+                    );
+                    output::status(
+                        Status::Skipped,
+                        format!("{}: {} is protected, remote change skipped", lang_code, key),
+                    );
+                }
+                _ => {}
             }
         }
+        remote
+    }
+
+    /// 配置了 keyStyle 时先统一为嵌套结构，便于合并和按命名空间拆分
+    fn normalize_key_style(&self, value: &serde_json::Value) -> Result<serde_json::Value> {
+        match self.config.key_style {
+            Some(_) => translation::apply_key_style(value, KeyStyle::Nested),
+            None => Ok(value.clone()),
+        }
+    }
+
+    /// 按配置的 jsonFormat 序列化要写入翻译文件的 JSON
+    fn to_json(&self, value: &serde_json::Value) -> Result<String> {
+        if self.config.normalize_nfc {
+            let normalized = translation::normalize_json_nfc(value);
+            return translation::to_json_string(&normalized, &self.config.json_format);
+        }
+        translation::to_json_string(value, &self.config.json_format)
+    }
+
+    /// 重写已有的翻译文件，保留原文件（JSONC）中的注释
+    fn to_json_preserving_comments(
+        &self,
+        value: &serde_json::Value,
+        existing_file: &Path,
+    ) -> Result<String> {
+        let comments = translation::read_comments(self.fs.as_ref(), existing_file)?;
+        if comments.is_empty() {
+            return self.to_json(value);
+        }
+        let normalized;
+        let value = if self.config.normalize_nfc {
+            normalized = translation::normalize_json_nfc(value);
+            &normalized
+        } else {
+            value
+        };
+        translation::to_json_string_with_comments(value, &self.config.json_format, &comments)
+    }
+
+    /// 按配置的 keyStyle 转换要写入文件的 JSON
+    fn apply_key_style(&self, value: serde_json::Value) -> Result<serde_json::Value> {
+        match self.config.key_style {
+            Some(style) => translation::apply_key_style(&value, style),
+            None => Ok(value),
+        }
+    }
+
+    /// 文件内容按配置（keyStyle、jsonFormat、normalizeNfc）重新输出的规范形式，key 按字典序排列
+    fn canonical_json(&self, file_path: &Path) -> Result<String> {
+        let value = self.apply_key_style(translation::read_json_file(file_path)?)?;
+        self.to_json_preserving_comments(&value, file_path)
+    }
 
-        // 找出值不同的键（将被更新的键）
-        let mut different_values = Vec::new();
-        for (key, local_value) in &local_map {
-            if let Some(remote_value) = remote_map.get(key) {
-                if local_value != remote_value {
-                    different_values.push((key, local_value, remote_value));
+    /// 列出内容不是规范形式的本地翻译文件
+    pub fn unformatted_files(&self) -> Result<Vec<PathBuf>> {
+        let mut unformatted = Vec::new();
+        for (_, path) in self.local_language_files()? {
+            for (_, path) in translation::namespace_files(&path)? {
+                let canonical = self
+                    .canonical_json(&path)
+                    .with_context(|| format!("无法格式化 {}", path.display()))?;
+                if std::fs::read(&path)? != canonical.as_bytes() {
+                    unformatted.push(path);
                 }
             }
         }
+        unformatted.sort();
+        Ok(unformatted)
+    }
+
+    /// 把本地翻译文件改写为规范形式，返回被修改的文件
+    pub fn format_files(&self) -> Result<Vec<PathBuf>> {
+        let mut formatted = Vec::new();
+        for path in self.unformatted_files()? {
+            let canonical = self.canonical_json(&path)?;
+            self.fs.write(&path, canonical.as_bytes())?;
+            formatted.push(path);
+        }
+        Ok(formatted)
+    }
 
-        // 打印差异信息
-        if !local_only.is_empty() {
-            tracing::info!("语言 {} 中本地独有的键（将被保留）:", lang_code);
-            for key in local_only {
-                tracing::info!("  * {}: {}", key, local_map.get(key).unwrap());
+    /// 把每种语言的单个文件按 key 的第一段拆分为命名空间文件，`merge` 时把命名空间文件合并为单个文件。
+    /// 返回每个文件的 (语言, 原路径, 新路径)，`dry_run` 时只返回不修改
+    pub fn reorganize(&self, merge: bool, dry_run: bool) -> Result<Vec<(String, String, String)>> {
+        let (base_path, files) = self.read_local_translations(None)?;
+        let mut moves = Vec::new();
+        for file in &files {
+            let split = file.relative_path.contains(translation::NAMESPACE_VAR);
+            let target = match (merge, split) {
+                (false, false) => {
+                    translation::split_namespace_path(&file.relative_path, &file.language_code)
+                }
+                (true, true) => translation::merge_namespace_path(&file.relative_path),
+                _ => continue,
+            };
+            // 不覆盖已有的文件
+            for (_, path) in translation::namespace_files(&base_path.join(&target))? {
+                if path.exists() {
+                    return Err(AppError::Validation(format!(
+                        "{} 已存在，无法改写 {}",
+                        path.display(),
+                        file.relative_path
+                    ))
+                    .into());
+                }
             }
+            moves.push((file, target));
         }
 
-        if !remote_only.is_empty() {
-            tracing::info!("语言 {} 中新增的键:", lang_code);
-            for key in remote_only {
-                tracing::info!("  + {}: {}", key, remote_map.get(key).unwrap());
+        let mut result = Vec::new();
+        for (file, target) in moves {
+            if !dry_run {
+                let old_files = translation::namespace_files(&base_path.join(&file.relative_path))?;
+                self.save_translation_file(file, &base_path.join(&target))?;
+                for (_, path) in old_files {
+                    self.fs
+                        .remove_file(&path)
+                        .with_context(|| format!("删除文件 {} 失败", path.display()))?;
+                    // 合并后命名空间文件所在的目录为空时一并删除
+                    if let Some(dir) = path.parent().filter(|_| merge) {
+                        let _ = std::fs::remove_dir(dir);
+                    }
+                }
             }
+            result.push((
+                file.language_code.clone(),
+                file.relative_path.clone(),
+                target,
+            ));
         }
+        Ok(result)
+    }
+
+    /// 添加新的辅助方法来保存翻译文件
+    fn save_translation_file(&self, translation: &TranslationFile, file_path: &Path) -> Result<()> {
+        // 将扁平的键值对转换为嵌套的 JSON 结构
+        self.write_json_files(
+            &translation::unflatten_json(&translation.content)?,
+            file_path,
+            false,
+        )
+    }
+
+    /// 写入一种语言的全部翻译，并按配置的 keyStyle 输出
+    fn write_language_json(&self, value: &serde_json::Value, file_path: &Path) -> Result<()> {
+        self.write_json_files(value, file_path, true)
+    }
 
-        if !different_values.is_empty() {
-            tracing::info!("语言 {} 中将被更新的键:", lang_code);
-            for (key, local_value, remote_value) in different_values {
-                tracing::info!("  ~ {}", key);
-                tracing::info!("    - 当前值: {}", local_value);
-                tracing::info!("    + 新值: {}", remote_value);
+    /// 写入一种语言的全部翻译，保留文件中已有的注释。路径包含 `{namespace}` 时按根节点拆分为多个文件，
+    /// 原本写在 translation.json 中的对象保持原位，写入后不再包含内容的命名空间文件被删除
+    fn write_json_files(
+        &self,
+        value: &serde_json::Value,
+        file_path: &Path,
+        key_style: bool,
+    ) -> Result<()> {
+        let mut stale = Vec::new();
+        let files = if file_path
+            .to_string_lossy()
+            .contains(translation::NAMESPACE_VAR)
+        {
+            let existing = translation::namespace_files(file_path)?;
+            let default_keys: HashSet<String> = match existing
+                .iter()
+                .find(|(namespace, _)| namespace == translation::DEFAULT_NAMESPACE)
+            {
+                Some((_, path)) => match translation::read_json_file(path)? {
+                    serde_json::Value::Object(map) => map.into_iter().map(|(k, _)| k).collect(),
+                    _ => HashSet::new(),
+                },
+                None => HashSet::new(),
+            };
+            let files: Vec<(PathBuf, serde_json::Value)> =
+                translation::split_namespaces_keeping(value, |key| default_keys.contains(key))
+                    .into_iter()
+                    .map(|(namespace, value)| {
+                        (
+                            translation::render_namespace_path(file_path, &namespace),
+                            value,
+                        )
+                    })
+                    .collect();
+            stale = existing
+                .into_iter()
+                .map(|(_, path)| path)
+                .filter(|path| files.iter().all(|(written, _)| written != path))
+                .collect();
+            files
+        } else {
+            vec![(file_path.to_path_buf(), value.clone())]
+        };
+        for (path, value) in files {
+            let value = if key_style {
+                self.apply_key_style(value)?
+            } else {
+                value
+            };
+            if let Some(parent) = path.parent() {
+                self.fs
+                    .create_dir_all(parent)
+                    .with_context(|| format!("创建目录 {} 失败", parent.display()))?;
             }
+            let json = self.to_json_preserving_comments(&value, &path)?;
+            self.fs
+                .write(&path, json.as_bytes())
+                .with_context(|| format!("写入文件 {} 失败", path.display()))?;
+        }
+        for path in stale {
+            self.fs
+                .remove_file(&path)
+                .with_context(|| format!("删除文件 {} 失败", path.display()))?;
+            tracing::info!("Removed empty namespace file {}", path.display());
+        }
+        Ok(())
+    }
+
+    // 修改为实例方法
+    fn print_json_diff(
+        &self,
+        local: &serde_json::Value,
+        remote: &serde_json::Value,
+        lang_code: &str,
+    ) -> DiffCounts {
+        let (counts, lines) = render_json_diff(local, remote, lang_code);
+        for line in lines {
+            tracing::info!("{}", line);
         }
+        counts
     }
 
     // 将方法改为静态方法
@@ -659,39 +3522,245 @@ impl TranslationService {
                     }
                 }
 
-                serde_json::Value::Object(merged)
-            }
-            // 如果远程值是空字符串，保留本地值
-            (local_value, serde_json::Value::String(remote_str))
-                if remote_str.trim().is_empty() =>
-            {
-                local_value.clone()
+                serde_json::Value::Object(merged)
+            }
+            // 如果远程值是空字符串，保留本地值
+            (local_value, serde_json::Value::String(remote_str))
+                if remote_str.trim().is_empty() =>
+            {
+                local_value.clone()
+            }
+            // 其他情况使用远程值
+            (_, remote_value) => remote_value.clone(),
+        }
+    }
+}
+
+/// 统计 JSON 中扁平化后的 key 数量
+/// 有语言上传失败时列出每种语言的结果和重试方式
+fn print_upload_summary(outcomes: &[UploadOutcome]) {
+    let failed: Vec<&str> = outcomes
+        .iter()
+        .filter(|o| o.error.is_some())
+        .map(|o| o.lang.as_str())
+        .collect();
+    if failed.is_empty() {
+        return;
+    }
+    let rows = outcomes.iter().map(|o| {
+        let result = match &o.error {
+            None => "ok".to_string(),
+            Some(e) => format!("failed: {}", e),
+        };
+        vec![o.lang.clone(), o.keys.to_string(), result]
+    });
+    output::table(["Language", "Keys", "Result"], rows);
+    output::status(
+        Status::Failed,
+        format!(
+            "{} of {} languages failed ({}); run `i18n-app push --resume` to retry only the failed languages",
+            failed.len(),
+            outcomes.len(),
+            failed.join(", ")
+        ),
+    );
+}
+
+fn count_keys(value: &serde_json::Value) -> usize {
+    let mut flattened = HashMap::new();
+    flatten_json_inner(value, String::new(), &mut flattened);
+    flattened.len()
+}
+
+/// 本地与远程翻译的差异，按 key 排序，返回差异数量和输出的文本行
+fn render_json_diff(
+    local: &serde_json::Value,
+    remote: &serde_json::Value,
+    lang_code: &str,
+) -> (DiffCounts, Vec<String>) {
+    // 将 JSON 扁平化以便比较
+    let local_map: BTreeMap<String, String> =
+        translation::flatten_json(local).into_iter().collect();
+    let remote_map: BTreeMap<String, String> =
+        translation::flatten_json(remote).into_iter().collect();
+
+    // 本地独有的键（将被保留）
+    let local_only: Vec<(&String, &String)> = local_map
+        .iter()
+        .filter(|(key, _)| !remote_map.contains_key(*key))
+        .collect();
+    // 远程有但本地没有的键（新增的键）
+    let remote_only: Vec<(&String, &String)> = remote_map
+        .iter()
+        .filter(|(key, _)| !local_map.contains_key(*key))
+        .collect();
+    // 值不同的键（将被更新的键）
+    let different_values: Vec<(&String, &String, &String)> = local_map
+        .iter()
+        .filter_map(|(key, local_value)| {
+            let remote_value = remote_map.get(key)?;
+            (local_value != remote_value).then_some((key, local_value, remote_value))
+        })
+        .collect();
+
+    let counts = DiffCounts {
+        local_only: local_only.len(),
+        remote_only: remote_only.len(),
+        changed: different_values.len(),
+    };
+
+    let mut lines = Vec::new();
+    if !local_only.is_empty() {
+        lines.push(format!("语言 {} 中本地独有的键（将被保留）:", lang_code));
+        for (key, value) in local_only {
+            lines.push(format!("  * {}: {}", key, value));
+        }
+    }
+    if !remote_only.is_empty() {
+        lines.push(format!("语言 {} 中新增的键:", lang_code));
+        for (key, value) in remote_only {
+            lines.push(format!("  + {}: {}", key, value));
+        }
+    }
+    if !different_values.is_empty() {
+        lines.push(format!("语言 {} 中将被更新的键:", lang_code));
+        for (key, local_value, remote_value) in different_values {
+            lines.push(format!("  ~ {}", key));
+            lines.push(format!("    - 当前值: {}", local_value));
+            lines.push(format!("    + 新值: {}", remote_value));
+        }
+    }
+    (counts, lines)
+}
+
+/// 本地与远程翻译差异的数量统计
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DiffCounts {
+    local_only: usize,
+    remote_only: usize,
+    changed: usize,
+}
+
+impl DiffCounts {
+    fn is_empty(&self) -> bool {
+        self.local_only == 0 && self.remote_only == 0 && self.changed == 0
+    }
+}
+
+/// 按基准语言文件的位置推导新语言文件的路径，如 `locales/en-US.json` -> `locales/zh-CN.json`
+/// 冲突文件与目标文件放在同一目录，如 `locales/en.conflicts.json`
+fn conflicts_file(target_path: &Path, lang_code: &str) -> PathBuf {
+    target_path.with_file_name(format!(
+        "{}{}",
+        lang_code,
+        translation::CONFLICTS_FILE_SUFFIX
+    ))
+}
+
+fn display_value(value: &Option<serde_json::Value>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "(deleted)".to_string())
+}
+
+/// 交互式选择冲突的处理方式，非交互环境中视为未解决
+fn prompt_conflict(lang_code: &str, conflict: &merge::Conflict) -> Result<merge::Resolution> {
+    if !console::user_attended() {
+        return Ok(merge::Resolution::Unresolved);
+    }
+    let term = console::Term::stdout();
+    term.write_line(&format!(
+        "{}: conflict on {}\n  base:   {}\n  local:  {}\n  remote: {}",
+        lang_code,
+        conflict.key,
+        display_value(&conflict.base),
+        display_value(&conflict.local),
+        display_value(&conflict.remote)
+    ))?;
+    term.write_str("Keep [l]ocal, take [r]emote or [s]kip? ")?;
+    Ok(match term.read_line()?.trim() {
+        "l" | "L" => merge::Resolution::Local,
+        "r" | "R" => merge::Resolution::Remote,
+        _ => merge::Resolution::Unresolved,
+    })
+}
+
+/// 离开作用域时删除的临时文件，下载被取消、future 被丢弃时也会清理
+struct TempFiles<'a> {
+    fs: &'a dyn FileSystem,
+    paths: [PathBuf; 2],
+}
+
+impl Drop for TempFiles<'_> {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            if self.fs.exists(path) {
+                let _ = self.fs.remove_file(path);
             }
-            // 其他情况使用远程值
-            (_, remote_value) => remote_value.clone(),
         }
     }
+}
+
+/// 只有非致命的接口错误跳过当前语言，其他错误（取消、写文件失败等）中止整个操作
+fn is_skippable_download_error(e: &anyhow::Error) -> bool {
+    matches!(e.downcast_ref::<ApiError>(), Some(api_error) if !api_error.is_fatal())
+}
 
-    // 改为公共方法
-    pub fn init_log_file() -> Result<File> {
-        let log_dir = PathBuf::from(".i18n-app");
-        fs::create_dir_all(&log_dir)?;
-        let log_file = log_dir.join("run.log");
-        let file = File::create(log_file)?;
-        Ok(file)
+/// include 模板包含 `{lang}` 时替换路径中对应的部分，如 `locales/en/app.json` -> `locales/zh-CN/app.json`
+fn missing_language_path(include: &[String], base_relative_path: &str, lang_code: &str) -> PathBuf {
+    if let Some(path) = include.iter().find_map(|pattern| {
+        translation::replace_path_var(
+            pattern,
+            base_relative_path,
+            translation::LANG_VAR,
+            lang_code,
+        )
+    }) {
+        return PathBuf::from(path);
     }
+    let base = Path::new(base_relative_path);
+    let file_name = match base.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}.{}", lang_code, ext),
+        None => lang_code.to_string(),
+    };
+    base.with_file_name(file_name)
+}
+
+/// 解析服务器返回的修改时间：RFC 3339 字符串，或秒/毫秒时间戳
+fn parse_change_time(value: &serde_json::Value) -> Option<DateTime<FixedOffset>> {
+    let timestamp = match value {
+        serde_json::Value::String(text) => {
+            if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+                return Some(time);
+            }
+            text.parse::<i64>().ok()?
+        }
+        serde_json::Value::Number(number) => number.as_i64()?,
+        _ => return None,
+    };
+    // 超过 10^11 的按毫秒处理（秒级时间戳要到 5138 年才会达到）
+    let millis = if timestamp > 100_000_000_000 {
+        timestamp
+    } else {
+        timestamp * 1000
+    };
+    DateTime::from_timestamp_millis(millis).map(|time| time.fixed_offset())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{LineEnding, PlaceholderStyleRule};
+    use crate::test_util;
+    use crate::vfs::MemoryFs;
     use serde_json::json;
     use tempfile::TempDir;
 
     fn create_test_service() -> TranslationService {
         let config = Config {
             host: "https://test.com".to_string(),
-            sub_system_name: "test".to_string(),
+            sub_systems: vec!["test".to_string()],
             product_code: "test".to_string(),
             version_no: "1.0.0".to_string(),
             base_language: "en-US".to_string(),
@@ -699,6 +3768,7 @@ mod tests {
             path_prefix: "test".to_string(),
             include: vec![],
             exclude: vec![],
+            ..Default::default()
         };
         TranslationService::new(config)
     }
@@ -766,14 +3836,16 @@ mod tests {
         let temp_dir = TempDir::new()?;
         let file_path = temp_dir.path().join("test.json");
 
-        let mut content = HashMap::new();
-        content.insert("common.time.tomorrow".to_string(), "Tomorrow".to_string());
-        content.insert("common.time.today".to_string(), "Today".to_string());
+        let content = translation::to_content([
+            ("common.time.tomorrow", "Tomorrow"),
+            ("common.time.today", "Today"),
+        ]);
 
         let translation = TranslationFile {
             language_code: "en-US".to_string(),
             relative_path: "test.json".to_string(),
             content,
+            ..Default::default()
         };
 
         service.save_translation_file(&translation, &file_path)?;
@@ -788,6 +3860,271 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_read_local_translations_base_path() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let fixtures = temp_dir.path().join("fixtures");
+        std::fs::create_dir_all(&fixtures)?;
+        std::fs::write(fixtures.join("en-US.json"), r#"{"a": "A"}"#)?;
+        let root = temp_dir.path().to_str().unwrap().to_string();
+
+        // 项目中真的有 fixtures 目录时，include 相对于 --path 原样匹配
+        let mut service = create_test_service();
+        service.config.include = vec!["fixtures/*.json".to_string()];
+        let (base_path, files) = service.read_local_translations(Some(root.clone()))?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            service.get_full_path(&files[0], &base_path),
+            fixtures.join("en-US.json").display().to_string()
+        );
+
+        // 配置 basePath 时 include 相对于该目录，--path 优先于配置
+        service.config.base_path = Some(fixtures.to_str().unwrap().to_string());
+        service.config.include = vec!["*.json".to_string()];
+        let (base_path, files) = service.read_local_translations(None)?;
+        assert_eq!(base_path, fixtures);
+        assert_eq!(files[0].relative_path, "en-US.json");
+        let (_, files) = service.read_local_translations(Some(root))?;
+        assert!(files.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_and_get_local_value() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("de-DE.json");
+        std::fs::write(
+            &path,
+            "{\n  // 结算页\n  \"checkout\": {\"title\": \"Kasse\"}\n}\n",
+        )?;
+        std::fs::write(
+            temp_dir.path().join("en-US.json"),
+            r#"{"checkout": {"title": "Checkout"}}"#,
+        )?;
+        let mut service = create_test_service();
+        service.config.base_path = Some(temp_dir.path().to_str().unwrap().to_string());
+        service.config.include = vec!["*.json".to_string()];
+
+        let previous = service.set_local_value("de-DE", "checkout.title", "Zur Kasse")?;
+        assert_eq!(previous.as_deref(), Some("Kasse"));
+        assert_eq!(
+            service.set_local_value("de-DE", "checkout.note", "Hinweis")?,
+            None
+        );
+        assert!(service
+            .set_local_value("fr-FR", "checkout.title", "Caisse")
+            .is_err());
+        let text = std::fs::read_to_string(&path)?;
+        assert!(text.contains("// 结算页"));
+
+        let values: Vec<(String, String)> = service
+            .local_values("checkout.title")?
+            .into_iter()
+            .map(|(lang, value)| (lang, value.to_string()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("de-DE".to_string(), "Zur Kasse".to_string()),
+                ("en-US".to_string(), "Checkout".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_local_value_picks_file_holding_key() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        for (path, content) in [
+            (
+                "home/en-US.json",
+                r#"{"home": {"title": "Home", "intro": "Hi"}}"#,
+            ),
+            (
+                "settings/en-US.json",
+                r#"{"settings": {"title": "Settings"}}"#,
+            ),
+            ("home/de.json", r#"{"home": {"title": "Start"}}"#),
+            ("settings/de.json", r#"{"settings": {"title": "Optionen"}}"#),
+        ] {
+            std::fs::create_dir_all(root.join(path).parent().unwrap())?;
+            std::fs::write(root.join(path), content)?;
+        }
+        let mut service = create_test_service();
+        service.config.base_path = Some(root.display().to_string());
+        service.config.include = vec!["*/*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = service.with_fs(fs.clone());
+        let read = |path: &str| -> Result<serde_json::Value> {
+            Ok(serde_json::from_str(&fs.read_to_string(&root.join(path))?)?)
+        };
+
+        // 修改已有这个 key 的文件，而不是第一个 de 文件
+        service.set_local_value("de", "settings.title", "Einstellungen")?;
+        assert_eq!(
+            read("settings/de.json")?,
+            json!({"settings": {"title": "Einstellungen"}})
+        );
+        assert!(!fs.exists(&root.join("home/de.json")));
+        // 缺少的 key 写入与基准语言文件对应的文件
+        service.set_local_value("de", "home.intro", "Hallo")?;
+        assert_eq!(
+            read("home/de.json")?,
+            json!({"home": {"title": "Start", "intro": "Hallo"}})
+        );
+        assert!(service.has_local_key("de", "home.intro")?);
+        assert!(!service.has_local_key("de", "other")?);
+        // 新 key 无法确定写入哪个文件
+        assert!(service.set_local_value("de", "other", "X").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reorganize() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let locales = temp_dir.path().join("locales");
+        std::fs::create_dir_all(&locales)?;
+        for lang in ["en-US", "zh-CN"] {
+            std::fs::write(
+                locales.join(format!("{}.json", lang)),
+                r#"{"common": {"save": "Save"}, "home": {"title": "Home"}, "app": "App"}"#,
+            )?;
+        }
+        let mut service = create_test_service();
+        service.config.base_path = Some(temp_dir.path().to_str().unwrap().to_string());
+        service.config.include = vec!["locales/*.json".to_string()];
+
+        let moves = service.reorganize(false, true)?;
+        assert_eq!(moves.len(), 2);
+        assert!(locales.join("en-US.json").exists());
+
+        service.reorganize(false, false)?;
+        assert!(!locales.join("en-US.json").exists());
+        let common: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(locales.join("zh-CN/common.json"))?)?;
+        assert_eq!(common, json!({"save": "Save"}));
+        assert!(locales.join("zh-CN/translation.json").exists());
+
+        service.config.include = vec!["locales/{lang}/{namespace}.json".to_string()];
+        let (_, files) = service.read_local_translations(None)?;
+        assert_eq!(files.len(), 2);
+        assert_eq!(&*files[0].content["home.title"], "Home");
+        assert_eq!(&*files[0].content["app"], "App");
+
+        // 合并回每种语言一个文件
+        let moves = service.reorganize(true, false)?;
+        assert_eq!(moves[0].2, format!("locales/{}.json", moves[0].0));
+        assert!(!locales.join("en-US").exists());
+        let merged: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(locales.join("en-US.json"))?)?;
+        assert_eq!(merged["home"]["title"], "Home");
+        assert_eq!(merged["app"], "App");
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(
+            temp_dir.path().join("en-US.json"),
+            r#"{"save": "Save changes", "title": "Home", "ok": "OK"}"#,
+        )?;
+        std::fs::write(
+            temp_dir.path().join("de.json"),
+            r#"{"save": "Änderungen speichern", "title": "__MISSING__", "ok": "OK"}"#,
+        )?;
+        let mut service = create_test_service();
+        service.config.base_path = Some(temp_dir.path().to_str().unwrap().to_string());
+        service.config.include = vec!["*.json".to_string()];
+        service.config.identical_as_untranslated = true;
+        service.config.word_rates = HashMap::from([("de".to_string(), 0.5)]);
+
+        let estimates = service.estimate(&[], None)?;
+        assert_eq!(estimates.len(), 1);
+        // title 是 fillMissingKeys 的占位文本，ok 与基准文本相同
+        assert_eq!((estimates[0].keys, estimates[0].words), (2, 2));
+        assert_eq!(estimates[0].cost(), 1.0);
+
+        // 本地没有文件的语言按全部 key 计算，没有单价时报错
+        let langs = vec!["ja".to_string()];
+        assert!(service.estimate(&langs, None).is_err());
+        let estimates = service.estimate(&langs, Some(0.1))?;
+        assert_eq!((estimates[0].keys, estimates[0].words), (3, 4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_empty_base_values() {
+        let mut service = create_test_service();
+        let base = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            HashMap::from([
+                ("title".to_string(), "Title".to_string()),
+                ("subtitle".to_string(), "".to_string()),
+            ]),
+        );
+
+        let check = |service: &TranslationService| {
+            service.check_empty_base_values(&base, service.config.empty_base_values.severity())
+        };
+        assert!(check(&service).is_ok());
+        service.config.empty_base_values = EmptyBasePolicy::Error;
+        let err = check(&service).unwrap_err();
+        assert!(err.to_string().contains("subtitle"));
+        service.config.empty_base_values = EmptyBasePolicy::Allow;
+        assert!(check(&service).is_ok());
+    }
+
+    #[test]
+    fn test_canonical_json() -> Result<()> {
+        let service = create_test_service();
+        let temp_dir = TempDir::new()?;
+        let file_path = temp_dir.path().join("en-US.json");
+        std::fs::write(
+            &file_path,
+            "{\r\n    \"b\": \"B\", // note\r\n    \"a\": {\"n\": 1.50}\r\n}",
+        )?;
+
+        assert_eq!(
+            service.canonical_json(&file_path)?,
+            "{\n  \"a\": {\n    \"n\": 1.5\n  },\n  \"b\": \"B\" // note\n}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_format() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let en_us = temp_dir.path().join("en-US.json");
+        let de = temp_dir.path().join("de.json");
+        std::fs::write(&en_us, r#"{"save": "Save"}"#)?;
+        std::fs::write(&de, "{\r\n    \"save\": \"Speichern\"\r\n}")?;
+        let mut service = create_test_service();
+        service.config.base_path = Some(temp_dir.path().display().to_string());
+        service.config.include = vec!["*.json".to_string()];
+        std::fs::write(&en_us, service.canonical_json(&en_us)?)?;
+
+        assert_eq!(service.unformatted_files()?, vec![de.clone()]);
+        let format_only = CheckOptions::new(false, false, false, false, true);
+        let err = service.check(&format_only).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::Validation(_))
+        ));
+
+        // 格式化后输出与平台无关，再次检查通过
+        assert_eq!(service.format_files()?, vec![de.clone()]);
+        assert!(!std::fs::read_to_string(&de)?.contains('\r'));
+        service.check(&format_only)?;
+
+        // 配置 CRLF 后 LF 文件不再是规范格式
+        service.config.json_format.line_ending = LineEnding::Crlf;
+        assert_eq!(service.unformatted_files()?, vec![de, en_us]);
+        Ok(())
+    }
+
     #[test]
     fn test_print_json_diff() {
         let service = create_test_service();
@@ -810,20 +4147,903 @@ mod tests {
             }
         });
 
-        // 这个测试主要是确保方法不会崩溃，因为它只是打印日志
-        service.print_json_diff(&local, &remote, "en-US");
+        let counts = service.print_json_diff(&local, &remote, "en-US");
+        assert_eq!(
+            counts,
+            DiffCounts {
+                local_only: 1,
+                remote_only: 1,
+                changed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pull_merge_and_diff_golden() {
+        for (case, dir) in test_util::fixture_cases("merge") {
+            let local = test_util::read_fixture(dir.join("local.json"));
+            let remote = test_util::read_fixture(dir.join("remote.json"));
+            let merged = TranslationService::merge_json_content(&local, &remote);
+            insta::assert_json_snapshot!(format!("pull_merge_{}", case), merged);
+
+            let (_, lines) = render_json_diff(&local, &remote, "en-US");
+            insta::assert_snapshot!(format!("diff_{}", case), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn test_missing_language_path() {
+        let include = vec!["fixtures/*.json".to_string()];
+        assert_eq!(
+            missing_language_path(&include, "fixtures/en-US.json", "zh-CN"),
+            PathBuf::from("fixtures/zh-CN.json")
+        );
+        assert_eq!(
+            missing_language_path(&include, "en-US.json", "th-TH"),
+            PathBuf::from("th-TH.json")
+        );
+
+        let include = vec!["locales/{lang}/{namespace}.json".to_string()];
+        assert_eq!(
+            missing_language_path(&include, "locales/en/{namespace}.json", "zh-CN"),
+            PathBuf::from("locales/zh-CN/{namespace}.json")
+        );
+    }
+
+    #[test]
+    fn test_protected_keys_keep_local_values() {
+        let mut service = create_test_service();
+        service.config.protected_keys = vec!["legal.*".to_string()];
+
+        let local = json!({"legal": {"terms": "Approved terms"}, "home": "Home"});
+        let remote = json!({
+            "legal": {"terms": "Changed terms", "privacy": "Privacy"},
+            "home": "Home updated"
+        });
+
+        let remote = service.skip_protected_keys("en-US", &local, &remote);
+        let merged = TranslationService::merge_json_content(&local, &remote);
+        assert_eq!(merged["legal"]["terms"], "Approved terms");
+        // 本地没有的受保护 key 仍然可以从服务器同步
+        assert_eq!(merged["legal"]["privacy"], "Privacy");
+        assert_eq!(merged["home"], "Home updated");
+    }
+
+    #[test]
+    fn test_remove_ignored_keys() {
+        let mut service = create_test_service();
+        service.config.ignored_keys = vec!["debug.*".to_string(), "internal.*".to_string()];
+
+        let mut translation = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            HashMap::from([
+                ("debug.panel".to_string(), "Debug".to_string()),
+                ("internal.flag".to_string(), "Flag".to_string()),
+                ("home.title".to_string(), "Home".to_string()),
+            ]),
+        );
+        service.remove_ignored_keys(&mut translation);
+        assert_eq!(translation.content.len(), 1);
+        assert!(translation.content.contains_key("home.title"));
+    }
+
+    #[test]
+    fn test_placeholder_styles() {
+        let mut service = create_test_service();
+        service.config.placeholder_styles = vec![PlaceholderStyleRule {
+            pattern: "ios/{lang}.json".to_string(),
+            style: PlaceholderStyle::Ios,
+        }];
+        let remote_base =
+            HashMap::from([("greeting".to_string(), "Hi {name}, {count} new".to_string())]);
+        let remote = json!({"greeting": "{count} nouveaux, {name}"});
+
+        let local = service.to_local_placeholders("ios/fr-FR.json", &remote, &remote_base);
+        assert_eq!(local["greeting"], "%2$@ nouveaux, %1$@");
+        // 没有匹配规则的文件不转换
+        assert_eq!(
+            service.to_local_placeholders("web/fr-FR.json", &remote, &remote_base),
+            remote
+        );
+
+        let mut translation = TranslationFile::from_content(
+            "fr-FR".to_string(),
+            "ios/fr-FR.json".to_string(),
+            translation::flatten_json(&local),
+        );
+        let base = TranslationFile::from_content(
+            "en-US".to_string(),
+            "ios/en-US.json".to_string(),
+            remote_base,
+        );
+        service.to_server_placeholders(&mut translation, Some(&base));
+        assert_eq!(
+            &*translation.content["greeting"],
+            "{count} nouveaux, {name}"
+        );
+    }
+
+    #[test]
+    fn test_confirm_protected_push() {
+        let mut service = create_test_service();
+        service.config.protected_environments = vec!["prod".to_string()];
+        assert!(service.confirm_protected_push(3, false).is_ok());
+
+        service.config.environment = Some("prod".to_string());
+        assert!(service.confirm_protected_push(3, true).is_ok());
+        // 测试中没有终端，无法确认时拒绝推送
+        let err = service.confirm_protected_push(3, false).unwrap_err();
+        assert!(err.to_string().contains("--yes-production"));
+    }
+
+    #[test]
+    fn test_parse_change_time() {
+        let expected = DateTime::parse_from_rfc3339("2024-01-01T00:00:00+00:00").ok();
+        assert_eq!(
+            parse_change_time(&json!("2024-01-01T08:00:00+08:00")),
+            expected
+        );
+        assert_eq!(parse_change_time(&json!(1704067200000_i64)), expected);
+        assert_eq!(parse_change_time(&json!("1704067200")), expected);
+        assert_eq!(parse_change_time(&json!("yesterday")), None);
+    }
+
+    #[tokio::test]
+    async fn test_promote_from() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let polling = "/api/At.Locazy/user/i18n/long-polling";
+        let files = |version: &str| {
+            format!(
+                r#"{{"code":0,"data":{{"files":[{{"lang":"de","url":"{}/{}/de.json"}}]}}}}"#,
+                server.url(),
+                version
+            )
+        };
+        for (version, body) in [("1.4.0", files("1.4.0")), ("1.5.0", files("1.5.0"))] {
+            server
+                .mock("POST", polling)
+                .match_body(mockito::Matcher::PartialJson(
+                    json!({ "versionNo": version }),
+                ))
+                .with_body(body)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", "/1.4.0/de.json")
+            .with_body(r#"{"test/languages": {"checkout": {"pay": "Bezahlen", "back": "Zurück"}, "home": "Start"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/1.5.0/de.json")
+            .with_body(r#"{"test/languages": {"checkout": {"pay": "Zahlen", "back": "Zurück"}}}"#)
+            .create_async()
+            .await;
+
+        let mut service = create_test_service();
+        service.config.host = server.url();
+        // 下载的临时文件写入内存文件系统，不在项目中留下缓存目录
+        let fs = Arc::new(MemoryFs::new());
+        let source =
+            TranslationService::new(service.config.for_version("1.4.0")).with_fs(fs.clone());
+        let target =
+            TranslationService::new(service.config.for_version("1.5.0")).with_fs(fs.clone());
+        let keys = vec!["checkout.*".to_string()];
+
+        // 只复制匹配且值不同的 key
+        let summary = target.promote_from(&source, &keys, true, false).await?;
+        assert_eq!(summary, vec![("de".to_string(), 1)]);
+        let summary = target.promote_from(&source, &[], true, false).await?;
+        assert_eq!(summary, vec![("de".to_string(), 2)]);
+        assert!(fs.paths().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_rejects_content_not_matching_schema() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let schema_path = temp_dir.path().join("schema.json");
+        std::fs::write(
+            &schema_path,
+            r#"{"type": "object", "additionalProperties": {"type": "object"}}"#,
+        )?;
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(format!(
+                r#"{{"code":0,"data":{{"files":[{{"lang":"en-US","url":"{0}/en-US.json"}},{{"lang":"de","url":"{0}/de.json"}}]}}}}"#,
+                server.url()
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/en-US.json")
+            .with_body(r#"{"test/languages": {"home": {"title": "Home"}}}"#)
+            .create_async()
+            .await;
+        // 嵌套结构被服务器错误地返回为扁平的 key
+        server
+            .mock("GET", "/de.json")
+            .with_body(r#"{"test/languages": {"home.title": "Start"}}"#)
+            .create_async()
+            .await;
+
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.translation_schema = Some(schema_path.display().to_string());
+        let service = TranslationService::new(config);
+        let target = temp_dir.path().join("out");
+        let result = service
+            .download_translations(Some(target.display().to_string()), true)
+            .await;
+
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<AppError>(),
+            Some(AppError::Validation(_))
+        ));
+        assert!(error.to_string().contains("de"));
+        assert!(target.join("en-US.json").exists());
+        assert!(!target.join("de.json").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_push_refreshes_only_invalid_cache_entries() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        std::fs::write(temp_dir.path().join("de.json"), r#"{"save": "Speichern"}"#)?;
+
+        let mut server = mockito::Server::new_async().await;
+        let files = format!(
+            r#"{{"code":0,"data":{{"taskHash":"v1","files":[{{"lang":"en-US","url":"{0}/en.json"}},{{"lang":"de","url":"{0}/de.json"}}]}}}}"#,
+            server.url()
+        );
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(files)
+            .create_async()
+            .await;
+        let en = server
+            .mock("GET", "/en.json")
+            .with_body(r#"{"test/languages": {"save": "Save"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let de = server
+            .mock("GET", "/de.json")
+            .with_body(r#"{"test/languages": {"save": "Speichern"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.sub_systems = vec!["cache-manifest-test".to_string()];
+        config.include = vec!["*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = TranslationService::new(config).with_fs(fs.clone());
+        let path = Some(temp_dir.path().display().to_string());
+
+        service
+            .push_translations(path.clone(), PushOptions::default())
+            .await?;
+        // 服务器版本没有变化时只重新下载校验和不一致的语言
+        fs.write(&service.cached_language_path("de"), br#"{"save": "Spei"#)?;
+        assert_eq!(
+            service
+                .push_translations(path, PushOptions::default())
+                .await?,
+            0
+        );
+        en.assert_async().await;
+        de.assert_async().await;
+        assert_eq!(
+            fs.read_to_string(&service.cached_language_path("de"))?,
+            r#"{"save":"Speichern"}"#
+        );
+        // 缓存和清单都写入了内存文件系统，下载用的临时文件已删除
+        assert!(!service.cache_dir().exists());
+        assert!(fs.exists(&cache::manifest_file()));
+        assert!(!fs.paths().iter().any(|path| {
+            let path = path.to_string_lossy();
+            path.ends_with(".download") || path.ends_with(".part")
+        }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_push_keeps_remaining_uploads_in_journal() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        std::fs::write(temp_dir.path().join("de.json"), r#"{"save": "Speichern"}"#)?;
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(r#"{"code":0,"data":{"files":[]}}"#)
+            .create_async()
+            .await;
+        // 第二种语言上传期间按下 Ctrl-C
+        let cancel = CancellationToken::new();
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let upload = {
+            let cancel = cancel.clone();
+            let requests = requests.clone();
+            server
+                .mock("POST", "/api/At.Locazy/cli/terms/upload")
+                .with_body_from_request(move |_| {
+                    if requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 1 {
+                        cancel.cancel();
+                    }
+                    br#"{"code":0,"message":"success","data":{"success":true}}"#.to_vec()
+                })
+                .expect(2)
+                .create_async()
+                .await
+        };
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.upload_concurrency = 1;
+        config.include = vec!["*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = TranslationService::new(config)
+            .with_fs(fs.clone())
+            .with_cancellation(cancel);
+
+        let err = service
+            .push_translations(
+                Some(temp_dir.path().display().to_string()),
+                PushOptions::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::Cancelled)
+        ));
+        upload.assert_async().await;
+
+        // 第一种语言已经完成，被取消的语言留在上传计划中，--resume 时重新上传
+        let journal = PushJournal::load(fs.as_ref(), &journal::journal_file())?;
+        let pending = journal.get(&service.config).expect("journal entry");
+        assert_eq!(pending.uploads.len(), 2);
+        assert_eq!(pending.remaining(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_pull_writes_nothing() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        std::fs::write(temp_dir.path().join("de.json"), r#"{"save": "Speichern"}"#)?;
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(format!(
+                r#"{{"code":0,"data":{{"taskHash":"h1","files":[{{"lang":"en-US","url":"{0}/en-US.json"}},{{"lang":"de","url":"{0}/de.json"}}]}}}}"#,
+                server.url()
+            ))
+            .create_async()
+            .await;
+        // 第一种语言（de）下载期间按下 Ctrl-C
+        let cancel = CancellationToken::new();
+        let de = {
+            let cancel = cancel.clone();
+            server
+                .mock("GET", "/de.json")
+                .with_body_from_request(move |_| {
+                    cancel.cancel();
+                    br#"{"test/languages": {"save": "Speichern!"}}"#.to_vec()
+                })
+                .create_async()
+                .await
+        };
+        let en = server
+            .mock("GET", "/en-US.json")
+            .expect(0)
+            .create_async()
+            .await;
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.upload_concurrency = 1;
+        config.base_path = Some(temp_dir.path().display().to_string());
+        config.include = vec!["*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = TranslationService::new(config)
+            .with_fs(fs.clone())
+            .with_cancellation(cancel);
+
+        let err = service
+            .sync_translations(PullOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::Cancelled)
+        ));
+        de.assert_async().await;
+        en.assert_async().await;
+        // 已经下载的语言也不写入，缓存、快照和本地文件都没有变化
+        assert!(fs.paths().is_empty());
+        assert_eq!(service.synced_task_hash()?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pull_skips_unchanged_task_hash_unless_forced() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        // 还没有 push 过的本地语言不影响保存 taskHash
+        std::fs::write(
+            temp_dir.path().join("fr.json"),
+            r#"{"save": "Enregistrer"}"#,
+        )?;
+
+        let mut server = mockito::Server::new_async().await;
+        let polling = server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(format!(
+                r#"{{"code":0,"data":{{"taskHash":"h1","files":[{{"lang":"en-US","url":"{0}/en-US.json"}}]}}}}"#,
+                server.url()
+            ))
+            .expect(3)
+            .create_async()
+            .await;
+        let en = server
+            .mock("GET", "/en-US.json")
+            .with_body(r#"{"test/languages": {"save": "Save"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.base_path = Some(temp_dir.path().display().to_string());
+        config.include = vec!["*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = TranslationService::new(config).with_fs(fs.clone());
+
+        service.sync_translations(PullOptions::default()).await?;
+        assert_eq!(service.synced_task_hash()?.as_deref(), Some("h1"));
+        // taskHash 没有变化时不再下载
+        service.sync_translations(PullOptions::default()).await?;
+        // --force 忽略记录的 taskHash
+        service
+            .sync_translations(PullOptions {
+                force: true,
+                ..Default::default()
+            })
+            .await?;
+        polling.assert_async().await;
+        en.assert_async().await;
+        assert!(fs.exists(&sync_state::sync_state_file()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_routes_keys_by_layout() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        for dir in ["home", "settings"] {
+            std::fs::create_dir_all(root.join(dir))?;
+        }
+        std::fs::write(
+            root.join("home/en-US.json"),
+            r#"{"home": {"title": "Home"}}"#,
+        )?;
+        std::fs::write(
+            root.join("settings/en-US.json"),
+            r#"{"settings": {"title": "Settings"}}"#,
+        )?;
+        std::fs::write(root.join("home/de.json"), r#"{"home": {"title": "Start"}}"#)?;
+        let delivery = root.join("delivery.json");
+        std::fs::write(
+            &delivery,
+            r#"{"home.title": "Startseite", "settings.title": "Einstellungen", "other": "X"}"#,
+        )?;
+
+        let mut service = create_test_service();
+        service.config.base_path = Some(root.display().to_string());
+        service.config.include = vec!["*/*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = service.with_fs(fs.clone());
+
+        let unknown = service.import("de", &delivery, true)?;
+        assert_eq!(unknown, BTreeSet::from(["other".to_string()]));
+        // 已有的 key 写回原来的文件，新 key 按布局写入与基准语言文件对应的新文件
+        let home: serde_json::Value =
+            serde_json::from_slice(&fs.read(&root.join("home/de.json"))?)?;
+        assert_eq!(home, serde_json::json!({"home": {"title": "Startseite"}}));
+        let settings: serde_json::Value =
+            serde_json::from_slice(&fs.read(&root.join("settings/de.json"))?)?;
+        assert_eq!(
+            settings,
+            serde_json::json!({"settings": {"title": "Einstellungen"}})
+        );
+        let reviews = ReviewStore::load(fs.as_ref())?;
+        assert_eq!(
+            reviews.state("test", "de", "settings.title"),
+            ReviewState::NeedsReview
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_only_needs_review() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        std::fs::write(
+            root.join("en-US.json"),
+            r#"{"save": "Save", "cancel": "Cancel"}"#,
+        )?;
+        std::fs::write(
+            root.join("de.json"),
+            r#"{"save": "Speichern", "cancel": "Abbrechen"}"#,
+        )?;
+
+        let mut service = create_test_service();
+        service.config.base_path = Some(root.display().to_string());
+        service.config.include = vec!["*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = service.with_fs(fs.clone());
+        service.review_set("de", ReviewState::NeedsReview, &["save".to_string()], false)?;
+
+        let path = root.join("request.po");
+        service.export("de", &path, Some(ReviewState::NeedsReview))?;
+        let po = std::fs::read_to_string(&path)?;
+        assert!(po.contains("Speichern"));
+        assert!(!po.contains("Abbrechen"));
+
+        service.export("de", &path, None)?;
+        assert!(std::fs::read_to_string(&path)?.contains("Abbrechen"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_push_overwrites_only_recorded_changes() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(
+            temp_dir.path().join("en-US.json"),
+            r#"{"save": "Save", "open": "Open"}"#,
+        )?;
+        std::fs::write(
+            temp_dir.path().join("de.json"),
+            r#"{"save": "Sichern", "open": "Aufmachen"}"#,
+        )?;
+
+        let mut server = mockito::Server::new_async().await;
+        let files = format!(
+            r#"{{"code":0,"data":{{"taskHash":"v1","files":[{{"lang":"en-US","url":"{0}/en.json"}},{{"lang":"de","url":"{0}/de.json"}}]}}}}"#,
+            server.url()
+        );
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(files)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/en.json")
+            .with_body(r#"{"test/languages": {"save": "Save", "open": "Open"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/de.json")
+            .with_body(r#"{"test/languages": {"save": "Speichern", "open": "Öffnen"}}"#)
+            .create_async()
+            .await;
+        let bodies = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let upload = {
+            let bodies = bodies.clone();
+            server
+                .mock("POST", "/api/At.Locazy/cli/terms/upload")
+                .with_body_from_request(move |request| {
+                    let body = String::from_utf8_lossy(request.body().unwrap()).to_string();
+                    bodies.lock().unwrap().push(body);
+                    br#"{"code":0,"message":"success","data":{"success":true}}"#.to_vec()
+                })
+                .expect(1)
+                .create_async()
+                .await
+        };
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.sub_systems = vec!["pending-push-test".to_string()];
+        config.include = vec!["*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = TranslationService::new(config).with_fs(fs.clone());
+
+        // set --push 只记录修改的 key，其他与服务器不同的值不会被覆盖
+        service.record_pending_changes("de", ["save"])?;
+        service
+            .push_translations(
+                Some(temp_dir.path().display().to_string()),
+                PushOptions::default(),
+            )
+            .await?;
+        upload.assert_async().await;
+        let bodies = bodies.lock().unwrap();
+        assert!(bodies[0].contains("Sichern"));
+        assert!(!bodies[0].contains("Aufmachen"));
+        // 推送成功后清除记录
+        assert!(!fs.exists(&pending::pending_file()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_push_overwrite_changed_uploads_changed_values() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        std::fs::write(temp_dir.path().join("de.json"), r#"{"save": "Sichern"}"#)?;
+
+        let mut server = mockito::Server::new_async().await;
+        let files = format!(
+            r#"{{"code":0,"data":{{"taskHash":"v1","files":[{{"lang":"en-US","url":"{0}/en.json"}},{{"lang":"de","url":"{0}/de.json"}}]}}}}"#,
+            server.url()
+        );
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(files)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/en.json")
+            .with_body(r#"{"test/languages": {"save": "Save"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/de.json")
+            .with_body(r#"{"test/languages": {"save": "Speichern"}}"#)
+            .create_async()
+            .await;
+        let upload = server
+            .mock("POST", "/api/At.Locazy/cli/terms/upload")
+            .match_body(mockito::Matcher::Regex("Sichern".to_string()))
+            .with_body(r#"{"code":0,"message":"success","data":{"success":true}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.sub_systems = vec!["overwrite-changed-test".to_string()];
+        config.include = vec!["*.json".to_string()];
+        let service = TranslationService::new(config).with_fs(Arc::new(MemoryFs::new()));
+        let path = Some(temp_dir.path().display().to_string());
+
+        // 默认只记录值不同的 key，不上传
+        assert_eq!(
+            service
+                .push_translations(path.clone(), PushOptions::default())
+                .await?,
+            0
+        );
+        let options = PushOptions {
+            overwrite_changed: true,
+            yes: true,
+            ..Default::default()
+        };
+        assert_eq!(service.push_translations(path, options).await?, 1);
+        upload.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_push_continues_after_failed_language() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(temp_dir.path().join("en-US.json"), r#"{"save": "Save"}"#)?;
+        std::fs::write(temp_dir.path().join("de.json"), r#"{"save": "Speichern"}"#)?;
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/At.Locazy/user/i18n/long-polling")
+            .with_body(r#"{"code":0,"data":{"files":[]}}"#)
+            .create_async()
+            .await;
+        let mut config = create_test_service().config;
+        config.host = server.url();
+        config.upload_retries = 0;
+        config.include = vec!["*.json".to_string()];
+        let fs = Arc::new(MemoryFs::new());
+        let service = TranslationService::new(config).with_fs(fs.clone());
+        let path = Some(temp_dir.path().display().to_string());
+
+        // 默认两种语言都尝试上传，最后返回错误
+        let upload = server
+            .mock("POST", "/api/At.Locazy/cli/terms/upload")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+        let err = service
+            .push_translations(path.clone(), PushOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("2 of 2 languages failed"));
+        upload.assert_async().await;
+        upload.remove_async().await;
+
+        // --fail-fast 时第一种语言失败后停止
+        let upload = server
+            .mock("POST", "/api/At.Locazy/cli/terms/upload")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let options = PushOptions {
+            fail_fast: true,
+            ..Default::default()
+        };
+        assert!(service.push_translations(path, options).await.is_err());
+        upload.assert_async().await;
+        upload.remove_async().await;
+
+        // 上传计划保留了两种语言，--resume 只重试计划中的语言
+        let journal_path = journal::journal_file();
+        assert!(fs.paths().contains(&journal_path));
+        let journal = PushJournal::load(fs.as_ref(), &journal_path)?;
+        assert_eq!(journal.get(&service.config).map(|p| p.remaining()), Some(2));
+        let upload = server
+            .mock("POST", "/api/At.Locazy/cli/terms/upload")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+        let options = PushOptions {
+            resume: true,
+            ..Default::default()
+        };
+        assert!(service.push_translations(None, options).await.is_err());
+        upload.assert_async().await;
+        Ok(())
+    }
+
+    #[test]
+    fn test_pending_changes_status() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        std::fs::write(
+            temp_dir.path().join("de.json"),
+            r#"{"save": "Sichern", "open": "Öffnen", "close": "Schließen", "ok": "OK"}"#,
+        )?;
+        let fs = Arc::new(MemoryFs::new());
+        let mut service = create_test_service().with_fs(fs.clone());
+        service.config.base_path = Some(temp_dir.path().display().to_string());
+        service.config.include = vec!["*.json".to_string()];
+        fs.write(
+            &service.cached_language_path("de"),
+            br#"{"save": "Speichern", "open": "", "ok": "OK"}"#,
+        )?;
+
+        let statuses = |service: &TranslationService| -> Result<Vec<(String, ChangeStatus)>> {
+            Ok(service
+                .pending_changes()?
+                .into_iter()
+                .map(|c| (c.key, c.status))
+                .collect())
+        };
+        assert_eq!(
+            statuses(&service)?,
+            vec![
+                ("close".to_string(), ChangeStatus::New),
+                ("open".to_string(), ChangeStatus::New),
+                ("save".to_string(), ChangeStatus::Changed),
+            ]
+        );
+        // replace 等命令确认过的修改 push 时会覆盖服务器上的值
+        service.record_pending_changes("de", ["save"])?;
+        assert_eq!(
+            statuses(&service)?[2],
+            ("save".to_string(), ChangeStatus::Confirmed)
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_init_log_file() -> Result<()> {
+    fn test_save_namespace_files() -> Result<()> {
         let temp_dir = TempDir::new()?;
-        std::env::set_current_dir(temp_dir.path())?;
+        let dir = temp_dir.path().join("en");
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("common.json"), r#"{"save": "Save"}"#)?;
+        std::fs::write(dir.join("home.json"), r#"{"title": "Home"}"#)?;
+        std::fs::write(
+            dir.join("translation.json"),
+            r#"{"app": "App", "shared": {"ok": "OK"}}"#,
+        )?;
+        let path = dir.join("{namespace}.json");
+        let service = create_test_service();
+        let mut file = TranslationFile::from_content(
+            "en".to_string(),
+            "en/{namespace}.json".to_string(),
+            translation::flatten_json(&translation::read_json_file(&path)?),
+        );
+
+        // 删除 home 下的全部 key 后 home.json 不再保留，translation.json 中的对象不会被拆成 shared.json
+        file.content.retain(|key, _| !key.starts_with("home."));
+        service.save_translation_file(&file, &path)?;
+        assert!(!dir.join("home.json").exists());
+        assert!(!dir.join("shared.json").exists());
+        let read = |name: &str| -> Result<serde_json::Value> {
+            Ok(serde_json::from_str(&std::fs::read_to_string(
+                dir.join(name),
+            )?)?)
+        };
+        assert_eq!(read("common.json")?, json!({"save": "Save"}));
+        assert_eq!(
+            read("translation.json")?,
+            json!({"app": "App", "shared": {"ok": "OK"}})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_missing_keys_modes() -> Result<()> {
+        // 默认不补全
+        assert_eq!(create_test_service().config.fill_missing_keys, None);
+
+        let base = test_util::translation_file("en-US", &[("save", "Save"), ("open", "Open")]);
+        for (mode, expected) in [
+            (FillMissing::Base, "Open"),
+            (FillMissing::Empty, ""),
+            (FillMissing::Placeholder, "__TODO__"),
+        ] {
+            let fs = Arc::new(MemoryFs::new());
+            let mut service = create_test_service().with_fs(fs.clone());
+            service.config.missing_placeholder = "__TODO__".to_string();
+            let mut files = vec![
+                base.clone(),
+                test_util::translation_file("de", &[("save", "Speichern")]),
+                test_util::translation_file("fr", &[("save", "Enregistrer"), ("open", "Ouvrir")]),
+            ];
+            service.fill_missing_keys(Path::new("locales"), &mut files, &base, mode)?;
+
+            assert_eq!(files[1].content["open"].as_ref(), expected, "{:?}", mode);
+            // 只写入有缺失 key 的非基准语言文件
+            assert_eq!(fs.paths(), vec![PathBuf::from("locales/de.json")]);
+            let written: serde_json::Value =
+                serde_json::from_str(&fs.read_to_string(Path::new("locales/de.json"))?)?;
+            assert_eq!(written, json!({"open": expected, "save": "Speichern"}));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_translation_file_uses_fs() -> Result<()> {
+        let fs = Arc::new(MemoryFs::new());
+        let service = create_test_service().with_fs(fs.clone());
+        let file = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            HashMap::from([("common.save".to_string(), "Save".to_string())]),
+        );
+        let path = Path::new("virtual/locales/en-US.json");
+        assert!(!path.exists());
+        service.save_translation_file(&file, path)?;
 
-        TranslationService::init_log_file()?;
+        assert!(!path.exists());
+        assert_eq!(fs.paths(), vec![path.to_path_buf()]);
+        let written: serde_json::Value = serde_json::from_str(&fs.read_to_string(path)?)?;
+        assert_eq!(written, json!({"common": {"save": "Save"}}));
+        Ok(())
+    }
 
-        let log_file = temp_dir.path().join(".i18n-app").join("run.log");
-        assert!(log_file.exists());
+    #[test]
+    fn test_snapshots_are_scoped_by_host_and_version() -> Result<()> {
+        let fs = Arc::new(MemoryFs::new());
+        let service = create_test_service().with_fs(fs.clone());
+        service.write_snapshot("de", &json!({"save": "Speichern"}))?;
+        assert_eq!(
+            service.snapshots()?["de"],
+            HashMap::from([("save".to_string(), "Speichern".to_string())])
+        );
 
+        // 其他版本和环境的 pull 不拿这份快照做三方合并的基准
+        let other_version =
+            TranslationService::new(service.config.for_version("2.0.0")).with_fs(fs.clone());
+        assert!(other_version.snapshots()?.is_empty());
+        let mut config = service.config.clone();
+        config.host = "https://staging.test.com".to_string();
+        let other_host = TranslationService::new(config).with_fs(fs.clone());
+        assert!(other_host.snapshots()?.is_empty());
         Ok(())
     }
 }
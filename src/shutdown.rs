@@ -0,0 +1,12 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// 第一次 Ctrl-C 后等待命令在下一个取消点返回的最长时间，超时或再次按 Ctrl-C 时直接退出
+pub const GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// 进程级的取消信号，第一次 Ctrl-C 时取消。服务默认使用它，测试可以换成自己的 token
+pub fn token() -> CancellationToken {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+    TOKEN.get_or_init(CancellationToken::new).clone()
+}
@@ -0,0 +1,552 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::config::SpellCheckConfig;
+use crate::translation::TranslationFile;
+
+/// 拼写词典，可以由不同的词典实现提供
+pub trait Dictionary {
+    fn contains(&self, word: &str) -> bool;
+}
+
+/// hunspell 词缀标记的写法，由 .aff 文件的 `FLAG` 指定
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum FlagType {
+    /// 每个字符是一个标记（默认，也适用于 `FLAG UTF-8`）
+    #[default]
+    Char,
+    /// 每两个字符是一个标记
+    Long,
+    /// 逗号分隔的数字
+    Num,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagType::Num => flags
+                .split(',')
+                .map(|flag| flag.trim().to_string())
+                .filter(|flag| !flag.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// 词缀条件中的一个字符：`.`、单个字符或 `[...]` / `[^...]` 字符集
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionChar {
+    Any,
+    Char(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl ConditionChar {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionChar::Any => true,
+            ConditionChar::Char(expected) => *expected == c,
+            ConditionChar::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<ConditionChar> {
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parts.push(ConditionChar::Any),
+            '[' => {
+                let mut set: Vec<char> = chars.by_ref().take_while(|c| *c != ']').collect();
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                parts.push(ConditionChar::Set {
+                    chars: set,
+                    negated,
+                });
+            }
+            c => parts.push(ConditionChar::Char(c)),
+        }
+    }
+    parts
+}
+
+/// 一条前缀或后缀规则：去掉 `strip`，加上 `affix`，`condition` 匹配词首（前缀）或词尾（后缀）
+#[derive(Debug, Clone)]
+struct AffixEntry {
+    strip: String,
+    affix: String,
+    condition: Vec<ConditionChar>,
+}
+
+impl AffixEntry {
+    fn apply_suffix(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let n = self.condition.len();
+        if chars.len() < n || !word.ends_with(&self.strip) || word.len() == self.strip.len() {
+            return None;
+        }
+        let tail = &chars[chars.len() - n..];
+        if !self
+            .condition
+            .iter()
+            .zip(tail)
+            .all(|(c, ch)| c.matches(*ch))
+        {
+            return None;
+        }
+        Some(format!(
+            "{}{}",
+            &word[..word.len() - self.strip.len()],
+            self.affix
+        ))
+    }
+
+    fn apply_prefix(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() < self.condition.len()
+            || !word.starts_with(&self.strip)
+            || word.len() == self.strip.len()
+        {
+            return None;
+        }
+        if !self
+            .condition
+            .iter()
+            .zip(&chars)
+            .all(|(c, ch)| c.matches(*ch))
+        {
+            return None;
+        }
+        Some(format!("{}{}", self.affix, &word[self.strip.len()..]))
+    }
+}
+
+/// 同一个标记下的规则
+#[derive(Debug, Clone, Default)]
+struct AffixClass {
+    /// 可以与另一类词缀（前缀与后缀）组合
+    cross_product: bool,
+    entries: Vec<AffixEntry>,
+}
+
+/// hunspell .aff 文件中的前缀（PFX）和后缀（SFX）规则，其他指令（复合词、替换建议等）不支持
+#[derive(Debug, Default)]
+pub struct AffixRules {
+    flag_type: FlagType,
+    prefixes: HashMap<String, AffixClass>,
+    suffixes: HashMap<String, AffixClass>,
+}
+
+impl AffixRules {
+    pub fn parse(content: &str) -> Self {
+        let mut rules = AffixRules::default();
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => rules.flag_type = FlagType::Long,
+                ["FLAG", "num", ..] => rules.flag_type = FlagType::Num,
+                [kind @ ("PFX" | "SFX"), flag, cross, count] if count.parse::<usize>().is_ok() => {
+                    let classes = if *kind == "PFX" {
+                        &mut rules.prefixes
+                    } else {
+                        &mut rules.suffixes
+                    };
+                    classes.entry(flag.to_string()).or_default().cross_product = *cross == "Y";
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, affix, rest @ ..] => {
+                    let classes = if *kind == "PFX" {
+                        &mut rules.prefixes
+                    } else {
+                        &mut rules.suffixes
+                    };
+                    let empty = |value: &str| {
+                        if value == "0" {
+                            String::new()
+                        } else {
+                            value.to_string()
+                        }
+                    };
+                    // 词缀中 `/` 之后的连续标记不展开
+                    let affix = affix.split('/').next().unwrap_or_default();
+                    classes
+                        .entry(flag.to_string())
+                        .or_default()
+                        .entries
+                        .push(AffixEntry {
+                            strip: empty(strip),
+                            affix: empty(affix),
+                            condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                        });
+                }
+                _ => {}
+            }
+        }
+        rules
+    }
+
+    /// `word` 按 `flags` 展开后的所有词形，包括 `word` 本身
+    pub fn expand(&self, word: &str, flags: &str) -> Vec<String> {
+        let flags = self.flag_type.split(flags);
+        let classes = |map: &'_ HashMap<String, AffixClass>| -> Vec<AffixClass> {
+            flags
+                .iter()
+                .filter_map(|flag| map.get(flag).cloned())
+                .collect()
+        };
+        let prefixes = classes(&self.prefixes);
+        let suffixes = classes(&self.suffixes);
+
+        let mut forms = vec![word.to_string()];
+        for suffix in &suffixes {
+            for entry in &suffix.entries {
+                let Some(suffixed) = entry.apply_suffix(word) else {
+                    continue;
+                };
+                // 两类词缀都允许组合时，后缀形式再加前缀
+                for prefix in prefixes
+                    .iter()
+                    .filter(|p| p.cross_product && suffix.cross_product)
+                {
+                    forms.extend(
+                        prefix
+                            .entries
+                            .iter()
+                            .filter_map(|e| e.apply_prefix(&suffixed)),
+                    );
+                }
+                forms.push(suffixed);
+            }
+        }
+        for prefix in &prefixes {
+            forms.extend(prefix.entries.iter().filter_map(|e| e.apply_prefix(word)));
+        }
+        forms
+    }
+}
+
+/// 基于单词表的词典，支持 hunspell .dic 文件（按同名 .aff 文件展开前缀和后缀）和普通词表
+#[derive(Debug, Default)]
+pub struct WordListDictionary {
+    words: HashSet<String>,
+}
+
+impl WordListDictionary {
+    pub fn add_word(&mut self, word: &str) {
+        let word = word.trim();
+        if !word.is_empty() {
+            self.words.insert(word.to_lowercase());
+        }
+    }
+
+    /// 加载词典文件。hunspell .dic 文件第一行是单词数量，`/` 之后是词缀标记，
+    /// 同目录下有同名的 .aff 文件时按其中的 PFX/SFX 规则展开词形，没有时只加入原词
+    pub fn load_file(&mut self, path: &Path) -> Result<()> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("读取词典文件 {} 失败", path.display()))?;
+        let aff_path = path.with_extension("aff");
+        let rules = if aff_path.is_file() {
+            let aff = fs::read_to_string(&aff_path)
+                .with_context(|| format!("读取词缀文件 {} 失败", aff_path.display()))?;
+            Some(AffixRules::parse(&aff))
+        } else {
+            None
+        };
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if i == 0 && line.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            // 单词后面可能有以 tab 分隔的形态信息
+            let entry = line.split('\t').next().unwrap_or(line);
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            match &rules {
+                Some(rules) if !flags.is_empty() => {
+                    for form in rules.expand(word, flags) {
+                        self.add_word(&form);
+                    }
+                }
+                _ => self.add_word(word),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Dictionary for WordListDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+}
+
+/// 由 zspell 按 hunspell .aff/.dic 文件构建的词典，支持 .aff 中的全部规则
+#[cfg(feature = "zspell")]
+pub struct HunspellDictionary {
+    inner: zspell::Dictionary,
+}
+
+#[cfg(feature = "zspell")]
+impl HunspellDictionary {
+    pub fn load(dic_path: &Path, aff_path: &Path) -> Result<Self> {
+        let aff = fs::read_to_string(aff_path)
+            .with_context(|| format!("读取词缀文件 {} 失败", aff_path.display()))?;
+        let dic = fs::read_to_string(dic_path)
+            .with_context(|| format!("读取词典文件 {} 失败", dic_path.display()))?;
+        let inner = zspell::builder()
+            .config_str(&aff)
+            .dict_str(&dic)
+            .build()
+            .map_err(|e| anyhow::anyhow!("解析词典 {} 失败: {}", dic_path.display(), e))?;
+        Ok(Self { inner })
+    }
+}
+
+#[cfg(feature = "zspell")]
+impl Dictionary for HunspellDictionary {
+    /// 句首大写的词也按小写形式检查，与单词表词典一致
+    fn contains(&self, word: &str) -> bool {
+        self.inner.check_word(word) || self.inner.check_word(&word.to_lowercase())
+    }
+}
+
+/// 启用 `zspell` 特性且 `.dic` 同目录下有同名 `.aff` 文件时，由 zspell 加载完整的 hunspell 词典
+#[cfg(feature = "zspell")]
+fn load_hunspell(path: &Path) -> Result<Option<Box<dyn Dictionary>>> {
+    let aff_path = path.with_extension("aff");
+    if !aff_path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(Box::new(HunspellDictionary::load(path, &aff_path)?)))
+}
+
+/// 未启用 `zspell` 特性时由 [`WordListDictionary`] 按内置的前缀/后缀规则展开
+#[cfg(not(feature = "zspell"))]
+fn load_hunspell(_path: &Path) -> Result<Option<Box<dyn Dictionary>>> {
+    Ok(None)
+}
+
+/// 一种语言的词典：单词表（普通词表、`words` 和白名单）加上由 zspell 加载的 hunspell 词典
+#[derive(Default)]
+struct LanguageDictionary {
+    words: WordListDictionary,
+    hunspell: Vec<Box<dyn Dictionary>>,
+}
+
+impl Dictionary for LanguageDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.words.contains(word) || self.hunspell.iter().any(|d| d.contains(word))
+    }
+}
+
+/// 从文本中提取需要检查的单词，跳过占位符、HTML 标签、URL 和包含数字的词
+pub fn extract_words(text: &str) -> Vec<String> {
+    let mut cleaned = String::with_capacity(text.len());
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match c {
+            '{' | '<' => depth += 1,
+            '}' | '>' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => cleaned.push(c),
+            _ => {}
+        }
+    }
+
+    cleaned
+        .split_whitespace()
+        .filter(|token| !token.contains("://") && !token.starts_with('%'))
+        .flat_map(|token| {
+            token
+                .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+                .map(|w| w.trim_matches('\'').to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|w| w.chars().count() > 1 && w.chars().all(|c| c.is_alphabetic() || c == '\''))
+        .collect()
+}
+
+/// 按语言加载词典和白名单后执行拼写检查
+pub struct SpellChecker {
+    dictionaries: BTreeMap<String, LanguageDictionary>,
+}
+
+impl SpellChecker {
+    pub fn from_config(config: &SpellCheckConfig) -> Result<Self> {
+        let mut allowlist = Vec::new();
+        if let Some(file) = &config.allowlist_file {
+            let content = fs::read_to_string(file)
+                .with_context(|| format!("读取白名单文件 {} 失败", file))?;
+            allowlist.extend(
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                    .map(str::to_string),
+            );
+        }
+
+        let mut dictionaries = BTreeMap::new();
+        for (lang, files) in &config.dictionaries {
+            let mut dictionary = LanguageDictionary::default();
+            for file in files {
+                let path = Path::new(file);
+                match load_hunspell(path)? {
+                    Some(hunspell) => dictionary.hunspell.push(hunspell),
+                    None => dictionary.words.load_file(path)?,
+                }
+            }
+            for word in config.words.get(lang).into_iter().flatten() {
+                dictionary.words.add_word(word);
+            }
+            for word in &allowlist {
+                dictionary.words.add_word(word);
+            }
+            dictionaries.insert(lang.clone(), dictionary);
+        }
+        Ok(Self { dictionaries })
+    }
+
+    pub fn has_dictionary(&self, lang: &str) -> bool {
+        self.dictionaries.contains_key(lang)
+    }
+
+    /// 返回每个 key 中拼写错误的单词，没有词典的语言返回空
+    pub fn check(&self, file: &TranslationFile) -> BTreeMap<String, Vec<String>> {
+        let Some(dictionary) = self.dictionaries.get(&file.language_code) else {
+            return BTreeMap::new();
+        };
+        check_with(dictionary, file)
+    }
+}
+
+pub fn check_with(
+    dictionary: &dyn Dictionary,
+    file: &TranslationFile,
+) -> BTreeMap<String, Vec<String>> {
+    let mut misspelled = BTreeMap::new();
+    for (key, value) in &file.content {
+        let mut words: Vec<String> = extract_words(value)
+            .into_iter()
+            .filter(|w| !dictionary.contains(w))
+            .collect();
+        words.dedup();
+        if !words.is_empty() {
+            misspelled.insert(key.to_string(), words);
+        }
+    }
+    misspelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_extract_words_skips_placeholders() {
+        assert_eq!(
+            extract_words("Hello {name}, <b>welcome</b> to https://x.io %s it's 3D"),
+            vec!["Hello", "welcome", "to", "it's"]
+        );
+    }
+
+    #[test]
+    fn test_spell_checker_reports_unknown_words() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dic = temp_dir.path().join("en_US.dic");
+        fs::write(&dic, "5\nhello/M\nworld\nthe\nto\nsave/DSG\n")?;
+        let allowlist = temp_dir.path().join("allowlist.txt");
+        fs::write(&allowlist, "# product names\nLocazy\n")?;
+
+        let config = SpellCheckConfig {
+            dictionaries: HashMap::from([("en-US".to_string(), vec![dic.display().to_string()])]),
+            words: HashMap::from([("en-US".to_string(), vec!["changes".to_string()])]),
+            allowlist_file: Some(allowlist.display().to_string()),
+        };
+        let checker = SpellChecker::from_config(&config)?;
+
+        let file = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            HashMap::from([
+                ("greeting".to_string(), "Hello wrold".to_string()),
+                ("save".to_string(), "Save the changes to Locazy".to_string()),
+            ]),
+        );
+        let misspelled = checker.check(&file);
+        assert_eq!(misspelled.len(), 1);
+        assert_eq!(misspelled["greeting"], vec!["wrold"]);
+        assert!(!checker.has_dictionary("zh-CN"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_affix_rules_expand_word_forms() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(
+            temp_dir.path().join("en_US.aff"),
+            "SET UTF-8\n\
+             PFX A Y 1\n\
+             PFX A 0 re .\n\
+             SFX D Y 4\n\
+             SFX D 0 d e\n\
+             SFX D y ied [^aey]y\n\
+             SFX D 0 ed [^ey]\n\
+             SFX D 0 ed [aey]y\n\
+             SFX S N 1\n\
+             SFX S 0 s .\n",
+        )?;
+        let dic = temp_dir.path().join("en_US.dic");
+        fs::write(&dic, "4\nsave/ADS\ncopy/D\nplay/DS\nstart\n")?;
+        let mut dictionary = WordListDictionary::default();
+        dictionary.load_file(&dic)?;
+
+        for word in [
+            "save", "saved", "saves", "resave", "resaved", "copied", "played", "plays", "start",
+        ] {
+            assert!(dictionary.contains(word), "{} should be known", word);
+        }
+        // 不满足条件的规则不展开，S 不允许与前缀组合
+        for word in ["copyed", "playied", "resaves", "starts"] {
+            assert!(!dictionary.contains(word), "{} should be unknown", word);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "zspell")]
+    #[test]
+    fn test_hunspell_dictionary_uses_aff_rules() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let aff = temp_dir.path().join("en_US.aff");
+        fs::write(&aff, "SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n")?;
+        let dic = temp_dir.path().join("en_US.dic");
+        fs::write(&dic, "1\nsave/S\n")?;
+        let dictionary = HunspellDictionary::load(&dic, &aff)?;
+
+        assert!(dictionary.contains("saves"));
+        assert!(dictionary.contains("Save"));
+        assert!(!dictionary.contains("saved"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_long_and_numeric_flags() {
+        let rules = AffixRules::parse("FLAG long\nSFX Ab Y 1\nSFX Ab 0 s .\n");
+        assert_eq!(rules.expand("cat", "AbCd"), vec!["cat", "cats"]);
+        let rules = AffixRules::parse("FLAG num\nSFX 101 Y 1\nSFX 101 0 ing .\n");
+        assert_eq!(rules.expand("read", "7,101"), vec!["read", "reading"]);
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::AppError;
+use crate::jsonc;
+use crate::translation::{self, TranslationFile};
+
+/// 一种语言的 key 数量、词数和字符数
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageStats {
+    pub lang: String,
+    pub keys: usize,
+    pub words: usize,
+    /// 不含空白的字符数
+    pub characters: usize,
+}
+
+impl LanguageStats {
+    pub fn new(file: &TranslationFile) -> Self {
+        Self {
+            lang: file.language_code.clone(),
+            keys: file.content.len(),
+            words: file.content.values().map(|v| word_count(v)).sum(),
+            characters: file.content.values().map(|v| char_count(v)).sum(),
+        }
+    }
+}
+
+/// 中日文字符没有空格分词，按翻译行业的惯例每个字符计为一个词
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FA1F}')
+}
+
+/// 词数：以空白分隔的词（至少包含一个字母或数字），中日文每个字符计为一个词
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|word| {
+            let cjk = word.chars().filter(|c| is_cjk(*c)).count();
+            let other = word
+                .split(is_cjk)
+                .filter(|part| part.chars().any(char::is_alphanumeric))
+                .count();
+            cjk + other
+        })
+        .sum()
+}
+
+/// 不含空白的字符数
+pub fn char_count(text: &str) -> usize {
+    text.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+/// key 的第一段作为命名空间，没有 `.` 的 key 归入 [`translation::DEFAULT_NAMESPACE`]
+pub fn namespace_of(key: &str) -> &str {
+    key.split_once('.')
+        .map_or(translation::DEFAULT_NAMESPACE, |(namespace, _)| namespace)
+}
+
+/// 每个命名空间的 (key 数量, 词数)，按命名空间排序
+pub fn namespace_counts(file: &TranslationFile) -> BTreeMap<&str, (usize, usize)> {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for (key, value) in &file.content {
+        let entry = counts.entry(namespace_of(key)).or_default();
+        entry.0 += 1;
+        entry.1 += word_count(value);
+    }
+    counts
+}
+
+/// 字符数最多的 `limit` 个文本，字符数相同时按 key 排序
+pub fn largest_strings(file: &TranslationFile, limit: usize) -> Vec<(&str, &str, usize)> {
+    let mut strings: Vec<(&str, &str, usize)> = file
+        .content
+        .iter()
+        .map(|(key, value)| (key.as_ref(), value.as_ref(), char_count(value)))
+        .collect();
+    strings.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+    strings.truncate(limit);
+    strings
+}
+
+/// 一种语言待翻译的 key 数量、基准语言词数和单价
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostEstimate {
+    pub lang: String,
+    pub keys: usize,
+    pub words: usize,
+    pub rate: f64,
+}
+
+impl CostEstimate {
+    pub fn cost(&self) -> f64 {
+        self.words as f64 * self.rate
+    }
+}
+
+/// 基准语言中 `translated` 返回 false 的 key 的数量和这些 key 的基准文本词数
+pub fn untranslated_words(
+    base: &TranslationFile,
+    translated: impl Fn(&str) -> bool,
+) -> (usize, usize) {
+    base.content
+        .iter()
+        .filter(|(key, _)| !translated(key))
+        .fold((0, 0), |(keys, words), (_, value)| {
+            (keys + 1, words + word_count(value))
+        })
+}
+
+/// 在表格中显示的文本：换行替换为空格，超过 `max_chars` 个字符时截断
+pub fn preview(text: &str, max_chars: usize) -> String {
+    let text = text.replace(['\r', '\n'], " ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let mut shown: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    shown.push('…');
+    shown
+}
+
+/// 检查 git 引用是否存在
+pub fn verify_ref(git_ref: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", git_ref))
+        .output()
+        .context("运行 git 失败")?;
+    if !output.status.success() {
+        return Err(AppError::Validation(format!("无效的 git 引用 '{}'", git_ref)).into());
+    }
+    Ok(())
+}
+
+/// 文件在 git 引用处的 key 数量，文件当时不存在时返回 None
+pub fn keys_at_ref(git_ref: &str, path: &Path) -> Result<Option<usize>> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(None);
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    // `./` 前缀使路径相对于 -C 指定的目录，而不是仓库根目录
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("show")
+        .arg(format!("{}:./{}", git_ref, name.to_string_lossy()))
+        .output()
+        .context("运行 git 失败")?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let text = translation::decode_text(&output.stdout)?;
+    let value: serde_json::Value = serde_json::from_str(&jsonc::strip_comments(&text))?;
+    Ok(Some(translation::flatten_json(&value).len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_word_count() {
+        assert_eq!(word_count("Save  your changes?"), 3);
+        assert_eq!(word_count("保存更改"), 4);
+        assert_eq!(word_count("Hello世界 {name} - ok"), 5);
+        assert_eq!(char_count("a b\tc"), 3);
+    }
+
+    #[test]
+    fn test_namespace_counts_and_largest() {
+        let file = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            HashMap::from([
+                ("common.save".to_string(), "Save".to_string()),
+                ("common.cancel".to_string(), "Cancel it".to_string()),
+                ("home.title".to_string(), "Welcome home".to_string()),
+                ("app".to_string(), "App".to_string()),
+            ]),
+        );
+        let counts = namespace_counts(&file);
+        assert_eq!(counts["common"], (2, 3));
+        assert_eq!(counts["home"], (1, 2));
+        assert_eq!(counts[translation::DEFAULT_NAMESPACE], (1, 1));
+
+        let largest = largest_strings(&file, 2);
+        assert_eq!(largest[0], ("home.title", "Welcome home", 11));
+        assert_eq!(largest[1], ("common.cancel", "Cancel it", 8));
+        assert_eq!(preview("Welcome\nhome", 20), "Welcome home");
+        assert_eq!(preview("Welcome home", 5), "Welc…");
+
+        let stats = LanguageStats::new(&file);
+        assert_eq!((stats.keys, stats.words, stats.characters), (4, 6, 26));
+    }
+
+    #[test]
+    fn test_keys_at_ref() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(temp_dir.path())
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .status()
+        };
+        let locales = temp_dir.path().join("locales");
+        std::fs::create_dir_all(&locales)?;
+        let path = locales.join("en-US.json");
+        std::fs::write(&path, r#"{"a": "A", "b": {"c": "C"}}"#)?;
+        assert!(git(&["init", "--quiet"])?.success());
+        assert!(git(&["add", "."])?.success());
+        assert!(git(&["commit", "--quiet", "-m", "init"])?.success());
+        std::fs::write(&path, r#"{"a": "A", "b": {"c": "C", "d": "D"}}"#)?;
+
+        assert_eq!(keys_at_ref("HEAD", &path)?, Some(2));
+        assert_eq!(keys_at_ref("HEAD", &locales.join("zh-CN.json"))?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::vfs::FileSystem;
+use crate::workspace;
+
+pub fn sync_state_file() -> PathBuf {
+    workspace::work_dir().join("sync-state.json")
+}
+
+/// 一个子系统最近一次成功 pull 的状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEntry {
+    /// 最近一次成功 pull 的时间（RFC 3339）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<String>,
+    /// 最近一次完整同步时服务器返回的 taskHash，下次请求时发送给服务器
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_hash: Option<String>,
+}
+
+/// 各子系统的同步状态，按 `<子系统>@<host>/<产品>#<版本>` 区分，切换环境或版本不会互相影响
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    #[serde(default)]
+    entries: BTreeMap<String, SyncEntry>,
+}
+
+fn state_key(config: &Config) -> String {
+    format!(
+        "{}@{}/{}#{}",
+        config.sub_system_name(),
+        config.host,
+        config.product_code,
+        config.version_no
+    )
+}
+
+impl SyncState {
+    /// 读取同步状态，文件不存在时返回空状态
+    pub fn load(fs: &dyn FileSystem, path: &Path) -> Result<Self> {
+        if !fs.exists(path) {
+            return Ok(Self::default());
+        }
+        let content = fs
+            .read_to_string(path)
+            .with_context(|| format!("读取同步状态 {} 失败", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("解析同步状态 {} 失败", path.display()))
+    }
+
+    pub fn save(&self, fs: &dyn FileSystem, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)? + "\n";
+        fs.write(path, content.as_bytes())
+            .with_context(|| format!("写入同步状态 {} 失败", path.display()))
+    }
+
+    pub fn get(&self, config: &Config) -> SyncEntry {
+        self.entries
+            .get(&state_key(config))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn entry_mut(&mut self, config: &Config) -> &mut SyncEntry {
+        self.entries.entry(state_key(config)).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::RealFs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sync_state_roundtrip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(".i18n-app").join("sync-state.json");
+        let config = Config::default();
+
+        let mut state = SyncState::load(&RealFs, &path)?;
+        assert_eq!(state.get(&config), SyncEntry::default());
+        state.entry_mut(&config).last_sync = Some("2024-01-01T00:00:00+00:00".to_string());
+        state.save(&RealFs, &path)?;
+
+        let state = SyncState::load(&RealFs, &path)?;
+        assert!(state.get(&config).last_sync.is_some());
+        // 其他环境的同名子系统没有状态
+        let staging = Config {
+            host: "https://staging.test.com".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(state.get(&staging), SyncEntry::default());
+        // 同一 host 上的其他版本和产品也没有状态
+        assert_eq!(
+            state.get(&config.for_version("2.0.0")),
+            SyncEntry::default()
+        );
+        let product = Config {
+            product_code: "other".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(state.get(&product), SyncEntry::default());
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+//! 测试使用的 fixture。`fixtures/golden/<group>/<case>/` 下每个目录是一个用例，
+//! 启用 `test-util` feature 后依赖本库的项目也可以使用
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::translation::TranslationFile;
+
+/// 仓库中的 `fixtures` 目录
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// 由扁平的 key/value 构建一种语言的翻译文件，相对路径为 `<lang>.json`
+pub fn translation_file(lang: &str, entries: &[(&str, &str)]) -> TranslationFile {
+    TranslationFile::from_content(
+        lang.to_string(),
+        format!("{}.json", lang),
+        entries.iter().copied(),
+    )
+}
+
+/// 读取 JSON fixture，相对路径相对于 `fixtures` 目录。文件不存在或无法解析时 panic
+pub fn read_fixture(relative: impl AsRef<Path>) -> Value {
+    let path = fixtures_dir().join(relative);
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read fixture {}: {}", path.display(), e));
+    serde_json::from_str(&text)
+        .unwrap_or_else(|e| panic!("failed to parse fixture {}: {}", path.display(), e))
+}
+
+/// `fixtures/golden/<group>` 下的用例目录，按名称排序
+pub fn fixture_cases(group: &str) -> Vec<(String, PathBuf)> {
+    let dir = fixtures_dir().join("golden").join(group);
+    let entries = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("failed to read fixtures {}: {}", dir.display(), e));
+    let mut cases: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .map(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            (name.into_owned(), path)
+        })
+        .collect();
+    cases.sort();
+    cases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_cases() {
+        let cases: Vec<String> = fixture_cases("merge")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(cases, vec!["conflicts", "edits"]);
+        assert!(read_fixture("golden/nested/en-US.json").is_object());
+    }
+}
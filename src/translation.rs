@@ -1,169 +1,1673 @@
-use anyhow::{anyhow, Result};
-use glob::glob;
+use anyhow::{anyhow, Context, Result};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::{BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
 
-#[derive(Clone)]
+use crate::config::{JsonFormat, JsonIndent, KeyStyle, LineEnding};
+use crate::jsonc::{self, JsonComments};
+use crate::vfs::FileSystem;
+
+/// 下载文件默认的命名模板
+pub const DEFAULT_NAME_TEMPLATE: &str = "{lang}.json";
+/// 使用 `{namespace}` 拆分时，根节点下的非对象值归入的命名空间
+pub const DEFAULT_NAMESPACE: &str = "translation";
+
+/// 扁平化后的翻译：key -> 文本。[`read_translation_files`] 读取的各语言中同一个 key 共享一份内存
+pub type Content = HashMap<Arc<str>, Arc<str>>;
+
+/// 一次读取中出现过的 key。大型项目中每种语言都有相同的几万个 key，共享后只保存一份；
+/// 读取结束后随之释放，不会在进程中一直累积
+#[derive(Default)]
+pub struct KeyInterner {
+    keys: HashSet<Arc<str>>,
+}
+
+impl KeyInterner {
+    /// 返回 key 的共享副本
+    pub fn intern(&mut self, key: Arc<str>) -> Arc<str> {
+        match self.keys.get(&key) {
+            Some(shared) => shared.clone(),
+            None => {
+                self.keys.insert(key.clone());
+                key
+            }
+        }
+    }
+
+    /// 让文件中的 key 和位置信息使用共享的副本
+    pub fn share(&mut self, file: &mut TranslationFile) {
+        file.content = file
+            .content
+            .drain()
+            .map(|(key, value)| (self.intern(key), value))
+            .collect();
+        file.spans = file
+            .spans
+            .drain()
+            .map(|(key, span)| (self.intern(key), span))
+            .collect();
+    }
+}
+
+/// 把扁平的键值对转换为 [`Content`]
+pub fn to_content<K: AsRef<str>, V: Into<Arc<str>>>(
+    entries: impl IntoIterator<Item = (K, V)>,
+) -> Content {
+    entries
+        .into_iter()
+        .map(|(key, value)| (Arc::from(key.as_ref()), value.into()))
+        .collect()
+}
+
+#[derive(Clone, Default)]
 pub struct TranslationFile {
     pub language_code: String,
     pub relative_path: String,
-    pub content: HashMap<String, String>,
+    pub content: Content,
+    /// 从文件读取时各 key 在文件中的位置，用于报告问题
+    pub spans: HashMap<Arc<str>, Span>,
+}
+
+impl TranslationFile {
+    /// 读取文件，`relative_path` 是以 `/` 分隔的相对路径，文件名（不含扩展名）作为语言代码
+    fn read(file_path: &Path, relative_path: String) -> Result<Self> {
+        // Extract language code from filename (e.g., "en-US.json" -> "en-US")
+        let language_code = Path::new(&relative_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("Invalid file name"))?
+            .to_string();
+
+        let (content, spans) = read_content_with_spans(file_path)?;
+
+        Ok(TranslationFile {
+            language_code,
+            relative_path,
+            content,
+            spans,
+        })
+    }
+
+    /// key 的位置，如 `locales/en-US.json:12:5`，没有位置信息时只返回文件路径
+    pub fn location(&self, key: &str) -> String {
+        location(&self.relative_path, self.spans.get(key).copied())
+    }
+
+    /// 把所有 key 和文本规范化为 Unicode NFC
+    pub fn normalize_nfc(&mut self) {
+        self.content = self
+            .content
+            .drain()
+            .map(|(k, v)| {
+                let k: String = k.nfc().collect();
+                (Arc::from(k), Arc::from(v.nfc().collect::<String>()))
+            })
+            .collect();
+        self.spans = self
+            .spans
+            .drain()
+            .map(|(k, span)| (Arc::from(k.nfc().collect::<String>()), span))
+            .collect();
+    }
+
+    pub fn from_content<K: AsRef<str>, V: Into<Arc<str>>>(
+        language_code: String,
+        relative_path: String,
+        content: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        TranslationFile {
+            language_code,
+            relative_path,
+            content: to_content(content),
+            spans: HashMap::new(),
+        }
+    }
+}
+
+/// 按 BOM 检测编码并解码为 UTF-8 文本，支持 UTF-8 BOM 和 UTF-16 LE/BE，无 BOM 时按 UTF-8 处理
+pub fn decode_text(bytes: &[u8]) -> Result<String> {
+    let decode_utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| -> Result<String> {
+        if !bytes.len().is_multiple_of(2) {
+            return Err(anyhow!("Invalid UTF-16 content: odd number of bytes"));
+        }
+        let units = bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]));
+        char::decode_utf16(units)
+            .collect::<Result<String, _>>()
+            .map_err(|e| anyhow!("Invalid UTF-16 content: {}", e))
+    };
+
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => Ok(String::from_utf8(rest.to_vec())?),
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        _ => Ok(String::from_utf8(bytes.to_vec())?),
+    }
+}
+
+fn read_json_text(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF])
+        || bytes.starts_with(&[0xFF, 0xFE])
+        || bytes.starts_with(&[0xFE, 0xFF])
+    {
+        tracing::debug!("Detected BOM in {}, converting to UTF-8", path.display());
+    }
+    decode_text(&bytes)
+}
+
+/// 解析 JSON 文本，兼容 JSONC：去掉注释和结尾逗号后再解析
+fn parse_json_text(text: &str) -> Result<Value> {
+    Ok(serde_json::from_str(&jsonc::strip_comments(text))?)
+}
+
+/// 读取 JSON 文件，自动处理 BOM。路径包含 `{namespace}` 时读取所有命名空间文件，
+/// 合并为以命名空间为根节点 key 的对象。[`DEFAULT_NAMESPACE`] 文件中的根节点与其他命名空间文件
+/// 同名时无法确定以哪个为准，返回错误
+pub fn read_json_file(path: &Path) -> Result<Value> {
+    if !path.to_string_lossy().contains(NAMESPACE_VAR) {
+        return parse_json_text(&read_json_text(path)?);
+    }
+    let mut merged = serde_json::Map::new();
+    let mut sources: HashMap<String, PathBuf> = HashMap::new();
+    for (namespace, file) in namespace_files(path)? {
+        let entries = match parse_json_text(&read_json_text(&file)?)? {
+            Value::Object(map) if namespace == DEFAULT_NAMESPACE => map.into_iter().collect(),
+            value => vec![(namespace, value)],
+        };
+        for (key, value) in entries {
+            if let Some(previous) = sources.insert(key.clone(), file.clone()) {
+                return Err(anyhow!(
+                    "命名空间 '{}' 同时定义在 {} 和 {} 中",
+                    key,
+                    previous.display(),
+                    file.display()
+                ));
+            }
+            merged.insert(key, value);
+        }
+    }
+    Ok(Value::Object(merged))
+}
+
+/// 超过这个大小的文件边读边扁平化，不在内存中构建完整的 JSON 树
+#[cfg(not(test))]
+pub const STREAMING_PARSE_THRESHOLD: u64 = 16 * 1024 * 1024;
+/// 测试中使用较小的阈值，不需要生成很大的文件
+#[cfg(test)]
+pub const STREAMING_PARSE_THRESHOLD: u64 = 64 * 1024;
+
+/// 流式解析大文件，每解析出一个扁平化的 key 就交给 `sink`。
+/// UTF-16 和 JSONC 等流式解析不支持的内容返回错误，调用方回退到完整读取
+fn stream_flat_json_file(path: &Path, sink: &mut dyn FnMut(String, String)) -> Result<()> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut bom = [0u8; 3];
+    reader.read_exact(&mut bom)?;
+    if bom == [0xEF, 0xBB, 0xBF] {
+        for_each_flat_entry(reader, sink)
+    } else {
+        for_each_flat_entry(bom.chain(reader), sink)
+    }
+}
+
+fn log_streaming_fallback(path: &Path, e: &anyhow::Error) {
+    tracing::debug!(
+        "Streaming parse of {} failed, reading whole file: {}",
+        path.display(),
+        e
+    );
+}
+
+/// 读取 JSON 文件并扁平化，大文件使用流式解析
+pub fn read_flat_json_file(path: &Path) -> Result<HashMap<String, String>> {
+    if fs::metadata(path)?.len() >= STREAMING_PARSE_THRESHOLD {
+        let mut map = HashMap::new();
+        match stream_flat_json_file(path, &mut |key, value| {
+            map.insert(key, value);
+        }) {
+            Ok(()) => return Ok(map),
+            Err(e) => log_streaming_fallback(path, &e),
+        }
+    }
+    Ok(flatten_json(&read_json_file(path)?))
+}
+
+/// 读取 JSON 文件，直接扁平化为 [`Content`]，同时记录各 key 的位置；流式解析的大文件不记录位置
+pub fn read_content_with_spans(path: &Path) -> Result<(Content, HashMap<Arc<str>, Span>)> {
+    let mut content = Content::new();
+    if fs::metadata(path)?.len() >= STREAMING_PARSE_THRESHOLD {
+        match stream_flat_json_file(path, &mut |key, value| {
+            content.insert(key.into(), value.into());
+        }) {
+            Ok(()) => return Ok((content, HashMap::new())),
+            Err(e) => log_streaming_fallback(path, &e),
+        }
+        content.clear();
+    }
+    let text = read_json_text(path)?;
+    for_each_flat_entry(
+        jsonc::strip_comments(&text).as_bytes(),
+        &mut |key, value| {
+            content.insert(key.into(), value.into());
+        },
+    )?;
+    Ok((content, key_positions(&text)))
+}
+
+/// 从 reader 流式解析 JSON 并扁平化，结果与 `flatten_json` 相同
+pub fn flatten_json_reader<R: Read>(reader: R) -> Result<HashMap<String, String>> {
+    let mut map = HashMap::new();
+    for_each_flat_entry(reader, &mut |key, value| {
+        map.insert(key, value);
+    })?;
+    Ok(map)
+}
+
+/// 从 reader 流式解析 JSON，每解析出一个扁平化的 key 就交给 `sink`，不保留已解析的内容
+pub fn for_each_flat_entry<R: Read>(reader: R, sink: &mut dyn FnMut(String, String)) -> Result<()> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    FlattenSeed {
+        prefix: String::new(),
+        sink,
+    }
+    .deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(())
+}
+
+/// 从 reader 流式读取顶层对象，把字段 `key` 的值以紧凑格式写入 `out`，其余字段只解析不保留。
+/// 返回是否找到这个字段
+pub fn copy_json_field<R: Read>(reader: R, key: &str, out: &mut dyn Write) -> Result<bool> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let found = FieldSeed { key, out }.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(found)
+}
+
+/// 在顶层对象中查找字段并交给 [`CopySeed`] 写出
+struct FieldSeed<'k, 'w> {
+    key: &'k str,
+    out: &'w mut dyn Write,
+}
+
+impl<'de> DeserializeSeed<'de> for FieldSeed<'_, '_> {
+    type Value = bool;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldSeed<'_, '_> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<bool, A::Error> {
+        let mut found = false;
+        while let Some(key) = access.next_key::<String>()? {
+            if key == self.key && !found {
+                access.next_value_seed(CopySeed {
+                    out: &mut *self.out,
+                    comma: false,
+                })?;
+                found = true;
+            } else {
+                access.next_value::<de::IgnoredAny>()?;
+            }
+        }
+        Ok(found)
+    }
 }
 
-impl TranslationFile {
-    pub fn from_path<P: AsRef<Path>>(base_path: P, file_path: P) -> Result<Self> {
-        let file_path = file_path.as_ref().canonicalize()?;
-        let base_path = base_path.as_ref().canonicalize()?;
-
-        // Get relative path from base_path
-        let relative_path = file_path
-            .strip_prefix(&base_path)
-            .map_err(|_| anyhow!("File path must be under base path"))?
-            .to_str()
-            .ok_or_else(|| anyhow!("Invalid file path"))?
-            .to_string();
+/// 边解析边把值重新序列化到 `out`，不在内存中构建完整的值
+struct CopySeed<'w> {
+    out: &'w mut dyn Write,
+    /// 数组中除第一个以外的元素，写入前先写逗号。只有确实还有元素时才会调用 `deserialize`
+    comma: bool,
+}
+
+impl CopySeed<'_> {
+    fn write<E: de::Error>(&mut self, value: impl Serialize) -> Result<(), E> {
+        serde_json::to_writer(&mut *self.out, &value).map_err(E::custom)
+    }
+
+    fn write_raw<E: de::Error>(&mut self, text: &[u8]) -> Result<(), E> {
+        self.out.write_all(text).map_err(E::custom)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for CopySeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(mut self, deserializer: D) -> Result<(), D::Error> {
+        if self.comma {
+            self.write_raw(b",")?;
+        }
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for CopySeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut access: A) -> Result<(), A::Error> {
+        self.write_raw(b"{")?;
+        let mut first = true;
+        while let Some(key) = access.next_key::<String>()? {
+            if !first {
+                self.write_raw(b",")?;
+            }
+            first = false;
+            self.write(&key)?;
+            self.write_raw(b":")?;
+            access.next_value_seed(CopySeed {
+                out: &mut *self.out,
+                comma: false,
+            })?;
+        }
+        self.write_raw(b"}")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut access: A) -> Result<(), A::Error> {
+        self.write_raw(b"[")?;
+        let mut first = true;
+        while access
+            .next_element_seed(CopySeed {
+                out: &mut *self.out,
+                comma: !first,
+            })?
+            .is_some()
+        {
+            first = false;
+        }
+        self.write_raw(b"]")
+    }
+
+    fn visit_str<E: de::Error>(mut self, v: &str) -> Result<(), E> {
+        self.write(v)
+    }
+
+    fn visit_bool<E: de::Error>(mut self, v: bool) -> Result<(), E> {
+        self.write(v)
+    }
+
+    fn visit_i64<E: de::Error>(mut self, v: i64) -> Result<(), E> {
+        self.write(v)
+    }
+
+    fn visit_u64<E: de::Error>(mut self, v: u64) -> Result<(), E> {
+        self.write(v)
+    }
+
+    fn visit_f64<E: de::Error>(mut self, v: f64) -> Result<(), E> {
+        self.write(v)
+    }
+
+    fn visit_unit<E: de::Error>(mut self) -> Result<(), E> {
+        self.write_raw(b"null")
+    }
+}
+
+/// 把解析到的值按扁平化后的 key 直接交给 `sink` 的反序列化器
+struct FlattenSeed<'m> {
+    prefix: String,
+    sink: &'m mut dyn FnMut(String, String),
+}
+
+impl FlattenSeed<'_> {
+    fn insert(self, value: Value) {
+        let text = match value {
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        (self.sink)(self.prefix, text);
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for FlattenSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FlattenSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<(), A::Error> {
+        while let Some(key) = access.next_key::<String>()? {
+            let prefix = if self.prefix.is_empty() {
+                key
+            } else {
+                format!("{}.{}", self.prefix, key)
+            };
+            access.next_value_seed(FlattenSeed {
+                prefix,
+                sink: &mut *self.sink,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, access: A) -> Result<(), A::Error> {
+        // 数组与 flatten_json 一样整体保存为 JSON 文本
+        let value = Value::deserialize(de::value::SeqAccessDeserializer::new(access))?;
+        self.insert(value);
+        Ok(())
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<(), E> {
+        self.insert(Value::String(v.to_string()));
+        Ok(())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<(), E> {
+        self.insert(Value::String(v));
+        Ok(())
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<(), E> {
+        self.insert(Value::from(v));
+        Ok(())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<(), E> {
+        self.insert(Value::from(v));
+        Ok(())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<(), E> {
+        self.insert(Value::from(v));
+        Ok(())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<(), E> {
+        self.insert(Value::from(v));
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.insert(Value::Null);
+        Ok(())
+    }
+}
+
+/// 读取 JSONC 文件中的注释，文件不存在时返回空
+pub fn read_comments(fs: &dyn FileSystem, path: &Path) -> Result<JsonComments> {
+    if !fs.exists(path) {
+        return Ok(JsonComments::default());
+    }
+    Ok(jsonc::extract_comments(&decode_text(&fs.read(path)?)?))
+}
+
+/// key 在 JSON 文本中的位置，行号和列号从 1 开始，列按字符计算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// 记录 JSON 文本中每个扁平 key 的位置，支持 JSONC 注释；遇到语法错误时返回已找到的部分
+pub fn key_positions(text: &str) -> HashMap<Arc<str>, Span> {
+    let mut scanner = SpanScanner {
+        text,
+        pos: 0,
+        line: 1,
+        column: 1,
+        spans: HashMap::new(),
+    };
+    scanner.skip_whitespace();
+    if scanner.peek() == Some('{') {
+        let _ = scanner.scan_object("");
+    }
+    scanner.spans
+}
+
+/// 格式化问题位置，如 `locales/en-US.json:12:5`
+pub fn location(path: &str, span: Option<Span>) -> String {
+    match span {
+        Some(span) => format!("{}:{}:{}", path, span.line, span.column),
+        None => path.to_string(),
+    }
+}
+
+struct SpanScanner<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+    spans: HashMap<Arc<str>, Span>,
+}
+
+impl SpanScanner<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_until(&mut self, end: &str) {
+        while self.pos < self.text.len() && !self.text[self.pos..].starts_with(end) {
+            self.bump();
+        }
+        for _ in end.chars() {
+            self.bump();
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = &self.text[self.pos..];
+            if rest.starts_with("//") {
+                self.skip_until("\n");
+            } else if rest.starts_with("/*") {
+                self.skip_until("*/");
+            } else if self
+                .peek()
+                .is_some_and(|c| c.is_whitespace() || c == '\u{feff}')
+            {
+                self.bump();
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn scan_string(&mut self) -> Option<String> {
+        let start = self.pos;
+        self.bump();
+        loop {
+            match self.bump()? {
+                '\\' => {
+                    self.bump()?;
+                }
+                '"' => break,
+                _ => {}
+            }
+        }
+        serde_json::from_str(&self.text[start..self.pos]).ok()
+    }
+
+    fn scan_object(&mut self, prefix: &str) -> Option<()> {
+        self.bump();
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                '}' => {
+                    self.bump();
+                    return Some(());
+                }
+                ',' => {
+                    self.bump();
+                    continue;
+                }
+                '"' => {}
+                _ => return None,
+            }
+            let span = Span {
+                line: self.line,
+                column: self.column,
+            };
+            let key = self.scan_string()?;
+            self.skip_whitespace();
+            if self.bump()? != ':' {
+                return None;
+            }
+            self.skip_whitespace();
+            let key = if prefix.is_empty() {
+                key
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            if self.peek()? == '{' {
+                self.scan_object(&key)?;
+            } else {
+                self.spans.insert(key.into(), span);
+                self.skip_value()?;
+            }
+        }
+    }
+
+    /// 跳过字符串、数字、字面量或数组，数组中的对象不记录位置
+    fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            '"' => {
+                self.scan_string()?;
+            }
+            '[' | '{' => {
+                let mut depth = 0;
+                loop {
+                    match self.peek()? {
+                        '"' => {
+                            self.scan_string()?;
+                            continue;
+                        }
+                        '[' | '{' => depth += 1,
+                        ']' | '}' => depth -= 1,
+                        _ => {}
+                    }
+                    self.bump();
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|c| !matches!(c, ',' | '}' | ']' | '/') && !c.is_whitespace())
+                {
+                    self.bump();
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+/// pull 遇到未解决冲突时写出的冲突文件后缀，读取翻译文件时跳过
+pub const CONFLICTS_FILE_SUFFIX: &str = ".conflicts.json";
+
+pub fn read_translation_files<P: AsRef<Path>>(
+    base_path: P,
+    include_patterns: &[String],
+    exclude_patterns: &[String],
+) -> Result<Vec<TranslationFile>> {
+    let base_path = base_path.as_ref();
+    tracing::info!("Reading translations from: {:?}", base_path);
+    fs::metadata(base_path).with_context(|| format!("无法读取目录 {}", base_path.display()))?;
+    let mut files = Vec::new();
+    let mut matched_files = Vec::new();
+    let mut matched_patterns = Vec::new();
+
+    let exclude_patterns = exclude_patterns
+        .iter()
+        .map(|pattern| relative_pattern(pattern))
+        .collect::<Result<Vec<_>>>()?;
+
+    for pattern in include_patterns {
+        tracing::info!("Searching for pattern: {}", pattern);
+        for (file_path, relative_path) in find_files(base_path, pattern)? {
+            let excluded = relative_path.ends_with(CONFLICTS_FILE_SUFFIX)
+                || exclude_patterns
+                    .iter()
+                    .any(|exclude| exclude.matches_with(&relative_path, MATCH_OPTIONS));
+            if !excluded {
+                matched_files.push((file_path, relative_path));
+                matched_patterns.push(pattern);
+            }
+        }
+    }
+
+    // 并行解析，所有文件都读取完后再汇总报告失败的文件
+    let mut errors = Vec::new();
+    for (((file_path, _), pattern), result) in matched_files
+        .iter()
+        .zip(matched_patterns)
+        .zip(read_files_parallel(&matched_files))
+    {
+        match result {
+            Ok(file) => files.push(apply_path_template(file, pattern)),
+            Err(e) => {
+                tracing::error!("Failed to read file {:?}: {:#}", file_path, e);
+                errors.push(format!("{}: {:#}", file_path.display(), e));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(anyhow!(
+            "{} 个翻译文件读取失败:\n  {}",
+            errors.len(),
+            errors.join("\n  ")
+        ));
+    }
+
+    // 各语言的同一个 key 共享一份内存
+    let mut files = merge_namespaces(files);
+    let mut keys = KeyInterner::default();
+    for file in &mut files {
+        keys.share(file);
+    }
+    Ok(files)
+}
+
+/// 按 include 模板确定文件的语言：模板包含 `{lang}` 时取对应部分，否则使用文件名。
+/// 模板包含 `{namespace}` 时 key 加上命名空间前缀，路径保留 `{namespace}` 以便之后合并
+fn apply_path_template(mut file: TranslationFile, template: &str) -> TranslationFile {
+    let Some(mut vars) = capture_path_vars(template, &file.relative_path) else {
+        return file;
+    };
+    if let Some(lang) = vars.remove(LANG_VAR) {
+        file.language_code = lang;
+    }
+    if let Some(namespace) = vars.remove(NAMESPACE_VAR) {
+        if let Some(path) =
+            replace_path_var(template, &file.relative_path, NAMESPACE_VAR, NAMESPACE_VAR)
+        {
+            file.relative_path = path;
+        }
+        if namespace != DEFAULT_NAMESPACE {
+            file.content = file
+                .content
+                .into_iter()
+                .map(|(key, value)| (Arc::from(format!("{}.{}", namespace, key)), value))
+                .collect();
+        }
+        // 位置信息对应的是单个命名空间文件，与合并后的路径不一致
+        file.spans.clear();
+    }
+    file
+}
+
+/// 合并同一语言、同一模板路径的命名空间文件
+fn merge_namespaces(files: Vec<TranslationFile>) -> Vec<TranslationFile> {
+    let mut merged: Vec<TranslationFile> = Vec::new();
+    for file in files {
+        let existing = merged.iter_mut().find(|f| {
+            f.relative_path.contains(NAMESPACE_VAR)
+                && f.relative_path == file.relative_path
+                && f.language_code == file.language_code
+        });
+        match existing {
+            Some(existing) => existing.content.extend(file.content),
+            None => merged.push(file),
+        }
+    }
+    merged
+}
+
+/// 在多个线程中读取并扁平化文件，结果与输入的顺序一致
+fn read_files_parallel(paths: &[(PathBuf, String)]) -> Vec<Result<TranslationFile>> {
+    if paths.is_empty() {
+        return Vec::new();
+    }
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(paths.len());
+    let chunk_size = paths.len().div_ceil(workers);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(path, relative)| TranslationFile::read(path, relative.clone()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    })
+}
+
+pub fn flatten_json(value: &Value) -> HashMap<String, String> {
+    let mut map = HashMap::with_capacity(leaf_count(value));
+    flatten_json_inner(value, String::new(), &mut map);
+    map
+}
+
+/// JSON 中字符串等叶子节点的数量，用于预先分配容量
+fn leaf_count(value: &Value) -> usize {
+    match value {
+        Value::Object(obj) => obj.values().map(leaf_count).sum(),
+        _ => 1,
+    }
+}
+
+pub fn flatten_json_inner(value: &Value, mut prefix: String, map: &mut HashMap<String, String>) {
+    flatten_into(value, &mut prefix, map);
+}
+
+/// 递归时复用同一个 `prefix` 缓冲区，只在插入叶子节点时分配 key
+fn flatten_into(value: &Value, prefix: &mut String, map: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(obj) => {
+            for (key, val) in obj {
+                let len = prefix.len();
+                if len > 0 {
+                    prefix.push('.');
+                }
+                prefix.push_str(key);
+                flatten_into(val, prefix, map);
+                prefix.truncate(len);
+            }
+        }
+        Value::String(s) => {
+            map.insert(prefix.clone(), s.clone());
+        }
+        _ => {
+            map.insert(prefix.clone(), value.to_string());
+        }
+    }
+}
+
+/// 将扁平的键值对转换为嵌套的 JSON 结构，`a` 与 `a.b` 同时存在时返回错误
+pub fn unflatten_json<K: AsRef<str>, V: AsRef<str>>(map: &HashMap<K, V>) -> Result<Value> {
+    let mut entries: Vec<(&str, &str)> = map
+        .iter()
+        .map(|(key, value)| (key.as_ref(), value.as_ref()))
+        .collect();
+    entries.sort_unstable_by_key(|(key, _)| *key);
+
+    let mut root = serde_json::Map::new();
+    for (key, value) in entries {
+        let (parents, leaf) = match key.rsplit_once('.') {
+            Some((parents, leaf)) => (Some(parents), leaf),
+            None => (None, key),
+        };
+        let mut current = &mut root;
+
+        // 创建嵌套结构
+        for part in parents.into_iter().flat_map(|p| p.split('.')) {
+            current = current
+                .entry(part)
+                .or_insert_with(|| Value::Object(serde_json::Map::new()))
+                .as_object_mut()
+                .ok_or_else(|| anyhow!("Failed to create nested structure for key '{}'", key))?;
+        }
+        current.insert(leaf.to_string(), Value::String(value.to_string()));
+    }
+    Ok(Value::Object(root))
+}
+
+/// 按配置的格式序列化 JSON
+pub fn to_json_string(value: &Value, format: &JsonFormat) -> Result<String> {
+    let indent = match format.indent {
+        JsonIndent::Minified => None,
+        JsonIndent::Tab => Some(b"\t".to_vec()),
+        JsonIndent::Spaces(n) => Some(vec![b' '; n as usize]),
+    };
+
+    let mut output = match indent {
+        None => serde_json::to_string(value)?,
+        Some(indent) => {
+            let mut buf = Vec::new();
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent);
+            let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+            serde::Serialize::serialize(value, &mut serializer)?;
+            String::from_utf8(buf)?
+        }
+    };
+    if format.escape_non_ascii {
+        // 非 ASCII 字符只会出现在字符串中，直接转义整个输出即可
+        output = escape_non_ascii(&output);
+    }
+    Ok(finish_output(output, format))
+}
+
+/// 添加末尾换行并按配置转换换行符，保证不同平台输出的字节相同
+fn finish_output(mut output: String, format: &JsonFormat) -> String {
+    if format.trailing_newline {
+        output.push('\n');
+    }
+    match format.line_ending {
+        LineEnding::Lf => output,
+        // 字符串中的换行已转义为 \n，这里只会替换格式中的换行
+        LineEnding::Crlf => output.replace('\n', "\r\n"),
+    }
+}
+
+/// 按配置的格式序列化 JSON，并把原文件中的注释放回对应的 key 旁边。
+/// minified 格式无法保留注释
+pub fn to_json_string_with_comments(
+    value: &Value,
+    format: &JsonFormat,
+    comments: &JsonComments,
+) -> Result<String> {
+    let indent = match format.indent {
+        _ if comments.is_empty() => return to_json_string(value, format),
+        JsonIndent::Minified => {
+            tracing::debug!("Comments are dropped when writing minified JSON");
+            return to_json_string(value, format);
+        }
+        JsonIndent::Tab => "\t".to_string(),
+        JsonIndent::Spaces(n) => " ".repeat(n as usize),
+    };
+
+    let escape_string = |s: &str| -> Result<String> {
+        let quoted = serde_json::to_string(s)?;
+        Ok(if format.escape_non_ascii {
+            escape_non_ascii(&quoted)
+        } else {
+            quoted
+        })
+    };
+    let output = jsonc::to_string_with_comments(value, comments, &indent, &escape_string)?;
+    Ok(finish_output(output, format))
+}
+
+fn escape_non_ascii(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
+
+/// 把 JSON 中所有 key 和字符串值规范化为 Unicode NFC
+pub fn normalize_json_nfc(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.nfc().collect(), normalize_json_nfc(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(normalize_json_nfc).collect()),
+        Value::String(s) => Value::String(s.nfc().collect()),
+        other => other.clone(),
+    }
+}
+
+/// 按指定的 key 结构转换 JSON
+pub fn apply_key_style(value: &Value, style: KeyStyle) -> Result<Value> {
+    let flattened = flatten_json(value);
+    match style {
+        KeyStyle::Nested => unflatten_json(&flattened),
+        KeyStyle::Flat => Ok(Value::Object(
+            flattened
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect(),
+        )),
+    }
+}
+
+/// 提取文本中的命名占位符，支持 `{name}`、`{{name}}` 和 ICU 的 `{count, plural, ...}`，
+/// 按出现顺序去重
+pub fn extract_placeholders(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut names: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '{' {
+            i += 1;
+            continue;
+        }
+        let double = chars.get(i + 1) == Some(&'{');
+        let start = if double { i + 2 } else { i + 1 };
+
+        // 找到匹配的右括号，ICU 消息中嵌套的 {...} 一并跳过
+        let mut depth = 1;
+        let mut end = start;
+        while end < chars.len() {
+            match chars[end] {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            end += 1;
+        }
+        if end >= chars.len() {
+            break;
+        }
+
+        let inner: String = chars[start..end].iter().collect();
+        let name = inner.split(',').next().unwrap_or("").trim();
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'));
+        if valid && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        i = if double { end + 2 } else { end + 1 };
+    }
+    names
+}
+
+/// key 是否匹配通配符模式，`*` 匹配任意字符（包括 `.`），`?` 匹配单个字符
+pub fn key_matches(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+    let (mut p, mut k) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while k < key.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == key[k]) {
+            p += 1;
+            k += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, k));
+            p += 1;
+        } else if let Some((star_p, star_k)) = backtrack {
+            p = star_p + 1;
+            k = star_k + 1;
+            backtrack = Some((star_p, star_k + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+pub fn key_matches_any(patterns: &[String], key: &str) -> bool {
+    patterns.iter().any(|pattern| key_matches(pattern, key))
+}
+
+/// 按扁平 key 从 JSON 中删除值，同时支持嵌套结构和包含 `.` 的扁平 key
+pub fn remove_flat_key(value: &mut Value, key: &str) -> Option<Value> {
+    let map = value.as_object_mut()?;
+    if let Some(removed) = map.remove(key) {
+        return Some(removed);
+    }
+    let mut split = key.match_indices('.').map(|(i, _)| i);
+    split.find_map(|i| {
+        let child = map.get_mut(&key[..i])?;
+        remove_flat_key(child, &key[i + 1..])
+    })
+}
+
+/// Compare two translation files and return the missing keys from base translation
+pub fn get_missing_keys(base: &TranslationFile, other: &TranslationFile) -> Content {
+    let mut missing = Content::new();
+
+    for (key, value) in &base.content {
+        if !other.content.contains_key(key) {
+            missing.insert(key.clone(), value.clone());
+        }
+    }
+
+    missing
+}
+
+/// 增量上传的内容：新增和修改的 key，以及修改前服务器上的值的哈希
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationDelta {
+    pub language_code: String,
+    pub relative_path: String,
+    pub added: Content,
+    pub changed: Content,
+    /// 修改的 key 在服务器上原值的 SHA-256，服务器据此发现期间的其他修改
+    pub base_hashes: HashMap<Arc<str>, String>,
+}
+
+impl TranslationDelta {
+    /// 按服务器当前的翻译把要上传的 key 分为新增和修改
+    pub fn new(upload: &TranslationFile, remote: &TranslationFile) -> Self {
+        let mut delta = TranslationDelta {
+            language_code: upload.language_code.clone(),
+            relative_path: upload.relative_path.clone(),
+            added: HashMap::new(),
+            changed: HashMap::new(),
+            base_hashes: HashMap::new(),
+        };
+        for (key, value) in &upload.content {
+            match remote.content.get(key) {
+                None => {
+                    delta.added.insert(key.clone(), value.clone());
+                }
+                Some(remote_value) => {
+                    delta.changed.insert(key.clone(), value.clone());
+                    delta
+                        .base_hashes
+                        .insert(key.clone(), text_hash(remote_value));
+                }
+            }
+        }
+        delta
+    }
+
+    pub fn key_count(&self) -> usize {
+        self.added.len() + self.changed.len()
+    }
+
+    /// 合并为完整的上传内容，供不支持增量上传的后端使用
+    pub fn to_translation_file(&self) -> TranslationFile {
+        let mut content = self.added.clone();
+        content.extend(self.changed.clone());
+        TranslationFile::from_content(
+            self.language_code.clone(),
+            self.relative_path.clone(),
+            content,
+        )
+    }
+}
+
+/// 文本的 SHA-256（十六进制）
+pub fn text_hash(text: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    // 逐字节查表比 `format!("{:x}")` 快，push 时每个修改的 key 都要计算一次
+    let digest = Sha256::digest(text.as_bytes());
+    let mut hash = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        hash.push(HEX[usize::from(byte >> 4)] as char);
+        hash.push(HEX[usize::from(byte & 0xf)] as char);
+    }
+    hash
+}
+
+/// 用回退链补全 `file` 中缺失或为空的 key，链中靠前的语言优先。只补全基准语言 `base` 中有的 key，
+/// 回退语言中已经从基准语言删除的 key 不会带入
+pub fn with_fallbacks(
+    file: &TranslationFile,
+    base: &TranslationFile,
+    chain: &[&TranslationFile],
+) -> TranslationFile {
+    let mut resolved = file.clone();
+    for key in base.content.keys() {
+        if resolved
+            .content
+            .get(key)
+            .is_some_and(|v| !v.trim().is_empty())
+        {
+            continue;
+        }
+        let fallback = chain
+            .iter()
+            .filter_map(|f| f.content.get(key))
+            .find(|value| !value.trim().is_empty());
+        if let Some(value) = fallback {
+            resolved.content.insert(key.clone(), value.clone());
+        }
+    }
+    resolved
+}
+
+/// 翻译是否有效：不为空；`identical_allowlist` 为 Some 时还要求与基准文本不同，
+/// 除非文本或 key 在 allowlist 中
+pub fn is_translated(
+    key: &str,
+    base_value: &str,
+    value: &str,
+    identical_allowlist: Option<&[String]>,
+) -> bool {
+    if value.trim().is_empty() {
+        return false;
+    }
+    match identical_allowlist {
+        Some(allowlist) if value == base_value => {
+            allowlist.iter().any(|allowed| allowed == value) || key_matches_any(allowlist, key)
+        }
+        _ => true,
+    }
+}
+
+/// Ratio of base keys that are translated in `other` (see [`is_translated`]), from 0.0 to 1.0
+pub fn completion_ratio(
+    base: &TranslationFile,
+    other: &TranslationFile,
+    identical_allowlist: Option<&[String]>,
+) -> f64 {
+    if base.content.is_empty() {
+        return 1.0;
+    }
+
+    let translated = base
+        .content
+        .iter()
+        .filter(|(key, base_value)| {
+            other
+                .content
+                .get(*key)
+                .is_some_and(|value| is_translated(key, base_value, value, identical_allowlist))
+        })
+        .count();
 
-        // Extract language code from filename (e.g., "en-US.json" -> "en-US")
-        let language_code = file_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .ok_or_else(|| anyhow!("Invalid file name"))?
-            .to_string();
+    translated as f64 / base.content.len() as f64
+}
 
-        let content = fs::read_to_string(&file_path)?;
-        let json: Value = serde_json::from_str(&content)?;
-        let flattened = flatten_json(&json);
+/// 按命名模板生成一种语言的输出文件列表 `(相对路径, 内容)`。
+///
+/// 支持的占位符：`{lang}`、`{subSystem}`、`{namespace}`。模板包含 `{namespace}` 时，
+/// 按根节点的每个 key 拆分为单独的文件。
+pub fn apply_name_template(
+    template: &str,
+    lang: &str,
+    sub_system: &str,
+    value: &Value,
+) -> Result<Vec<(PathBuf, Value)>> {
+    let render = |namespace: &str| -> Result<PathBuf> {
+        let rendered = template
+            .replace("{lang}", lang)
+            .replace("{subSystem}", sub_system)
+            .replace("{namespace}", namespace);
+        let path = PathBuf::from(&rendered);
+        let escapes = path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+        if rendered.trim().is_empty() || escapes {
+            return Err(anyhow!(
+                "命名模板 '{}' 生成了无效的路径 '{}'，只允许目标目录内的相对路径",
+                template,
+                rendered
+            ));
+        }
+        Ok(path)
+    };
 
-        Ok(TranslationFile {
-            language_code,
-            relative_path,
-            content: flattened,
-        })
+    if !template.contains(NAMESPACE_VAR) {
+        return Ok(vec![(render("")?, value.clone())]);
     }
 
-    pub fn from_content(
-        language_code: String,
-        relative_path: String,
-        content: HashMap<String, String>,
-    ) -> Self {
-        TranslationFile {
-            language_code,
-            relative_path,
-            content,
+    split_namespaces(value)
+        .into_iter()
+        .map(|(namespace, child)| Ok((render(&namespace)?, child)))
+        .collect()
+}
+
+/// 按根节点的 key 拆分命名空间，非对象的值归入 [`DEFAULT_NAMESPACE`]
+pub fn split_namespaces(value: &Value) -> Vec<(String, Value)> {
+    split_namespaces_keeping(value, |_| false)
+}
+
+/// 与 [`split_namespaces`] 相同，但 `keep_in_default` 返回 true 的对象根节点也留在
+/// [`DEFAULT_NAMESPACE`] 中，用于保留原本就写在 translation.json 里的对象
+pub fn split_namespaces_keeping(
+    value: &Value,
+    keep_in_default: impl Fn(&str) -> bool,
+) -> Vec<(String, Value)> {
+    let Value::Object(map) = value else {
+        return vec![(DEFAULT_NAMESPACE.to_string(), value.clone())];
+    };
+    let mut namespaces = Vec::new();
+    let mut rest = serde_json::Map::new();
+    for (key, child) in map {
+        if child.is_object() && !keep_in_default(key) {
+            namespaces.push((key.clone(), child.clone()));
+        } else {
+            rest.insert(key.clone(), child.clone());
         }
     }
+    if !rest.is_empty() {
+        namespaces.push((DEFAULT_NAMESPACE.to_string(), Value::Object(rest)));
+    }
+    namespaces
 }
 
-pub fn read_translation_files<P: AsRef<Path>>(
-    base_path: P,
-    include_patterns: &[String],
-    exclude_patterns: &[String],
-) -> Result<Vec<TranslationFile>> {
-    let base_path = base_path.as_ref().canonicalize()?;
-    tracing::info!("Reading translations from: {:?}", base_path);
-    let mut files = Vec::new();
-    let mut included_files = Vec::new();
+/// 路径模板中表示语言的变量
+pub const LANG_VAR: &str = "{lang}";
+/// 路径模板中表示命名空间的变量，同一语言的多个文件合并读取，key 以命名空间为前缀
+pub const NAMESPACE_VAR: &str = "{namespace}";
 
-    // First, collect all files that match include patterns
-    for pattern in include_patterns {
-        let pattern_path = base_path.join(pattern);
-        let pattern_str = pattern_path
-            .to_str()
-            .ok_or_else(|| anyhow!("Invalid pattern path"))?;
-
-        tracing::info!("Searching for pattern: {}", pattern_str);
-
-        for entry in glob(pattern_str)? {
-            match entry {
-                Ok(path) => {
-                    if path.is_file() {
-                        included_files.push(path);
-                    }
-                }
-                Err(e) => return Err(anyhow!("Failed to read glob pattern: {}", e)),
+/// 把路径模板中的 `{lang}`、`{namespace}` 替换为通配符，用于查找文件
+pub fn template_glob(template: &str) -> String {
+    template.replace(LANG_VAR, "*").replace(NAMESPACE_VAR, "*")
+}
+
+fn path_components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect()
+}
+
+/// 把模板的每一级与路径的每一级对应起来，返回 `(模板下标, 路径下标)`。
+/// 模板中的 `**` 可以对应任意多级，其前后的部分分别从路径的开头和结尾对齐
+fn align_components(template: &[&str], path: &[&str]) -> Option<Vec<(usize, usize)>> {
+    match template.iter().position(|c| *c == "**") {
+        None if template.len() == path.len() => Some((0..path.len()).map(|i| (i, i)).collect()),
+        None => None,
+        Some(star) => {
+            let tail = template.len() - star - 1;
+            if template[star + 1..].contains(&"**") || path.len() < star + tail {
+                return None;
             }
+            let tail_start = path.len() - tail;
+            Some(
+                (0..star)
+                    .map(|i| (i, i))
+                    .chain((0..tail).map(|i| (star + 1 + i, tail_start + i)))
+                    .collect(),
+            )
         }
     }
+}
 
-    // Then, filter out excluded files
-    for file_path in included_files {
-        let mut should_include = true;
+/// 在一级路径中找出模板变量的位置，返回 `(变量, 起始, 结束)`。
+/// 同一级中变量以外的部分含有通配符时无法确定变量的取值，返回 None
+fn match_component(template: &str, component: &str) -> Option<Vec<(&'static str, usize, usize)>> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    while let Some((index, var)) = [LANG_VAR, NAMESPACE_VAR]
+        .iter()
+        .filter_map(|var| rest.find(var).map(|index| (index, *var)))
+        .min()
+    {
+        tokens.push((var, &rest[..index]));
+        rest = &rest[index + var.len()..];
+    }
+    if tokens.is_empty() {
+        return Some(Vec::new());
+    }
+    if tokens
+        .iter()
+        .map(|(_, literal)| *literal)
+        .chain([rest])
+        .any(|literal| literal.contains(['*', '?', '[']))
+    {
+        return None;
+    }
 
-        for exclude_pattern in exclude_patterns {
-            let exclude_path = base_path.join(exclude_pattern);
-            let exclude_str = exclude_path
-                .to_str()
-                .ok_or_else(|| anyhow!("Invalid exclude pattern"))?;
+    let mut vars = Vec::new();
+    let mut pos = 0;
+    for (i, (var, literal)) in tokens.iter().enumerate() {
+        if !component[pos..].starts_with(literal) {
+            return None;
+        }
+        pos += literal.len();
+        let next = tokens.get(i + 1).map(|(_, literal)| *literal);
+        let end = match next {
+            Some("") => return None,
+            Some(next) => pos + component[pos..].find(next)?,
+            None if component.ends_with(rest) && component.len() >= pos + rest.len() => {
+                component.len() - rest.len()
+            }
+            None => return None,
+        };
+        if end == pos {
+            return None;
+        }
+        vars.push((*var, pos, end));
+        pos = end;
+    }
+    Some(vars)
+}
 
-            if let Ok(matches) = glob(exclude_str) {
-                for exclude_path in matches.flatten() {
-                    if file_path == exclude_path {
-                        should_include = false;
-                        break;
-                    }
-                }
+/// 按模板解析相对路径中 `{lang}`、`{namespace}` 的取值，路径与模板不对应时返回 None
+pub fn capture_path_vars(template: &str, path: &str) -> Option<HashMap<&'static str, String>> {
+    let template = path_components(template);
+    let path = path_components(path);
+    let mut vars = HashMap::new();
+    for (t, p) in align_components(&template, &path)? {
+        for (var, start, end) in match_component(template[t], path[p])? {
+            let value = &path[p][start..end];
+            if vars
+                .insert(var, value.to_string())
+                .is_some_and(|v| v != value)
+            {
+                return None;
             }
         }
+    }
+    Some(vars)
+}
 
-        if should_include {
-            match TranslationFile::from_path(&base_path, &file_path) {
-                Ok(file) => files.push(file),
-                Err(e) => tracing::warn!("Failed to read file {:?}: {}", file_path, e),
+/// 把路径中对应模板变量 `var` 的部分替换为 `value`，如按 `locales/{lang}/*.json`
+/// 把 `locales/en/app.json` 换成 `locales/zh-CN/app.json`
+pub fn replace_path_var(template: &str, path: &str, var: &str, value: &str) -> Option<String> {
+    let template = path_components(template);
+    let mut path: Vec<String> = path_components(path)
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let borrowed: Vec<&str> = path.iter().map(String::as_str).collect();
+    let mut changes = Vec::new();
+    for (t, p) in align_components(&template, &borrowed)? {
+        for (name, start, end) in match_component(template[t], borrowed[p])? {
+            if name == var {
+                changes.push((p, start, end));
             }
         }
     }
+    if changes.is_empty() {
+        return None;
+    }
+    // 从后往前替换，前面的位置不受影响
+    for (p, start, end) in changes.into_iter().rev() {
+        path[p].replace_range(start..end, value);
+    }
+    Some(path.join("/"))
+}
 
+/// 路径包含 `{namespace}` 时，列出实际存在的各命名空间文件 `(命名空间, 路径)`
+pub fn namespace_files(path: &Path) -> Result<Vec<(String, PathBuf)>> {
+    // 从第一个包含 `{namespace}` 的部分开始按模板查找，之前的目录可以不是 UTF-8
+    let components: Vec<_> = path.components().collect();
+    let Some(index) = components
+        .iter()
+        .position(|c| c.as_os_str().to_string_lossy().contains(NAMESPACE_VAR))
+    else {
+        return Ok(vec![(String::new(), path.to_path_buf())]);
+    };
+    let dir: PathBuf = components[..index].iter().collect();
+    let template = relative_path_string(&components[index..].iter().collect::<PathBuf>())?;
+
+    let mut files = Vec::new();
+    for (file, relative) in find_files(&dir, &template)? {
+        if let Some(namespace) =
+            capture_path_vars(&template, &relative).and_then(|mut vars| vars.remove(NAMESPACE_VAR))
+        {
+            files.push((namespace, file));
+        }
+    }
     Ok(files)
 }
 
-fn flatten_json(value: &Value) -> HashMap<String, String> {
-    let mut map = HashMap::new();
-    flatten_json_inner(value, String::new(), &mut map);
-    map
+/// 把路径中的 `{namespace}` 替换为命名空间，不是 UTF-8 的部分保持原样
+pub fn render_namespace_path(path: &Path, namespace: &str) -> PathBuf {
+    path.components()
+        .map(|c| match c.as_os_str().to_str() {
+            Some(text) if text.contains(NAMESPACE_VAR) => {
+                text.replace(NAMESPACE_VAR, namespace).into()
+            }
+            _ => c.as_os_str().to_os_string(),
+        })
+        .collect()
 }
 
-pub fn flatten_json_inner(value: &Value, prefix: String, map: &mut HashMap<String, String>) {
-    match value {
-        Value::Object(obj) => {
-            for (key, val) in obj {
-                let new_prefix = if prefix.is_empty() {
-                    key.clone()
-                } else {
-                    format!("{}.{}", prefix, key)
-                };
-                flatten_json_inner(val, new_prefix, map);
-            }
-        }
-        Value::String(s) => {
-            map.insert(prefix, s.clone());
-        }
-        _ => {
-            map.insert(prefix, value.to_string());
+/// 拆分为命名空间文件后的路径（以 `/` 分隔）。文件名包含语言（模板中为 `{lang}` 或 `*`）时
+/// 改为语言目录下的命名空间文件，如 `locales/en-US.json` → `locales/en-US/{namespace}.json`；
+/// 否则把文件名换成 `{namespace}`，如 `locales/en-US/messages.json` → `locales/en-US/{namespace}.json`
+pub fn split_namespace_path(path: &str, lang: &str) -> String {
+    let mut components = path_components(path);
+    let file = components.pop().unwrap_or_default();
+    let (stem, ext) = file.rsplit_once('.').unwrap_or((file, "json"));
+    if stem.contains(lang) || stem.contains('*') {
+        components.push(lang);
+    }
+    let file = format!("{}.{}", NAMESPACE_VAR, ext);
+    components.push(&file);
+    components.join("/")
+}
+
+/// 合并命名空间文件后的路径，与 [`split_namespace_path`] 相反：
+/// `locales/en-US/{namespace}.json` → `locales/en-US.json`，`locales/app.{namespace}.json` → `locales/app.json`
+pub fn merge_namespace_path(path: &str) -> String {
+    let mut components: Vec<String> = path_components(path)
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let Some(index) = components.iter().position(|c| c.contains(NAMESPACE_VAR)) else {
+        return components.join("/");
+    };
+    let component = components[index].clone();
+    let is_file = index + 1 == components.len();
+    let (stem, ext) = match component.rsplit_once('.') {
+        Some((stem, ext)) if is_file => (stem.to_string(), format!(".{}", ext)),
+        _ => (component.clone(), String::new()),
+    };
+
+    if stem == NAMESPACE_VAR {
+        components.remove(index);
+        // 文件名只有命名空间时，上一级目录成为合并后的文件
+        match components.last_mut() {
+            Some(dir) if is_file => dir.push_str(&ext),
+            None if is_file => components.push(format!("{}{}", DEFAULT_NAMESPACE, ext)),
+            _ => {}
         }
+    } else {
+        let stripped = ['.', '-', '_']
+            .iter()
+            .flat_map(|sep| {
+                [
+                    format!("{}{}", sep, NAMESPACE_VAR),
+                    format!("{}{}", NAMESPACE_VAR, sep),
+                ]
+            })
+            .find(|pattern| stem.contains(pattern.as_str()))
+            .map_or_else(
+                || stem.replace(NAMESPACE_VAR, ""),
+                |pattern| stem.replacen(&pattern, "", 1),
+            );
+        components[index] = format!("{}{}", stripped, ext);
     }
+    components.join("/")
 }
 
-/// Compare two translation files and return the missing keys from base translation
-pub fn get_missing_keys(
-    base: &TranslationFile,
-    other: &TranslationFile,
-) -> HashMap<String, String> {
-    let mut missing = HashMap::new();
+/// 匹配相对路径时 `*` 不跨越目录，与 shell 的 glob 一致
+const MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
 
-    for (key, value) in &base.content {
-        if !other.content.contains_key(key) {
-            missing.insert(key.clone(), value.clone());
+/// 把配置中的模式编译为匹配以 `/` 分隔的相对路径的 glob，`{lang}`、`{namespace}` 视为 `*`
+fn relative_pattern(pattern: &str) -> Result<glob::Pattern> {
+    let normalized = path_components(&template_glob(pattern)).join("/");
+    glob::Pattern::new(&normalized).with_context(|| format!("无效的匹配模式 '{}'", pattern))
+}
+
+/// 以 `/` 分隔的相对路径是否匹配配置中的模式，`{lang}`、`{namespace}` 视为 `*`
+pub fn path_matches(pattern: &str, relative_path: &str) -> bool {
+    relative_pattern(pattern)
+        .is_ok_and(|pattern| pattern.matches_with(relative_path, MATCH_OPTIONS))
+}
+
+/// 相对路径转为以 `/` 分隔的字符串，Windows 上也使用 `/`，保证配置和输出在各平台一致
+pub fn relative_path_string(path: &Path) -> Result<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => parts
+                .push(part.to_str().ok_or_else(|| {
+                    anyhow!("路径 '{}' 不是有效的 UTF-8", path.to_string_lossy())
+                })?),
+            Component::ParentDir => parts.push(".."),
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(anyhow!("'{}' 不是相对路径", path.display()))
+            }
         }
     }
+    Ok(parts.join("/"))
+}
 
-    missing
+/// 在 `base` 下查找相对路径匹配模式的文件，返回 `(路径, 以 / 分隔的相对路径)`，按路径排序。
+///
+/// 逐级读取目录，而不是把 `base` 拼进 glob 字符串，因此 `base` 可以包含任何字符
+/// （如 Windows 上本地化的用户名）。文件名不是 UTF-8 的文件无法匹配模式，会被跳过
+pub fn find_files(base: &Path, pattern: &str) -> Result<Vec<(PathBuf, String)>> {
+    let matcher = relative_pattern(pattern)?;
+    let normalized = matcher.as_str().to_string();
+    let components = path_components(&normalized);
+    // 不含通配符的开头部分直接作为起始目录，最后一级总是要匹配的文件名
+    let fixed = components
+        .iter()
+        .take_while(|c| !c.contains(['*', '?', '[']))
+        .count()
+        .min(components.len().saturating_sub(1));
+    let rest = &components[fixed..];
+    let max_depth = (!rest.contains(&"**")).then_some(rest.len());
+
+    let mut files = Vec::new();
+    let start: PathBuf = base.join(components[..fixed].iter().collect::<PathBuf>());
+    walk_dir(
+        &start,
+        components[..fixed].join("/"),
+        1,
+        max_depth,
+        &matcher,
+        &mut files,
+    )?;
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(files)
+}
+
+fn walk_dir(
+    dir: &Path,
+    relative_dir: String,
+    depth: usize,
+    max_depth: Option<usize>,
+    matcher: &glob::Pattern,
+    files: &mut Vec<(PathBuf, String)>,
+) -> Result<()> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            tracing::warn!("Skipping non-UTF-8 file name: {}", path.display());
+            continue;
+        };
+        let relative = if relative_dir.is_empty() {
+            name
+        } else {
+            format!("{}/{}", relative_dir, name)
+        };
+        let file_type = entry.file_type()?;
+        // `**` 不限制深度时不进入符号链接的目录，避免循环
+        let is_dir =
+            file_type.is_dir() || (file_type.is_symlink() && max_depth.is_some() && path.is_dir());
+        if is_dir {
+            if max_depth.is_none_or(|max| depth < max) {
+                walk_dir(&path, relative, depth + 1, max_depth, matcher, files)?;
+            }
+        } else if path.is_file() && matcher.matches_with(&relative, MATCH_OPTIONS) {
+            files.push((path, relative));
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::translation_file;
+    use std::collections::BTreeMap;
     use std::fs::File;
     use std::io::Write;
     use tempfile::TempDir;
@@ -172,7 +1676,7 @@ mod tests {
     fn test_flatten_json_simple() {
         let json: Value = serde_json::from_str(r#"{"key": "value"}"#).unwrap();
         let flattened = flatten_json(&json);
-        assert_eq!(flattened.get("key").unwrap(), "value");
+        assert_eq!(&*flattened["key"], "value");
     }
 
     #[test]
@@ -180,12 +1684,12 @@ mod tests {
         let json: Value =
             serde_json::from_str(r#"{"parent": {"child": "value", "child2": "value2"}}"#).unwrap();
         let flattened = flatten_json(&json);
-        assert_eq!(flattened.get("parent.child").unwrap(), "value");
-        assert_eq!(flattened.get("parent.child2").unwrap(), "value2");
+        assert_eq!(&*flattened["parent.child"], "value");
+        assert_eq!(&*flattened["parent.child2"], "value2");
     }
 
     #[test]
-    fn test_translation_file_from_path() -> Result<()> {
+    fn test_translation_file_read() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let file_path = temp_dir.path().join("en-US.json");
 
@@ -193,10 +1697,12 @@ mod tests {
         let mut file = File::create(&file_path)?;
         file.write_all(content.as_bytes())?;
 
-        let translation = TranslationFile::from_path(temp_dir.path(), &file_path)?;
+        let translation = TranslationFile::read(&file_path, "en-US.json".to_string())?;
         assert_eq!(translation.language_code, "en-US");
         assert_eq!(translation.relative_path, "en-US.json");
-        assert_eq!(translation.content.get("key").unwrap(), "value");
+        assert_eq!(&*translation.content["key"], "value");
+        assert_eq!(translation.location("key"), "en-US.json:1:2");
+        assert_eq!(translation.location("missing"), "en-US.json");
 
         Ok(())
     }
@@ -256,7 +1762,539 @@ mod tests {
 
         let missing = get_missing_keys(&base, &other);
         assert_eq!(missing.len(), 2);
-        assert_eq!(missing.get("key2").unwrap(), "Value2");
-        assert_eq!(missing.get("detail.label_time").unwrap(), "Time");
+        assert_eq!(&*missing["key2"], "Value2");
+        assert_eq!(&*missing["detail.label_time"], "Time");
+    }
+
+    #[test]
+    fn test_with_fallbacks() {
+        let pt_pt = translation_file("pt-PT", &[("a", "PT"), ("b", "")]);
+        let pt_br = translation_file("pt-BR", &[("a", "BR"), ("b", "BR"), ("old", "Velho")]);
+        let en_us = translation_file("en-US", &[("a", "A"), ("b", "B"), ("c", "C")]);
+
+        let resolved = with_fallbacks(&pt_pt, &en_us, &[&pt_br, &en_us]);
+        assert_eq!(&*resolved.content["a"], "PT");
+        assert_eq!(&*resolved.content["b"], "BR");
+        assert_eq!(&*resolved.content["c"], "C");
+        // 基准语言中没有的 key 不从回退语言带入
+        assert!(!resolved.content.contains_key("old"));
+        assert_eq!(completion_ratio(&en_us, &resolved, None), 1.0);
+    }
+
+    #[test]
+    fn test_flatten_json_reader_matches_flatten_json() -> Result<()> {
+        let text = r#"{"a": {"b": "x", "c": [1, "y"], "d": 1.5}, "e": null, "f": true, "g": 42}"#;
+        let expected = flatten_json(&serde_json::from_str(text)?);
+        assert_eq!(flatten_json_reader(text.as_bytes())?, expected);
+        assert_eq!(expected["a.c"], r#"[1,"y"]"#);
+
+        let wrapped = format!(r#"{{"meta": {{"x": [1, 2]}}, "app/languages": {}}}"#, text);
+        let mut field = Vec::new();
+        assert!(copy_json_field(
+            wrapped.as_bytes(),
+            "app/languages",
+            &mut field
+        )?);
+        assert_eq!(
+            serde_json::from_slice::<Value>(&field)?,
+            serde_json::from_str::<Value>(text)?
+        );
+        assert!(!copy_json_field(
+            wrapped.as_bytes(),
+            "missing",
+            &mut Vec::new()
+        )?);
+        assert!(copy_json_field("[1]".as_bytes(), "missing", &mut Vec::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_flat_json_file_streams_large_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("en-US.json");
+        let padding = "x".repeat(STREAMING_PARSE_THRESHOLD as usize);
+        fs::write(
+            &path,
+            format!(
+                "\u{feff}{{\"a\": {{\"b\": \"B\"}}, \"pad\": \"{}\"}}",
+                padding
+            ),
+        )?;
+
+        let map = read_flat_json_file(&path)?;
+        assert_eq!(map["a.b"], "B");
+        assert_eq!(map["pad"].len(), padding.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_path_template_vars() {
+        let vars = capture_path_vars("locales/{lang}/*.json", "locales/zh-CN/app.json").unwrap();
+        assert_eq!(vars.get(LANG_VAR).map(String::as_str), Some("zh-CN"));
+
+        let vars = capture_path_vars("**/{namespace}.{lang}.json", "a/b/home.en-US.json").unwrap();
+        assert_eq!(vars.get(NAMESPACE_VAR).map(String::as_str), Some("home"));
+        assert_eq!(vars.get(LANG_VAR).map(String::as_str), Some("en-US"));
+
+        assert!(capture_path_vars("locales/{lang}/*.json", "other/app.json").is_none());
+        assert!(capture_path_vars("messages_{lang}.json", "strings_en.json").is_none());
+
+        assert_eq!(
+            replace_path_var(
+                "./locales/{lang}/*.json",
+                "locales/en/app.json",
+                LANG_VAR,
+                "de"
+            ),
+            Some("locales/de/app.json".to_string())
+        );
+        assert_eq!(replace_path_var("*.json", "en.json", LANG_VAR, "de"), None);
+    }
+
+    #[test]
+    fn test_read_translation_files_with_template() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        for (path, content) in [
+            ("locales/en/common.json", r#"{"ok": "OK"}"#),
+            ("locales/en/home.json", r#"{"title": "Home"}"#),
+            ("locales/en/translation.json", r#"{"appName": "App"}"#),
+            ("locales/de/common.json", r#"{"ok": "Gut"}"#),
+        ] {
+            let file_path = temp_dir.path().join(path);
+            fs::create_dir_all(file_path.parent().unwrap())?;
+            fs::write(file_path, content)?;
+        }
+
+        let mut files = read_translation_files(
+            temp_dir.path(),
+            &["locales/{lang}/{namespace}.json".to_string()],
+            &[],
+        )?;
+        files.sort_by(|a, b| a.language_code.cmp(&b.language_code));
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].language_code, "de");
+        assert_eq!(files[1].language_code, "en");
+        assert_eq!(files[1].relative_path, "locales/en/{namespace}.json");
+        assert_eq!(files[1].content.len(), 3);
+        assert_eq!(&*files[1].content["common.ok"], "OK");
+        assert_eq!(&*files[1].content["home.title"], "Home");
+        assert_eq!(&*files[1].content["appName"], "App");
+
+        let merged = read_json_file(&temp_dir.path().join(&files[1].relative_path))?;
+        assert_eq!(
+            merged,
+            serde_json::json!({"common": {"ok": "OK"}, "home": {"title": "Home"}, "appName": "App"})
+        );
+
+        // translation.json 中的对象与 common.json 定义了同一个命名空间
+        fs::write(
+            temp_dir.path().join("locales/en/translation.json"),
+            r#"{"common": {"cancel": "Cancel"}}"#,
+        )?;
+        let err = read_json_file(&temp_dir.path().join(&files[1].relative_path)).unwrap_err();
+        assert!(err.to_string().contains("'common'"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_and_merge_namespace_path() {
+        let cases = [
+            (
+                "locales/en-US.json",
+                "en-US",
+                "locales/en-US/{namespace}.json",
+            ),
+            (
+                "locales/en-US/messages.json",
+                "en-US",
+                "locales/en-US/{namespace}.json",
+            ),
+            (
+                "locales/*.json",
+                LANG_VAR,
+                "locales/{lang}/{namespace}.json",
+            ),
+            ("./{lang}.json", LANG_VAR, "{lang}/{namespace}.json"),
+        ];
+        for (path, lang, expected) in cases {
+            assert_eq!(split_namespace_path(path, lang), expected);
+        }
+
+        assert_eq!(
+            merge_namespace_path("locales/en-US/{namespace}.json"),
+            "locales/en-US.json"
+        );
+        assert_eq!(
+            merge_namespace_path("locales/{lang}/app.{namespace}.json"),
+            "locales/{lang}/app.json"
+        );
+        assert_eq!(
+            merge_namespace_path("locales/{namespace}/{lang}.json"),
+            "locales/{lang}.json"
+        );
+        assert_eq!(
+            merge_namespace_path("locales/{lang}.json"),
+            "locales/{lang}.json"
+        );
+    }
+
+    #[test]
+    fn test_find_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        // 目录名不是 UTF-8 时（如 Linux 上 Latin-1 编码的用户目录）仍然可以查找
+        #[cfg(unix)]
+        let base = {
+            use std::os::unix::ffi::OsStrExt;
+            temp_dir
+                .path()
+                .join(std::ffi::OsStr::from_bytes(b"caf\xe9"))
+        };
+        #[cfg(not(unix))]
+        let base = temp_dir.path().join("café");
+        for path in ["en.json", "locales/de.json", "locales/web/fr.json"] {
+            let path = base.join(path);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, "{}")?;
+        }
+
+        let relative = |pattern: &str| -> Result<Vec<String>> {
+            Ok(find_files(&base, pattern)?
+                .into_iter()
+                .map(|(_, relative)| relative)
+                .collect())
+        };
+        assert_eq!(relative("*.json")?, vec!["en.json"]);
+        assert_eq!(relative("./locales/*.json")?, vec!["locales/de.json"]);
+        assert_eq!(
+            relative("**/*.json")?,
+            vec!["en.json", "locales/de.json", "locales/web/fr.json"]
+        );
+        assert_eq!(relative("locales\\{lang}.json")?, vec!["locales/de.json"]);
+
+        let files = read_translation_files(&base, &["**/*.json".to_string()], &[])?;
+        assert_eq!(files.len(), 3);
+        assert!(files
+            .iter()
+            .any(|f| f.relative_path == "locales/web/fr.json" && f.language_code == "fr"));
+
+        assert_eq!(
+            relative_path_string(&Path::new("a").join("b").join("c.json"))?,
+            "a/b/c.json"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_translation_files_reports_all_errors() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        for (name, content) in [
+            ("en-US.json", r#"{"a": "A"}"#),
+            ("zh-CN.json", r#"{"a": "甲"}"#),
+            ("ja-JP.json", "{"),
+            ("ko-KR.json", "[1,"),
+        ] {
+            fs::write(temp_dir.path().join(name), content)?;
+        }
+
+        let err = read_translation_files(temp_dir.path(), &["*.json".to_string()], &[])
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(err.starts_with("2 个翻译文件读取失败"));
+        assert!(err.contains("ja-JP.json") && err.contains("ko-KR.json"));
+
+        let files = read_translation_files(
+            temp_dir.path(),
+            &["*.json".to_string()],
+            &["ja-JP.json".to_string(), "ko-KR.json".to_string()],
+        )?;
+        let languages: Vec<_> = files.iter().map(|f| f.language_code.as_str()).collect();
+        assert_eq!(languages, ["en-US", "zh-CN"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_translation_delta() {
+        let file = |entries: &[(&str, &str)]| translation_file("en-US", entries);
+        let remote = file(&[("a", "A"), ("b", "")]);
+        let upload = file(&[("a", "A2"), ("b", "B"), ("c", "C")]);
+
+        let delta = TranslationDelta::new(&upload, &remote);
+        assert_eq!(delta.key_count(), 3);
+        assert_eq!(delta.added, to_content([("c", "C")]));
+        assert_eq!(delta.changed.len(), 2);
+        assert_eq!(delta.base_hashes["a"], text_hash("A"));
+        assert_eq!(
+            delta.base_hashes["b"],
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(delta.to_translation_file().content, upload.content);
+    }
+
+    #[test]
+    fn test_completion_ratio() {
+        let base = TranslationFile::from_content(
+            "en-US".to_string(),
+            "en-US.json".to_string(),
+            HashMap::from([
+                ("key1".to_string(), "Value1".to_string()),
+                ("key2".to_string(), "Value2".to_string()),
+                ("key3".to_string(), "Value3".to_string()),
+                ("key4".to_string(), "Value4".to_string()),
+            ]),
+        );
+        let other = TranslationFile::from_content(
+            "zh-CN".to_string(),
+            "zh-CN.json".to_string(),
+            HashMap::from([
+                ("key1".to_string(), "值1".to_string()),
+                ("key2".to_string(), " ".to_string()),
+                ("extra".to_string(), "多余".to_string()),
+            ]),
+        );
+
+        assert_eq!(completion_ratio(&base, &other, None), 0.25);
+        assert_eq!(completion_ratio(&base, &base, None), 1.0);
+
+        // 与基准文本相同的翻译视为未翻译，allowlist 中的文本或 key 除外
+        let mut backfilled = base.clone();
+        backfilled
+            .content
+            .insert(Arc::from("key1"), Arc::from("值1"));
+        assert_eq!(completion_ratio(&base, &backfilled, Some(&[])), 0.25);
+        let allowlist = ["Value2".to_string(), "key3".to_string()];
+        assert_eq!(completion_ratio(&base, &backfilled, Some(&allowlist)), 0.75);
+    }
+
+    #[test]
+    fn test_apply_name_template() -> Result<()> {
+        let value = serde_json::json!({
+            "common": {"ok": "OK"},
+            "home": {"title": "Home"},
+            "appName": "App"
+        });
+
+        let files = apply_name_template(DEFAULT_NAME_TEMPLATE, "en-US", "app", &value)?;
+        assert_eq!(files, vec![(PathBuf::from("en-US.json"), value.clone())]);
+
+        let files =
+            apply_name_template("{subSystem}/{lang}/{namespace}.json", "en", "app", &value)?;
+        let paths: Vec<_> = files.iter().map(|(p, _)| p.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("app/en/common.json"),
+                PathBuf::from("app/en/home.json"),
+                PathBuf::from("app/en/translation.json"),
+            ]
+        );
+        assert_eq!(files[1].1, serde_json::json!({"title": "Home"}));
+        assert_eq!(files[2].1, serde_json::json!({"appName": "App"}));
+
+        assert!(apply_name_template("../{lang}.json", "en", "app", &value).is_err());
+        assert!(apply_name_template("/tmp/{lang}.json", "en", "app", &value).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_key_style() -> Result<()> {
+        let nested = serde_json::json!({"home": {"title": "Home", "menu": {"open": "Open"}}});
+        let flat = serde_json::json!({"home.title": "Home", "home.menu.open": "Open"});
+
+        assert_eq!(apply_key_style(&nested, KeyStyle::Flat)?, flat);
+        assert_eq!(apply_key_style(&flat, KeyStyle::Nested)?, nested);
+        assert_eq!(apply_key_style(&nested, KeyStyle::Nested)?, nested);
+
+        let conflicting = serde_json::json!({"home": "Home", "home.title": "Title"});
+        assert!(apply_key_style(&conflicting, KeyStyle::Nested).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_json_string_formats() -> Result<()> {
+        let value = serde_json::json!({"home": {"title": "Home"}});
+        let format = |indent, trailing_newline| JsonFormat {
+            indent,
+            trailing_newline,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            to_json_string(&value, &JsonFormat::default())?,
+            serde_json::to_string_pretty(&value)?
+        );
+        assert_eq!(
+            to_json_string(&value, &format(JsonIndent::Spaces(4), true))?,
+            "{\n    \"home\": {\n        \"title\": \"Home\"\n    }\n}\n"
+        );
+        assert_eq!(
+            to_json_string(&value, &format(JsonIndent::Tab, false))?,
+            "{\n\t\"home\": {\n\t\t\"title\": \"Home\"\n\t}\n}"
+        );
+        assert_eq!(
+            to_json_string(&value, &format(JsonIndent::Minified, false))?,
+            r#"{"home":{"title":"Home"}}"#
+        );
+        let crlf = JsonFormat {
+            line_ending: LineEnding::Crlf,
+            ..format(JsonIndent::Spaces(2), true)
+        };
+        assert_eq!(
+            to_json_string(&serde_json::json!({"b": "x\ny", "a": 1.50}), &crlf)?,
+            "{\r\n  \"a\": 1.5,\r\n  \"b\": \"x\\ny\"\r\n}\r\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_text_with_bom() -> Result<()> {
+        let text = r#"{"title": "你好"}"#;
+
+        let mut utf8_bom = vec![0xEF, 0xBB, 0xBF];
+        utf8_bom.extend_from_slice(text.as_bytes());
+        assert_eq!(decode_text(&utf8_bom)?, text);
+
+        let mut utf16_le = vec![0xFF, 0xFE];
+        utf16_le.extend(text.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        assert_eq!(decode_text(&utf16_le)?, text);
+
+        let mut utf16_be = vec![0xFE, 0xFF];
+        utf16_be.extend(text.encode_utf16().flat_map(|u| u.to_be_bytes()));
+        assert_eq!(decode_text(&utf16_be)?, text);
+
+        assert_eq!(decode_text(text.as_bytes())?, text);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unicode_output_options() -> Result<()> {
+        let value = serde_json::json!({"title": "Caf\u{0065}\u{0301} 😀"});
+
+        let normalized = normalize_json_nfc(&value);
+        assert_eq!(normalized["title"], "Caf\u{00e9} 😀");
+
+        let format = JsonFormat {
+            indent: JsonIndent::Minified,
+            escape_non_ascii: true,
+            ..Default::default()
+        };
+        let output = to_json_string(&normalized, &format)?;
+        assert_eq!(output, r#"{"title":"Caf\u00e9 \ud83d\ude00"}"#);
+        let parsed: Value = serde_json::from_str(&output)?;
+        assert_eq!(parsed, normalized);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_positions() {
+        let text = "\u{feff}{\n  // 注释\n  \"home\": {\n    \"title\": \"首页\", \"list\": [{\"a\": 1}],\n    /* x */ \"a\\\"b\": null\n  },\n  \"ok\": \"OK\"\n}";
+        let spans = key_positions(text);
+        assert_eq!(spans.len(), 4);
+        assert_eq!(spans["home.title"], Span { line: 4, column: 5 });
+        assert_eq!(
+            spans["home.list"],
+            Span {
+                line: 4,
+                column: 20
+            }
+        );
+        assert_eq!(
+            spans["home.a\"b"],
+            Span {
+                line: 5,
+                column: 13
+            }
+        );
+        assert_eq!(spans["ok"], Span { line: 7, column: 3 });
+
+        // 语法错误之前的 key 仍然可以定位
+        let spans = key_positions("{\"a\": 1, \"b\" 2}");
+        assert_eq!(spans.len(), 1);
+    }
+
+    #[test]
+    fn test_extract_placeholders() {
+        assert_eq!(
+            extract_placeholders("Hello {name}, you have {{count}} new {name} messages"),
+            vec!["name", "count"]
+        );
+        assert_eq!(
+            extract_placeholders("{count, plural, one {# item} other {# items}} in {cart}"),
+            vec!["count", "cart"]
+        );
+        assert!(extract_placeholders("No placeholders, {} or { spaced out }").is_empty());
+    }
+
+    #[test]
+    fn test_key_matches() {
+        assert!(key_matches("legal.*", "legal.terms.title"));
+        assert!(key_matches("*.title", "home.title"));
+        assert!(key_matches("home.?", "home.a"));
+        assert!(key_matches("home.title", "home.title"));
+        assert!(!key_matches("legal.*", "legalese"));
+        assert!(!key_matches("home.?", "home.ab"));
+        assert!(key_matches_any(
+            &["debug.*".to_string(), "legal.*".to_string()],
+            "legal.privacy"
+        ));
+    }
+
+    #[test]
+    fn test_remove_flat_key() {
+        let mut value = serde_json::json!({
+            "legal": {"terms": "Terms", "privacy.title": "Privacy"},
+            "home.title": "Home"
+        });
+        assert_eq!(
+            remove_flat_key(&mut value, "legal.terms"),
+            Some("Terms".into())
+        );
+        assert_eq!(
+            remove_flat_key(&mut value, "legal.privacy.title"),
+            Some("Privacy".into())
+        );
+        assert_eq!(
+            remove_flat_key(&mut value, "home.title"),
+            Some("Home".into())
+        );
+        assert_eq!(remove_flat_key(&mut value, "missing.key"), None);
+        assert_eq!(value, serde_json::json!({"legal": {}}));
+    }
+
+    #[test]
+    fn test_flatten_unflatten_golden() -> Result<()> {
+        let nested = crate::test_util::read_fixture("golden/nested/en-US.json");
+        let flat: BTreeMap<String, String> = flatten_json(&nested).into_iter().collect();
+        insta::assert_json_snapshot!("flatten_nested", flat);
+
+        // 非字符串的值展开后变为字符串，其余结构原样还原
+        let restored = unflatten_json(&flat.into_iter().collect())?;
+        insta::assert_json_snapshot!("unflatten_nested", restored);
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_are_shared_between_languages() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(
+            temp_dir.path().join("en-US.json"),
+            r#"{"common": {"save": "Save"}}"#,
+        )?;
+        fs::write(
+            temp_dir.path().join("de.json"),
+            r#"{"common": {"save": "Speichern"}}"#,
+        )?;
+        let files = read_translation_files(temp_dir.path(), &["*.json".to_string()], &[])?;
+        let (en_key, _) = files[0].content.get_key_value("common.save").unwrap();
+        let (de_key, _) = files[1].content.get_key_value("common.save").unwrap();
+        let (span_key, _) = files[1].spans.get_key_value("common.save").unwrap();
+        assert!(Arc::ptr_eq(en_key, de_key));
+        assert!(Arc::ptr_eq(en_key, span_key));
+        Ok(())
     }
 }
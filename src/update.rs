@@ -1,4 +1,5 @@
 use crate::config::Config;
+use crate::platform;
 use anyhow::{anyhow, Context, Result};
 use futures_util::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
@@ -77,7 +78,7 @@ async fn check_rate_limit(client: &reqwest::Client) -> Result<()> {
     if remaining < 10 {
         if Config::get_github_token().is_none() {
             tracing::warn!(
-                "GitHub API 调用次数即将用尽（{}），建议配置 token 以提高限制。\n配置方法：创建文件 ~/.config/i18n-app/config.toml 并添加：\n[github]\ntoken = \"your_token\"",
+                "GitHub API 调用次数即将用尽（{}），建议配置 token 以提高限制。\n配置方法：运行 `i18n-app auth login --github`，token 保存在系统钥匙串中",
                 remaining
             );
         } else {
@@ -199,6 +200,23 @@ async fn download_file(client: &Client, url: &str, description: &str) -> Result<
     Ok(bytes)
 }
 
+/// 下载当前平台的发布文件并替换正在运行的可执行文件
+async fn replace_with_release(client: &Client, release: &GithubRelease) -> Result<bool> {
+    let asset = platform::RELEASE_ASSET
+        .and_then(|name| release.assets.iter().find(|asset| asset.name == name))
+        .ok_or_else(|| {
+            anyhow!(
+                "版本 {} 没有适用于当前平台的文件，请手动更新",
+                release.tag_name
+            )
+        })?;
+    let content = download_file(client, &asset.browser_download_url, "下载新版本").await?;
+    let exe = std::env::current_exe().context("无法确定当前可执行文件的位置")?;
+    platform::replace_executable(&exe, &content)?;
+    tracing::info!("更新成功！请重新运行程序。");
+    Ok(true)
+}
+
 pub async fn update() -> Result<bool> {
     match update_internal().await {
         Ok(updated) => Ok(updated),
@@ -219,6 +237,11 @@ async fn update_internal() -> Result<bool> {
 
         let client = create_client()?;
 
+        // Windows 没有 bash，直接下载发布的可执行文件替换自身
+        if cfg!(windows) {
+            return replace_with_release(&client, &release).await;
+        }
+
         // 下载安装脚本
         let install_script = download_file(
             &client,
@@ -233,18 +256,13 @@ async fn update_internal() -> Result<bool> {
         std::io::Write::write_all(&mut temp_file, &install_script)?;
 
         // 设置脚本文件为可执行
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = temp_file.as_file().metadata()?.permissions();
-            perms.set_mode(0o755);
-            temp_file.as_file().set_permissions(perms)?;
-        }
+        platform::make_executable(temp_file.path())?;
 
         // 执行安装脚本
-        let status = std::process::Command::new("/bin/bash")
+        let status = std::process::Command::new("bash")
             .arg(temp_file.path())
-            .status()?;
+            .status()
+            .context("执行安装脚本失败，请确认已安装 bash")?;
 
         if status.success() {
             tracing::info!("更新成功！请重新运行程序。");
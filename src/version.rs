@@ -0,0 +1,62 @@
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat};
+use serde::Serialize;
+
+/// 编译时由 build.rs 记录的构建信息
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub commit: &'static str,
+    /// RFC 3339 格式的构建时间（UTC）
+    pub build_date: String,
+    pub target: &'static str,
+}
+
+pub fn build_info() -> BuildInfo {
+    let build_date = env!("I18N_APP_BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default();
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        commit: env!("I18N_APP_COMMIT"),
+        build_date,
+        target: env!("I18N_APP_TARGET"),
+    }
+}
+
+/// 输出版本和构建信息，`json` 为 true 时输出 JSON，便于脚本校验安装的版本
+pub fn run(json: bool) -> Result<()> {
+    let info = build_info();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else {
+        println!("i18n-app {}", info.version);
+        println!("commit:     {}", info.commit);
+        println!("build date: {}", info.build_date);
+        println!("target:     {}", info.target);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info() -> Result<()> {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.target.is_empty());
+        assert!(info.build_date.ends_with('Z'));
+
+        let json = serde_json::to_value(&info)?;
+        for field in ["version", "commit", "buildDate", "target"] {
+            assert!(json.get(field).is_some(), "missing {}", field);
+        }
+        Ok(())
+    }
+}
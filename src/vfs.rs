@@ -0,0 +1,301 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+#[cfg(test)]
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
+/// 读写文件的接口。默认使用 [`RealFs`] 读写磁盘；测试使用 `MemoryFs`，不依赖当前目录，
+/// 也不会在项目中留下文件。
+///
+/// 服务写入的翻译文件，以及 `.i18n-app` 中的缓存、清单、快照、上传计划等都通过这个接口读写；
+/// 本地翻译文件按 include 模式在磁盘上查找和读取，测试把它们放在临时目录中
+pub trait FileSystem: Send + Sync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    /// 追加到文件末尾，文件不存在时创建
+    fn append(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    fn exists(&self, path: &Path) -> bool;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    /// 重命名文件或目录
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    /// 目录中的文件和子目录，顺序不确定
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// 打开文件用于流式读取，大文件不需要完整读入内存
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(io::Cursor::new(self.read(path)?)))
+    }
+
+    /// 文件的字节数
+    fn len(&self, path: &Path) -> io::Result<u64> {
+        io::copy(&mut self.open(path)?, &mut io::sink())
+    }
+
+    /// 创建（或清空）文件用于流式写入，写完后需要调用 `flush`
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write + Send + '_>> {
+        self.write(path, b"")?;
+        Ok(Box::new(AppendWriter {
+            fs: self,
+            path: path.to_path_buf(),
+        }))
+    }
+}
+
+/// 把每次写入追加到文件末尾
+struct AppendWriter<'a, F: ?Sized> {
+    fs: &'a F,
+    path: PathBuf,
+}
+
+impl<F: FileSystem + ?Sized> Write for AppendWriter<'_, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.fs.append(&self.path, buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 直接读写磁盘
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealFs;
+
+impl FileSystem for RealFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    /// 先写入同一目录中的临时文件再替换，写入过程中进程退出时原文件保持完整
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(format!(".{}.tmp", std::process::id()));
+        fs::write(&temp, contents)?;
+        fs::rename(&temp, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+    }
+
+    fn append(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(contents)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect()
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+
+    fn len(&self, path: &Path) -> io::Result<u64> {
+        Ok(fs::metadata(path)?.len())
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(io::BufReader::new(fs::File::open(path)?)))
+    }
+
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write + Send + '_>> {
+        Ok(Box::new(io::BufWriter::new(fs::File::create(path)?)))
+    }
+}
+
+/// 保存在内存中的文件，目录是隐式的：包含文件的路径都视为存在的目录
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+#[cfg(test)]
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} not found", path.display()),
+    )
+}
+
+#[cfg(test)]
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn files(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, Vec<u8>>> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 所有文件的路径，按路径排序
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+impl FileSystem for MemoryFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.files().insert(path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+
+    fn append(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.files()
+            .entry(path.to_path_buf())
+            .or_default()
+            .extend_from_slice(contents);
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files().keys().any(|file| file.starts_with(path))
+    }
+
+    fn create_dir_all(&self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.files()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut files = self.files();
+        let moved: Vec<PathBuf> = files
+            .keys()
+            .filter(|file| file.starts_with(from))
+            .cloned()
+            .collect();
+        if moved.is_empty() {
+            return Err(not_found(from));
+        }
+        // 目标是已有的目录时与磁盘上一样失败
+        if moved.iter().any(|file| file != from) && files.keys().any(|file| file.starts_with(to)) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", to.display()),
+            ));
+        }
+        for file in moved {
+            let contents = files.remove(&file).unwrap_or_default();
+            let target = match file.strip_prefix(from) {
+                Ok(rest) if !rest.as_os_str().is_empty() => to.join(rest),
+                _ => to.to_path_buf(),
+            };
+            files.insert(target, contents);
+        }
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let entries: BTreeSet<PathBuf> = self
+            .files()
+            .keys()
+            .filter_map(|file| {
+                let child = file.strip_prefix(path).ok()?.components().next()?;
+                Some(path.join(child))
+            })
+            .collect();
+        if entries.is_empty() {
+            return Err(not_found(path));
+        }
+        Ok(entries.into_iter().collect())
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut files = self.files();
+        let before = files.len();
+        files.retain(|file, _| !file.starts_with(path));
+        if files.len() == before {
+            return Err(not_found(path));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_fs() -> io::Result<()> {
+        let fs = MemoryFs::new();
+        let path = Path::new("locales/en-US.json");
+        assert!(!fs.exists(path));
+        assert!(fs.read(path).is_err());
+
+        fs.write(path, b"{}")?;
+        assert!(fs.exists(path));
+        assert!(fs.exists(Path::new("locales")));
+        assert_eq!(fs.read_to_string(path)?, "{}");
+
+        let mut file = fs.create(path)?;
+        file.write_all(b"{\"a\": ")?;
+        file.write_all(b"1}")?;
+        file.flush()?;
+        drop(file);
+        assert_eq!(fs.read_to_string(path)?, "{\"a\": 1}");
+        assert_eq!(fs.len(path)?, 8);
+
+        fs.rename(path, Path::new("locales/de.json"))?;
+        assert_eq!(fs.paths(), vec![PathBuf::from("locales/de.json")]);
+        fs.remove_file(Path::new("locales/de.json"))?;
+        assert!(fs.paths().is_empty());
+
+        // 目录：重命名、列出和删除
+        fs.write(Path::new("cache.partial/de.json"), b"{}")?;
+        fs.write(Path::new("cache.partial/app/en.json"), b"{}")?;
+        fs.rename(Path::new("cache.partial"), Path::new("cache"))?;
+        assert_eq!(
+            fs.read_dir(Path::new("cache"))?,
+            vec![PathBuf::from("cache/app"), PathBuf::from("cache/de.json")]
+        );
+        assert!(fs.read_dir(Path::new("cache.partial")).is_err());
+        fs.remove_dir_all(Path::new("cache"))?;
+        assert!(fs.paths().is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::config::{Config, WebhookConfig, WebhookFormat};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 一次 push/pull 运行的结果摘要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    pub command: String,
+    pub product_code: String,
+    pub version_no: String,
+    pub sub_systems: Vec<String>,
+    pub languages: Vec<String>,
+    pub keys_uploaded: usize,
+    pub keys_downloaded: usize,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RunSummary {
+    /// 面向聊天工具的一行文字描述
+    fn text(&self) -> String {
+        let outcome = if self.success { "succeeded" } else { "failed" };
+        let mut text = format!(
+            "i18n-app {} {} for {} {} ({})",
+            self.command,
+            outcome,
+            self.product_code,
+            self.version_no,
+            self.sub_systems.join(", ")
+        );
+        if !self.languages.is_empty() {
+            text.push_str(&format!("\nLanguages: {}", self.languages.join(", ")));
+        }
+        text.push_str(&format!(
+            "\nKeys uploaded: {}, keys downloaded: {}",
+            self.keys_uploaded, self.keys_downloaded
+        ));
+        if let Some(error) = &self.error {
+            text.push_str(&format!("\nError: {}", error));
+        }
+        text
+    }
+}
+
+/// 按配置的格式生成 webhook 请求体
+pub fn build_payload(format: WebhookFormat, summary: &RunSummary) -> Value {
+    match format {
+        WebhookFormat::Json => json!({
+            "event": format!("i18n-app.{}", summary.command),
+            "summary": summary,
+        }),
+        WebhookFormat::Slack => json!({ "text": summary.text() }),
+        WebhookFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": format!("i18n-app {}", summary.command),
+            "themeColor": if summary.success { "2EB67D" } else { "E01E5A" },
+            "text": summary.text().replace('\n', "<br>"),
+        }),
+    }
+}
+
+pub async fn send(webhook: &WebhookConfig, summary: &RunSummary) -> Result<()> {
+    let response = Client::new()
+        .post(&webhook.url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(&build_payload(webhook.format, summary))
+        .send()
+        .await
+        .with_context(|| format!("发送 webhook 到 {} 失败", webhook.url))?;
+    response
+        .error_for_status()
+        .with_context(|| format!("webhook {} 返回错误状态", webhook.url))?;
+    Ok(())
+}
+
+/// 需要发送通知的一次 push/pull（包括 `set --push`），`config` 是命令实际使用的配置（已应用 `--env`）
+pub struct Run {
+    pub command: &'static str,
+    pub config: Config,
+}
+
+/// push/pull 结束后按配置发送通知，通知失败只记录警告，不影响命令结果
+pub async fn notify(run: Option<&Run>, result: &Result<()>) {
+    let Some(Run { command, config }) = run else {
+        return;
+    };
+    if config.offline {
+        return;
+    }
+    let Some(webhook) = config.webhook.as_ref() else {
+        return;
+    };
+
+    let summary = RunSummary {
+        command: command.to_string(),
+        product_code: config.product_code.clone(),
+        version_no: config.version_no.clone(),
+        sub_systems: config.sub_systems.clone(),
+        languages: crate::history::languages(),
+        keys_uploaded: crate::history::keys_uploaded(),
+        keys_downloaded: crate::history::keys_downloaded(),
+        success: result.is_ok(),
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+    };
+    match send(webhook, &summary).await {
+        Ok(()) => tracing::debug!("Webhook notification sent to {}", webhook.url),
+        Err(e) => tracing::warn!("Failed to send webhook notification: {:#}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    fn summary() -> RunSummary {
+        RunSummary {
+            command: "push".to_string(),
+            product_code: "bos".to_string(),
+            version_no: "1.0.0".to_string(),
+            sub_systems: vec!["app".to_string()],
+            languages: vec!["en-US".to_string(), "zh-CN".to_string()],
+            keys_uploaded: 12,
+            keys_downloaded: 0,
+            success: true,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_build_payload_formats() {
+        let json = build_payload(WebhookFormat::Json, &summary());
+        assert_eq!(json["event"], "i18n-app.push");
+        assert_eq!(json["summary"]["keysUploaded"], 12);
+        assert!(json["summary"].get("error").is_none());
+
+        let slack = build_payload(WebhookFormat::Slack, &summary());
+        let text = slack["text"].as_str().unwrap();
+        assert!(text.contains("push succeeded"));
+        assert!(text.contains("en-US, zh-CN"));
+
+        let teams = build_payload(
+            WebhookFormat::Teams,
+            &RunSummary {
+                success: false,
+                error: Some("network".to_string()),
+                ..summary()
+            },
+        );
+        assert_eq!(teams["@type"], "MessageCard");
+        assert!(teams["text"].as_str().unwrap().contains("Error: network"));
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_payload() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({ "text": summary().text() }),
+            ))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let webhook = WebhookConfig {
+            url: format!("{}/hook", server.url()),
+            format: WebhookFormat::Slack,
+        };
+        send(&webhook, &summary()).await?;
+        mock.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_notify_uses_run_config_and_error_chain() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "event": "i18n-app.push",
+                "summary": {
+                    "productCode": "bos",
+                    "success": false,
+                    "error": "上传失败: connection refused",
+                },
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+        let mut config = Config {
+            product_code: "bos".to_string(),
+            webhook: Some(WebhookConfig {
+                url: format!("{}/hook", server.url()),
+                format: WebhookFormat::Json,
+            }),
+            ..Default::default()
+        };
+        let result = Err(anyhow::anyhow!("connection refused").context("上传失败"));
+
+        notify(None, &result).await;
+        config.offline = true;
+        let offline = Run {
+            command: "push",
+            config: config.clone(),
+        };
+        notify(Some(&offline), &result).await;
+        config.offline = false;
+        notify(
+            Some(&Run {
+                command: "push",
+                config,
+            }),
+            &result,
+        )
+        .await;
+        mock.expect(1).assert_async().await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+use anyhow::{bail, Context, Result};
+use std::fs::{self, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::locations;
+use crate::logs;
+use crate::output::{self, Status};
+use crate::vfs::FileSystem;
+
+/// 工作目录中自动生成的 .gitignore，lint baseline 和发布版本快照需要提交到仓库，不忽略
+const WORK_DIR_GITIGNORE: &str = "# 由 i18n-app 自动生成：缓存、日志、预览等文件不提交到仓库\n*\n!lint-baseline.json\n!releases/\n!releases/*.json\n";
+
+/// 工具的工作目录，备份、预览等与项目相关的文件放在这里，缓存和日志默认也放在这里
+pub fn work_dir() -> PathBuf {
+    PathBuf::from(locations::WORK_DIR)
+}
+
+pub fn cache_root() -> PathBuf {
+    locations::cache_home().join("cache")
+}
+
+/// 工作目录存在且没有 .gitignore 时生成一个，已有的文件不会被修改
+pub fn ensure_work_dir_gitignore() -> Result<()> {
+    ensure_gitignore_in(&work_dir())
+}
+
+fn ensure_gitignore_in(dir: &Path) -> Result<()> {
+    let path = dir.join(".gitignore");
+    if !dir.is_dir() || path.exists() {
+        return Ok(());
+    }
+    fs::write(&path, WORK_DIR_GITIGNORE).with_context(|| format!("写入 {} 失败", path.display()))
+}
+
+pub fn backups_dir() -> PathBuf {
+    work_dir().join("backups")
+}
+
+/// pull 后的本地内容快照，作为三方合并的基准
+pub fn snapshot_dir() -> PathBuf {
+    work_dir().join("snapshots")
+}
+
+/// `snapshot save` 保存的发布版本快照
+pub fn releases_dir() -> PathBuf {
+    work_dir().join("releases")
+}
+
+pub fn preview_dir() -> PathBuf {
+    work_dir().join("preview")
+}
+
+pub fn lock_file() -> PathBuf {
+    work_dir().join("i18n-app.lock")
+}
+
+/// 修改翻译的命令运行期间持有的锁，同一项目中同时只能运行一个这样的命令。
+/// 使用操作系统的文件锁，进程被强制结束时自动释放，留下的锁文件不会阻止下次运行
+#[derive(Debug)]
+pub struct WorkspaceLock {
+    _file: fs::File,
+    path: PathBuf,
+}
+
+impl WorkspaceLock {
+    pub fn acquire(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("打开锁文件 {} 失败", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let pid = fs::read_to_string(path).unwrap_or_default();
+                return Err(AppError::Validation(format!(
+                    "另一个 i18n-app 进程（pid {}）正在修改这个项目，请等待它结束后重试",
+                    pid.trim()
+                ))
+                .into());
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("锁定 {} 失败", path.display()))
+            }
+        }
+        file.set_len(0)?;
+        write!(file, "{}", std::process::id())?;
+        Ok(Self {
+            _file: file,
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for WorkspaceLock {
+    /// 先删除锁文件，关闭文件时释放锁
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 判断 `path` 是否位于 `root` 之内（不包括 `root` 本身），两者都必须存在
+pub fn is_inside(path: &Path, root: &Path) -> bool {
+    match (path.canonicalize(), root.canonicalize()) {
+        (Ok(path), Ok(root)) => path != root && path.starts_with(&root),
+        _ => false,
+    }
+}
+
+/// 项目 .gitignore 中忽略工作目录的规则，lint baseline 和发布版本快照仍可以提交
+const ROOT_GITIGNORE_ENTRY: &str = "# i18n-app 的缓存、日志和预览文件\n.i18n-app/*\n!.i18n-app/lint-baseline.json\n!.i18n-app/releases/\n";
+
+/// .gitignore 内容中是否已经有忽略工作目录的规则
+pub fn is_work_dir_ignored(gitignore: &str) -> bool {
+    gitignore.lines().any(|line| {
+        let pattern = line.trim().trim_start_matches('/');
+        matches!(
+            pattern,
+            ".i18n-app" | ".i18n-app/" | ".i18n-app/*" | ".i18n-app/**"
+        )
+    })
+}
+
+/// 确保项目的 .gitignore 忽略工作目录，文件不存在时创建，已有规则时不修改。返回是否写入了文件
+pub fn ensure_root_gitignore(project: &Path) -> Result<bool> {
+    let path = project.join(".gitignore");
+    let mut content = if path.exists() {
+        fs::read_to_string(&path).with_context(|| format!("读取 {} 失败", path.display()))?
+    } else {
+        String::new()
+    };
+    if is_work_dir_ignored(&content) {
+        return Ok(false);
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(ROOT_GITIGNORE_ENTRY);
+    fs::write(&path, content).with_context(|| format!("写入 {} 失败", path.display()))?;
+    Ok(true)
+}
+
+/// 删除目录前的安全检查：只允许自动删除工作目录（以及缓存目录）内的目录，避免误删用户文件
+pub fn remove_dir_safely(dir: &Path) -> Result<()> {
+    let cache = locations::cache_home();
+    if dir.exists() && is_inside(dir, &cache) {
+        return remove_dir_safely_in(dir, &cache);
+    }
+    remove_dir_safely_in(dir, &work_dir())
+}
+
+/// 与 [`remove_dir_safely`] 相同的检查，目录通过 `fs` 删除。磁盘上不存在的目录（如测试的内存文件系统中的目录）不需要检查
+pub fn remove_dir_safely_with(fs: &dyn FileSystem, dir: &Path) -> Result<()> {
+    if !fs.exists(dir) {
+        return Ok(());
+    }
+    if dir.exists() && !is_inside(dir, &locations::cache_home()) && !is_inside(dir, &work_dir()) {
+        bail!(
+            "拒绝删除 {}：只会自动删除 {} 目录内的文件",
+            dir.display(),
+            work_dir().display()
+        );
+    }
+    fs.remove_dir_all(dir)
+        .with_context(|| format!("删除目录 {} 失败", dir.display()))
+}
+
+pub fn remove_dir_safely_in(dir: &Path, root: &Path) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    if !is_inside(dir, root) {
+        bail!(
+            "拒绝删除 {}：只会自动删除 {} 目录内的文件",
+            dir.display(),
+            root.display()
+        );
+    }
+    fs::remove_dir_all(dir).with_context(|| format!("删除目录 {} 失败", dir.display()))
+}
+
+/// 递归查找目录中第一个非 JSON 文件
+pub fn find_non_json_file(dir: &Path) -> Result<Option<PathBuf>> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if let Some(found) = find_non_json_file(&path)? {
+                return Ok(Some(found));
+            }
+        } else if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+/// 删除工作目录之外的用户目录，目录中包含非 JSON 文件时拒绝删除
+pub fn remove_user_dir(dir: &Path) -> Result<()> {
+    if let Some(file) = find_non_json_file(dir)? {
+        bail!(
+            "拒绝清空 {}：目录中包含非 JSON 文件 {}",
+            dir.display(),
+            file.display()
+        );
+    }
+    fs::remove_dir_all(dir).with_context(|| format!("删除目录 {} 失败", dir.display()))
+}
+
+/// 在终端中询问用户确认，非交互环境下视为拒绝
+pub fn confirm(prompt: &str) -> Result<bool> {
+    if !console::user_attended() {
+        return Ok(false);
+    }
+    let term = console::Term::stdout();
+    term.write_str(&format!("{} [y/N] ", prompt))?;
+    let answer = term.read_line()?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "YES"))
+}
+
+/// clean 命令要清理的内容
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CleanTargets {
+    pub cache: bool,
+    pub logs: bool,
+    pub backups: bool,
+    pub preview: bool,
+}
+
+impl CleanTargets {
+    /// 未指定任何选项时清理全部内容
+    pub fn new(cache: bool, logs: bool, backups: bool, all: bool) -> Self {
+        let all = all || !(cache || logs || backups);
+        Self {
+            cache: cache || all,
+            logs: logs || all,
+            backups: backups || all,
+            preview: all,
+        }
+    }
+}
+
+/// 清理工作目录，配置文件和运行历史不受影响
+pub fn clean(targets: CleanTargets) -> Result<()> {
+    let dirs = [
+        (targets.cache, cache_root()),
+        (targets.backups, backups_dir()),
+        (targets.preview, preview_dir()),
+    ];
+    for (selected, dir) in dirs {
+        if !selected {
+            continue;
+        }
+        if dir.exists() {
+            remove_dir_safely(&dir)?;
+            output::status(Status::Ok, format!("removed {}", dir.display()));
+        } else {
+            output::status(Status::Skipped, format!("{} does not exist", dir.display()));
+        }
+    }
+
+    if targets.logs {
+        // 最新的日志文件属于本次运行，保留
+        let files = logs::list_log_files_in(&logs::log_dir())?;
+        let old = &files[..files.len().saturating_sub(1)];
+        for file in old {
+            fs::remove_file(file)
+                .with_context(|| format!("删除日志文件 {} 失败", file.display()))?;
+        }
+        output::status(Status::Ok, format!("removed {} log files", old.len()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remove_dir_safely_only_inside_root() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path().join(".i18n-app");
+        let cache = root.join("cache").join("app");
+        let user_dir = temp_dir.path().join("src").join("locales");
+        fs::create_dir_all(&cache)?;
+        fs::create_dir_all(&user_dir)?;
+
+        remove_dir_safely_in(&cache, &root)?;
+        assert!(!cache.exists());
+
+        assert!(remove_dir_safely_in(&user_dir, &root).is_err());
+        assert!(user_dir.exists());
+        assert!(remove_dir_safely_in(&root, &root).is_err());
+        // 通过 .. 跳出工作目录同样会被拒绝
+        assert!(remove_dir_safely_in(&root.join("..").join("src"), &root).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workspace_lock() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(".i18n-app").join("i18n-app.lock");
+
+        let lock = WorkspaceLock::acquire(&path)?;
+        assert_eq!(fs::read_to_string(&path)?, std::process::id().to_string());
+        let error = WorkspaceLock::acquire(&path).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<AppError>(),
+            Some(AppError::Validation(_))
+        ));
+
+        drop(lock);
+        assert!(!path.exists());
+        // 进程被强制结束时留下的锁文件没有被锁定，不影响下次运行
+        fs::write(&path, "12345")?;
+        drop(WorkspaceLock::acquire(&path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_user_dir_refuses_non_json() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let locales = temp_dir.path().join("locales");
+        fs::create_dir_all(locales.join("nested"))?;
+        fs::write(locales.join("en-US.json"), "{}")?;
+        fs::write(locales.join("nested").join("index.ts"), "export {}")?;
+
+        assert!(remove_user_dir(&locales).is_err());
+        assert!(locales.join("en-US.json").exists());
+
+        fs::remove_file(locales.join("nested").join("index.ts"))?;
+        remove_user_dir(&locales)?;
+        assert!(!locales.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ensure_gitignore() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path().join(".i18n-app");
+        ensure_gitignore_in(&root)?;
+        assert!(!root.exists());
+
+        fs::create_dir_all(&root)?;
+        ensure_gitignore_in(&root)?;
+        let content = fs::read_to_string(root.join(".gitignore"))?;
+        assert!(content.lines().any(|line| line == "*"));
+        assert!(content.contains("!lint-baseline.json"));
+
+        // 用户修改过的文件保持不变
+        fs::write(root.join(".gitignore"), "cache/\n")?;
+        ensure_gitignore_in(&root)?;
+        assert_eq!(fs::read_to_string(root.join(".gitignore"))?, "cache/\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_ensure_root_gitignore() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(".gitignore");
+
+        assert!(ensure_root_gitignore(temp_dir.path())?);
+        assert!(is_work_dir_ignored(&fs::read_to_string(&path)?));
+        // 重复执行不会再次追加
+        assert!(!ensure_root_gitignore(temp_dir.path())?);
+
+        fs::write(&path, "node_modules")?;
+        assert!(ensure_root_gitignore(temp_dir.path())?);
+        let content = fs::read_to_string(&path)?;
+        assert!(content.starts_with("node_modules\n# i18n-app"));
+
+        assert!(is_work_dir_ignored("dist\n/.i18n-app/\n"));
+        assert!(!is_work_dir_ignored("# .i18n-app\n.i18n-app.json\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_targets_default_to_all() {
+        let targets = CleanTargets::new(false, false, false, false);
+        assert!(targets.cache && targets.logs && targets.backups && targets.preview);
+
+        let targets = CleanTargets::new(true, false, false, false);
+        assert!(targets.cache && !targets.logs && !targets.backups && !targets.preview);
+    }
+}
@@ -1,7 +1,6 @@
 use anyhow::Result;
 use i18n_app::{api, config::Config, translation::TranslationFile};
 use mockito::Server;
-use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
 use tempfile::TempDir;
@@ -32,14 +31,11 @@ fn create_test_config(server_url: &str) -> Result<(TempDir, Config)> {
 }
 
 fn create_test_translation() -> TranslationFile {
-    let mut content = HashMap::new();
-    content.insert("test.key".to_string(), "Test Value".to_string());
-
-    TranslationFile {
-        language_code: "en-US".to_string(),
-        relative_path: "test/en-US.json".to_string(),
-        content,
-    }
+    TranslationFile::from_content(
+        "en-US".to_string(),
+        "test/en-US.json".to_string(),
+        [("test.key", "Test Value")],
+    )
 }
 
 #[test]